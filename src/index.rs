@@ -1,37 +1,42 @@
 use {
   self::{
-    entry::{BlockHashValue, Entry, InscriptionIdValue, OutPointValue, SatPointValue, SatRange},
+    block_files::{BlockFiles, RPC_TIP_DISTANCE},
+    entry::{
+      BlockHashValue, Entry, InscriptionIdValue, OutPointValue, SatPointValue, SatRange,
+      ScriptHashValue,
+    },
     reorg::*,
-    updater::Updater,
+    undo::UndoLog,
+    updater::{Preview, Updater},
   },
   super::*,
-  bitcoin::block::Header,
+  crate::metrics::{BlockStage, DatabaseMetrics, Metrics},
+  bitcoin::{block::Header, hashes::sha256, ScriptHash},
   bitcoincore_rpc::{
     json::{GetBlockHeaderResult, GetBlockResult},
-    Client,
+    Auth, Client,
   },
   chrono::SubsecRound,
   indicatif::{ProgressBar, ProgressStyle},
   log::log_enabled,
   okx::{
     datastore::{
-      brc20::{
-        self, redb as brc20_db, redb::try_init_tables as try_init_brc20,
-        DataStoreReadOnly as BRC20DataStoreReadOnly,
-      },
+      brc20::{self, redb as brc20_db, DataStoreReadOnly as BRC20DataStoreReadOnly},
       brc20s::{
-        self, redb as brc20s_db, redb::try_init_tables as try_init_brc20s,
-        DataStoreReadOnly as BRC20SDataStoreReadOnly, PledgedTick,
+        self, redb as brc20s_db, DataStoreReadOnly as BRC20SDataStoreReadOnly, PledgedTick,
       },
-      ord::{self, redb::try_init_tables as try_init_ord, DataStoreReadOnly},
+      ord::{self, DataStoreReadOnly},
+      page::{Page, PageRequest},
+      schema,
+      snapshot::{self, TableVisitor},
       ScriptKey,
     },
     protocol::brc20s::params::NATIVE_TOKEN_DECIMAL,
     reward,
   },
   redb::{
-    Database, MultimapTable, MultimapTableDefinition, ReadableMultimapTable, ReadableTable, Table,
-    TableDefinition, WriteTransaction,
+    Database, MultimapTable, MultimapTableDefinition, ReadTransaction, ReadableMultimapTable,
+    ReadableTable, Table, TableDefinition, WriteTransaction,
   },
   std::collections::HashMap,
   std::io::{BufWriter, Read, Write},
@@ -40,17 +45,25 @@ use {
 use crate::okx::datastore::ord::{bitmap::District, collections::CollectionKind};
 
 pub(super) use self::{
+  batch::{BRC20Account, BRC20SAccount},
   entry::{InscriptionEntry, InscriptionEntryValue},
-  updater::BlockData,
+  holders::{BRC20BalanceRow, BRC20SBalanceRow, BRC20SPositionRow, HolderRow},
+  reorg::ReorgEntry,
 };
 
+mod batch;
+mod block_files;
+pub(crate) mod children;
 mod entry;
 mod fetcher;
+mod holders;
+pub(crate) mod owners;
 mod reorg;
 mod rtx;
+pub(crate) mod undo;
 mod updater;
 
-const SCHEMA_VERSION: u64 = 6;
+pub(crate) const SCHEMA_VERSION: u64 = 6;
 
 macro_rules! define_table {
   ($name:ident, $key:ty, $value:ty) => {
@@ -68,18 +81,55 @@ macro_rules! define_multimap_table {
 define_multimap_table! { INSCRIPTION_ID_TO_CHILDREN, &InscriptionIdValue, &InscriptionIdValue }
 define_multimap_table! { SATPOINT_TO_INSCRIPTION_ID, &SatPointValue, &InscriptionIdValue }
 define_multimap_table! { SAT_TO_INSCRIPTION_ID, u64, &InscriptionIdValue }
+define_multimap_table! { SCRIPT_HASH_TO_INSCRIPTION_ID, &ScriptHashValue, &InscriptionIdValue }
 define_table! { HEIGHT_TO_BLOCK_HASH, u64, &BlockHashValue }
 define_table! { HEIGHT_TO_LAST_INSCRIPTION_NUMBER, u64, (i64, i64) }
+define_table! { HEIGHT_TO_STATE_HASH, u64, &[u8; 32] }
+define_table! { HEIGHT_TO_UNDO_LOG, u64, &[u8] }
+define_table! { INSCRIPTION_ID_AND_CHILD_NUMBER_TO_CHILD_ID, (&InscriptionIdValue, i64), &InscriptionIdValue }
+define_table! { INSCRIPTION_ID_TO_CONTENT_TYPE, &InscriptionIdValue, &str }
 define_table! { INSCRIPTION_ID_TO_INSCRIPTION_ENTRY, &InscriptionIdValue, InscriptionEntryValue }
 define_table! { INSCRIPTION_ID_TO_SATPOINT, &InscriptionIdValue, &SatPointValue }
+define_table! { INSCRIPTION_ID_TO_SCRIPT_HASH, &InscriptionIdValue, &ScriptHashValue }
 define_table! { INSCRIPTION_NUMBER_TO_INSCRIPTION_ID, i64, &InscriptionIdValue }
 define_table! { OUTPOINT_TO_SAT_RANGES, &OutPointValue, &[u8] }
 define_table! { OUTPOINT_TO_ENTRY, &OutPointValue, &[u8] }
+define_table! { REORG_ID_TO_REORG, u64, &[u8] }
 define_table! { REINSCRIPTION_ID_TO_SEQUENCE_NUMBER, &InscriptionIdValue, u64 }
 define_table! { SAT_TO_SATPOINT, u64, &SatPointValue }
 define_table! { STATISTIC_TO_COUNT, u64, u64 }
 define_table! { WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP, u64, u128 }
 
+/// Visits every table that holds index state, in a fixed order.
+///
+/// Undo logs, reorg history and commit timestamps describe how the index got
+/// to its state rather than the state itself, and are not visited.
+pub(crate) fn visit_tables<T: TableVisitor>(visitor: &mut T) -> Result {
+  visitor.visit(HEIGHT_TO_BLOCK_HASH)?;
+  visitor.visit(HEIGHT_TO_LAST_INSCRIPTION_NUMBER)?;
+  visitor.visit(HEIGHT_TO_STATE_HASH)?;
+  visitor.visit(INSCRIPTION_ID_AND_CHILD_NUMBER_TO_CHILD_ID)?;
+  visitor.visit(INSCRIPTION_ID_TO_CONTENT_TYPE)?;
+  visitor.visit(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+  visitor.visit(INSCRIPTION_ID_TO_SATPOINT)?;
+  visitor.visit(INSCRIPTION_ID_TO_SCRIPT_HASH)?;
+  visitor.visit(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
+  visitor.visit(OUTPOINT_TO_ENTRY)?;
+  visitor.visit(OUTPOINT_TO_SAT_RANGES)?;
+  visitor.visit(REINSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
+  visitor.visit(SAT_TO_SATPOINT)?;
+  visitor.visit(STATISTIC_TO_COUNT)?;
+  visitor.visit_multimap(INSCRIPTION_ID_TO_CHILDREN)?;
+  visitor.visit_multimap(SATPOINT_TO_INSCRIPTION_ID)?;
+  visitor.visit_multimap(SAT_TO_INSCRIPTION_ID)?;
+  visitor.visit_multimap(SCRIPT_HASH_TO_INSCRIPTION_ID)?;
+  okx::datastore::ord::redb::visit_tables(visitor)?;
+  okx::datastore::visit_tables(visitor)?;
+  okx::datastore::brc20::redb::visit_tables(visitor)?;
+  okx::datastore::brc20s::redb::visit_tables(visitor)?;
+  Ok(())
+}
+
 #[derive(Debug, PartialEq)]
 pub enum List {
   Spent,
@@ -133,6 +183,27 @@ pub(crate) struct TransactionInfo {
   pub(crate) starting_timestamp: u128,
 }
 
+/// Narrows down the inscriptions listed by `Index::ord_address_inscriptions`.
+#[derive(Debug, Default)]
+pub(crate) struct OwnedInscriptionFilter {
+  /// Only inscriptions with this content type, with or without parameters.
+  pub(crate) content_type: Option<String>,
+  /// Only BRC20 transfer inscriptions that have not been sent yet.
+  pub(crate) brc20_transferable: bool,
+  /// Only inscriptions that claimed a bitmap district.
+  pub(crate) bitmap: bool,
+}
+
+impl schema::Chain for Client {
+  fn transaction(&self, txid: Txid) -> Result<Option<Transaction>> {
+    self.get_raw_transaction(&txid, None).into_option()
+  }
+
+  fn block(&self, hash: BlockHash) -> Result<Option<Block>> {
+    self.get_block(&hash).into_option()
+  }
+}
+
 trait BitcoinCoreRpcResultExt<T> {
   fn into_option(self) -> Result<Option<T>>;
 }
@@ -159,20 +230,42 @@ impl<T> BitcoinCoreRpcResultExt<T> for Result<T, bitcoincore_rpc::Error> {
 pub(crate) struct Index {
   client: Client,
   database: Database,
+  db_cache_size: usize,
   durability: redb::Durability,
   first_inscription_height: u64,
   genesis_block_coinbase_transaction: Transaction,
   genesis_block_coinbase_txid: Txid,
   height_limit: Option<u64>,
+  metrics: Metrics,
   options: Options,
   path: PathBuf,
+  /// Idle scratch databases that transaction previews run their inscription
+  /// updates in, each used by one preview at a time.
+  preview_databases: Mutex<Vec<Database>>,
+  read_only: bool,
   unrecoverably_reorged: AtomicBool,
 }
 
 impl Index {
   pub(crate) fn open(options: &Options) -> Result<Self> {
-    let client = options.bitcoin_rpc_client()?;
+    Self::open_with_mode(options, options.bitcoin_rpc_client()?, false)
+  }
+
+  /// Opens an existing index without updating or migrating it, for commands
+  /// that only read from the index. redb still takes an exclusive lock on the
+  /// file, so this cannot open an index that another process has open.
+  pub(crate) fn open_read_only(options: &Options) -> Result<Self> {
+    Self::open_with_mode(options, options.bitcoin_rpc_client()?, true)
+  }
+
+  /// Opens an existing index read-only without connecting to Bitcoin Core,
+  /// for inspecting an index file on a machine without a node. Anything that
+  /// needs the node fails.
+  pub(crate) fn open_offline(options: &Options) -> Result<Self> {
+    Self::open_with_mode(options, Client::new(&options.rpc_url(), Auth::None)?, true)
+  }
 
+  fn open_with_mode(options: &Options, client: Client, read_only: bool) -> Result<Self> {
     let path = if let Some(path) = &options.index {
       path.clone()
     } else {
@@ -246,6 +339,10 @@ impl Index {
 
         database
       }
+      Err(err) if read_only => bail!(
+        "failed to open index at `{}` read-only: {err}",
+        path.display()
+      ),
       Err(_) => {
         let database = Database::builder()
           .set_cache_size(db_cache_size)
@@ -258,10 +355,15 @@ impl Index {
         tx.open_multimap_table(INSCRIPTION_ID_TO_CHILDREN)?;
         tx.open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)?;
         tx.open_multimap_table(SAT_TO_INSCRIPTION_ID)?;
+        tx.open_multimap_table(SCRIPT_HASH_TO_INSCRIPTION_ID)?;
         tx.open_table(HEIGHT_TO_BLOCK_HASH)?;
         tx.open_table(HEIGHT_TO_LAST_INSCRIPTION_NUMBER)?;
+        tx.open_table(HEIGHT_TO_STATE_HASH)?;
+        tx.open_table(INSCRIPTION_ID_AND_CHILD_NUMBER_TO_CHILD_ID)?;
+        tx.open_table(INSCRIPTION_ID_TO_CONTENT_TYPE)?;
         tx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
         tx.open_table(INSCRIPTION_ID_TO_SATPOINT)?;
+        tx.open_table(INSCRIPTION_ID_TO_SCRIPT_HASH)?;
         tx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
         tx.open_table(OUTPOINT_TO_ENTRY)?;
         tx.open_table(REINSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
@@ -281,15 +383,12 @@ impl Index {
         database
       }
     };
-    {
-      let wtx = database.begin_write()?;
-      let rtx = database.begin_read()?;
-      try_init_ord(&wtx, &rtx)?;
-      try_init_brc20(&wtx, &rtx)?;
-      try_init_brc20s(&wtx, &rtx)?;
-      wtx.commit()?;
-      log::info!("Options:\n{:#?}", options);
+    if read_only {
+      schema::check(&database.begin_read()?, &path)?;
+    } else {
+      schema::upgrade(&database, &path, &client)?;
     }
+    log::info!("Options:\n{:#?}", options);
     let genesis_block_coinbase_transaction =
       options.chain().genesis_block().coinbase().unwrap().clone();
 
@@ -297,16 +396,33 @@ impl Index {
       genesis_block_coinbase_txid: genesis_block_coinbase_transaction.txid(),
       client,
       database,
+      db_cache_size,
       durability,
       first_inscription_height: options.first_inscription_height(),
       genesis_block_coinbase_transaction,
       height_limit: options.height_limit,
+      metrics: Metrics::default(),
       options: options.clone(),
       path,
+      preview_databases: Mutex::new(Vec::new()),
+      read_only,
       unrecoverably_reorged: AtomicBool::new(false),
     })
   }
 
+  pub(crate) fn metrics(&self) -> &Metrics {
+    &self.metrics
+  }
+
+  /// Renders indexer and API metrics in the Prometheus text format.
+  pub(crate) fn render_metrics(&self) -> Result<String> {
+    Ok(self.metrics.render(&DatabaseMetrics {
+      index_height: self.block_height()?.map(|height| height.n()),
+      cache_size: self.db_cache_size,
+      file_size: fs::metadata(&self.path).map(|metadata| metadata.len()).ok(),
+    }))
+  }
+
   pub(crate) fn get_chain_network(&self) -> Network {
     self.options.chain().network()
   }
@@ -380,6 +496,14 @@ impl Index {
       .collect()
   }
 
+  pub(crate) fn has_brc20_index(&self) -> bool {
+    self.options.enable_index_brc20 || self.options.enable_index_brc20s
+  }
+
+  pub(crate) fn has_brc20s_index(&self) -> bool {
+    self.options.enable_index_brc20s
+  }
+
   pub(crate) fn has_sat_index(&self) -> Result<bool> {
     match self.begin_read()?.0.open_table(OUTPOINT_TO_SAT_RANGES) {
       Ok(_) => Ok(true),
@@ -461,11 +585,13 @@ impl Index {
 
           match err.downcast_ref() {
             Some(&ReorgError::Recoverable { height, depth }) => {
+              self.metrics.increment_reorgs("recoverable");
               Reorg::handle_reorg(self, height, depth)?;
 
               updater = Updater::new(self)?;
             }
             Some(&ReorgError::Unrecoverable) => {
+              self.metrics.increment_reorgs("unrecoverable");
               self
                 .unrecoverably_reorged
                 .store(true, atomic::Ordering::Relaxed);
@@ -543,6 +669,224 @@ impl Index {
     Ok(())
   }
 
+  /// Writes the index state at `height`, the indexed height by default, to a
+  /// snapshot file.
+  ///
+  /// Earlier heights are reached by unwinding the blocks above them with their
+  /// undo logs, in a write transaction that is aborted once the snapshot is
+  /// written, so they must lie within `--max-reorg-depth` of the indexed height.
+  pub(crate) fn export_snapshot(
+    &self,
+    height: Option<u64>,
+    filename: &Path,
+  ) -> Result<snapshot::SnapshotSummary> {
+    let rtx = self.begin_read()?;
+
+    let Some((tip, tip_hash)) = rtx.latest_block()? else {
+      bail!("index has no blocks, nothing to export");
+    };
+
+    let height = height.unwrap_or(tip.n());
+    if height > tip.n() {
+      bail!(
+        "index is at height {}, cannot export a snapshot at height {height}, rerun indexing with `--height-limit {}`",
+        tip.n(),
+        height + 1
+      );
+    }
+
+    if height == tip.n() {
+      return self.write_snapshot(
+        snapshot::Source::Read(&rtx.0),
+        height,
+        tip_hash,
+        self.state_hash(height)?,
+        filename,
+      );
+    }
+
+    let depth = undo::available_depth(&rtx.0, tip.n())?;
+    if tip.n() - height > depth {
+      bail!(
+        "index is at height {} and can only be unwound to height {} with its undo logs, cannot export a snapshot at height {height}",
+        tip.n(),
+        tip.n() - depth
+      );
+    }
+
+    let wtx = self.begin_write()?;
+    for unwound in (height + 1..=tip.n()).rev() {
+      undo::unwind_block(&wtx, unwound)?;
+    }
+
+    let Some(block_hash) = wtx
+      .open_table(HEIGHT_TO_BLOCK_HASH)?
+      .get(height)?
+      .map(|hash| BlockHash::load(*hash.value()))
+    else {
+      bail!("index has no block hash at height {height} after unwinding");
+    };
+    let state_hash = wtx
+      .open_table(HEIGHT_TO_STATE_HASH)?
+      .get(height)?
+      .map(|hash| sha256::Hash::from_byte_array(*hash.value()));
+
+    let summary = self.write_snapshot(
+      snapshot::Source::Write(&wtx),
+      height,
+      block_hash,
+      state_hash,
+      filename,
+    );
+
+    wtx.abort()?;
+
+    summary
+  }
+
+  fn write_snapshot(
+    &self,
+    source: snapshot::Source,
+    height: u64,
+    block_hash: BlockHash,
+    state_hash: Option<sha256::Hash>,
+    filename: &Path,
+  ) -> Result<snapshot::SnapshotSummary> {
+    let Some(state_hash) = state_hash else {
+      bail!("index has no state hash at height {height}, index at least one more block first");
+    };
+
+    log::info!(
+      "exporting snapshot at height {height} to {}",
+      filename.display()
+    );
+
+    snapshot::export(
+      source,
+      snapshot::SnapshotHeader::new(
+        self.options.chain().network().to_string(),
+        height,
+        block_hash.to_byte_array(),
+        state_hash.to_byte_array(),
+        self.has_sat_index()?,
+      ),
+      BufWriter::new(File::create(filename)?),
+    )
+  }
+
+  /// Fills an empty index with the content of a snapshot, so that indexing
+  /// resumes from the block after the snapshot height.
+  pub(crate) fn import_snapshot(&self, filename: &Path) -> Result<snapshot::SnapshotSummary> {
+    if let Some(height) = self.block_height()? {
+      bail!(
+        "index is already at height {height}, snapshots can only be imported into an empty index"
+      );
+    }
+
+    let wtx = self.begin_write()?;
+
+    let summary = snapshot::import(&wtx, io::BufReader::new(File::open(filename)?), |header| {
+      let network = self.options.chain().network().to_string();
+      if header.network != network {
+        bail!("snapshot is for {}, index is for {network}", header.network);
+      }
+
+      let expected = snapshot::SnapshotHeader::new(
+        network,
+        header.height,
+        header.block_hash,
+        header.state_hash,
+        self.options.index_sats,
+      );
+      if header.schema_versions != expected.schema_versions {
+        bail!(
+          "snapshot schema versions {:?} do not match ord schema versions {:?}",
+          header.schema_versions,
+          expected.schema_versions
+        );
+      }
+
+      if header.index_sats != expected.index_sats {
+        bail!(
+          "snapshot was {} with `--index-sats`, but this index is {}",
+          if header.index_sats {
+            "built"
+          } else {
+            "not built"
+          },
+          if self.options.index_sats {
+            "built with it"
+          } else {
+            "not"
+          }
+        );
+      }
+
+      let block_hash = BlockHash::from_byte_array(header.block_hash);
+      let bitcoind_hash = self.client.get_block_hash(header.height)?;
+      if bitcoind_hash != block_hash {
+        bail!(
+          "snapshot block hash {block_hash} at height {} does not match bitcoind block hash {bitcoind_hash}",
+          header.height
+        );
+      }
+
+      Ok(())
+    })?;
+
+    // Undo logs describe how the exporting node reached its state and are not
+    // part of the snapshot, so none may be left for blocks up to its height.
+    wtx.delete_table(HEIGHT_TO_UNDO_LOG)?;
+
+    let header = &summary.header;
+    let imported_hash = wtx
+      .open_table(HEIGHT_TO_BLOCK_HASH)?
+      .range(0..)?
+      .next_back()
+      .transpose()?
+      .map(|(height, hash)| (height.value(), *hash.value()));
+    if imported_hash != Some((header.height, header.block_hash)) {
+      bail!("snapshot tables do not end at the block in its header");
+    }
+
+    let imported_state = wtx
+      .open_table(HEIGHT_TO_STATE_HASH)?
+      .get(header.height)?
+      .map(|hash| *hash.value());
+    if imported_state != Some(header.state_hash) {
+      bail!("snapshot tables do not match the state hash in its header");
+    }
+
+    wtx.commit()?;
+
+    Ok(summary)
+  }
+
+  /// Returns the state hash recorded for the block at `height`.
+  ///
+  /// Each block's state hash chains its BRC20 and BRC20S receipts onto the
+  /// previous one, so nodes can compare their protocol state height by height.
+  pub(crate) fn state_hash(&self, height: u64) -> Result<Option<sha256::Hash>> {
+    let rtx = self.database.begin_read()?;
+    let table = match rtx.open_table(HEIGHT_TO_STATE_HASH) {
+      Ok(table) => table,
+      Err(redb::TableError::TableDoesNotExist(_)) => return Ok(None),
+      Err(err) => return Err(err.into()),
+    };
+    let hash = table
+      .get(height)?
+      .map(|hash| sha256::Hash::from_byte_array(*hash.value()));
+    Ok(hash)
+  }
+
+  pub(crate) fn reorg_history(
+    &self,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<((Vec<ReorgEntry>, usize), Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| Reorg::history(rtx, start, limit))
+  }
+
   pub(crate) fn is_unrecoverably_reorged(&self) -> bool {
     self.unrecoverably_reorged.load(atomic::Ordering::Relaxed)
   }
@@ -556,6 +900,10 @@ impl Index {
   }
 
   fn begin_write(&self) -> Result<WriteTransaction> {
+    if self.read_only {
+      bail!("index at `{}` is open read-only", self.path.display());
+    }
+
     let mut tx = self.database.begin_write()?;
     tx.set_durability(self.durability);
     Ok(tx)
@@ -586,13 +934,12 @@ impl Index {
       .unwrap_or(0)
   }
 
-  pub(crate) fn height(&self) -> Result<Option<Height>> {
-    self.begin_read()?.block_height()
-  }
-
-  pub(crate) fn height_btc(&self, query_btc: bool) -> Result<(Option<Height>, Option<Height>)> {
-    let ord_height = self.begin_read()?.block_height()?;
-    if let Some(height) = ord_height {
+  pub(crate) fn height_btc(
+    &self,
+    query_btc: bool,
+  ) -> Result<(Option<(Height, BlockHash)>, Option<Height>)> {
+    let ord_block = self.begin_read()?.latest_block()?;
+    if let Some(block) = ord_block {
       if query_btc {
         let btc_height = match self.client.get_blockchain_info() {
           Ok(info) => Height(info.headers),
@@ -603,9 +950,9 @@ impl Index {
             ));
           }
         };
-        return Ok((Some(height), Some(btc_height)));
+        return Ok((Some(block), Some(btc_height)));
       }
-      Ok((Some(height), None))
+      Ok((Some(block), None))
     } else {
       Ok((None, None))
     }
@@ -623,10 +970,6 @@ impl Index {
     self.begin_read()?.block_hash(height)
   }
 
-  pub(crate) fn latest_block(&self) -> Result<Option<(Height, BlockHash)>> {
-    self.begin_read()?.latest_block()
-  }
-
   pub(crate) fn blocks(&self, take: usize) -> Result<Vec<(u64, BlockHash)>> {
     let rtx = self.begin_read()?;
 
@@ -722,6 +1065,170 @@ impl Index {
       .collect()
   }
 
+  /// Returns a page of the children of an inscription along with their
+  /// numbers, in inscription number order from `start`, or None if the
+  /// inscription does not exist. `start` is read from the page cursor with
+  /// `children::start`.
+  pub(crate) fn ord_children(
+    &self,
+    inscription_id: InscriptionId,
+    start: i64,
+    page: &PageRequest,
+  ) -> Result<(
+    Option<Page<(InscriptionId, i64)>>,
+    Option<(Height, BlockHash)>,
+  )> {
+    self.read_at_height(|rtx| {
+      if rtx
+        .open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?
+        .get(&inscription_id.store())?
+        .is_none()
+      {
+        return Ok(None);
+      }
+
+      let parent = inscription_id.store();
+      let table = rtx.open_table(INSCRIPTION_ID_AND_CHILD_NUMBER_TO_CHILD_ID)?;
+      let children = table
+        .range((&parent, start)..=(&parent, i64::MAX))?
+        .map(|result| {
+          let (key, child) = result?;
+          let (_, number) = key.value();
+          Ok::<_, Error>((
+            children::number_key(number).to_vec(),
+            (InscriptionId::load(*child.value()), number),
+          ))
+        });
+
+      Ok(Some(page.collect(children)?))
+    })
+  }
+
+  /// Walks the parents of an inscription up to the root and returns them,
+  /// nearest first, along with their numbers, or None if the inscription does
+  /// not exist.
+  pub(crate) fn ord_ancestry(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<(
+    Option<Vec<(InscriptionId, i64)>>,
+    Option<(Height, BlockHash)>,
+  )> {
+    self.read_at_height(|rtx| {
+      let entries = rtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+
+      let Some(mut entry) = entries
+        .get(&inscription_id.store())?
+        .map(|entry| InscriptionEntry::load(entry.value()))
+      else {
+        return Ok(None);
+      };
+
+      let mut ancestors = Vec::new();
+
+      while let Some(parent) = entry.parent {
+        entry = entries
+          .get(&parent.store())?
+          .map(|entry| InscriptionEntry::load(entry.value()))
+          .ok_or_else(|| anyhow!("parent inscription {parent} has no entry"))?;
+        ancestors.push((parent, entry.number));
+      }
+
+      Ok(Some(ancestors))
+    })
+  }
+
+  /// Returns a page of the inscriptions currently held by `address` along
+  /// with their numbers and locations, in inscription id order.
+  pub(crate) fn ord_address_inscriptions(
+    &self,
+    address: &bitcoin::Address,
+    filter: &OwnedInscriptionFilter,
+    page: &PageRequest,
+  ) -> Result<(
+    Page<(InscriptionId, i64, SatPoint)>,
+    Option<(Height, BlockHash)>,
+  )> {
+    let script_key = ScriptKey::from_address(address.clone());
+
+    self.read_at_height(|rtx| {
+      let content_types = rtx.open_table(INSCRIPTION_ID_TO_CONTENT_TYPE)?;
+      let entries = rtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+      let satpoints = rtx.open_table(INSCRIPTION_ID_TO_SATPOINT)?;
+      let owned = rtx.open_multimap_table(SCRIPT_HASH_TO_INSCRIPTION_ID)?;
+      let brc20 = brc20_db::DataStoreReader::new(rtx);
+      let ord = ord::OrdDbReader::new(rtx);
+
+      let matches = |inscription_id: InscriptionId| -> Result<bool> {
+        if let Some(content_type) = &filter.content_type {
+          let matched = content_types
+            .get(&inscription_id.store())?
+            .map_or(false, |actual| {
+              let actual = actual.value();
+              actual == content_type || actual.split(';').next().unwrap().trim() == content_type
+            });
+          if !matched {
+            return Ok(false);
+          }
+        }
+
+        if filter.brc20_transferable
+          && brc20
+            .get_transferable_by_id(&script_key, &inscription_id)?
+            .is_none()
+        {
+          return Ok(false);
+        }
+
+        if filter.bitmap
+          && !ord
+            .get_collections_of_inscription(inscription_id)?
+            .unwrap_or_default()
+            .contains(&CollectionKind::BitMap)
+        {
+          return Ok(false);
+        }
+
+        Ok(true)
+      };
+
+      let item = |inscription_id: InscriptionId| -> Result<_> {
+        let number = entries
+          .get(&inscription_id.store())?
+          .map(|entry| InscriptionEntry::load(entry.value()).number)
+          .ok_or_else(|| anyhow!("inscription {inscription_id} has no entry"))?;
+        let satpoint = satpoints
+          .get(&inscription_id.store())?
+          .map(|satpoint| Entry::load(*satpoint.value()))
+          .ok_or_else(|| anyhow!("inscription {inscription_id} has no satpoint"))?;
+
+        Ok((
+          inscription_id.store().to_vec(),
+          (inscription_id, number, satpoint),
+        ))
+      };
+
+      let inscription_ids = owned.get(&script_key.script_hash().store())?;
+
+      page.collect(inscription_ids.filter_map(|result| {
+        let inscription_id = match result {
+          Ok(inscription_id) => InscriptionId::load(*inscription_id.value()),
+          Err(err) => return Some(Err(err.into())),
+        };
+
+        if inscription_id.store().as_slice() < page.start() {
+          return None;
+        }
+
+        match matches(inscription_id) {
+          Ok(true) => Some(item(inscription_id)),
+          Ok(false) => None,
+          Err(err) => Some(Err(err)),
+        }
+      }))
+    })
+  }
+
   pub(crate) fn get_inscription_ids_by_sat(&self, sat: Sat) -> Result<Vec<InscriptionId>> {
     let rtx = &self.database.begin_read()?;
 
@@ -789,12 +1296,11 @@ impl Index {
   pub(crate) fn ord_get_district_inscription_id(
     &self,
     number: u64,
-  ) -> Result<Option<InscriptionId>> {
+  ) -> Result<(Option<InscriptionId>, Option<(Height, BlockHash)>)> {
     let district = District { number };
-    Ok(
-      ord::OrdDbReader::new(&self.database.begin_read()?)
-        .get_collection_inscription_id(&district.to_collection_key())?,
-    )
+    self.read_at_height(|rtx| {
+      Ok(ord::OrdDbReader::new(rtx).get_collection_inscription_id(&district.to_collection_key())?)
+    })
   }
 
   pub(crate) fn get_inscription_by_id(
@@ -1033,7 +1539,6 @@ impl Index {
     }
   }
 
-  #[allow(unused)]
   pub(crate) fn get_inscriptions(
     &self,
     utxos: BTreeMap<OutPoint, Amount>,
@@ -1183,14 +1688,7 @@ impl Index {
     &self,
     inscription_id: InscriptionId,
   ) -> Result<Option<InscriptionEntry>> {
-    Ok(
-      self
-        .database
-        .begin_read()?
-        .open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?
-        .get(&inscription_id.store())?
-        .map(|value| InscriptionEntry::load(value.value())),
-    )
+    self.begin_read()?.inscription_entry(inscription_id)
   }
 
   #[cfg(test)]
@@ -1386,18 +1884,121 @@ impl Index {
     )
   }
 
+  pub(crate) fn preview_transaction(&self, unsigned: &UnsignedTransaction) -> Result<Preview> {
+    updater::preview(self, unsigned)
+  }
+
+  pub(crate) fn brc20_batch_accounts(
+    &self,
+    addresses: &[bitcoin::Address],
+    ticks: Option<&[brc20::Tick]>,
+  ) -> Result<(Vec<BRC20Account>, Option<(Height, BlockHash)>)> {
+    let scripts = addresses
+      .iter()
+      .map(|address| ScriptKey::from_address(address.clone()))
+      .collect::<Vec<_>>();
+    self.read_at_height(|rtx| batch::brc20_accounts(rtx, &scripts, ticks))
+  }
+
+  pub(crate) fn brc20s_batch_accounts(
+    &self,
+    addresses: &[bitcoin::Address],
+    tick_ids: Option<&[brc20s::TickId]>,
+    pids: Option<&[brc20s::Pid]>,
+  ) -> Result<(Vec<BRC20SAccount>, Option<(Height, BlockHash)>)> {
+    let scripts = addresses
+      .iter()
+      .map(|address| ScriptKey::from_address(address.clone()))
+      .collect::<Vec<_>>();
+    self.read_at_height(|rtx| batch::brc20s_accounts(rtx, &scripts, tick_ids, pids))
+  }
+
+  /// Streams a row for every non-zero BRC20 balance to `f` and returns the
+  /// height they were read at.
+  pub(crate) fn brc20_holder_balances(
+    &self,
+    f: impl FnMut(BRC20BalanceRow) -> Result<()>,
+  ) -> Result<Option<Height>> {
+    Ok(
+      self
+        .read_at_height(|rtx| holders::brc20_balances(rtx, f))?
+        .1
+        .map(|(height, _)| height),
+    )
+  }
+
+  /// Streams a row for every non-zero BRC20S balance to `f` and returns the
+  /// height they were read at.
+  pub(crate) fn brc20s_holder_balances(
+    &self,
+    f: impl FnMut(BRC20SBalanceRow) -> Result<()>,
+  ) -> Result<Option<Height>> {
+    Ok(
+      self
+        .read_at_height(|rtx| holders::brc20s_balances(rtx, f))?
+        .1
+        .map(|(height, _)| height),
+    )
+  }
+
+  /// Streams a row for every BRC20S pool position to `f` and returns the
+  /// height they were read at.
+  pub(crate) fn brc20s_holder_positions(
+    &self,
+    f: impl FnMut(BRC20SPositionRow) -> Result<()>,
+  ) -> Result<Option<Height>> {
+    Ok(
+      self
+        .read_at_height(|rtx| holders::brc20s_positions(rtx, f))?
+        .1
+        .map(|(height, _)| height),
+    )
+  }
+
   pub(crate) fn brc20_get_tick_info(&self, name: &brc20::Tick) -> Result<Option<brc20::TokenInfo>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
-    let info = brc20_db.get_token_info(name)?;
-    Ok(info)
+    self.begin_read()?.brc20_tick_info(name)
   }
 
-  pub(crate) fn brc20_get_all_tick_info(&self) -> Result<Vec<brc20::TokenInfo>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
-    let info = brc20_db.get_tokens_info()?;
-    Ok(info)
+  /// Runs `f` against a single read transaction and returns its result along
+  /// with the latest block the transaction reflects.
+  pub(crate) fn read_at_height<T>(
+    &self,
+    f: impl FnOnce(&rtx::Rtx) -> Result<T>,
+  ) -> Result<(T, Option<(Height, BlockHash)>)> {
+    let rtx = self.begin_read()?;
+    let block = rtx.latest_block()?;
+    Ok((f(&rtx)?, block))
+  }
+
+  /// Collects a page of the per-transaction results of `f` for `txs`, which
+  /// are keyed by their position in the block.
+  fn read_block_page<T>(
+    &self,
+    txs: &[Txid],
+    page: &PageRequest,
+    f: impl Fn(&ReadTransaction, &Txid) -> Result<Vec<T>>,
+  ) -> Result<(Page<(Txid, Vec<T>)>, Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| {
+      page.collect(
+        txs
+          .iter()
+          .enumerate()
+          .map(|(position, txid)| (u32::try_from(position).unwrap().to_be_bytes(), txid))
+          .filter(|(key, _)| key.as_slice() >= page.start())
+          .filter_map(|(key, txid)| match f(rtx, txid) {
+            Ok(items) if items.is_empty() => None,
+            Ok(items) => Some(Ok((key.to_vec(), (*txid, items)))),
+            Err(err) => Some(Err(err)),
+          }),
+      )
+    })
+  }
+
+  pub(crate) fn brc20_get_all_tick_info(
+    &self,
+    page: &PageRequest,
+  ) -> Result<(Page<brc20::TokenInfo>, Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| Ok(brc20_db::DataStoreReader::new(rtx).get_tokens_info_page(page)?))
   }
 
   pub(crate) fn brc20_get_balance_by_address(
@@ -1405,19 +2006,22 @@ impl Index {
     tick: &brc20::Tick,
     address: &bitcoin::Address,
   ) -> Result<Option<brc20::Balance>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
-    let bal = brc20_db.get_balance(&ScriptKey::from_address(address.clone()), tick)?;
-    Ok(bal)
+    self
+      .begin_read()?
+      .brc20_balance(&ScriptKey::from_address(address.clone()), tick)
   }
 
   pub(crate) fn brc20_get_all_balance_by_address(
     &self,
     address: &bitcoin::Address,
-  ) -> Result<Vec<brc20::Balance>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
-    Ok(brc20_db.get_balances(&ScriptKey::from_address(address.clone()))?)
+    page: &PageRequest,
+  ) -> Result<(Page<brc20::Balance>, Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| {
+      Ok(
+        brc20_db::DataStoreReader::new(rtx)
+          .get_balances_page(&ScriptKey::from_address(address.clone()), page)?,
+      )
+    })
   }
 
   pub(crate) fn get_transaction_info(
@@ -1437,145 +2041,147 @@ impl Index {
   pub(crate) fn brc20_get_tx_events_by_txid(
     &self,
     txid: &bitcoin::Txid,
-  ) -> Result<Option<Vec<brc20::Receipt>>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
-    let res = brc20_db.get_transaction_receipts(txid)?;
-
-    if res.is_empty() {
-      let tx = self.client.get_raw_transaction_info(txid, None)?;
-      if let Some(tx_blockhash) = tx.blockhash {
-        let tx_bh = self.client.get_block_header_info(&tx_blockhash)?;
-        let parsed_height = self.height()?;
-        if parsed_height.is_none() || tx_bh.height as u64 > parsed_height.unwrap().0 {
-          return Ok(None);
+  ) -> Result<(Option<Vec<brc20::Receipt>>, Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| {
+      let res = brc20_db::DataStoreReader::new(rtx).get_transaction_receipts(txid)?;
+
+      if res.is_empty() {
+        let tx = self.client.get_raw_transaction_info(txid, None)?;
+        if let Some(tx_blockhash) = tx.blockhash {
+          let tx_bh = self.client.get_block_header_info(&tx_blockhash)?;
+          let parsed_height = rtx.block_height()?;
+          if parsed_height.is_none() || tx_bh.height as u64 > parsed_height.unwrap().0 {
+            return Ok(None);
+          }
+        } else {
+          return Err(anyhow!("can't get tx block hash: {txid}"));
         }
-      } else {
-        return Err(anyhow!("can't get tx block hash: {txid}"));
       }
-    }
 
-    Ok(Some(res))
+      Ok(Some(res))
+    })
   }
 
   pub(crate) fn brc20_get_txs_events(
     &self,
-    txs: &Vec<Txid>,
-  ) -> Result<Vec<(bitcoin::Txid, Vec<brc20::Receipt>)>> {
-    let rtx = self.database.begin_read()?;
-    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
-    let mut result = Vec::new();
-    for txid in txs {
-      let tx_events = brc20_db.get_transaction_receipts(txid)?;
-      if tx_events.is_empty() {
-        continue;
-      }
-      result.push((*txid, tx_events));
-    }
-    Ok(result)
+    txs: &[Txid],
+    page: &PageRequest,
+  ) -> Result<(
+    Page<(Txid, Vec<brc20::Receipt>)>,
+    Option<(Height, BlockHash)>,
+  )> {
+    self.read_block_page(txs, page, |rtx, txid| {
+      Ok(brc20_db::DataStoreReader::new(rtx).get_transaction_receipts(txid)?)
+    })
+  }
+
+  /// Returns up to `limit` receipts of `history` matching `filter`, newest
+  /// first, reading at most `scan` receipts.
+  pub(crate) fn brc20_receipt_history(
+    &self,
+    history: brc20::ReceiptHistory,
+    limit: usize,
+    scan: usize,
+    filter: impl Fn(&brc20::Receipt) -> bool,
+  ) -> Result<Vec<(u64, Txid, brc20::Receipt)>> {
+    Ok(
+      brc20_db::DataStoreReader::new(&self.begin_read()?.0)
+        .get_receipt_history(history, limit, scan, filter)?,
+    )
   }
 
   pub(crate) fn brc20_get_tick_transferable_by_address(
     &self,
     tick: &brc20::Tick,
     address: &bitcoin::Address,
-  ) -> Result<Vec<brc20::TransferableLog>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
-    let res = brc20_db.get_transferable_by_tick(&ScriptKey::from_address(address.clone()), tick)?;
-
-    Ok(res)
+    page: &PageRequest,
+  ) -> Result<(Page<brc20::TransferableLog>, Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| {
+      Ok(
+        brc20_db::DataStoreReader::new(rtx).get_transferable_by_tick_page(
+          &ScriptKey::from_address(address.clone()),
+          tick,
+          page,
+        )?,
+      )
+    })
   }
 
   pub(crate) fn brc20_get_all_transferable_by_address(
     &self,
     address: &bitcoin::Address,
-  ) -> Result<Vec<brc20::TransferableLog>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
-    let res = brc20_db.get_transferable(&ScriptKey::from_address(address.clone()))?;
-
-    Ok(res)
+    page: &PageRequest,
+  ) -> Result<(Page<brc20::TransferableLog>, Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| {
+      Ok(
+        brc20_db::DataStoreReader::new(rtx)
+          .get_transferable_page(&ScriptKey::from_address(address.clone()), page)?,
+      )
+    })
   }
 
   pub(crate) fn brc20s_all_tick_info(
     &self,
-    start: usize,
-    limit: Option<usize>,
-  ) -> Result<(Vec<brc20s::TickInfo>, usize)> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
-    let all_tick = brc20s_db.get_all_tick_info(start, limit)?;
-    Ok(all_tick)
+    page: &PageRequest,
+  ) -> Result<((Page<brc20s::TickInfo>, usize), Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| Ok(brc20s_db::DataStoreReader::new(rtx).get_tick_info_page(page)?))
   }
 
   pub(crate) fn brc20s_tick_info(
     &self,
     tick_id: &brc20s::TickId,
   ) -> Result<Option<brc20s::TickInfo>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
-    let info = brc20s_db.get_tick_info(tick_id)?;
-    Ok(info)
+    self.begin_read()?.brc20s_tick_info(tick_id)
   }
 
-  pub(crate) fn brc20s_pool_info(&self, pid: &brc20s::Pid) -> Result<Option<brc20s::PoolInfo>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
-    let info = brc20s_db.get_pid_to_poolinfo(pid)?;
-    Ok(info)
+  pub(crate) fn brc20s_pool_info(
+    &self,
+    pid: &brc20s::Pid,
+  ) -> Result<(Option<brc20s::PoolInfo>, Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| rtx.brc20s_pool_info(pid))
   }
   pub(crate) fn brc20s_stake_info(
     &self,
     address: &bitcoin::Address,
     pledged_tick: &PledgedTick,
-  ) -> Result<Option<brc20s::StakeInfo>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
-
-    let info =
-      brc20s_db.get_user_stakeinfo(&ScriptKey::from_address(address.clone()), pledged_tick)?;
-    Ok(info)
+  ) -> Result<(Option<brc20s::StakeInfo>, Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| {
+      rtx.brc20s_stake_info(&ScriptKey::from_address(address.clone()), pledged_tick)
+    })
   }
 
   pub(crate) fn brc20s_all_pool_info(
     &self,
-    start: usize,
-    limit: Option<usize>,
-  ) -> Result<(Vec<brc20s::PoolInfo>, usize)> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
-    let all_pool = brc20s_db.get_all_poolinfo(start, limit)?;
-    Ok(all_pool)
+    page: &PageRequest,
+  ) -> Result<((Page<brc20s::PoolInfo>, usize), Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| Ok(brc20s_db::DataStoreReader::new(rtx).get_poolinfo_page(page)?))
   }
 
   pub(crate) fn brc20s_all_pools_by_tid(
     &self,
     tick_id: &brc20s::TickId,
-  ) -> Result<Vec<brc20s::PoolInfo>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
-    let all_pool = brc20s_db.get_all_pools_by_tid(tick_id)?;
-    Ok(all_pool)
+    page: &PageRequest,
+  ) -> Result<((Page<brc20s::PoolInfo>, usize), Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| {
+      Ok(brc20s_db::DataStoreReader::new(rtx).get_pools_by_tid_page(tick_id, page)?)
+    })
   }
 
   pub(crate) fn brc20s_user_info(
     &self,
     pid: &brc20s::Pid,
     address: &bitcoin::Address,
-  ) -> Result<Option<brc20s::UserInfo>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
-    let info = brc20s_db.get_pid_to_use_info(&ScriptKey::from_address(address.clone()), pid)?;
-    Ok(info)
+  ) -> Result<(Option<brc20s::UserInfo>, Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| rtx.brc20s_user_info(&ScriptKey::from_address(address.clone()), pid))
   }
 
   pub(crate) fn brc20s_user_pending_reward(
     &self,
     pid: &brc20s::Pid,
     address: &bitcoin::Address,
-  ) -> Result<(Option<String>, Option<String>)> {
-    let rtx = self.database.begin_read().unwrap();
+  ) -> Result<(String, Option<(Height, BlockHash)>)> {
+    let rtx = self.begin_read()?;
+    let block = rtx.latest_block()?;
     let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
     let brc20_db = brc20_db::DataStoreReader::new(&rtx);
     let user_info = brc20s_db
@@ -1593,138 +2199,165 @@ impl Index {
       PledgedTick::Unknown => 0_u8,
     };
 
-    let block = self.height().unwrap().unwrap_or(Height(0)).n();
-
     let result = reward::query_reward(
       user_info,
       pool_info,
-      self.height().unwrap().unwrap_or(Height(0)).n(),
+      block.map_or(0, |(height, _)| height.n()),
       dec,
     )?;
 
-    Ok((Some(result.to_string()), Some(block.to_string())))
+    Ok((result.to_string(), block))
+  }
+
+  /// Returns the decimals of the token staked in a pool.
+  pub(crate) fn brc20s_stake_decimal(&self, stake: &PledgedTick) -> Result<u8> {
+    let rtx = self.database.begin_read()?;
+    Ok(match stake {
+      PledgedTick::Native => NATIVE_TOKEN_DECIMAL,
+      PledgedTick::BRC20STick(tick_id) => brc20s_db::DataStoreReader::new(&rtx)
+        .get_tick_info(tick_id)?
+        .map(|tick_info| tick_info.decimal)
+        .unwrap_or_default(),
+      PledgedTick::BRC20Tick(tick) => brc20_db::DataStoreReader::new(&rtx)
+        .get_token_info(tick)?
+        .map(|token| token.decimal)
+        .unwrap_or_default(),
+      PledgedTick::Unknown => 0,
+    })
   }
 
   pub(crate) fn brc20s_balance(
     &self,
     tick_id: &brc20s::TickId,
     address: &bitcoin::Address,
-  ) -> Result<Option<brc20s::Balance>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
-    let info = brc20s_db.get_balance(&ScriptKey::from_address(address.clone()), tick_id)?;
-    Ok(info)
+  ) -> Result<(Option<brc20s::Balance>, Option<(Height, BlockHash)>)> {
+    self
+      .read_at_height(|rtx| rtx.brc20s_balance(&ScriptKey::from_address(address.clone()), tick_id))
   }
 
   pub(crate) fn brc20s_all_balance(
     &self,
     address: &bitcoin::Address,
-  ) -> Result<Vec<(brc20s::TickId, brc20s::Balance)>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
-    let all_balance = brc20s_db.get_balances(&ScriptKey::from_address(address.clone()))?;
-    Ok(all_balance)
+    page: &PageRequest,
+  ) -> Result<(Page<brc20s::Balance>, Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| {
+      Ok(
+        brc20s_db::DataStoreReader::new(rtx)
+          .get_balances_page(&ScriptKey::from_address(address.clone()), page)?,
+      )
+    })
   }
 
   pub(crate) fn brc20s_tickid_transferable(
     &self,
     tick_id: &brc20s::TickId,
     address: &bitcoin::Address,
-  ) -> Result<Vec<brc20s::TransferableAsset>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
-
-    let result =
-      brc20s_db.get_transferable_by_tickid(&ScriptKey::from_address(address.clone()), tick_id)?;
-    Ok(result)
+    page: &PageRequest,
+  ) -> Result<(Page<brc20s::TransferableAsset>, Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| {
+      Ok(
+        brc20s_db::DataStoreReader::new(rtx).get_transferable_by_tickid_page(
+          &ScriptKey::from_address(address.clone()),
+          tick_id,
+          page,
+        )?,
+      )
+    })
   }
 
   pub(crate) fn brc20s_all_transferable(
     &self,
     address: &bitcoin::Address,
-  ) -> Result<Vec<brc20s::TransferableAsset>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
-    let info = brc20s_db.get_transferable(&ScriptKey::from_address(address.clone()))?;
-    Ok(info)
+    page: &PageRequest,
+  ) -> Result<(Page<brc20s::TransferableAsset>, Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| {
+      Ok(
+        brc20s_db::DataStoreReader::new(rtx)
+          .get_transferable_page(&ScriptKey::from_address(address.clone()), page)?,
+      )
+    })
   }
 
-  pub(crate) fn brc20s_txid_receipts(&self, txid: &Txid) -> Result<Option<Vec<brc20s::Receipt>>> {
-    let rtx = self.database.begin_read().unwrap();
-    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
-    let res = brc20s_db.get_txid_to_receipts(txid)?;
-
-    if res.is_empty() {
-      let tx = self.client.get_raw_transaction_info(txid, None)?;
-      if let Some(tx_blockhash) = tx.blockhash {
-        let tx_bh = self.client.get_block_header_info(&tx_blockhash)?;
-        let parsed_height = self.height()?;
-        if parsed_height.is_none() || tx_bh.height as u64 > parsed_height.unwrap().0 {
-          return Ok(None);
+  pub(crate) fn brc20s_txid_receipts(
+    &self,
+    txid: &Txid,
+  ) -> Result<(Option<Vec<brc20s::Receipt>>, Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| {
+      let res = brc20s_db::DataStoreReader::new(rtx).get_txid_to_receipts(txid)?;
+
+      if res.is_empty() {
+        let tx = self.client.get_raw_transaction_info(txid, None)?;
+        if let Some(tx_blockhash) = tx.blockhash {
+          let tx_bh = self.client.get_block_header_info(&tx_blockhash)?;
+          let parsed_height = rtx.block_height()?;
+          if parsed_height.is_none() || tx_bh.height as u64 > parsed_height.unwrap().0 {
+            return Ok(None);
+          }
+        } else {
+          return Err(anyhow!("can't get tx block hash: {txid}"));
         }
-      } else {
-        return Err(anyhow!("can't get tx block hash: {txid}"));
       }
-    }
 
-    Ok(Some(res))
+      Ok(Some(res))
+    })
   }
 
   pub(crate) fn brc20s_txs_receipts(
     &self,
-    txs: &Vec<Txid>,
-  ) -> Result<Vec<(bitcoin::Txid, Vec<brc20s::Receipt>)>> {
-    let rtx = self.database.begin_read()?;
-    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
-    let mut result = Vec::new();
-    for txid in txs {
-      let tx_events = brc20s_db.get_txid_to_receipts(txid)?;
-      if tx_events.is_empty() {
-        continue;
-      }
-      result.push((*txid, tx_events));
-    }
-    Ok(result)
+    txs: &[Txid],
+    page: &PageRequest,
+  ) -> Result<(
+    Page<(Txid, Vec<brc20s::Receipt>)>,
+    Option<(Height, BlockHash)>,
+  )> {
+    self.read_block_page(txs, page, |rtx, txid| {
+      Ok(brc20s_db::DataStoreReader::new(rtx).get_txid_to_receipts(txid)?)
+    })
+  }
+
+  /// Returns up to `limit` receipts of `history`, newest first.
+  pub(crate) fn brc20s_receipt_history(
+    &self,
+    history: &brc20s::ReceiptHistory,
+    limit: usize,
+  ) -> Result<Vec<(u64, Txid, brc20s::Receipt)>> {
+    Ok(brc20s_db::DataStoreReader::new(&self.begin_read()?.0).get_receipt_history(history, limit)?)
   }
 
   pub(crate) fn ord_txid_inscriptions(
     &self,
     txid: &Txid,
-  ) -> Result<Option<Vec<ord::InscriptionOp>>> {
-    let rtx = self.database.begin_read().unwrap();
-    let ord_db = ord::OrdDbReader::new(&rtx);
-    let res = ord_db.get_transaction_operations(txid)?;
-
-    if res.is_empty() {
-      let tx = self.client.get_raw_transaction_info(txid, None)?;
-      if let Some(tx_blockhash) = tx.blockhash {
-        let tx_bh = self.client.get_block_header_info(&tx_blockhash)?;
-        let parsed_height = self.height()?;
-        if parsed_height.is_none() || tx_bh.height as u64 > parsed_height.unwrap().0 {
-          return Ok(None);
+  ) -> Result<(Option<Vec<ord::InscriptionOp>>, Option<(Height, BlockHash)>)> {
+    self.read_at_height(|rtx| {
+      let res = ord::OrdDbReader::new(rtx).get_transaction_operations(txid)?;
+
+      if res.is_empty() {
+        let tx = self.client.get_raw_transaction_info(txid, None)?;
+        if let Some(tx_blockhash) = tx.blockhash {
+          let tx_bh = self.client.get_block_header_info(&tx_blockhash)?;
+          let parsed_height = rtx.block_height()?;
+          if parsed_height.is_none() || tx_bh.height as u64 > parsed_height.unwrap().0 {
+            return Ok(None);
+          }
+        } else {
+          return Err(anyhow!("can't get tx block hash: {txid}"));
         }
-      } else {
-        return Err(anyhow!("can't get tx block hash: {txid}"));
       }
-    }
 
-    Ok(Some(res))
+      Ok(Some(res))
+    })
   }
   pub(crate) fn ord_get_txs_inscriptions(
     &self,
-    txs: &Vec<Txid>,
-  ) -> Result<Vec<(bitcoin::Txid, Vec<ord::InscriptionOp>)>> {
-    let rtx = self.database.begin_read()?;
-    let ord_db = ord::OrdDbReader::new(&rtx);
-    let mut result = Vec::new();
-    for txid in txs {
-      let inscriptions = ord_db.get_transaction_operations(txid)?;
-      if inscriptions.is_empty() {
-        continue;
-      }
-      result.push((*txid, inscriptions));
-    }
-    Ok(result)
+    txs: &[Txid],
+    page: &PageRequest,
+  ) -> Result<(
+    Page<(Txid, Vec<ord::InscriptionOp>)>,
+    Option<(Height, BlockHash)>,
+  )> {
+    self.read_block_page(txs, page, |rtx, txid| {
+      Ok(ord::OrdDbReader::new(rtx).get_transaction_operations(txid)?)
+    })
   }
 }
 
@@ -1732,7 +2365,10 @@ impl Index {
 mod tests {
   use {
     super::*,
-    bitcoin::secp256k1::rand::{self, RngCore},
+    bitcoin::{
+      hashes::HashEngine,
+      secp256k1::rand::{self, RngCore},
+    },
     std::ffi::OsString,
     tempfile::TempDir,
   };
@@ -1856,6 +2492,211 @@ mod tests {
     }
   }
 
+  #[test]
+  fn snapshot_bootstraps_an_empty_index() {
+    let context = Context::builder().build();
+    context.mine_blocks(1);
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..Default::default()
+    });
+    context.mine_blocks(1);
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    let path = context.tempdir.path().join("snapshot.bin");
+
+    assert_eq!(
+      context
+        .index
+        .export_snapshot(Some(5), &path)
+        .unwrap_err()
+        .to_string(),
+      "index is at height 2, cannot export a snapshot at height 5, rerun indexing with `--height-limit 6`"
+    );
+
+    let exported = context.index.export_snapshot(Some(2), &path).unwrap();
+    assert_eq!(exported.header.height, 2);
+    assert_eq!(
+      Some(BlockHash::from_byte_array(exported.header.block_hash)),
+      context.index.block_hash(Some(2)).unwrap()
+    );
+    assert_eq!(
+      Some(sha256::Hash::from_byte_array(exported.header.state_hash)),
+      context.index.state_hash(2).unwrap()
+    );
+
+    assert_eq!(
+      context
+        .index
+        .import_snapshot(&path)
+        .unwrap_err()
+        .to_string(),
+      "index is already at height 2, snapshots can only be imported into an empty index"
+    );
+
+    let mut options = context.options.clone();
+    options.index = Some(context.tempdir.path().join("fresh.redb"));
+    let fresh = Index::open(&options).unwrap();
+
+    let imported = fresh.import_snapshot(&path).unwrap();
+    assert_eq!(imported, exported);
+    assert_eq!(fresh.block_height().unwrap(), Some(Height(2)));
+    assert!(fresh
+      .get_inscription_entry(inscription_id)
+      .unwrap()
+      .is_some());
+    assert_eq!(
+      undo::available_depth(&fresh.database.begin_read().unwrap(), 2).unwrap(),
+      0
+    );
+
+    context.mine_blocks(1);
+    fresh.update().unwrap();
+    assert_eq!(fresh.block_height().unwrap(), Some(Height(3)));
+    assert_eq!(
+      fresh.state_hash(3).unwrap(),
+      context.index.state_hash(3).unwrap()
+    );
+  }
+
+  #[test]
+  fn snapshot_exports_earlier_heights_by_unwinding_blocks() {
+    let context = Context::builder().build();
+    context.mine_blocks(1);
+    let first = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "first").to_witness())],
+      ..Default::default()
+    });
+    context.mine_blocks(1);
+    let second = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, inscription("text/plain", "second").to_witness())],
+      ..Default::default()
+    });
+    context.mine_blocks(1);
+    context.index.update().unwrap();
+
+    let path = context.tempdir.path().join("snapshot.bin");
+    let exported = context.index.export_snapshot(Some(2), &path).unwrap();
+    assert_eq!(exported.header.height, 2);
+    assert_eq!(
+      Some(BlockHash::from_byte_array(exported.header.block_hash)),
+      context.index.block_hash(Some(2)).unwrap()
+    );
+    assert_eq!(
+      Some(sha256::Hash::from_byte_array(exported.header.state_hash)),
+      context.index.state_hash(2).unwrap()
+    );
+    assert_eq!(context.index.block_height().unwrap(), Some(Height(3)));
+
+    let mut options = context.options.clone();
+    options.index = Some(context.tempdir.path().join("fresh.redb"));
+    let fresh = Index::open(&options).unwrap();
+    fresh.import_snapshot(&path).unwrap();
+    assert_eq!(fresh.block_height().unwrap(), Some(Height(2)));
+    let inscribed = |id| {
+      fresh
+        .get_inscription_entry(InscriptionId { txid: id, index: 0 })
+        .unwrap()
+        .is_some()
+    };
+    assert!(inscribed(first));
+    assert!(!inscribed(second));
+
+    fresh.update().unwrap();
+    assert!(inscribed(second));
+    assert_eq!(
+      fresh.state_hash(3).unwrap(),
+      context.index.state_hash(3).unwrap()
+    );
+
+    let wtx = context.index.database.begin_write().unwrap();
+    wtx
+      .open_table(HEIGHT_TO_UNDO_LOG)
+      .unwrap()
+      .remove(2)
+      .unwrap();
+    wtx.commit().unwrap();
+    assert_eq!(
+      context
+        .index
+        .export_snapshot(Some(1), &path)
+        .unwrap_err()
+        .to_string(),
+      "index is at height 3 and can only be unwound to height 2 with its undo logs, cannot export a snapshot at height 1"
+    );
+  }
+
+  #[test]
+  fn previews_do_not_leak_into_each_other_or_the_index() {
+    let context = Context::builder().build();
+    let coinbase = context.mine_blocks(1)[0].txdata[0].txid();
+
+    let unsigned = UnsignedTransaction {
+      transaction: Transaction {
+        version: 2,
+        lock_time: bitcoin::locktime::absolute::LockTime::ZERO,
+        input: vec![TxIn {
+          previous_output: OutPoint {
+            txid: coinbase,
+            vout: 0,
+          },
+          script_sig: ScriptBuf::new(),
+          sequence: Sequence::MAX,
+          witness: inscription("text/plain", "hello").to_witness(),
+        }],
+        output: vec![TxOut {
+          value: 50 * COIN_VALUE,
+          script_pubkey: ScriptBuf::new(),
+        }],
+      },
+      prevouts: BTreeMap::new(),
+    };
+
+    // Previews running at the same time each get a scratch database of their
+    // own, and later ones reuse the idle ones.
+    for _ in 0..2 {
+      thread::scope(|scope| {
+        let previews = (0..8)
+          .map(|_| scope.spawn(|| context.index.preview_transaction(&unsigned).unwrap()))
+          .collect::<Vec<_>>();
+        for preview in previews {
+          let preview = preview.join().unwrap();
+          assert_eq!(preview.inscriptions.len(), 1);
+          assert_eq!(
+            preview.inscriptions[0].operation.inscription_number,
+            Some(0)
+          );
+        }
+      });
+      assert!(!context.index.preview_databases.lock().unwrap().is_empty());
+    }
+
+    assert!(context
+      .index
+      .get_inscription_entry(InscriptionId {
+        txid: unsigned.transaction.txid(),
+        index: 0,
+      })
+      .unwrap()
+      .is_none());
+  }
+
+  #[test]
+  fn state_hash_chains_block_receipts() {
+    let context = Context::builder().build();
+    context.mine_blocks(2);
+
+    let hashes = (0..=2)
+      .map(|height| context.index.state_hash(height).unwrap().unwrap())
+      .collect::<Vec<_>>();
+    assert_ne!(hashes[1], hashes[2]);
+
+    let mut engine = sha256::Hash::engine();
+    engine.input(hashes[1].as_byte_array());
+    engine.input(sha256::Hash::hash(&[]).as_byte_array());
+    assert_eq!(sha256::Hash::from_engine(engine), hashes[2]);
+  }
+
   #[test]
   fn inscriptions_below_first_inscription_height_are_skipped() {
     let inscription = inscription("text/plain;charset=utf-8", "hello");
@@ -2894,6 +3735,33 @@ mod tests {
       format!("index at `{}{delimiter}regtest{delimiter}index.redb` appears to have been built with a newer, incompatible version of ord, consider updating ord: index schema {}, ord schema {SCHEMA_VERSION}", path.display(), u64::MAX));
   }
 
+  #[test]
+  fn new_protocol_schema_gives_correct_error() {
+    let tempdir = {
+      let context = Context::builder().build();
+
+      let wtx = context.index.database.begin_write().unwrap();
+
+      wtx
+        .open_table(schema::OKX_SCHEMA_VERSION)
+        .unwrap()
+        .insert(schema::Protocol::BRC20.name(), &u64::MAX)
+        .unwrap();
+
+      wtx.commit().unwrap();
+
+      context.tempdir
+    };
+
+    let path = tempdir.path().to_owned();
+
+    let delimiter = if cfg!(windows) { '\\' } else { '/' };
+
+    assert_eq!(
+      Context::builder().tempdir(tempdir).try_build().err().unwrap().to_string(),
+      format!("index at `{}{delimiter}regtest{delimiter}index.redb` appears to have been built with a newer, incompatible version of ord, consider updating ord: index brc20 schema {}, ord brc20 schema {}", path.display(), u64::MAX, schema::Protocol::BRC20.schema_version()));
+  }
+
   #[test]
   fn inscriptions_on_output() {
     for context in Context::configurations() {
@@ -3987,6 +4855,139 @@ mod tests {
     }
   }
 
+  #[test]
+  fn parallel_fetch_keeps_blocks_in_order() {
+    for args in [["--fetch-concurrency", "4"], ["--prefetch-depth", "0"]] {
+      let context = Context::builder().args(args).build();
+
+      let mut blocks = context.rpc_server.mine_blocks(1);
+
+      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+        ..Default::default()
+      });
+
+      blocks.extend(context.rpc_server.mine_blocks(25));
+
+      context.index.update().unwrap();
+
+      assert_eq!(context.index.block_count().unwrap(), 27);
+      for (height, block) in blocks.iter().enumerate() {
+        assert_eq!(
+          context
+            .index
+            .block_hash(Some(u64::try_from(height).unwrap() + 1))
+            .unwrap(),
+          Some(block.block_hash())
+        );
+      }
+      context.index.assert_inscription_location(
+        InscriptionId { txid, index: 0 },
+        SatPoint {
+          outpoint: OutPoint { txid, vout: 0 },
+          offset: 0,
+        },
+        None,
+      );
+    }
+  }
+
+  #[test]
+  fn blocks_are_batched_until_near_chain_tip() {
+    let context = Context::builder().args(["--commit-interval", "5"]).build();
+    let commits = context.index.statistic(Statistic::Commits);
+
+    context.rpc_server.mine_blocks(50);
+    context.index.update().unwrap();
+
+    // blocks 1 to 25 in batches of 5, blocks 26 to 29 when block 29 gets
+    // near the tip and then blocks 30 to 50 one by one
+    assert_eq!(context.index.statistic(Statistic::Commits) - commits, 27);
+  }
+
+  #[test]
+  fn commit_when_range_cache_is_full() {
+    let context = Context::builder()
+      .args(["--index-sats", "--commit-cache-entries", "10"])
+      .build();
+    let commits = context.index.statistic(Statistic::Commits);
+
+    context.rpc_server.mine_blocks(50);
+    context.index.update().unwrap();
+
+    // every block adds its coinbase output to the range cache, so blocks 1 to
+    // 20 are committed in batches of 10 before block 29 gets near the tip
+    assert_eq!(context.index.statistic(Statistic::Commits) - commits, 24);
+  }
+
+  #[test]
+  fn index_from_block_files() {
+    for args in [vec![], vec!["--index-sats"]] {
+      let tempdir = TempDir::new().unwrap();
+      let context = Context::builder()
+        .arg("--read-block-files")
+        .arg("--bitcoin-data-dir")
+        .arg(tempdir.path())
+        .args(args)
+        .tempdir(tempdir)
+        .build();
+
+      let mut blocks = vec![bitcoin::blockdata::constants::genesis_block(
+        Network::Regtest,
+      )];
+      blocks.extend(context.rpc_server.mine_blocks(1));
+
+      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+        ..Default::default()
+      });
+      let inscription_id = InscriptionId { txid, index: 0 };
+
+      blocks.extend(context.rpc_server.mine_blocks(30));
+
+      // the block files hold the chain in a different order than it was mined
+      let (first, second) = blocks.split_at(16);
+      block_files::tests::write_block_files(
+        &context.tempdir.path().join("regtest/blocks"),
+        Some([1, 2, 3, 4, 5, 6, 7, 8]),
+        &[second, first],
+      );
+
+      context.index.update().unwrap();
+
+      assert_eq!(context.index.block_count().unwrap(), 32);
+      context.index.assert_inscription_location(
+        inscription_id,
+        SatPoint {
+          outpoint: OutPoint { txid, vout: 0 },
+          offset: 0,
+        },
+        Some(50 * COIN_VALUE),
+      );
+    }
+  }
+
+  #[test]
+  fn index_from_missing_block_files_is_an_error() {
+    let tempdir = TempDir::new().unwrap();
+    let context = Context::builder()
+      .arg("--read-block-files")
+      .arg("--bitcoin-data-dir")
+      .arg(tempdir.path())
+      .tempdir(tempdir)
+      .build();
+
+    context.rpc_server.mine_blocks(30);
+
+    assert_eq!(
+      context.index.update().unwrap_err().to_string(),
+      format!(
+        "failed to read block files in {}",
+        context.tempdir.path().join("regtest/blocks").display()
+      )
+    );
+  }
+
   #[test]
   fn recover_from_reorg() {
     for mut context in Context::configurations() {
@@ -4171,6 +5172,676 @@ mod tests {
     }
   }
 
+  #[test]
+  fn recover_from_deep_reorg_with_undo_logs() {
+    for context in Context::configurations() {
+      context.mine_blocks(1);
+
+      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(
+          1,
+          0,
+          0,
+          inscription("text/plain;charset=utf-8", "hello").to_witness(),
+        )],
+        ..Default::default()
+      });
+      let first_id = InscriptionId { txid, index: 0 };
+      let first_location = SatPoint {
+        outpoint: OutPoint { txid, vout: 0 },
+        offset: 0,
+      };
+
+      context.mine_blocks(1);
+
+      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(
+          2,
+          0,
+          0,
+          inscription("text/plain;charset=utf-8", "hello").to_witness(),
+        )],
+        ..Default::default()
+      });
+      let second_id = InscriptionId { txid, index: 0 };
+      let second_location = SatPoint {
+        outpoint: OutPoint { txid, vout: 0 },
+        offset: 0,
+      };
+
+      context.mine_blocks(25);
+
+      context
+        .index
+        .assert_inscription_location(second_id, second_location, Some(100 * COIN_VALUE));
+
+      for _ in 0..25 {
+        context.rpc_server.invalidate_tip();
+      }
+
+      context.mine_blocks(27);
+
+      context.index.assert_non_existence_of_inscription(second_id);
+
+      context
+        .index
+        .assert_inscription_location(first_id, first_location, Some(50 * COIN_VALUE));
+
+      assert_eq!(context.index.block_count().unwrap(), 30);
+    }
+  }
+
+  #[test]
+  fn read_only_index_is_not_updated() {
+    let Context {
+      options,
+      rpc_server,
+      tempdir: _tempdir,
+      index,
+    } = Context::builder().build();
+
+    rpc_server.mine_blocks(2);
+    index.update().unwrap();
+    drop(index);
+
+    let index = Index::open_read_only(&options).unwrap();
+    assert_eq!(index.block_count().unwrap(), 3);
+
+    rpc_server.mine_blocks(1);
+    assert_eq!(
+      index.update().unwrap_err().to_string(),
+      format!("index at `{}` is open read-only", index.path.display())
+    );
+    assert_eq!(index.block_count().unwrap(), 3);
+  }
+
+  #[test]
+  fn inscription_owners_are_backfilled_on_upgrade() {
+    let Context {
+      options,
+      rpc_server,
+      tempdir: _tempdir,
+      index,
+    } = Context::builder().build();
+
+    rpc_server.mine_blocks(2);
+    rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..Default::default()
+    });
+    rpc_server.mine_blocks(1);
+    rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, inscription("image/png", [1; 100]).to_witness())],
+      ..Default::default()
+    });
+    rpc_server.mine_blocks(1);
+    rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(3, 1, 0, Default::default())],
+      outputs: 2,
+      ..Default::default()
+    });
+    rpc_server.mine_blocks(1);
+    index.update().unwrap();
+
+    let dump = |index: &Index| {
+      let rtx = index.database.begin_read().unwrap();
+      let content_types = rtx
+        .open_table(INSCRIPTION_ID_TO_CONTENT_TYPE)
+        .unwrap()
+        .iter()
+        .unwrap()
+        .map(|result| {
+          let (id, content_type) = result.unwrap();
+          (*id.value(), content_type.value().to_string())
+        })
+        .collect::<Vec<_>>();
+      let owners = rtx
+        .open_table(INSCRIPTION_ID_TO_SCRIPT_HASH)
+        .unwrap()
+        .iter()
+        .unwrap()
+        .map(|result| {
+          let (id, script_hash) = result.unwrap();
+          (*id.value(), *script_hash.value())
+        })
+        .collect::<Vec<_>>();
+      let owned = rtx
+        .open_multimap_table(SCRIPT_HASH_TO_INSCRIPTION_ID)
+        .unwrap()
+        .iter()
+        .unwrap()
+        .flat_map(|result| {
+          let (script_hash, ids) = result.unwrap();
+          ids
+            .map(|id| (*script_hash.value(), *id.unwrap().value()))
+            .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+      (content_types, owners, owned)
+    };
+
+    let expected = dump(&index);
+    assert_eq!(expected.0.len(), 2);
+    assert_eq!(expected.1.len(), 2);
+    assert_eq!(expected.2.len(), 2);
+
+    let wtx = index.database.begin_write().unwrap();
+    wtx.delete_table(INSCRIPTION_ID_TO_CONTENT_TYPE).unwrap();
+    wtx.delete_table(INSCRIPTION_ID_TO_SCRIPT_HASH).unwrap();
+    wtx
+      .delete_multimap_table(SCRIPT_HASH_TO_INSCRIPTION_ID)
+      .unwrap();
+    wtx
+      .open_table(schema::OKX_SCHEMA_VERSION)
+      .unwrap()
+      .insert(schema::Protocol::Ord.name(), 1)
+      .unwrap();
+    wtx.commit().unwrap();
+
+    // A backfill resumed after the first inscription fills in only the second.
+    let wtx = index.database.begin_write().unwrap();
+    let rtx = index.database.begin_read().unwrap();
+    let progress = [expected.0[0].0.as_slice(), &1u64.to_be_bytes()].concat();
+    assert_eq!(
+      owners::backfill(&wtx, &rtx, &index.client, Some(&progress)).unwrap(),
+      Some([expected.0[1].0.as_slice(), &2u64.to_be_bytes()].concat())
+    );
+    wtx.commit().unwrap();
+    drop(rtx);
+    assert_eq!(dump(&index).0, expected.0[1..]);
+    drop(index);
+
+    let index = Index::open(&options).unwrap();
+    assert_eq!(dump(&index), expected);
+  }
+
+  #[test]
+  fn receipt_histories_are_backfilled_on_upgrade() {
+    let Context {
+      options,
+      rpc_server,
+      tempdir: _tempdir,
+      index,
+    } = Context::builder().arg("--enable-index-brc20").build();
+
+    rpc_server.mine_blocks(1);
+    rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        inscription(
+          "text/plain;charset=utf-8",
+          r#"{"p":"brc-20","op":"deploy","tick":"ordi","max":"21000000","lim":"1000"}"#,
+        )
+        .to_witness(),
+      )],
+      ..Default::default()
+    });
+    rpc_server.mine_blocks(1);
+    rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        2,
+        0,
+        0,
+        inscription(
+          "text/plain;charset=utf-8",
+          r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"1000"}"#,
+        )
+        .to_witness(),
+      )],
+      ..Default::default()
+    });
+    rpc_server.mine_blocks(1);
+    index.update().unwrap();
+
+    let tick = brc20::Tick::from_str("ORDI").unwrap();
+    let dump = |index: &Index| {
+      (
+        index
+          .brc20_receipt_history(brc20::ReceiptHistory::Tick(&tick), 10, 10, |_| true)
+          .unwrap(),
+        index
+          .brc20_receipt_history(brc20::ReceiptHistory::Deploys, 10, 10, |_| true)
+          .unwrap(),
+      )
+    };
+
+    let expected = dump(&index);
+    assert_eq!(
+      expected
+        .0
+        .iter()
+        .map(|(height, _, _)| *height)
+        .collect::<Vec<_>>(),
+      [3, 2]
+    );
+    assert_eq!(expected.1.len(), 1);
+
+    let wtx = index.database.begin_write().unwrap();
+    for table in [
+      "BRC20_TICK_RECEIPTS",
+      "BRC20_TRANSFER_RECEIPTS",
+      "BRC20_DEPLOY_RECEIPTS",
+    ] {
+      wtx
+        .delete_table(TableDefinition::<&[u8], &[u8]>::new(table))
+        .unwrap();
+    }
+    {
+      let mut versions = wtx.open_table(schema::OKX_SCHEMA_VERSION).unwrap();
+      for protocol in [schema::Protocol::BRC20, schema::Protocol::BRC20S] {
+        versions.insert(protocol.name(), 2).unwrap();
+      }
+    }
+    wtx.commit().unwrap();
+    drop(index);
+
+    let index = Index::open(&options).unwrap();
+    assert_eq!(dump(&index), expected);
+
+    let rtx = index.database.begin_read().unwrap();
+    for protocol in [schema::Protocol::BRC20, schema::Protocol::BRC20S] {
+      assert_eq!(
+        schema::get_schema_version(&rtx, protocol).unwrap(),
+        Some(protocol.schema_version())
+      );
+    }
+  }
+
+  #[test]
+  fn children_are_paged_by_number_and_backfilled_on_upgrade() {
+    let Context {
+      options,
+      rpc_server,
+      tempdir: _tempdir,
+      index,
+    } = Context::builder().build();
+
+    rpc_server.mine_blocks(1);
+    let txid = rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "parent").to_witness())],
+      ..Default::default()
+    });
+    rpc_server.mine_blocks(1);
+    let parent = InscriptionId { txid, index: 0 };
+
+    let mut children = Vec::new();
+    for height in 2..5 {
+      let txid = rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[
+          (
+            height,
+            0,
+            0,
+            Inscription {
+              content_type: Some("text/plain".into()),
+              body: Some("child".into()),
+              parent: Some(parent.parent_value()),
+              unrecognized_even_field: false,
+            }
+            .to_witness(),
+          ),
+          (height, 1, 0, Default::default()),
+        ],
+        ..Default::default()
+      });
+      rpc_server.mine_blocks(1);
+      children.push((
+        InscriptionId { txid, index: 0 },
+        i64::try_from(height).unwrap() - 1,
+      ));
+    }
+    index.update().unwrap();
+
+    let walk = |index: &Index| {
+      let mut pages = Vec::new();
+      let mut request = PageRequest {
+        limit: Some(2),
+        ..Default::default()
+      };
+      loop {
+        let start = children::start(&request).unwrap();
+        let page = index
+          .ord_children(parent, start, &request)
+          .unwrap()
+          .0
+          .unwrap();
+        pages.push(page.items);
+        match page.next {
+          Some(next) => request.cursor = Some(next.to_string().parse().unwrap()),
+          None => break,
+        }
+      }
+      pages
+    };
+
+    let expected = vec![children[..2].to_vec(), children[2..].to_vec()];
+    assert_eq!(walk(&index), expected);
+
+    let wtx = index.database.begin_write().unwrap();
+    wtx
+      .delete_table(INSCRIPTION_ID_AND_CHILD_NUMBER_TO_CHILD_ID)
+      .unwrap();
+    wtx
+      .open_table(schema::OKX_SCHEMA_VERSION)
+      .unwrap()
+      .insert(schema::Protocol::Ord.name(), 2)
+      .unwrap();
+    wtx.commit().unwrap();
+    drop(index);
+
+    let index = Index::open(&options).unwrap();
+    assert_eq!(walk(&index), expected);
+  }
+
+  #[test]
+  fn read_only_index_must_exist() {
+    let context = Context::builder().build();
+    let options = Options {
+      index: Some(context.tempdir.path().join("missing.redb")),
+      ..context.options.clone()
+    };
+
+    assert!(Index::open_read_only(&options)
+      .err()
+      .unwrap()
+      .to_string()
+      .starts_with("failed to open index at"));
+    assert!(!options.index.unwrap().exists());
+  }
+
+  #[test]
+  fn batch_accounts_read_every_address() {
+    use crate::okx::datastore::{brc20::DataStoreReadWrite as _, brc20s::DataStoreReadWrite as _};
+
+    let context = Context::builder().build();
+    context.mine_blocks(1);
+
+    let tick = brc20::Tick::from_str("abcd").unwrap();
+    let other = brc20::Tick::from_str("efgh").unwrap();
+    let pids = ["a012345679#01", "a012345679#02"].map(|pid| brc20s::Pid::from_str(pid).unwrap());
+
+    {
+      let wtx = context.index.begin_write().unwrap();
+      let brc20_store = brc20_db::DataStore::new(&wtx);
+      let brc20s_store = brc20s_db::DataStore::new(&wtx);
+      let script = ScriptKey::from_address(address());
+      for tick in [&tick, &other] {
+        brc20_store
+          .update_token_balance(
+            &script,
+            brc20::Balance {
+              tick: tick.clone(),
+              overall_balance: 10,
+              transferable_balance: 0,
+            },
+          )
+          .unwrap();
+      }
+      for pid in &pids {
+        brc20s_store
+          .set_pid_to_use_info(&script, pid, &brc20s::UserInfo::default(pid))
+          .unwrap();
+      }
+      wtx.commit().unwrap();
+    }
+
+    let addresses = [address(), recipient()];
+
+    let (accounts, height) = context
+      .index
+      .brc20_batch_accounts(&addresses, None)
+      .unwrap();
+    assert_eq!(height.map(|(height, _)| height), Some(Height(1)));
+    assert_eq!(accounts.len(), 2);
+    assert_eq!(accounts[0].balances.len(), 2);
+    assert!(accounts[1].balances.is_empty());
+
+    let (accounts, _) = context
+      .index
+      .brc20_batch_accounts(&addresses, Some(std::slice::from_ref(&other)))
+      .unwrap();
+    assert_eq!(accounts[0].balances.len(), 1);
+    assert_eq!(accounts[0].balances[0].tick, other);
+
+    let (accounts, _) = context
+      .index
+      .brc20s_batch_accounts(&addresses, None, None)
+      .unwrap();
+    assert_eq!(
+      accounts[0]
+        .positions
+        .iter()
+        .map(|position| position.pid.clone())
+        .collect::<Vec<_>>(),
+      pids.to_vec()
+    );
+    assert!(accounts[1].positions.is_empty());
+
+    let (accounts, _) = context
+      .index
+      .brc20s_batch_accounts(&addresses, None, Some(&pids[1..]))
+      .unwrap();
+    assert_eq!(accounts[0].positions.len(), 1);
+    assert_eq!(accounts[0].positions[0].pid, pids[1]);
+  }
+
+  #[test]
+  fn holder_balances_read_owners_from_balances() {
+    use crate::okx::datastore::brc20::DataStoreReadWrite as _;
+
+    let context = Context::builder().build();
+    context.mine_blocks(1);
+
+    let tick = brc20::Tick::from_str("abcd").unwrap();
+
+    {
+      let wtx = context.index.begin_write().unwrap();
+      let brc20_store = brc20_db::DataStore::new(&wtx);
+      brc20_store
+        .insert_token_info(
+          &tick,
+          &brc20::TokenInfo {
+            tick: brc20::Tick::from_str("AbCd").unwrap(),
+            inscription_id: inscription_id(1),
+            inscription_number: 0,
+            supply: 100,
+            minted: 30,
+            limit_per_mint: 10,
+            decimal: 18,
+            deploy_by: ScriptKey::from_address(address()),
+            deployed_number: 1,
+            deployed_timestamp: 0,
+            latest_mint_number: 1,
+          },
+        )
+        .unwrap();
+      for (holder, overall) in [(address(), 20), (recipient(), 10), (change(0), 0)] {
+        brc20_store
+          .update_token_balance(
+            &ScriptKey::from_address(holder),
+            brc20::Balance {
+              tick: tick.clone(),
+              overall_balance: overall,
+              transferable_balance: 0,
+            },
+          )
+          .unwrap();
+      }
+      wtx.commit().unwrap();
+    }
+
+    let mut rows = Vec::new();
+    let height = context
+      .index
+      .brc20_holder_balances(|row| {
+        rows.push(row);
+        Ok(())
+      })
+      .unwrap();
+
+    assert_eq!(height, Some(Height(1)));
+    rows.sort_by(|a, b| a.overall.cmp(&b.overall));
+    assert_eq!(
+      rows,
+      [
+        BRC20BalanceRow {
+          address: recipient().to_string(),
+          tick: "AbCd".into(),
+          overall: "10".into(),
+          transferable: "0".into(),
+          decimals: 18,
+        },
+        BRC20BalanceRow {
+          address: address().to_string(),
+          tick: "AbCd".into(),
+          overall: "20".into(),
+          transferable: "0".into(),
+          decimals: 18,
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn block_inscriptions_are_paged_by_transaction() {
+    let context = Context::builder().arg("--enable-save-ord-receipts").build();
+    context.mine_blocks(2);
+
+    let txids = [1, 2].map(|height| {
+      context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(
+          height,
+          0,
+          0,
+          inscription("text/plain;charset=utf-8", "hello").to_witness(),
+        )],
+        ..Default::default()
+      })
+    });
+
+    let block = &context.mine_blocks(1)[0];
+    let txs = block.txdata.iter().map(|tx| tx.txid()).collect::<Vec<_>>();
+
+    let mut request = PageRequest {
+      limit: Some(1),
+      ..Default::default()
+    };
+
+    let (first, height) = context
+      .index
+      .ord_get_txs_inscriptions(&txs, &request)
+      .unwrap();
+    assert_eq!(height.map(|(height, _)| height), Some(Height(3)));
+    assert_eq!(first.items.len(), 1);
+    assert!(first.next.is_some());
+
+    request.cursor = Some(first.next.unwrap().to_string().parse().unwrap());
+
+    let (second, _) = context
+      .index
+      .ord_get_txs_inscriptions(&txs, &request)
+      .unwrap();
+    assert_eq!(second.items.len(), 1);
+    assert_eq!(second.next, None);
+
+    let mut paged = vec![first.items[0].0, second.items[0].0];
+    paged.sort();
+    let mut expected = txids.to_vec();
+    expected.sort();
+    assert_eq!(paged, expected);
+  }
+
+  #[test]
+  fn reorg_history_records_orphaned_blocks() {
+    for context in [
+      Context::builder().arg("--enable-save-ord-receipts").build(),
+      Context::builder()
+        .args(["--enable-save-ord-receipts", "--index-sats"])
+        .build(),
+    ] {
+      context.mine_blocks(1);
+
+      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(
+          1,
+          0,
+          0,
+          inscription("text/plain;charset=utf-8", "hello").to_witness(),
+        )],
+        ..Default::default()
+      });
+
+      context.mine_blocks(1);
+
+      assert_eq!(context.index.reorg_history(0, None).unwrap().0, (vec![], 0));
+
+      let inscriptions = context
+        .index
+        .ord_txid_inscriptions(&txid)
+        .unwrap()
+        .0
+        .unwrap();
+      assert_eq!(inscriptions.len(), 1);
+
+      let old_hash = context.rpc_server.invalidate_tip();
+      context.mine_blocks(2);
+
+      let ((history, total), _) = context.index.reorg_history(0, None).unwrap();
+      assert_eq!(total, 1);
+      assert_eq!(history[0].height, 3);
+      assert_eq!(history[0].depth, 2);
+      assert_eq!(
+        history[0].orphaned_blocks,
+        vec![OrphanedBlock {
+          height: 2,
+          old_hash,
+          new_hash: context.index.block_hash(Some(2)).unwrap(),
+          transactions: Some(vec![OrphanedTransaction {
+            txid,
+            inscriptions,
+            brc20_receipts: Vec::new(),
+            brc20s_receipts: Vec::new(),
+          }]),
+          brc20s_ticks: Vec::new(),
+        }]
+      );
+      assert_eq!(
+        history[0].orphaned_blocks[0].transactions.as_ref().unwrap()[0].protocols(),
+        vec!["ord".to_string()]
+      );
+    }
+  }
+
+  #[test]
+  fn reorg_history_survives_savepoint_restore() {
+    let mut context = Context::builder().args(["--max-reorg-depth", "0"]).build();
+    context.index.set_durability(redb::Durability::Immediate);
+
+    context.mine_blocks(12);
+
+    for _ in 0..2 {
+      context.rpc_server.invalidate_tip();
+      context.mine_blocks(2);
+    }
+
+    let ((history, total), _) = context.index.reorg_history(0, None).unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(
+      history
+        .iter()
+        .map(|entry| (entry.height, entry.depth))
+        .collect::<Vec<(u64, u64)>>(),
+      [(14, 2), (13, 2)]
+    );
+
+    let ((history, total), _) = context.index.reorg_history(1, Some(1)).unwrap();
+    assert_eq!(total, 2);
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].height, 13);
+  }
+
   #[test]
   fn inscription_without_parent_tag_has_no_parent_entry() {
     for context in Context::configurations() {
@@ -0,0 +1,132 @@
+use {super::*, redb::ReadTransaction};
+
+/// The BRC20 state of one address.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BRC20Account {
+  pub(crate) balances: Vec<brc20::Balance>,
+  pub(crate) transferable: Vec<brc20::TransferableLog>,
+}
+
+/// The BRC20S state of one address, with the tick names and inscription
+/// numbers needed to present it resolved from the same transaction.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BRC20SAccount {
+  pub(crate) balances: Vec<(brc20s::Balance, brc20s::Tick)>,
+  pub(crate) transferable: Vec<(brc20s::TransferableAsset, brc20s::Tick, i64)>,
+  pub(crate) positions: Vec<brc20s::UserInfo>,
+}
+
+/// Reads the BRC20 balances and transferable inscriptions of every script,
+/// restricted to `ticks` if given.
+pub(super) fn brc20_accounts(
+  rtx: &ReadTransaction,
+  scripts: &[ScriptKey],
+  ticks: Option<&[brc20::Tick]>,
+) -> Result<Vec<BRC20Account>> {
+  let brc20_db = brc20_db::DataStoreReader::new(rtx);
+
+  let mut accounts = Vec::with_capacity(scripts.len());
+  for script in scripts {
+    let account = match ticks {
+      Some(ticks) => {
+        let mut account = BRC20Account::default();
+        for tick in ticks {
+          account.balances.extend(brc20_db.get_balance(script, tick)?);
+          account
+            .transferable
+            .extend(brc20_db.get_transferable_by_tick(script, tick)?);
+        }
+        account
+      }
+      None => BRC20Account {
+        balances: brc20_db.get_balances(script)?,
+        transferable: brc20_db.get_transferable(script)?,
+      },
+    };
+    accounts.push(account);
+  }
+
+  Ok(accounts)
+}
+
+/// Reads the BRC20S balances, transferable assets and pool positions of every
+/// script, restricted to `tick_ids` and `pids` if given.
+pub(super) fn brc20s_accounts(
+  rtx: &ReadTransaction,
+  scripts: &[ScriptKey],
+  tick_ids: Option<&[brc20s::TickId]>,
+  pids: Option<&[brc20s::Pid]>,
+) -> Result<Vec<BRC20SAccount>> {
+  let brc20s_db = brc20s_db::DataStoreReader::new(rtx);
+  let inscription_entries = rtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+
+  let mut names = HashMap::<String, brc20s::Tick>::new();
+  let mut tick_name = |tick_id: &brc20s::TickId| -> Result<brc20s::Tick> {
+    if let Some(name) = names.get(&tick_id.hex()) {
+      return Ok(name.clone());
+    }
+    let name = brc20s_db
+      .get_tick_info(tick_id)?
+      .ok_or_else(|| anyhow!("tick info not found for {}", tick_id.hex()))?
+      .name;
+    names.insert(tick_id.hex(), name.clone());
+    Ok(name)
+  };
+
+  let mut accounts = Vec::with_capacity(scripts.len());
+  for script in scripts {
+    let (balances, transferable) = match tick_ids {
+      Some(tick_ids) => {
+        let mut balances = Vec::new();
+        let mut transferable = Vec::new();
+        for tick_id in tick_ids {
+          balances.extend(brc20s_db.get_balance(script, tick_id)?);
+          transferable.extend(brc20s_db.get_transferable_by_tickid(script, tick_id)?);
+        }
+        (balances, transferable)
+      }
+      None => (
+        brc20s_db
+          .get_balances(script)?
+          .into_iter()
+          .map(|(_, balance)| balance)
+          .collect(),
+        brc20s_db.get_transferable(script)?,
+      ),
+    };
+
+    let positions = match pids {
+      Some(pids) => {
+        let mut positions = Vec::new();
+        for pid in pids {
+          positions.extend(brc20s_db.get_pid_to_use_info(script, pid)?);
+        }
+        positions
+      }
+      None => brc20s_db.get_user_infos(script)?,
+    };
+
+    let mut account = BRC20SAccount {
+      positions,
+      ..Default::default()
+    };
+
+    for balance in balances {
+      let name = tick_name(&balance.tick_id)?;
+      account.balances.push((balance, name));
+    }
+
+    for asset in transferable {
+      let name = tick_name(&asset.tick_id)?;
+      let number = inscription_entries
+        .get(&asset.inscription_id.store())?
+        .map(|value| InscriptionEntry::load(value.value()).number)
+        .ok_or_else(|| anyhow!("inscription entry not found for {}", asset.inscription_id))?;
+      account.transferable.push((asset, name, number));
+    }
+
+    accounts.push(account);
+  }
+
+  Ok(accounts)
+}
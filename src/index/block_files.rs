@@ -0,0 +1,359 @@
+use {
+  super::*,
+  std::io::{BufReader, Seek, SeekFrom},
+};
+
+/// Blocks within this distance of the chain tip are always fetched over RPC,
+/// since they may still be reorged away.
+pub(crate) const RPC_TIP_DISTANCE: u64 = 21;
+
+const RECORD_HEADER_SIZE: u64 = 8;
+const BLOCK_HEADER_SIZE: usize = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Location {
+  file: u32,
+  offset: u64,
+  size: u32,
+}
+
+/// BlockFiles reads the blocks of the active chain straight from the `blk*.dat`
+/// files in bitcoind's `blocks` directory.
+///
+/// bitcoind writes blocks in the order they arrive, so the files are scanned once
+/// for block headers and the active chain is rebuilt by following previous block
+/// hashes back from a tip obtained over RPC.
+pub(crate) struct BlockFiles {
+  dir: PathBuf,
+  xor_key: [u8; 8],
+  chain: Vec<Location>,
+}
+
+impl BlockFiles {
+  pub(crate) fn open(dir: &Path, network: Network, tip: BlockHash) -> Result<Self> {
+    let xor_key = match fs::read(dir.join("xor.dat")) {
+      Ok(key) => key
+        .try_into()
+        .map_err(|key: Vec<u8>| anyhow!("unexpected xor key length {}", key.len()))?,
+      Err(err) if err.kind() == io::ErrorKind::NotFound => [0; 8],
+      Err(err) => return Err(err.into()),
+    };
+
+    let mut block_files = Self {
+      dir: dir.into(),
+      xor_key,
+      chain: Vec::new(),
+    };
+
+    let mut blocks = HashMap::new();
+    for file in 0.. {
+      let path = block_files.path(file);
+      if !path.exists() {
+        break;
+      }
+      block_files.scan(file, network, &mut blocks)?;
+    }
+
+    let mut hash = tip;
+    loop {
+      let (prev_blockhash, location) = blocks
+        .get(&hash)
+        .ok_or_else(|| anyhow!("block {hash} not found in {}", dir.display()))?;
+      block_files.chain.push(*location);
+      if *prev_blockhash == BlockHash::all_zeros() {
+        break;
+      }
+      hash = *prev_blockhash;
+    }
+    block_files.chain.reverse();
+
+    Ok(block_files)
+  }
+
+  /// Number of blocks of the active chain, starting at genesis, that can be read from disk.
+  pub(crate) fn block_count(&self) -> u64 {
+    self.chain.len().try_into().unwrap()
+  }
+
+  pub(crate) fn get_block(&self, height: u64, header_only: bool) -> Result<Option<Block>> {
+    let Some(location) = usize::try_from(height)
+      .ok()
+      .and_then(|height| self.chain.get(height).copied())
+    else {
+      return Ok(None);
+    };
+
+    if header_only {
+      let header = self.read(location.file, location.offset, BLOCK_HEADER_SIZE)?;
+      return Ok(Some(Block {
+        header: consensus::deserialize(&header)?,
+        txdata: Vec::new(),
+      }));
+    }
+
+    let block = self.read(
+      location.file,
+      location.offset,
+      location.size.try_into().unwrap(),
+    )?;
+
+    Ok(Some(consensus::deserialize(&block)?))
+  }
+
+  fn path(&self, file: u32) -> PathBuf {
+    self.dir.join(format!("blk{file:05}.dat"))
+  }
+
+  fn deobfuscate(&self, buffer: &mut [u8], offset: u64) {
+    for (i, byte) in buffer.iter_mut().enumerate() {
+      *byte ^= self.xor_key[usize::try_from((offset + i as u64) % 8).unwrap()];
+    }
+  }
+
+  /// Records the location and previous block hash of every block in `file`.
+  fn scan(
+    &self,
+    file: u32,
+    network: Network,
+    blocks: &mut HashMap<BlockHash, (BlockHash, Location)>,
+  ) -> Result {
+    let path = self.path(file);
+    let mut reader = BufReader::new(File::open(&path)?);
+    let magic = network.magic().to_bytes();
+    let mut offset = 0;
+
+    loop {
+      let mut record = [0; 8];
+      if !read_exact_or_eof(&mut reader, &mut record)? {
+        break;
+      }
+      self.deobfuscate(&mut record, offset);
+
+      // bitcoind preallocates block files with zeros
+      if record[..4] == [0; 4] {
+        break;
+      }
+
+      if record[..4] != magic {
+        bail!(
+          "unexpected network magic {} at offset {offset} of {}",
+          hex::encode(&record[..4]),
+          path.display()
+        );
+      }
+
+      let size = u32::from_le_bytes(record[4..].try_into().unwrap());
+
+      let mut header = [0; BLOCK_HEADER_SIZE];
+      if !read_exact_or_eof(&mut reader, &mut header)? {
+        break;
+      }
+      self.deobfuscate(&mut header, offset + RECORD_HEADER_SIZE);
+      let header = Header::consensus_decode(&mut header.as_slice())?;
+
+      blocks.insert(
+        header.block_hash(),
+        (
+          header.prev_blockhash,
+          Location {
+            file,
+            offset: offset + RECORD_HEADER_SIZE,
+            size,
+          },
+        ),
+      );
+
+      offset += RECORD_HEADER_SIZE + u64::from(size);
+      reader.seek(SeekFrom::Start(offset))?;
+    }
+
+    Ok(())
+  }
+
+  fn read(&self, file: u32, offset: u64, len: usize) -> Result<Vec<u8>> {
+    let mut handle = File::open(self.path(file))?;
+    handle.seek(SeekFrom::Start(offset))?;
+    let mut buffer = vec![0; len];
+    handle.read_exact(&mut buffer)?;
+    self.deobfuscate(&mut buffer, offset);
+
+    Ok(buffer)
+  }
+}
+
+/// Fills `buffer`, returning false if the reader was already at its end or
+/// ends before `buffer` is full, as when bitcoind is still writing a block.
+fn read_exact_or_eof(reader: &mut impl Read, buffer: &mut [u8]) -> Result<bool> {
+  match reader.read_exact(buffer) {
+    Ok(()) => Ok(true),
+    Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+    Err(err) => Err(err.into()),
+  }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use {
+    super::*, bitcoin::blockdata::constants::genesis_block, tempfile::TempDir,
+    test_bitcoincore_rpc::TransactionTemplate,
+  };
+
+  const XOR_KEY: [u8; 8] = [0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0];
+
+  /// Writes `files` as bitcoind would, each holding the given blocks in order.
+  pub(crate) fn write_block_files(dir: &Path, xor_key: Option<[u8; 8]>, files: &[&[Block]]) {
+    fs::create_dir_all(dir).unwrap();
+
+    if let Some(xor_key) = xor_key {
+      fs::write(dir.join("xor.dat"), xor_key).unwrap();
+    }
+
+    for (i, blocks) in files.iter().enumerate() {
+      let mut data = Vec::new();
+      for block in blocks.iter() {
+        let block = consensus::serialize(block);
+        data.extend_from_slice(&Network::Regtest.magic().to_bytes());
+        data.extend_from_slice(&u32::try_from(block.len()).unwrap().to_le_bytes());
+        data.extend_from_slice(&block);
+      }
+
+      // preallocated space that has not been written yet
+      data.extend_from_slice(&[0; 64]);
+
+      if let Some(xor_key) = xor_key {
+        for (i, byte) in data.iter_mut().enumerate() {
+          *byte ^= xor_key[i % 8];
+        }
+      }
+
+      fs::write(dir.join(format!("blk{i:05}.dat")), data).unwrap();
+    }
+  }
+
+  fn mine_chain(n: u64) -> (test_bitcoincore_rpc::Handle, Vec<Block>) {
+    let rpc_server = test_bitcoincore_rpc::builder()
+      .network(Network::Regtest)
+      .build();
+
+    let mut blocks = vec![genesis_block(Network::Regtest)];
+    blocks.extend(rpc_server.mine_blocks(1));
+    rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, Default::default())],
+      ..Default::default()
+    });
+    blocks.extend(rpc_server.mine_blocks(n - 1));
+
+    (rpc_server, blocks)
+  }
+
+  #[test]
+  fn reads_active_chain_from_out_of_order_files() {
+    let (_rpc_server, blocks) = mine_chain(5);
+    let tempdir = TempDir::new().unwrap();
+
+    write_block_files(
+      tempdir.path(),
+      Some(XOR_KEY),
+      &[
+        &[blocks[0].clone(), blocks[2].clone(), blocks[1].clone()],
+        &[blocks[4].clone(), blocks[3].clone(), blocks[5].clone()],
+      ],
+    );
+
+    let block_files =
+      BlockFiles::open(tempdir.path(), Network::Regtest, blocks[4].block_hash()).unwrap();
+
+    assert_eq!(block_files.block_count(), 5);
+
+    for (height, block) in blocks.iter().enumerate().take(5) {
+      assert_eq!(
+        block_files
+          .get_block(height.try_into().unwrap(), false)
+          .unwrap()
+          .as_ref(),
+        Some(block)
+      );
+    }
+
+    assert_eq!(
+      block_files.get_block(3, true).unwrap(),
+      Some(Block {
+        header: blocks[3].header,
+        txdata: Vec::new(),
+      })
+    );
+
+    assert_eq!(block_files.get_block(5, false).unwrap(), None);
+  }
+
+  #[test]
+  fn ignores_stale_blocks() {
+    let (rpc_server, mut blocks) = mine_chain(3);
+    rpc_server.invalidate_tip();
+    let stale = blocks.pop().unwrap();
+    blocks.extend(rpc_server.mine_blocks(2));
+    let tempdir = TempDir::new().unwrap();
+
+    write_block_files(
+      tempdir.path(),
+      None,
+      &[&[
+        blocks[0].clone(),
+        blocks[1].clone(),
+        blocks[2].clone(),
+        stale,
+        blocks[3].clone(),
+        blocks[4].clone(),
+      ]],
+    );
+
+    let block_files =
+      BlockFiles::open(tempdir.path(), Network::Regtest, blocks[4].block_hash()).unwrap();
+
+    assert_eq!(block_files.block_count(), 5);
+    assert_eq!(
+      block_files.get_block(3, false).unwrap().as_ref(),
+      Some(&blocks[3])
+    );
+  }
+
+  #[test]
+  fn missing_block_is_an_error() {
+    let (_rpc_server, blocks) = mine_chain(3);
+    let tempdir = TempDir::new().unwrap();
+
+    write_block_files(
+      tempdir.path(),
+      None,
+      &[&[blocks[0].clone(), blocks[1].clone(), blocks[3].clone()]],
+    );
+
+    assert_eq!(
+      BlockFiles::open(tempdir.path(), Network::Regtest, blocks[3].block_hash())
+        .err()
+        .unwrap()
+        .to_string(),
+      format!(
+        "block {} not found in {}",
+        blocks[2].block_hash(),
+        tempdir.path().display()
+      )
+    );
+  }
+
+  #[test]
+  fn wrong_network_is_an_error() {
+    let (_rpc_server, blocks) = mine_chain(1);
+    let tempdir = TempDir::new().unwrap();
+
+    write_block_files(tempdir.path(), None, &[&blocks]);
+
+    assert!(
+      BlockFiles::open(tempdir.path(), Network::Bitcoin, blocks[1].block_hash())
+        .err()
+        .unwrap()
+        .to_string()
+        .starts_with("unexpected network magic fabfb5da at offset 0")
+    );
+  }
+}
@@ -0,0 +1,56 @@
+use super::*;
+
+/// Encodes an inscription number as a cursor key whose byte order matches
+/// the number order, so cursed inscriptions come first.
+pub(super) fn number_key(number: i64) -> [u8; 8] {
+  let mut key = number.to_be_bytes();
+  key[0] ^= 0x80;
+  key
+}
+
+pub(super) fn number_from_key(mut key: [u8; 8]) -> i64 {
+  key[0] ^= 0x80;
+  i64::from_be_bytes(key)
+}
+
+/// Returns the inscription number a page of children starts at, or None if
+/// the cursor was not handed out by a page of children.
+pub(crate) fn start(page: &PageRequest) -> Option<i64> {
+  match page.start() {
+    [] => Some(i64::MIN),
+    start => <[u8; 8]>::try_from(start).ok().map(number_from_key),
+  }
+}
+
+/// Fills in the table of children by inscription number for indexes built
+/// before it existed, from the children of each parent and their entries.
+pub(crate) fn backfill(wtx: &WriteTransaction, rtx: &ReadTransaction) -> Result {
+  let mut id_and_child_number_to_child_id =
+    wtx.open_table(INSCRIPTION_ID_AND_CHILD_NUMBER_TO_CHILD_ID)?;
+
+  let id_to_children = match rtx.open_multimap_table(INSCRIPTION_ID_TO_CHILDREN) {
+    Ok(table) => table,
+    Err(redb::TableError::TableDoesNotExist(_)) => return Ok(()),
+    Err(err) => return Err(err.into()),
+  };
+  let id_to_entry = rtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+
+  for result in id_to_children.iter()? {
+    let (parent, children) = result?;
+    for child in children {
+      let child = child?;
+      let number = id_to_entry
+        .get(child.value())?
+        .map(|entry| InscriptionEntry::load(entry.value()).number)
+        .ok_or_else(|| {
+          anyhow!(
+            "child inscription {} has no entry",
+            InscriptionId::load(*child.value())
+          )
+        })?;
+      id_and_child_number_to_child_id.insert((parent.value(), number), child.value())?;
+    }
+  }
+
+  Ok(())
+}
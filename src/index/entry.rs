@@ -190,6 +190,20 @@ impl Entry for SatPoint {
   }
 }
 
+pub(super) type ScriptHashValue = [u8; 20];
+
+impl Entry for ScriptHash {
+  type Value = ScriptHashValue;
+
+  fn load(value: Self::Value) -> Self {
+    ScriptHash::from_byte_array(value)
+  }
+
+  fn store(self) -> Self::Value {
+    self.to_byte_array()
+  }
+}
+
 pub(super) type SatRange = (u64, u64);
 
 impl Entry for SatRange {
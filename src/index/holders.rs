@@ -0,0 +1,180 @@
+use {super::*, redb::ReadTransaction};
+
+/// A row of a holder export, written as one CSV record or JSON object.
+pub(crate) trait HolderRow: Serialize {
+  const HEADER: &'static [&'static str];
+
+  fn record(&self) -> Vec<String>;
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct BRC20BalanceRow {
+  pub(crate) address: String,
+  pub(crate) tick: String,
+  pub(crate) overall: String,
+  pub(crate) transferable: String,
+  pub(crate) decimals: u8,
+}
+
+impl HolderRow for BRC20BalanceRow {
+  const HEADER: &'static [&'static str] =
+    &["address", "tick", "overall", "transferable", "decimals"];
+
+  fn record(&self) -> Vec<String> {
+    vec![
+      self.address.clone(),
+      self.tick.clone(),
+      self.overall.clone(),
+      self.transferable.clone(),
+      self.decimals.to_string(),
+    ]
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct BRC20SBalanceRow {
+  pub(crate) address: String,
+  pub(crate) tick_id: String,
+  pub(crate) tick: String,
+  pub(crate) overall: String,
+  pub(crate) transferable: String,
+  pub(crate) decimals: u8,
+}
+
+impl HolderRow for BRC20SBalanceRow {
+  const HEADER: &'static [&'static str] = &[
+    "address",
+    "tick_id",
+    "tick",
+    "overall",
+    "transferable",
+    "decimals",
+  ];
+
+  fn record(&self) -> Vec<String> {
+    vec![
+      self.address.clone(),
+      self.tick_id.clone(),
+      self.tick.clone(),
+      self.overall.clone(),
+      self.transferable.clone(),
+      self.decimals.to_string(),
+    ]
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct BRC20SPositionRow {
+  pub(crate) address: String,
+  pub(crate) pid: String,
+  pub(crate) staked: String,
+  pub(crate) minted: String,
+  pub(crate) pending_reward: String,
+  pub(crate) latest_updated_block: u64,
+}
+
+impl HolderRow for BRC20SPositionRow {
+  const HEADER: &'static [&'static str] = &[
+    "address",
+    "pid",
+    "staked",
+    "minted",
+    "pending_reward",
+    "latest_updated_block",
+  ];
+
+  fn record(&self) -> Vec<String> {
+    vec![
+      self.address.clone(),
+      self.pid.clone(),
+      self.staked.clone(),
+      self.minted.clone(),
+      self.pending_reward.clone(),
+      self.latest_updated_block.to_string(),
+    ]
+  }
+}
+
+/// Calls `f` with a row for every non-zero BRC20 balance.
+pub(super) fn brc20_balances(
+  rtx: &ReadTransaction,
+  mut f: impl FnMut(BRC20BalanceRow) -> Result<()>,
+) -> Result<()> {
+  let brc20_db = brc20_db::DataStoreReader::new(rtx);
+
+  let mut tokens = HashMap::<String, brc20::TokenInfo>::new();
+
+  brc20_db.for_each_balance(|owner, balance| {
+    if balance.overall_balance == 0 {
+      return Ok(());
+    }
+
+    let key = balance.tick.to_lowercase().to_string();
+    if !tokens.contains_key(&key) {
+      let token = brc20_db
+        .get_token_info(&balance.tick)?
+        .ok_or_else(|| anyhow!("token info not found for {}", balance.tick))?;
+      tokens.insert(key.clone(), token);
+    }
+    let token = &tokens[&key];
+
+    f(BRC20BalanceRow {
+      address: owner.to_string(),
+      tick: token.tick.to_string(),
+      overall: balance.overall_balance.to_string(),
+      transferable: balance.transferable_balance.to_string(),
+      decimals: token.decimal,
+    })
+  })
+}
+
+/// Calls `f` with a row for every non-zero BRC20S balance.
+pub(super) fn brc20s_balances(
+  rtx: &ReadTransaction,
+  mut f: impl FnMut(BRC20SBalanceRow) -> Result<()>,
+) -> Result<()> {
+  let brc20s_db = brc20s_db::DataStoreReader::new(rtx);
+
+  let mut ticks = HashMap::<String, brc20s::TickInfo>::new();
+
+  brc20s_db.for_each_balance(|owner, balance| {
+    if balance.overall_balance == 0 {
+      return Ok(());
+    }
+
+    let key = balance.tick_id.hex();
+    if !ticks.contains_key(&key) {
+      let tick = brc20s_db
+        .get_tick_info(&balance.tick_id)?
+        .ok_or_else(|| anyhow!("tick info not found for {key}"))?;
+      ticks.insert(key.clone(), tick);
+    }
+    let tick = &ticks[&key];
+
+    f(BRC20SBalanceRow {
+      address: owner.to_string(),
+      tick_id: key,
+      tick: tick.name.as_str().to_string(),
+      overall: balance.overall_balance.to_string(),
+      transferable: balance.transferable_balance.to_string(),
+      decimals: tick.decimal,
+    })
+  })
+}
+
+/// Calls `f` with a row for every BRC20S pool position.
+pub(super) fn brc20s_positions(
+  rtx: &ReadTransaction,
+  mut f: impl FnMut(BRC20SPositionRow) -> Result<()>,
+) -> Result<()> {
+  brc20s_db::DataStoreReader::new(rtx).for_each_user_info(|owner, position| {
+    f(BRC20SPositionRow {
+      address: owner.to_string(),
+      pid: position.pid.as_str().to_string(),
+      staked: position.staked.to_string(),
+      minted: position.minted.to_string(),
+      pending_reward: position.pending_reward.to_string(),
+      latest_updated_block: position.latest_updated_block,
+    })
+  })
+}
@@ -0,0 +1,114 @@
+use {super::*, crate::okx::datastore::schema::Chain, std::ops::Bound};
+
+// Owners are backfilled for this many inscriptions at a time.
+const INSCRIPTIONS_PER_CHUNK: usize = 10_000;
+
+/// Fills in the owner and content type tables for indexes built before they
+/// existed. The owner is the script of the output an inscription sits in, and
+/// the content type is read from the transaction that revealed it.
+///
+/// Both come from the bitcoin node when the index does not have them: one
+/// `getrawtransaction` call per reveal transaction, and one more per
+/// inscription sitting in an output the index does not keep. On mainnet that
+/// is tens of millions of calls, and the node must run with `-txindex` to
+/// answer them for transactions that are not in its mempool.
+///
+/// Handles up to `INSCRIPTIONS_PER_CHUNK` inscriptions after the ones
+/// `progress` covers, and returns the progress to resume from, or None once
+/// every inscription was handled. The progress is the id of the last
+/// inscription handled and the number handled so far.
+pub(crate) fn backfill(
+  wtx: &WriteTransaction,
+  rtx: &ReadTransaction,
+  chain: &dyn Chain,
+  progress: Option<&[u8]>,
+) -> Result<Option<Vec<u8>>> {
+  let mut id_to_content_type = wtx.open_table(INSCRIPTION_ID_TO_CONTENT_TYPE)?;
+  let mut id_to_script_hash = wtx.open_table(INSCRIPTION_ID_TO_SCRIPT_HASH)?;
+  let mut script_hash_to_id = wtx.open_multimap_table(SCRIPT_HASH_TO_INSCRIPTION_ID)?;
+
+  let id_to_satpoint = match rtx.open_table(INSCRIPTION_ID_TO_SATPOINT) {
+    Ok(table) => table,
+    Err(redb::TableError::TableDoesNotExist(_)) => return Ok(None),
+    Err(err) => return Err(err.into()),
+  };
+  let outpoint_to_entry = rtx.open_table(OUTPOINT_TO_ENTRY)?;
+
+  let (after, mut done) = match progress {
+    Some(progress) => (
+      Some(InscriptionIdValue::try_from(&progress[..36])?),
+      u64::from_be_bytes(progress[36..].try_into()?),
+    ),
+    None => (None, 0),
+  };
+  let start = after.as_ref().map_or(Bound::Unbounded, Bound::Excluded);
+  let mut last = None;
+
+  // Inscriptions are visited in id order, so the ones revealed by the same
+  // transaction come one after another.
+  let mut reveal: Option<(Txid, Transaction)> = None;
+  let fetch = |txid: Txid| {
+    chain
+      .transaction(txid)?
+      .ok_or_else(|| anyhow!("transaction {txid} not found"))
+  };
+
+  for result in id_to_satpoint
+    .range::<&InscriptionIdValue>((start, Bound::Unbounded))?
+    .take(INSCRIPTIONS_PER_CHUNK)
+  {
+    let (id, satpoint) = result?;
+    let inscription_id = InscriptionId::load(*id.value());
+    let satpoint = SatPoint::load(*satpoint.value());
+    last = Some(*id.value());
+    done += 1;
+
+    if reveal.as_ref().map(|(txid, _)| *txid) != Some(inscription_id.txid) {
+      reveal = Some((inscription_id.txid, fetch(inscription_id.txid)?));
+    }
+    let (_, reveal_tx) = reveal.as_ref().unwrap();
+
+    if let Some(content_type) = Inscription::from_transaction(reveal_tx)
+      .get(usize::try_from(inscription_id.index).unwrap())
+      .and_then(|inscription| inscription.inscription.content_type())
+    {
+      id_to_content_type.insert(&inscription_id.store(), content_type)?;
+    }
+
+    let outpoint = satpoint.outpoint;
+    if outpoint.is_null() || outpoint == unbound_outpoint() {
+      continue;
+    }
+
+    let script_pubkey = match outpoint_to_entry.get(&outpoint.store())? {
+      Some(entry) => TxOut::consensus_decode(&mut io::Cursor::new(entry.value()))?.script_pubkey,
+      None if outpoint.txid == inscription_id.txid => reveal_tx
+        .output
+        .get(usize::try_from(outpoint.vout).unwrap())
+        .ok_or_else(|| anyhow!("output {outpoint} not found"))?
+        .script_pubkey
+        .clone(),
+      None => {
+        fetch(outpoint.txid)?
+          .output
+          .into_iter()
+          .nth(usize::try_from(outpoint.vout).unwrap())
+          .ok_or_else(|| anyhow!("output {outpoint} not found"))?
+          .script_pubkey
+      }
+    };
+
+    let script_hash = script_pubkey.script_hash().store();
+    id_to_script_hash.insert(&inscription_id.store(), &script_hash)?;
+    script_hash_to_id.insert(&script_hash, &inscription_id.store())?;
+  }
+
+  let Some(last) = last else {
+    return Ok(None);
+  };
+  log::info!(
+    "Backfilled owners of {done}/{} inscriptions",
+    id_to_satpoint.len()?
+  );
+  Ok(Some([last.as_slice(), &done.to_be_bytes()].concat()))
+}
@@ -1,4 +1,4 @@
-use {super::*, updater::BlockData};
+use {super::*, redb::ReadTransaction, updater::BlockData};
 
 #[derive(Debug, PartialEq)]
 pub(crate) enum ReorgError {
@@ -19,9 +19,59 @@ impl fmt::Display for ReorgError {
 
 impl std::error::Error for ReorgError {}
 
+/// A reorg the index recovered from, kept so that clients can discard data
+/// they read from orphaned blocks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ReorgEntry {
+  /// Height of the block whose parent did not match the index.
+  pub(crate) height: u64,
+  pub(crate) depth: u64,
+  /// Seconds since the unix epoch at which the reorg was handled.
+  pub(crate) timestamp: u64,
+  pub(crate) orphaned_blocks: Vec<OrphanedBlock>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct OrphanedBlock {
+  pub(crate) height: u64,
+  pub(crate) old_hash: BlockHash,
+  /// Hash of the block now at `height`, if the new chain reaches it yet.
+  pub(crate) new_hash: Option<BlockHash>,
+  /// Transactions of the orphaned block that had protocol receipts, or `None`
+  /// if bitcoind could no longer serve the block.
+  pub(crate) transactions: Option<Vec<OrphanedTransaction>>,
+  /// BRC20S ticks the receipts of the transactions refer to, as they were
+  /// before the block was rolled back.
+  pub(crate) brc20s_ticks: Vec<brc20s::TickInfo>,
+}
+
+/// The receipts an orphaned transaction had before it was rolled back.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct OrphanedTransaction {
+  pub(crate) txid: Txid,
+  pub(crate) inscriptions: Vec<ord::InscriptionOp>,
+  pub(crate) brc20_receipts: Vec<brc20::Receipt>,
+  pub(crate) brc20s_receipts: Vec<brc20s::Receipt>,
+}
+
+impl OrphanedTransaction {
+  /// Protocols that had receipts for the transaction: `ord`, `brc20` or `brc20s`.
+  pub(crate) fn protocols(&self) -> Vec<String> {
+    [
+      ("ord", self.inscriptions.is_empty()),
+      ("brc20", self.brc20_receipts.is_empty()),
+      ("brc20s", self.brc20s_receipts.is_empty()),
+    ]
+    .into_iter()
+    .filter(|(_, empty)| !empty)
+    .map(|(protocol, _)| protocol.into())
+    .collect()
+  }
+}
+
 const MAX_SAVEPOINTS: usize = 2;
 const SAVEPOINT_INTERVAL: u64 = 10;
-const CHAIN_TIP_DISTANCE: u64 = 21;
+pub(crate) const CHAIN_TIP_DISTANCE: u64 = 21;
 
 pub(crate) struct Reorg {}
 
@@ -32,8 +82,9 @@ impl Reorg {
     match index.block_hash(height.checked_sub(1))? {
       Some(index_prev_blockhash) if index_prev_blockhash == bitcoind_prev_blockhash => Ok(()),
       Some(index_prev_blockhash) if index_prev_blockhash != bitcoind_prev_blockhash => {
-        let max_recoverable_reorg_depth =
-          (MAX_SAVEPOINTS as u64 - 1) * SAVEPOINT_INTERVAL + height % SAVEPOINT_INTERVAL;
+        let max_recoverable_reorg_depth = ((MAX_SAVEPOINTS as u64 - 1) * SAVEPOINT_INTERVAL
+          + height % SAVEPOINT_INTERVAL)
+          .max(Self::undo_depth(index, height)? + 2);
 
         for depth in 1..max_recoverable_reorg_depth {
           let index_block_hash = index.block_hash(height.checked_sub(depth))?;
@@ -53,13 +104,60 @@ impl Reorg {
     }
   }
 
+  /// Returns how many blocks below `height` can be unwound with undo logs.
+  fn undo_depth(index: &Index, height: u64) -> Result<u64> {
+    match height.checked_sub(1) {
+      Some(tip) => undo::available_depth(&index.database.begin_read()?, tip),
+      None => Ok(0),
+    }
+  }
+
   pub(crate) fn handle_reorg(index: &Index, height: u64, depth: u64) -> Result {
     log::info!("rolling back database after reorg of depth {depth} at height {height}");
 
+    let entry = ReorgEntry {
+      height,
+      depth,
+      timestamp: SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default(),
+      orphaned_blocks: Self::orphaned_blocks(index, height, depth)?,
+    };
+
+    // The blocks above the common ancestor at `height - depth` are stale.
+    if Self::undo_depth(index, height)? >= depth - 1 {
+      let wtx = index.begin_write()?;
+
+      for stale in (height - depth + 1..height).rev() {
+        undo::unwind_block(&wtx, stale)?;
+      }
+
+      // Savepoints taken on the stale branch must never be restored.
+      for savepoint in wtx.list_persistent_savepoints()?.collect::<Vec<u64>>() {
+        wtx.delete_persistent_savepoint(savepoint)?;
+      }
+
+      Self::record(&wtx, Vec::new(), &entry)?;
+
+      Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
+      wtx.commit()?;
+
+      log::info!(
+        "successfully unwound database to height {} using undo logs",
+        index.block_count()?
+      );
+
+      return Ok(());
+    }
+
     if let redb::Durability::None = index.durability {
       panic!("set index durability to `Durability::Immediate` to test reorg handling");
     }
 
+    // Restoring the savepoint also rolls back the reorg history, so keep it aside.
+    let history = Self::raw_history(&index.database.begin_read()?)?;
+
     let mut wtx = index.begin_write()?;
 
     let oldest_savepoint =
@@ -67,6 +165,8 @@ impl Reorg {
 
     wtx.restore_savepoint(&oldest_savepoint)?;
 
+    Self::record(&wtx, history, &entry)?;
+
     Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
     wtx.commit()?;
 
@@ -78,6 +178,122 @@ impl Reorg {
     Ok(())
   }
 
+  /// Collects the blocks above the common ancestor at `height - depth`, before
+  /// they are rolled back, along with their transactions that had receipts.
+  fn orphaned_blocks(index: &Index, height: u64, depth: u64) -> Result<Vec<OrphanedBlock>> {
+    let rtx = index.database.begin_read()?;
+    let ord_db = ord::OrdDbReader::new(&rtx);
+    let brc20_db = brc20_db::DataStoreReader::new(&rtx);
+    let brc20s_db = brc20s_db::DataStoreReader::new(&rtx);
+
+    let mut orphaned_blocks = Vec::new();
+    for height in height - depth + 1..height {
+      let Some(old_hash) = index.block_hash(Some(height))? else {
+        continue;
+      };
+
+      let mut brc20s_ticks = Vec::new();
+      let transactions = match index.client.get_block(&old_hash).into_option()? {
+        Some(block) => {
+          let mut transactions = Vec::new();
+          for tx in &block.txdata {
+            let txid = tx.txid();
+            let transaction = OrphanedTransaction {
+              txid,
+              inscriptions: ord_db.get_transaction_operations(&txid)?,
+              brc20_receipts: brc20_db.get_transaction_receipts(&txid)?,
+              brc20s_receipts: brc20s_db.get_txid_to_receipts(&txid)?,
+            };
+            if transaction.protocols().is_empty() {
+              continue;
+            }
+            for tick_id in transaction
+              .brc20s_receipts
+              .iter()
+              .flat_map(brc20s::Receipt::tick_ids)
+            {
+              if brc20s_ticks
+                .iter()
+                .all(|tick: &brc20s::TickInfo| tick.tick_id != tick_id)
+              {
+                brc20s_ticks.extend(brc20s_db.get_tick_info(&tick_id)?);
+              }
+            }
+            transactions.push(transaction);
+          }
+          Some(transactions)
+        }
+        None => None,
+      };
+
+      orphaned_blocks.push(OrphanedBlock {
+        height,
+        old_hash,
+        new_hash: index.client.get_block_hash(height).into_option()?,
+        transactions,
+        brc20s_ticks,
+      });
+    }
+
+    Ok(orphaned_blocks)
+  }
+
+  fn raw_history(rtx: &ReadTransaction) -> Result<Vec<(u64, Vec<u8>)>> {
+    let table = match rtx.open_table(REORG_ID_TO_REORG) {
+      Ok(table) => table,
+      Err(redb::TableError::TableDoesNotExist(_)) => return Ok(Vec::new()),
+      Err(err) => return Err(err.into()),
+    };
+
+    let mut history = Vec::new();
+    for result in table.iter()? {
+      let (id, entry) = result?;
+      history.push((id.value(), entry.value().to_vec()));
+    }
+
+    Ok(history)
+  }
+
+  /// Writes back `history`, then appends `entry` after the last recorded reorg.
+  fn record(wtx: &WriteTransaction, history: Vec<(u64, Vec<u8>)>, entry: &ReorgEntry) -> Result {
+    let mut table = wtx.open_table(REORG_ID_TO_REORG)?;
+
+    for (id, entry) in history {
+      table.insert(id, entry.as_slice())?;
+    }
+
+    let id = table
+      .iter()?
+      .next_back()
+      .transpose()?
+      .map(|(id, _)| id.value() + 1)
+      .unwrap_or_default();
+
+    table.insert(id, bincode::serialize(entry)?.as_slice())?;
+
+    Ok(())
+  }
+
+  /// Returns recorded reorgs, most recent first, and the total number recorded.
+  pub(crate) fn history(
+    rtx: &ReadTransaction,
+    start: usize,
+    limit: Option<usize>,
+  ) -> Result<(Vec<ReorgEntry>, usize)> {
+    let history = Self::raw_history(rtx)?;
+    let total = history.len();
+
+    let entries = history
+      .into_iter()
+      .rev()
+      .skip(start)
+      .take(limit.unwrap_or(usize::MAX))
+      .map(|(_, entry)| Ok(bincode::deserialize(&entry)?))
+      .collect::<Result<Vec<ReorgEntry>>>()?;
+
+    Ok((entries, total))
+  }
+
   pub(crate) fn update_savepoints(index: &Index, height: u64) -> Result {
     if let redb::Durability::None = index.durability {
       return Ok(());
@@ -1,4 +1,4 @@
-use super::*;
+use {super::*, std::ops::Deref};
 
 pub(crate) struct Rtx<'a>(pub(crate) redb::ReadTransaction<'a>);
 
@@ -61,3 +61,97 @@ impl Rtx<'_> {
     )
   }
 }
+
+impl<'a> Deref for Rtx<'a> {
+  type Target = redb::ReadTransaction<'a>;
+
+  fn deref(&self) -> &Self::Target {
+    &self.0
+  }
+}
+
+impl Rtx<'_> {
+  pub(crate) fn inscription_entry(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<InscriptionEntry>> {
+    Ok(
+      self
+        .0
+        .open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?
+        .get(&inscription_id.store())?
+        .map(|value| InscriptionEntry::load(value.value())),
+    )
+  }
+
+  pub(crate) fn inscription_satpoint(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<SatPoint>> {
+    Ok(
+      self
+        .0
+        .open_table(INSCRIPTION_ID_TO_SATPOINT)?
+        .get(&inscription_id.store())?
+        .map(|satpoint| Entry::load(*satpoint.value())),
+    )
+  }
+
+  pub(crate) fn inscriptions_on_output(
+    &self,
+    outpoint: OutPoint,
+  ) -> Result<Vec<(SatPoint, InscriptionId)>> {
+    Index::inscriptions_on_output_ordered(
+      &self.0.open_table(REINSCRIPTION_ID_TO_SEQUENCE_NUMBER)?,
+      &self.0.open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)?,
+      outpoint,
+    )
+  }
+
+  pub(crate) fn brc20_tick_info(&self, tick: &brc20::Tick) -> Result<Option<brc20::TokenInfo>> {
+    Ok(brc20_db::DataStoreReader::new(&self.0).get_token_info(tick)?)
+  }
+
+  pub(crate) fn brc20_balance(
+    &self,
+    script_key: &ScriptKey,
+    tick: &brc20::Tick,
+  ) -> Result<Option<brc20::Balance>> {
+    Ok(brc20_db::DataStoreReader::new(&self.0).get_balance(script_key, tick)?)
+  }
+
+  pub(crate) fn brc20s_tick_info(
+    &self,
+    tick_id: &brc20s::TickId,
+  ) -> Result<Option<brc20s::TickInfo>> {
+    Ok(brc20s_db::DataStoreReader::new(&self.0).get_tick_info(tick_id)?)
+  }
+
+  pub(crate) fn brc20s_pool_info(&self, pid: &brc20s::Pid) -> Result<Option<brc20s::PoolInfo>> {
+    Ok(brc20s_db::DataStoreReader::new(&self.0).get_pid_to_poolinfo(pid)?)
+  }
+
+  pub(crate) fn brc20s_user_info(
+    &self,
+    script_key: &ScriptKey,
+    pid: &brc20s::Pid,
+  ) -> Result<Option<brc20s::UserInfo>> {
+    Ok(brc20s_db::DataStoreReader::new(&self.0).get_pid_to_use_info(script_key, pid)?)
+  }
+
+  pub(crate) fn brc20s_balance(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &brc20s::TickId,
+  ) -> Result<Option<brc20s::Balance>> {
+    Ok(brc20s_db::DataStoreReader::new(&self.0).get_balance(script_key, tick_id)?)
+  }
+
+  pub(crate) fn brc20s_stake_info(
+    &self,
+    script_key: &ScriptKey,
+    pledged_tick: &PledgedTick,
+  ) -> Result<Option<brc20s::StakeInfo>> {
+    Ok(brc20s_db::DataStoreReader::new(&self.0).get_user_stakeinfo(script_key, pledged_tick)?)
+  }
+}
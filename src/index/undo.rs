@@ -0,0 +1,398 @@
+use {
+  super::*,
+  crate::okx::datastore::snapshot::TableVisitor,
+  redb::{MultimapTableHandle, ReadTransaction, RedbKey, RedbValue, TableHandle},
+  std::{borrow::Borrow, cell::RefCell},
+};
+
+/// A single reversible mutation, holding what is needed to restore the previous state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum UndoOp {
+  /// `key` had `value` in `table` before the block, or was absent if `None`.
+  Put {
+    table: String,
+    key: Vec<u8>,
+    value: Option<Vec<u8>>,
+  },
+  /// `value` was added under `key` to multimap `table`.
+  MultimapInserted {
+    table: String,
+    key: Vec<u8>,
+    value: Vec<u8>,
+  },
+  /// `value` was removed from `key` of multimap `table`.
+  MultimapRemoved {
+    table: String,
+    key: Vec<u8>,
+    value: Vec<u8>,
+  },
+}
+
+impl UndoOp {
+  fn table(&self) -> &str {
+    match self {
+      UndoOp::Put { table, .. }
+      | UndoOp::MultimapInserted { table, .. }
+      | UndoOp::MultimapRemoved { table, .. } => table,
+    }
+  }
+}
+
+/// UndoLog collects the mutations of one block so that the block can be unwound after a reorg.
+///
+/// Writers record through the free functions of this module, which take an
+/// `Option<&UndoLog>` and simply write when no log is being kept.
+#[derive(Debug, Default)]
+pub(crate) struct UndoLog {
+  ops: RefCell<Vec<UndoOp>>,
+}
+
+impl UndoLog {
+  pub(crate) fn record(&self, op: UndoOp) {
+    self.ops.borrow_mut().push(op);
+  }
+
+  /// Records that `key` was newly created outside of a table, e.g. in a write cache.
+  pub(crate) fn record_created<K: RedbKey + 'static, V: RedbValue + 'static>(
+    &self,
+    definition: TableDefinition<K, V>,
+    key: &[u8],
+  ) {
+    self.record(UndoOp::Put {
+      table: definition.name().into(),
+      key: key.to_vec(),
+      value: None,
+    });
+  }
+
+  /// Records that `key` held `value` before it was removed outside of a table.
+  pub(crate) fn record_removed<K: RedbKey + 'static, V: RedbValue + 'static>(
+    &self,
+    definition: TableDefinition<K, V>,
+    key: &[u8],
+    value: &[u8],
+  ) {
+    self.record(UndoOp::Put {
+      table: definition.name().into(),
+      key: key.to_vec(),
+      value: Some(value.to_vec()),
+    });
+  }
+
+  /// Stores the collected ops as the undo log of `height` and drops logs older than `max_depth`.
+  pub(crate) fn save(self, wtx: &WriteTransaction, height: u64, max_depth: u64) -> Result {
+    let mut table = wtx.open_table(HEIGHT_TO_UNDO_LOG)?;
+    table.insert(
+      height,
+      bincode::serialize(&self.ops.into_inner())?.as_slice(),
+    )?;
+
+    let oldest = (height + 1).saturating_sub(max_depth);
+    let stale = table
+      .range(..oldest)?
+      .map(|result| result.map(|(height, _)| height.value()))
+      .collect::<Result<Vec<u64>, redb::StorageError>>()?;
+    for height in stale {
+      table.remove(height)?;
+    }
+
+    Ok(())
+  }
+}
+
+fn key_bytes<K: RedbKey>(key: &K::SelfType<'_>) -> Vec<u8> {
+  K::as_bytes(key).as_ref().to_vec()
+}
+
+pub(crate) fn insert<'k, 'v, K: RedbKey + 'static, V: RedbValue + 'static>(
+  undo: Option<&UndoLog>,
+  definition: TableDefinition<K, V>,
+  table: &mut Table<K, V>,
+  key: impl Borrow<K::SelfType<'k>>,
+  value: impl Borrow<V::SelfType<'v>>,
+) -> Result<(), redb::StorageError> {
+  let old = table.insert(key.borrow(), value)?;
+  if let Some(undo) = undo {
+    undo.record(UndoOp::Put {
+      table: definition.name().into(),
+      key: key_bytes::<K>(key.borrow()),
+      value: old.map(|old| V::as_bytes(&old.value()).as_ref().to_vec()),
+    });
+  }
+  Ok(())
+}
+
+pub(crate) fn remove<'k, K: RedbKey + 'static, V: RedbValue + 'static>(
+  undo: Option<&UndoLog>,
+  definition: TableDefinition<K, V>,
+  table: &mut Table<K, V>,
+  key: impl Borrow<K::SelfType<'k>>,
+) -> Result<Option<Vec<u8>>, redb::StorageError> {
+  let old = table
+    .remove(key.borrow())?
+    .map(|old| V::as_bytes(&old.value()).as_ref().to_vec());
+  if let (Some(undo), Some(value)) = (undo, &old) {
+    undo.record(UndoOp::Put {
+      table: definition.name().into(),
+      key: key_bytes::<K>(key.borrow()),
+      value: Some(value.clone()),
+    });
+  }
+  Ok(old)
+}
+
+pub(crate) fn multimap_insert<'k, 'v, K: RedbKey + 'static, V: RedbKey + 'static>(
+  undo: Option<&UndoLog>,
+  definition: MultimapTableDefinition<K, V>,
+  table: &mut MultimapTable<K, V>,
+  key: impl Borrow<K::SelfType<'k>>,
+  value: impl Borrow<V::SelfType<'v>>,
+) -> Result<(), redb::StorageError> {
+  let existed = table.insert(key.borrow(), value.borrow())?;
+  if let (Some(undo), false) = (undo, existed) {
+    undo.record(UndoOp::MultimapInserted {
+      table: definition.name().into(),
+      key: key_bytes::<K>(key.borrow()),
+      value: key_bytes::<V>(value.borrow()),
+    });
+  }
+  Ok(())
+}
+
+pub(crate) fn multimap_remove_all<'k, K: RedbKey + 'static, V: RedbKey + 'static>(
+  undo: Option<&UndoLog>,
+  definition: MultimapTableDefinition<K, V>,
+  table: &mut MultimapTable<K, V>,
+  key: impl Borrow<K::SelfType<'k>>,
+) -> Result<(), redb::StorageError> {
+  let values = table.remove_all(key.borrow())?;
+  if let Some(undo) = undo {
+    for value in values {
+      undo.record(UndoOp::MultimapRemoved {
+        table: definition.name().into(),
+        key: key_bytes::<K>(key.borrow()),
+        value: key_bytes::<V>(&value?.value()),
+      });
+    }
+  }
+  Ok(())
+}
+
+pub(crate) fn multimap_remove<'a, K: RedbKey + 'static, V: RedbKey + 'static>(
+  undo: Option<&UndoLog>,
+  definition: MultimapTableDefinition<K, V>,
+  table: &mut MultimapTable<K, V>,
+  key: impl Borrow<K::SelfType<'a>>,
+  value: impl Borrow<V::SelfType<'a>>,
+) -> Result<(), redb::StorageError> {
+  let existed = table.remove(key.borrow(), value.borrow())?;
+  if let (Some(undo), true) = (undo, existed) {
+    undo.record(UndoOp::MultimapRemoved {
+      table: definition.name().into(),
+      key: key_bytes::<K>(key.borrow()),
+      value: key_bytes::<V>(value.borrow()),
+    });
+  }
+  Ok(())
+}
+
+/// Returns how many consecutive blocks ending at `height` have an undo log.
+pub(crate) fn available_depth(rtx: &ReadTransaction, height: u64) -> Result<u64> {
+  let table = match rtx.open_table(HEIGHT_TO_UNDO_LOG) {
+    Ok(table) => table,
+    Err(redb::TableError::TableDoesNotExist(_)) => return Ok(0),
+    Err(err) => return Err(err.into()),
+  };
+
+  let mut depth = 0;
+  for result in table.range(..=height)?.rev() {
+    let (h, _) = result?;
+    if h.value() + depth != height {
+      break;
+    }
+    depth += 1;
+  }
+
+  Ok(depth)
+}
+
+/// Reverts every mutation recorded for `height` and removes its undo log.
+pub(crate) fn unwind_block(wtx: &WriteTransaction, height: u64) -> Result {
+  let ops: Vec<UndoOp> = {
+    let mut table = wtx.open_table(HEIGHT_TO_UNDO_LOG)?;
+    let ops = table
+      .remove(height)?
+      .ok_or_else(|| anyhow!("no undo log for block {height}"))?;
+    let ops = bincode::deserialize(ops.value())?;
+    ops
+  };
+
+  let mut unwinder = Unwinder { wtx, ops };
+
+  visit_tables(&mut unwinder)?;
+
+  if let Some(op) = unwinder.ops.first() {
+    bail!(
+      "undo log of block {height} references unknown table {}",
+      op.table()
+    );
+  }
+
+  Ok(())
+}
+
+struct Unwinder<'db, 'a> {
+  wtx: &'a WriteTransaction<'db>,
+  ops: Vec<UndoOp>,
+}
+
+impl<'db, 'a> Unwinder<'db, 'a> {
+  fn take_ops(&mut self, name: &str) -> Vec<UndoOp> {
+    let (mut ops, rest) = std::mem::take(&mut self.ops)
+      .into_iter()
+      .partition::<Vec<UndoOp>, _>(|op| op.table() == name);
+    self.ops = rest;
+    ops.reverse();
+    ops
+  }
+}
+
+impl<'db, 'a> TableVisitor for Unwinder<'db, 'a> {
+  fn visit<K: RedbKey + 'static, V: RedbValue + 'static>(
+    &mut self,
+    definition: TableDefinition<K, V>,
+  ) -> Result {
+    let ops = self.take_ops(definition.name());
+    if ops.is_empty() {
+      return Ok(());
+    }
+
+    let mut table = self.wtx.open_table(definition)?;
+    for op in ops {
+      match op {
+        UndoOp::Put {
+          key,
+          value: Some(value),
+          ..
+        } => {
+          table.insert(K::from_bytes(&key), V::from_bytes(&value))?;
+        }
+        UndoOp::Put {
+          key, value: None, ..
+        } => {
+          table.remove(K::from_bytes(&key))?;
+        }
+        _ => bail!("unexpected op for table {}", definition.name()),
+      }
+    }
+    Ok(())
+  }
+
+  fn visit_multimap<K: RedbKey + 'static, V: RedbKey + 'static>(
+    &mut self,
+    definition: MultimapTableDefinition<K, V>,
+  ) -> Result {
+    let ops = self.take_ops(definition.name());
+    if ops.is_empty() {
+      return Ok(());
+    }
+
+    let mut table = self.wtx.open_multimap_table(definition)?;
+    for op in ops {
+      match op {
+        UndoOp::MultimapInserted { key, value, .. } => {
+          table.remove(K::from_bytes(&key), V::from_bytes(&value))?;
+        }
+        UndoOp::MultimapRemoved { key, value, .. } => {
+          table.insert(K::from_bytes(&key), V::from_bytes(&value))?;
+        }
+        UndoOp::Put { .. } => bail!("unexpected op for multimap {}", definition.name()),
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, redb::Database, tempfile::NamedTempFile};
+
+  #[test]
+  fn unwind_block_restores_previous_state() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+
+    let wtx = db.begin_write().unwrap();
+    {
+      let mut table = wtx.open_table(STATISTIC_TO_COUNT).unwrap();
+      table.insert(1, 10).unwrap();
+      table.insert(2, 20).unwrap();
+    }
+    wtx.commit().unwrap();
+
+    let wtx = db.begin_write().unwrap();
+    let log = UndoLog::default();
+    {
+      let mut table = wtx.open_table(STATISTIC_TO_COUNT).unwrap();
+      insert(Some(&log), STATISTIC_TO_COUNT, &mut table, 1, 11).unwrap();
+      insert(Some(&log), STATISTIC_TO_COUNT, &mut table, 1, 12).unwrap();
+      insert(Some(&log), STATISTIC_TO_COUNT, &mut table, 3, 30).unwrap();
+      assert_eq!(
+        remove(Some(&log), STATISTIC_TO_COUNT, &mut table, 2).unwrap(),
+        Some(20u64.to_le_bytes().to_vec())
+      );
+
+      let mut multimap = wtx.open_multimap_table(SAT_TO_INSCRIPTION_ID).unwrap();
+      multimap_insert(
+        Some(&log),
+        SAT_TO_INSCRIPTION_ID,
+        &mut multimap,
+        5,
+        &[1; 36],
+      )
+      .unwrap();
+    }
+    log.save(&wtx, 7, 10).unwrap();
+    wtx.commit().unwrap();
+
+    assert_eq!(available_depth(&db.begin_read().unwrap(), 7).unwrap(), 1);
+    assert_eq!(available_depth(&db.begin_read().unwrap(), 8).unwrap(), 0);
+
+    let wtx = db.begin_write().unwrap();
+    unwind_block(&wtx, 7).unwrap();
+    wtx.commit().unwrap();
+
+    let rtx = db.begin_read().unwrap();
+    let table = rtx.open_table(STATISTIC_TO_COUNT).unwrap();
+    assert_eq!(table.get(1).unwrap().unwrap().value(), 10);
+    assert_eq!(table.get(2).unwrap().unwrap().value(), 20);
+    assert!(table.get(3).unwrap().is_none());
+    assert!(rtx
+      .open_multimap_table(SAT_TO_INSCRIPTION_ID)
+      .unwrap()
+      .get(5)
+      .unwrap()
+      .next()
+      .is_none());
+    assert_eq!(available_depth(&rtx, 7).unwrap(), 0);
+  }
+
+  #[test]
+  fn save_prunes_logs_beyond_max_depth() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+
+    for height in 0..5 {
+      let wtx = db.begin_write().unwrap();
+      UndoLog::default().save(&wtx, height, 3).unwrap();
+      wtx.commit().unwrap();
+    }
+
+    let rtx = db.begin_read().unwrap();
+    assert_eq!(available_depth(&rtx, 4).unwrap(), 3);
+    assert_eq!(
+      rtx.open_table(HEIGHT_TO_UNDO_LOG).unwrap().len().unwrap(),
+      3
+    );
+  }
+}
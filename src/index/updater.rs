@@ -5,12 +5,16 @@ use {
     datastore::StateReadWrite,
     protocol::{BlockContext, ProtocolConfig, ProtocolManager},
   },
+  bitcoin::hashes::{sha256, HashEngine},
   futures::future::try_join_all,
   std::sync::mpsc,
   tokio::sync::mpsc::{error::TryRecvError, Receiver, Sender},
 };
 
 mod inscription_updater;
+mod preview;
+
+pub(super) use preview::{preview, Preview};
 
 pub(crate) struct BlockData {
   pub(crate) header: Header,
@@ -42,6 +46,7 @@ pub(crate) struct Updater<'index> {
   outputs_cached: u64,
   outputs_inserted_since_flush: u64,
   outputs_traversed: u64,
+  undo_from_height: u64,
 }
 
 impl<'index> Updater<'_> {
@@ -55,12 +60,19 @@ impl<'index> Updater<'_> {
       outputs_cached: 0,
       outputs_inserted_since_flush: 0,
       outputs_traversed: 0,
+      undo_from_height: u64::MAX,
     })
   }
 
   pub(crate) fn update_index(&mut self) -> Result {
     let mut wtx = self.index.begin_write()?;
     let starting_height = self.index.client.get_block_count()? + 1;
+    self.index.metrics().set_chain_height(starting_height - 1);
+
+    // Undo logs are only needed for blocks that may still be reorged.
+    if self.index.options.max_reorg_depth > 0 {
+      self.undo_from_height = starting_height.saturating_sub(self.index.options.max_reorg_depth);
+    }
 
     wtx
       .open_table(WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?
@@ -92,7 +104,13 @@ impl<'index> Updater<'_> {
     let (mut outpoint_sender, mut tx_out_receiver) = Self::spawn_fetcher(self.index)?;
 
     let mut uncommitted = 0;
-    while let Ok(block) = rx.recv() {
+    let mut last_commit = Instant::now();
+    while let Ok((block, fetch_time)) = rx.recv() {
+      self
+        .index
+        .metrics()
+        .observe_block_stage(BlockStage::Fetch, fetch_time);
+
       self.index_block(
         self.index,
         &mut outpoint_sender,
@@ -115,9 +133,10 @@ impl<'index> Updater<'_> {
 
       uncommitted += 1;
 
-      if uncommitted == 200 {
+      if self.should_commit(uncommitted, last_commit, starting_height) {
         self.commit(wtx)?;
         uncommitted = 0;
+        last_commit = Instant::now();
         wtx = self.index.begin_write()?;
         let height = wtx
           .open_table(HEIGHT_TO_BLOCK_HASH)?
@@ -160,35 +179,74 @@ impl<'index> Updater<'_> {
 
   fn fetch_blocks_from(
     index: &Index,
-    mut height: u64,
+    height: u64,
     index_sats: bool,
-  ) -> Result<mpsc::Receiver<BlockData>> {
-    let (tx, rx) = mpsc::sync_channel(32);
+  ) -> Result<mpsc::Receiver<(BlockData, Duration)>> {
+    let (tx, rx) = mpsc::sync_channel(index.options.prefetch_depth);
 
     let height_limit = index.height_limit;
 
-    let client = index.options.bitcoin_rpc_client()?;
-
     let first_inscription_height = index.first_inscription_height;
 
-    thread::spawn(move || loop {
-      if let Some(height_limit) = height_limit {
-        if height >= height_limit {
-          break;
+    let block_files =
+      Self::open_block_files(index, &index.options.bitcoin_rpc_client()?, height)?.map(Arc::new);
+
+    // Each worker fetches every `concurrency`-th block, so taking blocks from
+    // the workers in turn yields them in order.
+    let concurrency = index.options.fetch_concurrency.max(1);
+    let stride = u64::try_from(concurrency).unwrap();
+
+    let mut workers = Vec::with_capacity(concurrency);
+    for worker in 0..stride {
+      let client = index.options.bitcoin_rpc_client()?;
+      let mut block_files = block_files.clone();
+      let (worker_tx, worker_rx) = mpsc::sync_channel::<(BlockData, Duration)>(0);
+      workers.push(worker_rx);
+
+      let mut height = height + worker;
+      thread::spawn(move || loop {
+        if let Some(height_limit) = height_limit {
+          if height >= height_limit {
+            break;
+          }
         }
-      }
 
-      match Self::get_block_with_retries(&client, height, index_sats, first_inscription_height) {
-        Ok(Some(block)) => {
-          if let Err(err) = tx.send(block.into()) {
-            log::info!("Block receiver disconnected: {err}");
+        let header_only = !index_sats && height < first_inscription_height;
+
+        let start = Instant::now();
+        let result = match &block_files {
+          Some(files) if height < files.block_count() => files.get_block(height, header_only),
+          _ => {
+            // free the block locations once the rest comes over RPC
+            block_files = None;
+            Self::get_block_with_retries(&client, height, index_sats, first_inscription_height)
+          }
+        };
+
+        match result {
+          Ok(Some(block)) => {
+            if worker_tx.send((block.into(), start.elapsed())).is_err() {
+              break;
+            }
+            height += stride;
+          }
+          Ok(None) => break,
+          Err(err) => {
+            log::error!("failed to fetch block {height}: {err}");
             break;
           }
-          height += 1;
         }
-        Ok(None) => break,
-        Err(err) => {
-          log::error!("failed to fetch block {height}: {err}");
+      });
+    }
+
+    thread::spawn(move || {
+      for worker in workers.iter().cycle() {
+        let Ok(block) = worker.recv() else {
+          break;
+        };
+
+        if let Err(err) = tx.send(block) {
+          log::info!("Block receiver disconnected: {err}");
           break;
         }
       }
@@ -197,6 +255,39 @@ impl<'index> Updater<'_> {
     Ok(rx)
   }
 
+  /// Opens bitcoind's block files if `--read-block-files` is set and the index is
+  /// far enough behind the chain tip for reading them to be worthwhile.
+  fn open_block_files(index: &Index, client: &Client, height: u64) -> Result<Option<BlockFiles>> {
+    if !index.options.read_block_files {
+      return Ok(None);
+    }
+
+    let Some(tip_height) = client.get_block_count()?.checked_sub(RPC_TIP_DISTANCE) else {
+      return Ok(None);
+    };
+
+    if tip_height < height {
+      return Ok(None);
+    }
+
+    let dir = index.options.bitcoin_blocks_dir()?;
+    log::info!("Scanning block files in {}", dir.display());
+
+    let block_files = BlockFiles::open(
+      &dir,
+      index.options.chain().network(),
+      client.get_block_hash(tip_height)?,
+    )
+    .with_context(|| format!("failed to read block files in {}", dir.display()))?;
+
+    log::info!(
+      "Reading blocks {height} to {} from block files",
+      block_files.block_count() - 1
+    );
+
+    Ok(Some(block_files))
+  }
+
   fn get_block_with_retries(
     client: &Client,
     height: u64,
@@ -323,6 +414,7 @@ impl<'index> Updater<'_> {
     Reorg::detect_reorg(&block, self.height, self.index)?;
 
     let start = Instant::now();
+    let outputs_cached = self.outputs_cached;
     let mut sat_ranges_written = 0;
     let mut outputs_in_block = 0;
 
@@ -332,6 +424,9 @@ impl<'index> Updater<'_> {
       return Err(anyhow!("Previous block did not consume all input values"));
     };
 
+    let undo_log = UndoLog::default();
+    let undo = (self.height >= self.undo_from_height).then_some(&undo_log);
+
     let mut outpoint_to_entry = wtx.open_table(OUTPOINT_TO_ENTRY)?;
 
     let index_inscriptions = self.height >= index.first_inscription_height;
@@ -386,13 +481,19 @@ impl<'index> Updater<'_> {
       wtx.open_table(HEIGHT_TO_LAST_INSCRIPTION_NUMBER)?;
     let mut inscription_id_to_inscription_entry =
       wtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+    let mut inscription_id_and_child_number_to_child_id =
+      wtx.open_table(INSCRIPTION_ID_AND_CHILD_NUMBER_TO_CHILD_ID)?;
+    let mut inscription_id_to_content_type = wtx.open_table(INSCRIPTION_ID_TO_CONTENT_TYPE)?;
     let mut inscription_id_to_satpoint = wtx.open_table(INSCRIPTION_ID_TO_SATPOINT)?;
+    let mut inscription_id_to_script_hash = wtx.open_table(INSCRIPTION_ID_TO_SCRIPT_HASH)?;
     let mut inscription_number_to_inscription_id =
       wtx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
     let mut reinscription_id_to_seq_num = wtx.open_table(REINSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
     let mut sat_to_inscription_id = wtx.open_multimap_table(SAT_TO_INSCRIPTION_ID)?;
     let mut inscription_id_to_children = wtx.open_multimap_table(INSCRIPTION_ID_TO_CHILDREN)?;
     let mut satpoint_to_inscription_id = wtx.open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)?;
+    let mut script_hash_to_inscription_id =
+      wtx.open_multimap_table(SCRIPT_HASH_TO_INSCRIPTION_ID)?;
     let mut statistic_to_count = wtx.open_table(STATISTIC_TO_COUNT)?;
 
     let mut lost_sats = statistic_to_count
@@ -409,7 +510,10 @@ impl<'index> Updater<'_> {
     let mut inscription_updater = InscriptionUpdater::new(
       self.height,
       &mut inscription_id_to_children,
+      &mut inscription_id_and_child_number_to_child_id,
+      &mut inscription_id_to_content_type,
       &mut inscription_id_to_satpoint,
+      &mut inscription_id_to_script_hash,
       tx_out_receiver,
       &mut inscription_id_to_inscription_entry,
       lost_sats,
@@ -418,9 +522,11 @@ impl<'index> Updater<'_> {
       &mut reinscription_id_to_seq_num,
       &mut sat_to_inscription_id,
       &mut satpoint_to_inscription_id,
+      &mut script_hash_to_inscription_id,
       block.header.time,
       unbound_inscriptions,
       &mut tx_out_cache,
+      undo,
     )?;
 
     if self.index_sats {
@@ -447,13 +553,18 @@ impl<'index> Updater<'_> {
           let sat_ranges = match self.range_cache.remove(&key) {
             Some(sat_ranges) => {
               self.outputs_cached += 1;
+              if let Some(undo) = undo {
+                undo.record_removed(OUTPOINT_TO_SAT_RANGES, &key, &sat_ranges);
+              }
               sat_ranges
             }
-            None => outpoint_to_sat_ranges
-              .remove(&key)?
-              .ok_or_else(|| anyhow!("Could not find outpoint {} in index", input.previous_output))?
-              .value()
-              .to_vec(),
+            None => undo::remove(
+              undo,
+              OUTPOINT_TO_SAT_RANGES,
+              &mut outpoint_to_sat_ranges,
+              &key,
+            )?
+            .ok_or_else(|| anyhow!("Could not find outpoint {} in index", input.previous_output))?,
           };
 
           for chunk in sat_ranges.chunks_exact(11) {
@@ -470,6 +581,7 @@ impl<'index> Updater<'_> {
           &mut outputs_in_block,
           &mut inscription_updater,
           index_inscriptions,
+          undo,
         )?;
 
         coinbase_inputs.extend(input_sat_ranges);
@@ -485,18 +597,25 @@ impl<'index> Updater<'_> {
           &mut outputs_in_block,
           &mut inscription_updater,
           index_inscriptions,
+          undo,
         )?;
       }
 
       if !coinbase_inputs.is_empty() {
-        let mut lost_sat_ranges = outpoint_to_sat_ranges
-          .remove(&OutPoint::null().store())?
-          .map(|ranges| ranges.value().to_vec())
-          .unwrap_or_default();
+        let mut lost_sat_ranges = undo::remove(
+          undo,
+          OUTPOINT_TO_SAT_RANGES,
+          &mut outpoint_to_sat_ranges,
+          &OutPoint::null().store(),
+        )?
+        .unwrap_or_default();
 
         for (start, end) in coinbase_inputs {
           if !Sat(start).is_common() {
-            sat_to_satpoint.insert(
+            undo::insert(
+              undo,
+              SAT_TO_SATPOINT,
+              &mut sat_to_satpoint,
               &start,
               &SatPoint {
                 outpoint: OutPoint::null(),
@@ -511,7 +630,13 @@ impl<'index> Updater<'_> {
           lost_sats += end - start;
         }
 
-        outpoint_to_sat_ranges.insert(&OutPoint::null().store(), lost_sat_ranges.as_slice())?;
+        undo::insert(
+          undo,
+          OUTPOINT_TO_SAT_RANGES,
+          &mut outpoint_to_sat_ranges,
+          &OutPoint::null().store(),
+          lost_sat_ranges.as_slice(),
+        )?;
       }
     } else {
       for (tx, txid) in block.txdata.iter().skip(1).chain(block.txdata.first()) {
@@ -523,6 +648,7 @@ impl<'index> Updater<'_> {
       &mut height_to_last_inscription_number,
       &inscription_updater,
       index_inscriptions,
+      undo,
     )?;
 
     let lost_sats = inscription_updater.lost_sats;
@@ -533,29 +659,92 @@ impl<'index> Updater<'_> {
     for (outpoint, tx_out) in tx_out_cache {
       let mut entry = Vec::new();
       tx_out.consensus_encode(&mut entry)?;
-      outpoint_to_entry.insert(&outpoint.store(), entry.as_slice())?;
+      undo::insert(
+        undo,
+        OUTPOINT_TO_ENTRY,
+        &mut outpoint_to_entry,
+        &outpoint.store(),
+        entry.as_slice(),
+      )?;
     }
 
     std::mem::drop(inscription_id_to_inscription_entry);
     std::mem::drop(outpoint_to_entry);
 
+    index
+      .metrics()
+      .observe_block_stage(BlockStage::Inscriptions, start.elapsed());
+
     // Create a protocol manager to index the block of brc20, brc20s data.
     let config = ProtocolConfig::new_with_options(&index.options);
-    ProtocolManager::new(&index.client, &StateReadWrite::new(wtx), &config).index_block(
+    let receipts = ProtocolManager::new(
+      &index.client,
+      &StateReadWrite::new(wtx).with_undo_log(undo),
+      &config,
+      index.metrics(),
+    )
+    .index_block(
       BlockContext {
         network: index.get_chain_network(),
         blockheight: self.height,
         blocktime: block.header.time,
       },
-      &block,
+      &block.txdata,
       operations,
     )?;
 
-    statistic_to_count.insert(&Statistic::LostSats.key(), &lost_sats)?;
+    // Chain the receipts of this block onto the state hash of the previous one,
+    // so equal hashes at a height mean equal protocol history up to it.
+    let mut height_to_state_hash = wtx.open_table(HEIGHT_TO_STATE_HASH)?;
+    let previous = match self.height.checked_sub(1) {
+      Some(height) => height_to_state_hash.get(height)?.map(|hash| *hash.value()),
+      None => None,
+    };
+    let mut state = sha256::Hash::engine();
+    state.input(&previous.unwrap_or_default());
+    state.input(receipts.as_byte_array());
+    undo::insert(
+      undo,
+      HEIGHT_TO_STATE_HASH,
+      &mut height_to_state_hash,
+      &self.height,
+      sha256::Hash::from_engine(state).as_byte_array(),
+    )?;
+
+    undo::insert(
+      undo,
+      STATISTIC_TO_COUNT,
+      &mut statistic_to_count,
+      &Statistic::LostSats.key(),
+      &lost_sats,
+    )?;
+
+    undo::insert(
+      undo,
+      STATISTIC_TO_COUNT,
+      &mut statistic_to_count,
+      &Statistic::UnboundInscriptions.key(),
+      &unbound_inscriptions,
+    )?;
 
-    statistic_to_count.insert(&Statistic::UnboundInscriptions.key(), &unbound_inscriptions)?;
+    undo::insert(
+      undo,
+      HEIGHT_TO_BLOCK_HASH,
+      &mut height_to_block_hash,
+      &self.height,
+      &block.header.block_hash().store(),
+    )?;
 
-    height_to_block_hash.insert(&self.height, &block.header.block_hash().store())?;
+    if undo.is_some() {
+      undo_log.save(wtx, self.height, index.options.max_reorg_depth)?;
+    }
+
+    if self.index_sats {
+      index.metrics().record_sat_range_cache(
+        self.range_cache.len().try_into().unwrap(),
+        self.outputs_cached - outputs_cached,
+      );
+    }
 
     self.height += 1;
     self.outputs_traversed += outputs_in_block;
@@ -578,6 +767,7 @@ impl<'index> Updater<'_> {
     outputs_traversed: &mut u64,
     inscription_updater: &mut InscriptionUpdater,
     index_inscriptions: bool,
+    undo: Option<&UndoLog>,
   ) -> Result {
     if index_inscriptions {
       inscription_updater.index_transaction_inscriptions(tx, txid, Some(input_sat_ranges))?;
@@ -597,7 +787,10 @@ impl<'index> Updater<'_> {
           .ok_or_else(|| anyhow!("insufficient inputs for transaction outputs"))?;
 
         if !Sat(range.0).is_common() {
-          sat_to_satpoint.insert(
+          undo::insert(
+            undo,
+            SAT_TO_SATPOINT,
+            sat_to_satpoint,
             &range.0,
             &SatPoint {
               outpoint,
@@ -627,6 +820,9 @@ impl<'index> Updater<'_> {
 
       *outputs_traversed += 1;
 
+      if let Some(undo) = undo {
+        undo.record_created(OUTPOINT_TO_SAT_RANGES, &outpoint.store());
+      }
       self.range_cache.insert(outpoint.store(), sats);
       self.outputs_inserted_since_flush += 1;
     }
@@ -639,12 +835,16 @@ impl<'index> Updater<'_> {
     height_to_inscription_number: &mut Table<u64, (i64, i64)>,
     inscription_updater: &InscriptionUpdater,
     index_inscription: bool,
+    undo: Option<&UndoLog>,
   ) -> Result {
     if !index_inscription {
       return Ok(());
     }
 
-    height_to_inscription_number.insert(
+    undo::insert(
+      undo,
+      HEIGHT_TO_LAST_INSCRIPTION_NUMBER,
+      height_to_inscription_number,
       &self.height,
       (
         inscription_updater.next_number,
@@ -655,7 +855,26 @@ impl<'index> Updater<'_> {
     Ok(())
   }
 
+  /// Blocks near the chain tip are committed one by one, deeper blocks are
+  /// batched until one of the configured limits is reached.
+  fn should_commit(&self, uncommitted: u64, last_commit: Instant, chain_tip: u64) -> bool {
+    let options = &self.index.options;
+
+    uncommitted >= options.commit_interval
+      || self.height + CHAIN_TIP_DISTANCE >= chain_tip
+      || options
+        .commit_interval_seconds
+        .map(|seconds| last_commit.elapsed() >= Duration::from_secs(seconds))
+        .unwrap_or_default()
+      || options
+        .commit_cache_entries
+        .map(|entries| self.range_cache.len() >= entries)
+        .unwrap_or_default()
+  }
+
   fn commit(&mut self, wtx: WriteTransaction) -> Result {
+    let start = Instant::now();
+
     log::info!(
       "Committing at block height {}, {} outputs traversed, {} in map, {} cached",
       self.height,
@@ -689,6 +908,11 @@ impl<'index> Updater<'_> {
 
     wtx.commit()?;
 
+    self
+      .index
+      .metrics()
+      .observe_block_stage(BlockStage::Commit, start.elapsed());
+
     Reorg::update_savepoints(self.index, self.height)?;
 
     Ok(())
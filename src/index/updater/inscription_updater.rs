@@ -31,7 +31,11 @@ pub(super) struct InscriptionUpdater<'a, 'db, 'tx> {
   height: u64,
   id_to_children:
     &'a mut MultimapTable<'db, 'tx, &'static InscriptionIdValue, &'static InscriptionIdValue>,
+  id_and_child_number_to_child_id:
+    &'a mut Table<'db, 'tx, (&'static InscriptionIdValue, i64), &'static InscriptionIdValue>,
+  id_to_content_type: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static str>,
   id_to_satpoint: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static SatPointValue>,
+  id_to_script_hash: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static ScriptHashValue>,
   tx_out_receiver: &'a mut Receiver<TxOut>,
   id_to_entry: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, InscriptionEntryValue>,
   pub(super) lost_sats: u64,
@@ -44,9 +48,12 @@ pub(super) struct InscriptionUpdater<'a, 'db, 'tx> {
   sat_to_inscription_id: &'a mut MultimapTable<'db, 'tx, u64, &'static InscriptionIdValue>,
   satpoint_to_id:
     &'a mut MultimapTable<'db, 'tx, &'static SatPointValue, &'static InscriptionIdValue>,
+  script_hash_to_id:
+    &'a mut MultimapTable<'db, 'tx, &'static ScriptHashValue, &'static InscriptionIdValue>,
   timestamp: u32,
   pub(super) unbound_inscriptions: u64,
   tx_out_cache: &'a mut HashMap<OutPoint, TxOut>,
+  undo: Option<&'a UndoLog>,
 }
 
 impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
@@ -58,7 +65,20 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
       &'static InscriptionIdValue,
       &'static InscriptionIdValue,
     >,
+    id_and_child_number_to_child_id: &'a mut Table<
+      'db,
+      'tx,
+      (&'static InscriptionIdValue, i64),
+      &'static InscriptionIdValue,
+    >,
+    id_to_content_type: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static str>,
     id_to_satpoint: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static SatPointValue>,
+    id_to_script_hash: &'a mut Table<
+      'db,
+      'tx,
+      &'static InscriptionIdValue,
+      &'static ScriptHashValue,
+    >,
     tx_out_receiver: &'a mut Receiver<TxOut>,
     id_to_entry: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, InscriptionEntryValue>,
     lost_sats: u64,
@@ -72,9 +92,16 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
       &'static SatPointValue,
       &'static InscriptionIdValue,
     >,
+    script_hash_to_id: &'a mut MultimapTable<
+      'db,
+      'tx,
+      &'static ScriptHashValue,
+      &'static InscriptionIdValue,
+    >,
     timestamp: u32,
     unbound_inscriptions: u64,
     tx_out_cache: &'a mut HashMap<OutPoint, TxOut>,
+    undo: Option<&'a UndoLog>,
   ) -> Result<Self> {
     let next_cursed_number = number_to_id
       .iter()?
@@ -95,7 +122,10 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
       operations: HashMap::new(),
       height,
       id_to_children,
+      id_and_child_number_to_child_id,
+      id_to_content_type,
       id_to_satpoint,
+      id_to_script_hash,
       tx_out_receiver,
       id_to_entry,
       lost_sats,
@@ -107,9 +137,11 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
       reinscription_id_to_seq_num,
       sat_to_inscription_id,
       satpoint_to_id,
+      script_hash_to_id,
       timestamp,
       unbound_inscriptions,
       tx_out_cache,
+      undo,
     })
   }
 
@@ -202,9 +234,13 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
           log::info!("processing reinscription {inscription_id} on sat {:?}: sequence number {seq_num}, inscribed offsets {:?}", sat, inscribed_offsets);
 
           // if reinscription track its ordering
-          self
-            .reinscription_id_to_seq_num
-            .insert(&inscription_id.store(), seq_num)?;
+          undo::insert(
+            self.undo,
+            REINSCRIPTION_ID_TO_SEQUENCE_NUMBER,
+            self.reinscription_id_to_seq_num,
+            &inscription_id.store(),
+            seq_num,
+          )?;
 
           Some(Curse::Reinscription)
         } else {
@@ -368,6 +404,7 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
           input_sat_ranges,
           inscriptions.next().unwrap(),
           new_satpoint,
+          Some(&tx_out.script_pubkey),
         )?;
       }
 
@@ -388,7 +425,7 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
           outpoint: OutPoint::null(),
           offset: self.lost_sats + flotsam.offset - output_value,
         };
-        self.update_inscription_location(input_sat_ranges, flotsam, new_satpoint)?;
+        self.update_inscription_location(input_sat_ranges, flotsam, new_satpoint, None)?;
       }
       self.lost_sats += self.reward - output_value;
       Ok(())
@@ -427,20 +464,24 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
     input_sat_ranges: Option<&VecDeque<(u64, u64)>>,
     flotsam: Flotsam,
     new_satpoint: SatPoint,
+    script_pubkey: Option<&Script>,
   ) -> Result {
     let inscription_id = flotsam.inscription_id.store();
     let unbound = match flotsam.origin {
       Origin::Old => {
-        self
-          .satpoint_to_id
-          .remove_all(&flotsam.old_satpoint.store())?;
+        undo::multimap_remove_all(
+          self.undo,
+          SATPOINT_TO_INSCRIPTION_ID,
+          self.satpoint_to_id,
+          &flotsam.old_satpoint.store(),
+        )?;
         false
       }
       Origin::New {
         cursed,
         fee,
         parent,
-        inscription: _,
+        ref inscription,
         unbound,
       } => {
         let number = if cursed {
@@ -455,7 +496,13 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
           next_number
         };
 
-        self.number_to_id.insert(number, &inscription_id)?;
+        undo::insert(
+          self.undo,
+          INSCRIPTION_NUMBER_TO_INSCRIPTION_ID,
+          self.number_to_id,
+          number,
+          &inscription_id,
+        )?;
 
         let sat = if unbound {
           None
@@ -464,10 +511,19 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
         };
 
         if let Some(Sat(n)) = sat {
-          self.sat_to_inscription_id.insert(&n, &inscription_id)?;
+          undo::multimap_insert(
+            self.undo,
+            SAT_TO_INSCRIPTION_ID,
+            self.sat_to_inscription_id,
+            &n,
+            &inscription_id,
+          )?;
         }
 
-        self.id_to_entry.insert(
+        undo::insert(
+          self.undo,
+          INSCRIPTION_ID_TO_INSCRIPTION_ENTRY,
+          self.id_to_entry,
           &inscription_id,
           &InscriptionEntry {
             fee,
@@ -480,10 +536,32 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
           .store(),
         )?;
 
+        if let Some(content_type) = inscription.content_type() {
+          undo::insert(
+            self.undo,
+            INSCRIPTION_ID_TO_CONTENT_TYPE,
+            self.id_to_content_type,
+            &inscription_id,
+            content_type,
+          )?;
+        }
+
         if let Some(parent) = parent {
-          self
-            .id_to_children
-            .insert(&parent.store(), &inscription_id)?;
+          undo::multimap_insert(
+            self.undo,
+            INSCRIPTION_ID_TO_CHILDREN,
+            self.id_to_children,
+            &parent.store(),
+            &inscription_id,
+          )?;
+
+          undo::insert(
+            self.undo,
+            INSCRIPTION_ID_AND_CHILD_NUMBER_TO_CHILD_ID,
+            self.id_and_child_number_to_child_id,
+            (&parent.store(), number),
+            &inscription_id,
+          )?;
         }
 
         unbound
@@ -529,8 +607,54 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
         new_satpoint: Some(Entry::load(satpoint)),
       });
 
-    self.satpoint_to_id.insert(&satpoint, &inscription_id)?;
-    self.id_to_satpoint.insert(&inscription_id, &satpoint)?;
+    undo::multimap_insert(
+      self.undo,
+      SATPOINT_TO_INSCRIPTION_ID,
+      self.satpoint_to_id,
+      &satpoint,
+      &inscription_id,
+    )?;
+    undo::insert(
+      self.undo,
+      INSCRIPTION_ID_TO_SATPOINT,
+      self.id_to_satpoint,
+      &inscription_id,
+      &satpoint,
+    )?;
+
+    if let Some(script_hash) = undo::remove(
+      self.undo,
+      INSCRIPTION_ID_TO_SCRIPT_HASH,
+      self.id_to_script_hash,
+      &inscription_id,
+    )? {
+      undo::multimap_remove(
+        self.undo,
+        SCRIPT_HASH_TO_INSCRIPTION_ID,
+        self.script_hash_to_id,
+        &ScriptHashValue::try_from(script_hash.as_slice())?,
+        &inscription_id,
+      )?;
+    }
+
+    // unbound inscriptions and inscriptions lost to fees have no owner
+    if let Some(script_pubkey) = script_pubkey.filter(|_| !unbound) {
+      let script_hash = script_pubkey.script_hash().store();
+      undo::insert(
+        self.undo,
+        INSCRIPTION_ID_TO_SCRIPT_HASH,
+        self.id_to_script_hash,
+        &inscription_id,
+        &script_hash,
+      )?;
+      undo::multimap_insert(
+        self.undo,
+        SCRIPT_HASH_TO_INSCRIPTION_ID,
+        self.script_hash_to_id,
+        &script_hash,
+        &inscription_id,
+      )?;
+    }
 
     Ok(())
   }
@@ -0,0 +1,410 @@
+use {
+  super::{inscription_updater::InscriptionUpdater, *},
+  crate::okx::datastore::{
+    ord::{Action, DataStoreReadWrite, InscriptionOp},
+    StateOverlay, StateRWriter, StateReadOnly, StateReader,
+  },
+  bitcoin::locktime::absolute::LockTime,
+  std::time::{SystemTime, UNIX_EPOCH},
+};
+
+const SCRATCH_CACHE_SIZE: usize = 64 << 20;
+
+/// Scratch databases kept for later previews once they are done. Concurrent
+/// previews beyond this create their own and drop them afterwards.
+const IDLE_SCRATCH_DATABASES: usize = 4;
+
+/// The effects an unbroadcast transaction would have on the index if it were
+/// mined in the next block.
+#[derive(Debug, Serialize)]
+pub(crate) struct Preview {
+  pub(crate) txid: Txid,
+  pub(crate) inscriptions: Vec<PreviewInscription>,
+  pub(crate) brc20_receipts: Vec<brc20::Receipt>,
+  pub(crate) brc20s_receipts: Vec<brc20s::Receipt>,
+  pub(crate) brc20_balance_changes: Vec<BalanceChange<brc20::Balance>>,
+  pub(crate) brc20s_balance_changes: Vec<BalanceChange<brc20s::Balance>>,
+  /// BRC20S ticks the receipts and balance changes refer to, including any
+  /// the transaction deploys.
+  pub(crate) brc20s_ticks: Vec<brc20s::TickInfo>,
+  pub(crate) fee_inscriptions: Vec<InscriptionId>,
+  /// The latest indexed block, which the transaction is previewed on top of.
+  pub(crate) block: Option<(Height, BlockHash)>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PreviewInscription {
+  pub(crate) operation: InscriptionOp,
+  pub(crate) from: ScriptKey,
+  pub(crate) to: Option<ScriptKey>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct BalanceChange<B> {
+  pub(crate) script_key: ScriptKey,
+  pub(crate) before: B,
+  pub(crate) after: B,
+}
+
+/// Runs the inscription updater and the protocol executors over `unsigned`
+/// without writing to the index.
+///
+/// Inscriptions are tracked in a write transaction on a scratch database of
+/// its own, seeded with the rows the transaction's inputs touch and aborted
+/// afterwards. BRC20 and BRC20S operations are executed against a
+/// `StateOverlay` of the current state.
+pub(crate) fn preview(index: &Index, unsigned: &UnsignedTransaction) -> Result<Preview> {
+  let tx = &unsigned.transaction;
+  let txid = tx.txid();
+
+  if tx.is_coin_base() {
+    bail!("cannot preview a coinbase transaction");
+  }
+
+  let rtx = index.begin_read()?;
+  let block = rtx.latest_block()?;
+  let height = rtx.block_count()?;
+  let network = index.get_chain_network();
+  let timestamp = u32::try_from(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())?;
+
+  let prevouts = previous_outputs(index, &rtx.0, unsigned)?;
+
+  let idle = index.preview_databases.lock().unwrap().pop();
+  let database = match idle {
+    Some(database) => database,
+    // redb 1.3 has no in-memory backend, so scratch databases live in unnamed
+    // temporary files that are removed once they are dropped.
+    None => Database::builder()
+      .set_cache_size(SCRATCH_CACHE_SIZE)
+      .create_file(tempfile::tempfile()?)?,
+  };
+  let mut wtx = database.begin_write()?;
+  wtx.set_durability(redb::Durability::None);
+
+  let (lost_sats, unbound_inscriptions) = seed(&rtx.0, &wtx, &prevouts)?;
+
+  let mut tx_out_cache = HashMap::new();
+  let operations = {
+    let mut id_to_children = wtx.open_multimap_table(INSCRIPTION_ID_TO_CHILDREN)?;
+    let mut id_and_child_number_to_child_id =
+      wtx.open_table(INSCRIPTION_ID_AND_CHILD_NUMBER_TO_CHILD_ID)?;
+    let mut id_to_content_type = wtx.open_table(INSCRIPTION_ID_TO_CONTENT_TYPE)?;
+    let mut id_to_satpoint = wtx.open_table(INSCRIPTION_ID_TO_SATPOINT)?;
+    let mut id_to_script_hash = wtx.open_table(INSCRIPTION_ID_TO_SCRIPT_HASH)?;
+    let mut id_to_entry = wtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+    let mut number_to_id = wtx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
+    let mut outpoint_to_entry = wtx.open_table(OUTPOINT_TO_ENTRY)?;
+    let mut reinscription_id_to_seq_num = wtx.open_table(REINSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
+    let mut sat_to_inscription_id = wtx.open_multimap_table(SAT_TO_INSCRIPTION_ID)?;
+    let mut satpoint_to_id = wtx.open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)?;
+    let mut script_hash_to_id = wtx.open_multimap_table(SCRIPT_HASH_TO_INSCRIPTION_ID)?;
+    let (_, mut tx_out_receiver) = tokio::sync::mpsc::channel::<TxOut>(1);
+
+    let mut inscription_updater = InscriptionUpdater::new(
+      height,
+      &mut id_to_children,
+      &mut id_and_child_number_to_child_id,
+      &mut id_to_content_type,
+      &mut id_to_satpoint,
+      &mut id_to_script_hash,
+      &mut tx_out_receiver,
+      &mut id_to_entry,
+      lost_sats,
+      &mut number_to_id,
+      &mut outpoint_to_entry,
+      &mut reinscription_id_to_seq_num,
+      &mut sat_to_inscription_id,
+      &mut satpoint_to_id,
+      &mut script_hash_to_id,
+      timestamp,
+      unbound_inscriptions,
+      &mut tx_out_cache,
+      None,
+    )?;
+
+    inscription_updater.index_transaction_inscriptions(tx, txid, None)?;
+
+    // Inscriptions left over by the transaction go to fees, which a coinbase
+    // without outputs sends to the null outpoint.
+    let coinbase = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: OutPoint::null(),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::MAX,
+        witness: bitcoin::Witness::new(),
+      }],
+      output: Vec::new(),
+    };
+    inscription_updater.index_transaction_inscriptions(&coinbase, coinbase.txid(), None)?;
+
+    inscription_updater
+      .operations
+      .remove(&txid)
+      .unwrap_or_default()
+  };
+
+  wtx.abort()?;
+
+  {
+    let mut idle = index.preview_databases.lock().unwrap();
+    if idle.len() < IDLE_SCRATCH_DATABASES {
+      idle.push(database);
+    }
+  }
+
+  let state = StateReadOnly::new(&rtx.0);
+  let overlay = StateOverlay::new(&state);
+
+  for (outpoint, tx_out) in prevouts.iter().chain(&tx_out_cache) {
+    overlay.ord().set_outpoint_to_txout(*outpoint, tx_out)?;
+  }
+
+  for operation in &operations {
+    if let (Action::New { .. }, Some(number)) = (&operation.action, operation.inscription_number) {
+      overlay
+        .ord()
+        .set_inscription_number(operation.inscription_id, number);
+    }
+  }
+
+  let config = ProtocolConfig::new_with_options(&index.options);
+  ProtocolManager::new(&index.client, &overlay, &config, &Metrics::default()).index_block(
+    BlockContext {
+      network,
+      blockheight: height,
+      blocktime: timestamp,
+    },
+    &[(tx.clone(), txid)],
+    HashMap::from([(txid, operations.clone())]),
+  )?;
+
+  let brc20_receipts = overlay.brc20().get_transaction_receipts(&txid)?;
+  let brc20s_receipts = overlay.brc20s().get_transaction_receipts(&txid)?;
+
+  let inscriptions = operations
+    .iter()
+    .map(|operation| PreviewInscription {
+      operation: operation.clone(),
+      from: ScriptKey::from_script(
+        &prevouts[&operation.old_satpoint.outpoint].script_pubkey,
+        network,
+      ),
+      to: operation
+        .new_satpoint
+        .filter(|satpoint| satpoint.outpoint.txid == txid)
+        .map(|satpoint| {
+          ScriptKey::from_script(
+            &tx.output[usize::try_from(satpoint.outpoint.vout).unwrap()].script_pubkey,
+            network,
+          )
+        }),
+    })
+    .collect::<Vec<PreviewInscription>>();
+
+  let fee_inscriptions = operations
+    .iter()
+    .filter(|operation| {
+      operation
+        .new_satpoint
+        .map(|satpoint| satpoint.outpoint == OutPoint::null())
+        .unwrap_or_default()
+    })
+    .map(|operation| operation.inscription_id)
+    .collect();
+
+  let mut script_keys = Vec::new();
+  for script_key in inscriptions
+    .iter()
+    .flat_map(|inscription| [Some(&inscription.from), inscription.to.as_ref()])
+    .flatten()
+    .chain(
+      brc20_receipts
+        .iter()
+        .flat_map(|receipt| [&receipt.from, &receipt.to]),
+    )
+    .chain(
+      brc20s_receipts
+        .iter()
+        .flat_map(|receipt| [&receipt.from, &receipt.to]),
+    )
+  {
+    if !script_keys.contains(script_key) {
+      script_keys.push(script_key.clone());
+    }
+  }
+
+  let mut brc20_balance_changes = Vec::new();
+  let mut brc20s_balance_changes = Vec::new();
+  for script_key in script_keys {
+    let before = state.brc20().get_balances(&script_key)?;
+    for after in overlay.brc20().get_balances(&script_key)? {
+      let before = before
+        .iter()
+        .find(|balance| balance.tick == after.tick)
+        .cloned()
+        .unwrap_or_else(|| brc20::Balance::new(&after.tick));
+      if before != after {
+        brc20_balance_changes.push(BalanceChange {
+          script_key: script_key.clone(),
+          before,
+          after,
+        });
+      }
+    }
+
+    let before = state.brc20s().get_balances(&script_key)?;
+    for (tick_id, after) in overlay.brc20s().get_balances(&script_key)? {
+      let before = before
+        .iter()
+        .find(|(before_tick_id, _)| *before_tick_id == tick_id)
+        .map(|(_, balance)| *balance)
+        .unwrap_or_else(|| brc20s::Balance::new(tick_id));
+      if before != after {
+        brc20s_balance_changes.push(BalanceChange {
+          script_key: script_key.clone(),
+          before,
+          after,
+        });
+      }
+    }
+  }
+
+  let mut tick_ids = Vec::new();
+  for tick_id in brc20s_receipts
+    .iter()
+    .flat_map(brc20s::Receipt::tick_ids)
+    .chain(
+      brc20s_balance_changes
+        .iter()
+        .map(|change| change.after.tick_id),
+    )
+  {
+    if !tick_ids.contains(&tick_id) {
+      tick_ids.push(tick_id);
+    }
+  }
+
+  let mut brc20s_ticks = Vec::new();
+  for tick_id in tick_ids {
+    if let Some(tick_info) = overlay.brc20s().get_tick_info(&tick_id)? {
+      brc20s_ticks.push(tick_info);
+    }
+  }
+
+  Ok(Preview {
+    txid,
+    inscriptions,
+    brc20_receipts,
+    brc20s_receipts,
+    brc20_balance_changes,
+    brc20s_balance_changes,
+    brc20s_ticks,
+    fee_inscriptions,
+    block,
+  })
+}
+
+/// Looks up the outputs spent by `unsigned`, preferring the index, then the
+/// PSBT, then the node.
+fn previous_outputs(
+  index: &Index,
+  rtx: &ReadTransaction,
+  unsigned: &UnsignedTransaction,
+) -> Result<BTreeMap<OutPoint, TxOut>> {
+  let outpoint_to_entry = rtx.open_table(OUTPOINT_TO_ENTRY)?;
+
+  let mut prevouts = BTreeMap::new();
+  for tx_in in &unsigned.transaction.input {
+    let outpoint = tx_in.previous_output;
+
+    let tx_out = match Index::transaction_output_by_outpoint(&outpoint_to_entry, outpoint)? {
+      Some(tx_out) => tx_out,
+      None => match unsigned.prevouts.get(&outpoint) {
+        Some(tx_out) => tx_out.clone(),
+        None => index
+          .get_transaction(outpoint.txid)?
+          .and_then(|tx| {
+            tx.output
+              .into_iter()
+              .nth(usize::try_from(outpoint.vout).unwrap())
+          })
+          .ok_or_else(|| anyhow!("previous output {outpoint} not found"))?,
+      },
+    };
+
+    prevouts.insert(outpoint, tx_out);
+  }
+
+  Ok(prevouts)
+}
+
+/// Copies the index rows the inscription updater reads for `prevouts` into
+/// the scratch database, returning the lost sats and unbound inscriptions
+/// statistics.
+fn seed(
+  rtx: &ReadTransaction,
+  wtx: &WriteTransaction,
+  prevouts: &BTreeMap<OutPoint, TxOut>,
+) -> Result<(u64, u64)> {
+  let base_satpoint_to_id = rtx.open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)?;
+  let base_id_to_entry = rtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+  let base_reinscription_id_to_seq_num = rtx.open_table(REINSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
+  let base_number_to_id = rtx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
+  let base_statistic_to_count = rtx.open_table(STATISTIC_TO_COUNT)?;
+
+  let mut satpoint_to_id = wtx.open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)?;
+  let mut id_to_entry = wtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+  let mut reinscription_id_to_seq_num = wtx.open_table(REINSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
+  let mut number_to_id = wtx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
+  let mut outpoint_to_entry = wtx.open_table(OUTPOINT_TO_ENTRY)?;
+
+  for (outpoint, tx_out) in prevouts {
+    let mut entry = Vec::new();
+    tx_out.consensus_encode(&mut entry)?;
+    outpoint_to_entry.insert(&outpoint.store(), entry.as_slice())?;
+
+    for (satpoint, inscription_id) in
+      Index::inscriptions_on_output_unordered(&base_satpoint_to_id, *outpoint)?
+    {
+      let inscription_id = inscription_id.store();
+      satpoint_to_id.insert(&satpoint.store(), &inscription_id)?;
+
+      if let Some(entry) = base_id_to_entry.get(&inscription_id)? {
+        id_to_entry.insert(&inscription_id, entry.value())?;
+      }
+
+      if let Some(sequence_number) = base_reinscription_id_to_seq_num.get(&inscription_id)? {
+        reinscription_id_to_seq_num.insert(&inscription_id, sequence_number.value())?;
+      }
+    }
+  }
+
+  // The updater numbers new inscriptions from the lowest and highest existing
+  // numbers.
+  for result in [
+    base_number_to_id.iter()?.next(),
+    base_number_to_id.iter()?.next_back(),
+  ]
+  .into_iter()
+  .flatten()
+  {
+    let (number, inscription_id) = result?;
+    number_to_id.insert(number.value(), inscription_id.value())?;
+  }
+
+  let statistic = |statistic: Statistic| -> Result<u64> {
+    Ok(
+      base_statistic_to_count
+        .get(&statistic.key())?
+        .map(|count| count.value())
+        .unwrap_or(0),
+    )
+  };
+
+  Ok((
+    statistic(Statistic::LostSats)?,
+    statistic(Statistic::UnboundInscriptions)?,
+  ))
+}
@@ -39,7 +39,6 @@ pub(crate) struct TransactionInscription {
 }
 
 impl Inscription {
-  #[cfg(test)]
   pub(crate) fn new(content_type: Option<Vec<u8>>, body: Option<Vec<u8>>) -> Self {
     Self {
       content_type,
@@ -72,7 +71,6 @@ impl Inscription {
     result
   }
 
-  #[allow(unused)]
   pub(crate) fn from_file(
     chain: Chain,
     path: impl AsRef<Path>,
@@ -99,7 +97,6 @@ impl Inscription {
     })
   }
 
-  #[allow(unused)]
   fn append_reveal_script_to_builder(&self, mut builder: script::Builder) -> script::Builder {
     builder = builder
       .push_opcode(opcodes::OP_FALSE)
@@ -128,7 +125,6 @@ impl Inscription {
     builder.push_opcode(opcodes::all::OP_ENDIF)
   }
 
-  #[allow(unused)]
   pub(crate) fn append_reveal_script(&self, builder: script::Builder) -> ScriptBuf {
     self.append_reveal_script_to_builder(builder).into_script()
   }
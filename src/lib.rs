@@ -19,6 +19,7 @@ use {
     degree::Degree,
     deserialize_from_str::DeserializeFromStr,
     epoch::Epoch,
+    fee_rate::FeeRate,
     height::Height,
     index::{Index, List},
     inscription_id::InscriptionId,
@@ -27,6 +28,7 @@ use {
     representation::Representation,
     subcommand::{Subcommand, SubcommandResult},
     tally::Tally,
+    unsigned_transaction::UnsignedTransaction,
   },
   anyhow::{anyhow, bail, Context, Error},
   bip39::Mnemonic,
@@ -100,12 +102,14 @@ mod decimal;
 mod degree;
 mod deserialize_from_str;
 mod epoch;
+mod fee_rate;
 mod height;
 mod index;
 mod inscription;
 pub mod inscription_id;
 mod logger;
 mod media;
+mod metrics;
 mod object;
 mod okx;
 mod options;
@@ -117,6 +121,7 @@ mod sat_point;
 pub mod subcommand;
 mod tally;
 pub mod templates;
+mod unsigned_transaction;
 
 type Result<T = (), E = Error> = std::result::Result<T, E>;
 
@@ -0,0 +1,317 @@
+use {
+  super::*,
+  std::{collections::BTreeMap, fmt::Write},
+};
+
+/// Upper bounds of histogram buckets in seconds, the Prometheus client defaults.
+const BUCKETS: [f64; 11] = [
+  0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// A stage of indexing a block, timed separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum BlockStage {
+  Fetch,
+  Inscriptions,
+  ProtocolResolve,
+  ProtocolExecute,
+  Commit,
+}
+
+impl BlockStage {
+  fn name(self) -> &'static str {
+    match self {
+      Self::Fetch => "fetch",
+      Self::Inscriptions => "inscriptions",
+      Self::ProtocolResolve => "protocol_resolve",
+      Self::ProtocolExecute => "protocol_execute",
+      Self::Commit => "commit",
+    }
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+  buckets: [u64; BUCKETS.len()],
+  count: u64,
+  sum: f64,
+}
+
+impl Histogram {
+  fn observe(&mut self, duration: Duration) {
+    let seconds = duration.as_secs_f64();
+    for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS) {
+      if seconds <= bound {
+        *bucket += 1;
+      }
+    }
+    self.count += 1;
+    self.sum += seconds;
+  }
+}
+
+#[derive(Debug, Default)]
+struct State {
+  chain_height: Option<u64>,
+  block_stages: BTreeMap<BlockStage, Histogram>,
+  protocol_messages: BTreeMap<&'static str, u64>,
+  reorgs: BTreeMap<&'static str, u64>,
+  sat_range_cache_entries: u64,
+  sat_range_cache_hits: u64,
+  api_requests: BTreeMap<(String, String, u16), Histogram>,
+}
+
+/// Metrics collects indexer and API measurements and renders them in the
+/// Prometheus text exposition format.
+#[derive(Debug, Default)]
+pub(crate) struct Metrics {
+  state: Mutex<State>,
+}
+
+impl Metrics {
+  fn state(&self) -> std::sync::MutexGuard<'_, State> {
+    self.state.lock().unwrap()
+  }
+
+  pub(crate) fn set_chain_height(&self, height: u64) {
+    self.state().chain_height = Some(height);
+  }
+
+  pub(crate) fn observe_block_stage(&self, stage: BlockStage, duration: Duration) {
+    self
+      .state()
+      .block_stages
+      .entry(stage)
+      .or_default()
+      .observe(duration);
+  }
+
+  pub(crate) fn add_protocol_messages(&self, protocol: &'static str, messages: u64) {
+    *self.state().protocol_messages.entry(protocol).or_default() += messages;
+  }
+
+  pub(crate) fn increment_reorgs(&self, kind: &'static str) {
+    *self.state().reorgs.entry(kind).or_default() += 1;
+  }
+
+  pub(crate) fn record_sat_range_cache(&self, entries: u64, hits: u64) {
+    let mut state = self.state();
+    state.sat_range_cache_entries = entries;
+    state.sat_range_cache_hits += hits;
+  }
+
+  pub(crate) fn observe_api_request(
+    &self,
+    route: &str,
+    method: &str,
+    status: u16,
+    duration: Duration,
+  ) {
+    self
+      .state()
+      .api_requests
+      .entry((route.into(), method.into(), status))
+      .or_default()
+      .observe(duration);
+  }
+
+  /// Renders every metric, together with the database figures read by the caller.
+  pub(crate) fn render(&self, database: &DatabaseMetrics) -> String {
+    let state = self.state();
+    let mut out = String::new();
+
+    gauge(
+      &mut out,
+      "ord_index_height",
+      "Height of the last indexed block.",
+      database.index_height,
+    );
+    gauge(
+      &mut out,
+      "ord_chain_height",
+      "Height of the chain tip reported by bitcoind at the last index update.",
+      state.chain_height,
+    );
+
+    header(
+      &mut out,
+      "ord_block_stage_duration_seconds",
+      "Time spent on each stage of indexing a block.",
+      "histogram",
+    );
+    for (stage, histogram) in &state.block_stages {
+      histogram_samples(
+        &mut out,
+        "ord_block_stage_duration_seconds",
+        &format!("stage=\"{}\"", stage.name()),
+        histogram,
+      );
+    }
+
+    header(
+      &mut out,
+      "ord_protocol_messages_total",
+      "Protocol messages executed, by protocol.",
+      "counter",
+    );
+    for (protocol, messages) in &state.protocol_messages {
+      writeln!(
+        out,
+        "ord_protocol_messages_total{{protocol=\"{protocol}\"}} {messages}"
+      )
+      .unwrap();
+    }
+
+    header(
+      &mut out,
+      "ord_reorgs_total",
+      "Reorgs detected by the indexer, by whether they could be recovered from.",
+      "counter",
+    );
+    for (kind, reorgs) in &state.reorgs {
+      writeln!(out, "ord_reorgs_total{{kind=\"{kind}\"}} {reorgs}").unwrap();
+    }
+
+    gauge(
+      &mut out,
+      "ord_database_cache_size_bytes",
+      "Size of the redb page cache.",
+      Some(database.cache_size),
+    );
+    gauge(
+      &mut out,
+      "ord_database_file_size_bytes",
+      "Size of the index file.",
+      database.file_size,
+    );
+    gauge(
+      &mut out,
+      "ord_sat_range_cache_entries",
+      "Sat range entries held in memory, not yet written to the database.",
+      Some(state.sat_range_cache_entries),
+    );
+    header(
+      &mut out,
+      "ord_sat_range_cache_hits_total",
+      "Spent outputs whose sat ranges were found in memory instead of the database.",
+      "counter",
+    );
+    writeln!(
+      out,
+      "ord_sat_range_cache_hits_total {}",
+      state.sat_range_cache_hits
+    )
+    .unwrap();
+
+    header(
+      &mut out,
+      "ord_api_request_duration_seconds",
+      "Time spent serving HTTP requests, by route, method and status.",
+      "histogram",
+    );
+    for ((route, method, status), histogram) in &state.api_requests {
+      histogram_samples(
+        &mut out,
+        "ord_api_request_duration_seconds",
+        &format!(
+          "route=\"{}\",method=\"{method}\",status=\"{status}\"",
+          escape_label(route)
+        ),
+        histogram,
+      );
+    }
+
+    out
+  }
+}
+
+/// Figures read from the index when metrics are rendered.
+pub(crate) struct DatabaseMetrics {
+  pub(crate) index_height: Option<u64>,
+  pub(crate) cache_size: usize,
+  pub(crate) file_size: Option<u64>,
+}
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+  writeln!(out, "# HELP {name} {help}").unwrap();
+  writeln!(out, "# TYPE {name} {kind}").unwrap();
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: Option<impl Display>) {
+  header(out, name, help, "gauge");
+  if let Some(value) = value {
+    writeln!(out, "{name} {value}").unwrap();
+  }
+}
+
+fn histogram_samples(out: &mut String, name: &str, labels: &str, histogram: &Histogram) {
+  for (bound, count) in BUCKETS.iter().zip(histogram.buckets) {
+    writeln!(out, "{name}_bucket{{{labels},le=\"{bound}\"}} {count}").unwrap();
+  }
+  writeln!(
+    out,
+    "{name}_bucket{{{labels},le=\"+Inf\"}} {}",
+    histogram.count
+  )
+  .unwrap();
+  writeln!(out, "{name}_sum{{{labels}}} {}", histogram.sum).unwrap();
+  writeln!(out, "{name}_count{{{labels}}} {}", histogram.count).unwrap();
+}
+
+fn escape_label(value: &str) -> String {
+  value
+    .replace('\\', "\\\\")
+    .replace('"', "\\\"")
+    .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, pretty_assertions::assert_eq};
+
+  #[test]
+  fn render() {
+    let metrics = Metrics::default();
+    metrics.set_chain_height(12);
+    metrics.observe_block_stage(BlockStage::Commit, Duration::from_millis(30));
+    metrics.observe_block_stage(BlockStage::Commit, Duration::from_secs(20));
+    metrics.add_protocol_messages("brc20", 3);
+    metrics.add_protocol_messages("brc20", 2);
+    metrics.increment_reorgs("recoverable");
+    metrics.record_sat_range_cache(7, 4);
+    metrics.observe_api_request("/tx/:txid", "GET", 200, Duration::from_millis(1));
+
+    let rendered = metrics.render(&DatabaseMetrics {
+      index_height: Some(10),
+      cache_size: 1024,
+      file_size: None,
+    });
+
+    for line in [
+      "ord_index_height 10",
+      "ord_chain_height 12",
+      "ord_block_stage_duration_seconds_bucket{stage=\"commit\",le=\"0.025\"} 0",
+      "ord_block_stage_duration_seconds_bucket{stage=\"commit\",le=\"0.05\"} 1",
+      "ord_block_stage_duration_seconds_bucket{stage=\"commit\",le=\"+Inf\"} 2",
+      "ord_block_stage_duration_seconds_sum{stage=\"commit\"} 20.03",
+      "ord_block_stage_duration_seconds_count{stage=\"commit\"} 2",
+      "ord_protocol_messages_total{protocol=\"brc20\"} 5",
+      "ord_reorgs_total{kind=\"recoverable\"} 1",
+      "ord_database_cache_size_bytes 1024",
+      "ord_sat_range_cache_entries 7",
+      "ord_sat_range_cache_hits_total 4",
+      "ord_api_request_duration_seconds_count{route=\"/tx/:txid\",method=\"GET\",status=\"200\"} 1",
+    ] {
+      assert!(rendered.lines().any(|l| l == line), "missing `{line}`");
+    }
+
+    assert!(!rendered
+      .lines()
+      .any(|l| l.starts_with("ord_database_file_size_bytes ")));
+  }
+
+  #[test]
+  fn escape_label_values() {
+    assert_eq!(escape_label("a\"b\\c\n"), "a\\\"b\\\\c\\n");
+  }
+}
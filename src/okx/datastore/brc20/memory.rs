@@ -0,0 +1,357 @@
+use super::{
+  redb::{max_script_tick_key, min_script_tick_key, script_tick_key, tick_key},
+  Balance, DataStoreReadOnly, DataStoreReadWrite, Receipt, ReceiptHistory, ScriptKey, Tick,
+  TokenInfo, TransferInfo, TransferableLog,
+};
+use crate::okx::datastore::redb::receipt_key;
+use crate::InscriptionId;
+use bitcoin::Txid;
+use redb::TableHandle;
+use std::{
+  cell::RefCell,
+  collections::{BTreeMap, HashMap},
+  convert::Infallible,
+};
+
+/// MemoryStore is an in-memory implementation of the BRC20 datastore traits.
+///
+/// It keeps the same key layout and bincode encoding as the redb tables, so range
+/// queries return results in the same order as the persistent store.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+  balances: RefCell<BTreeMap<Vec<u8>, Vec<u8>>>,
+  tokens: RefCell<BTreeMap<Vec<u8>, Vec<u8>>>,
+  events: RefCell<HashMap<Txid, Vec<u8>>>,
+  transferable_logs: RefCell<BTreeMap<Vec<u8>, Vec<u8>>>,
+  inscribe_transfer: RefCell<HashMap<InscriptionId, Vec<u8>>>,
+  receipt_histories: RefCell<BTreeMap<(String, Vec<u8>), Txid>>,
+}
+
+impl MemoryStore {
+  pub fn new() -> Self {
+    Self::default()
+  }
+}
+
+impl DataStoreReadOnly for MemoryStore {
+  type Error = Infallible;
+
+  fn get_balances(&self, script_key: &ScriptKey) -> Result<Vec<Balance>, Self::Error> {
+    Ok(
+      self
+        .balances
+        .borrow()
+        .range(min_script_tick_key(script_key)..=max_script_tick_key(script_key))
+        .map(|(_, data)| bincode::deserialize::<Balance>(data).unwrap())
+        .collect(),
+    )
+  }
+
+  fn get_balance(
+    &self,
+    script_key: &ScriptKey,
+    tick: &Tick,
+  ) -> Result<Option<Balance>, Self::Error> {
+    Ok(
+      self
+        .balances
+        .borrow()
+        .get(&script_tick_key(script_key, tick))
+        .map(|v| bincode::deserialize::<Balance>(v).unwrap()),
+    )
+  }
+
+  fn get_token_info(&self, tick: &Tick) -> Result<Option<TokenInfo>, Self::Error> {
+    Ok(
+      self
+        .tokens
+        .borrow()
+        .get(&tick_key(tick))
+        .map(|v| bincode::deserialize::<TokenInfo>(v).unwrap()),
+    )
+  }
+
+  fn get_transaction_receipts(&self, txid: &Txid) -> Result<Vec<Receipt>, Self::Error> {
+    Ok(self.events.borrow().get(txid).map_or(Vec::new(), |v| {
+      bincode::deserialize::<Vec<Receipt>>(v).unwrap()
+    }))
+  }
+
+  fn get_transferable(&self, script: &ScriptKey) -> Result<Vec<TransferableLog>, Self::Error> {
+    Ok(
+      self
+        .transferable_logs
+        .borrow()
+        .range(min_script_tick_key(script)..=max_script_tick_key(script))
+        .flat_map(|(_, v)| bincode::deserialize::<Vec<TransferableLog>>(v).unwrap())
+        .collect(),
+    )
+  }
+
+  fn get_transferable_by_tick(
+    &self,
+    script: &ScriptKey,
+    tick: &Tick,
+  ) -> Result<Vec<TransferableLog>, Self::Error> {
+    Ok(
+      self
+        .transferable_logs
+        .borrow()
+        .get(&script_tick_key(script, tick))
+        .map_or(Vec::new(), |v| {
+          bincode::deserialize::<Vec<TransferableLog>>(v).unwrap()
+        }),
+    )
+  }
+
+  fn get_transferable_by_id(
+    &self,
+    script: &ScriptKey,
+    inscription_id: &InscriptionId,
+  ) -> Result<Option<TransferableLog>, Self::Error> {
+    Ok(
+      self
+        .get_transferable(script)?
+        .into_iter()
+        .find(|log| log.inscription_id == *inscription_id),
+    )
+  }
+
+  fn get_inscribe_transfer_inscription(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<TransferInfo>, Self::Error> {
+    Ok(
+      self
+        .inscribe_transfer
+        .borrow()
+        .get(&inscription_id)
+        .map(|v| bincode::deserialize::<TransferInfo>(v).unwrap()),
+    )
+  }
+}
+
+impl DataStoreReadWrite for MemoryStore {
+  fn update_token_balance(
+    &self,
+    script_key: &ScriptKey,
+    new_balance: Balance,
+  ) -> Result<(), Self::Error> {
+    self.balances.borrow_mut().insert(
+      script_tick_key(script_key, &new_balance.tick),
+      bincode::serialize(&new_balance).unwrap(),
+    );
+    Ok(())
+  }
+
+  fn insert_token_info(&self, tick: &Tick, new_info: &TokenInfo) -> Result<(), Self::Error> {
+    self
+      .tokens
+      .borrow_mut()
+      .insert(tick_key(tick), bincode::serialize(new_info).unwrap());
+    Ok(())
+  }
+
+  fn update_mint_token_info(
+    &self,
+    tick: &Tick,
+    minted_amt: u128,
+    minted_block_number: u64,
+  ) -> Result<(), Self::Error> {
+    let mut info = self
+      .get_token_info(tick)?
+      .unwrap_or_else(|| panic!("token {} not exist", tick.as_str()));
+
+    info.minted = minted_amt;
+    info.latest_mint_number = minted_block_number;
+
+    self.insert_token_info(tick, &info)
+  }
+
+  fn save_transaction_receipts(
+    &self,
+    txid: &Txid,
+    receipts: &[Receipt],
+  ) -> Result<(), Self::Error> {
+    self
+      .events
+      .borrow_mut()
+      .insert(*txid, bincode::serialize(receipts).unwrap());
+    Ok(())
+  }
+
+  fn add_transaction_receipt(&self, txid: &Txid, receipt: &Receipt) -> Result<(), Self::Error> {
+    let mut receipts = self.get_transaction_receipts(txid)?;
+    receipts.push(receipt.clone());
+    self.save_transaction_receipts(txid, &receipts)
+  }
+
+  fn save_receipt_history(
+    &self,
+    height: u64,
+    position: u32,
+    txid: &Txid,
+    receipts: &[Receipt],
+  ) -> Result<(), Self::Error> {
+    for (index, receipt) in receipts.iter().enumerate() {
+      let key = receipt_key(height, position, u32::try_from(index).unwrap());
+      for history in ReceiptHistory::of(receipt) {
+        self.receipt_histories.borrow_mut().insert(
+          (
+            history.table().name().to_string(),
+            [history.prefix().as_slice(), &key].concat(),
+          ),
+          *txid,
+        );
+      }
+    }
+    Ok(())
+  }
+
+  fn insert_transferable(
+    &self,
+    script: &ScriptKey,
+    tick: &Tick,
+    inscription: TransferableLog,
+  ) -> Result<(), Self::Error> {
+    let mut logs = self.get_transferable_by_tick(script, tick)?;
+    if logs
+      .iter()
+      .any(|log| log.inscription_id == inscription.inscription_id)
+    {
+      return Ok(());
+    }
+
+    logs.push(inscription);
+
+    self.transferable_logs.borrow_mut().insert(
+      script_tick_key(script, tick),
+      bincode::serialize(&logs).unwrap(),
+    );
+    Ok(())
+  }
+
+  fn remove_transferable(
+    &self,
+    script: &ScriptKey,
+    tick: &Tick,
+    inscription_id: InscriptionId,
+  ) -> Result<(), Self::Error> {
+    let mut logs = self.get_transferable_by_tick(script, tick)?;
+    let old_len = logs.len();
+
+    logs.retain(|log| log.inscription_id != inscription_id);
+
+    if logs.len() != old_len {
+      self.transferable_logs.borrow_mut().insert(
+        script_tick_key(script, tick),
+        bincode::serialize(&logs).unwrap(),
+      );
+    }
+    Ok(())
+  }
+
+  fn insert_inscribe_transfer_inscription(
+    &self,
+    inscription_id: InscriptionId,
+    transfer_info: TransferInfo,
+  ) -> Result<(), Self::Error> {
+    self
+      .inscribe_transfer
+      .borrow_mut()
+      .insert(inscription_id, bincode::serialize(&transfer_info).unwrap());
+    Ok(())
+  }
+
+  fn remove_inscribe_transfer_inscription(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<(), Self::Error> {
+    self.inscribe_transfer.borrow_mut().remove(&inscription_id);
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::okx::datastore::brc20::redb::DataStore;
+  use bitcoin::Address;
+  use redb::Database;
+  use std::str::FromStr;
+  use tempfile::NamedTempFile;
+
+  #[test]
+  fn test_balances_match_redb_order() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let redb_store = DataStore::new(&wtx);
+    let memory_store = MemoryStore::new();
+
+    let script = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+
+    for (tick, amount) in [("abcd", 10), ("1234", 30), (";23!", 100), ("abİ", 50)] {
+      let balance = Balance {
+        tick: Tick::from_str(tick).unwrap(),
+        overall_balance: amount,
+        transferable_balance: amount / 2,
+      };
+      redb_store
+        .update_token_balance(&script, balance.clone())
+        .unwrap();
+      memory_store.update_token_balance(&script, balance).unwrap();
+    }
+
+    assert_eq!(
+      memory_store.get_balances(&script).unwrap(),
+      redb_store.get_balances(&script).unwrap()
+    );
+    assert_eq!(
+      memory_store
+        .get_balance(&script, &Tick::from_str("ABCD").unwrap())
+        .unwrap()
+        .unwrap()
+        .overall_balance,
+      10
+    );
+  }
+
+  #[test]
+  fn test_insert_remove_transferable() {
+    let memory_store = MemoryStore::new();
+    let script = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    let tick = Tick::from_str("abcd").unwrap();
+    let inscription_id =
+      InscriptionId::from_str("1111111111111111111111111111111111111111111111111111111111111111i1")
+        .unwrap();
+    let log = TransferableLog {
+      inscription_id,
+      inscription_number: 1,
+      amount: 100,
+      tick: tick.clone(),
+      owner: script.clone(),
+    };
+
+    memory_store
+      .insert_transferable(&script, &tick, log.clone())
+      .unwrap();
+    memory_store
+      .insert_transferable(&script, &tick, log.clone())
+      .unwrap();
+    assert_eq!(memory_store.get_transferable(&script).unwrap(), vec![log]);
+
+    memory_store
+      .remove_transferable(&script, &tick, inscription_id)
+      .unwrap();
+    assert!(memory_store.get_transferable(&script).unwrap().is_empty());
+  }
+}
@@ -1,6 +1,8 @@
 pub(super) mod balance;
 pub(super) mod errors;
 pub(super) mod events;
+pub mod memory;
+pub mod overlay;
 pub mod redb;
 pub(super) mod tick;
 pub(super) mod token_info;
@@ -16,6 +18,32 @@ use crate::{InscriptionId, Result};
 use bitcoin::Txid;
 use std::fmt::{Debug, Display};
 
+/// A list of receipts in the order they were made, read newest first.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReceiptHistory<'a> {
+  /// Successful receipts of a tick.
+  Tick(&'a Tick),
+  /// Successful transfers of a tick.
+  Transfers(&'a Tick),
+  /// Successful deploys of every tick.
+  Deploys,
+}
+
+impl<'a> ReceiptHistory<'a> {
+  /// Returns the histories `receipt` is listed in.
+  pub fn of(receipt: &'a Receipt) -> Vec<Self> {
+    match &receipt.result {
+      Ok(Event::Deploy(deploy)) => vec![Self::Tick(&deploy.tick), Self::Deploys],
+      Ok(Event::Mint(mint)) => vec![Self::Tick(&mint.tick)],
+      Ok(Event::InscribeTransfer(transfer)) => vec![Self::Tick(&transfer.tick)],
+      Ok(Event::Transfer(transfer)) => {
+        vec![Self::Tick(&transfer.tick), Self::Transfers(&transfer.tick)]
+      }
+      Err(_) => Vec::new(),
+    }
+  }
+}
+
 pub trait DataStoreReadOnly {
   type Error: Debug + Display;
 
@@ -27,7 +55,6 @@ pub trait DataStoreReadOnly {
   ) -> Result<Option<Balance>, Self::Error>;
 
   fn get_token_info(&self, tick: &Tick) -> Result<Option<TokenInfo>, Self::Error>;
-  fn get_tokens_info(&self) -> Result<Vec<TokenInfo>, Self::Error>;
 
   fn get_transaction_receipts(&self, txid: &Txid) -> Result<Vec<Receipt>, Self::Error>;
 
@@ -70,6 +97,16 @@ pub trait DataStoreReadWrite: DataStoreReadOnly {
 
   fn add_transaction_receipt(&self, txid: &Txid, receipt: &Receipt) -> Result<(), Self::Error>;
 
+  /// Lists the receipts of the transaction at `position` in the block at
+  /// `height` in the receipt histories they belong to.
+  fn save_receipt_history(
+    &self,
+    height: u64,
+    position: u32,
+    txid: &Txid,
+    receipts: &[Receipt],
+  ) -> Result<(), Self::Error>;
+
   fn insert_transferable(
     &self,
     script: &ScriptKey,
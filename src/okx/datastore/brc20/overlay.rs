@@ -0,0 +1,423 @@
+use super::{
+  memory::MemoryStore,
+  redb::{script_tick_key, tick_key},
+  Balance, DataStoreReadOnly, DataStoreReadWrite, Receipt, ScriptKey, Tick, TokenInfo,
+  TransferInfo, TransferableLog,
+};
+use crate::InscriptionId;
+use bitcoin::{consensus, Txid};
+use std::{
+  cell::RefCell,
+  collections::{BTreeMap, HashSet},
+};
+
+/// OverlayStore layers a `MemoryStore` over a read-only BRC20 store.
+///
+/// Every entry is copied from the base store into memory the first time it is
+/// read or written, and only the memory copy is touched afterwards, so the base
+/// store never sees a write and entries removed in the overlay stay removed.
+pub struct OverlayStore<'a, R: DataStoreReadOnly> {
+  base: &'a R,
+  memory: MemoryStore,
+  loaded: RefCell<HashSet<(&'static str, Vec<u8>)>>,
+}
+
+impl<'a, R: DataStoreReadOnly> OverlayStore<'a, R> {
+  pub fn new(base: &'a R) -> Self {
+    Self {
+      base,
+      memory: MemoryStore::new(),
+      loaded: RefCell::new(HashSet::new()),
+    }
+  }
+
+  /// Runs `load` to copy an entry from the base store unless it is already in memory.
+  fn hydrate(
+    &self,
+    table: &'static str,
+    key: Vec<u8>,
+    load: impl FnOnce() -> Result<(), R::Error>,
+  ) -> Result<(), R::Error> {
+    if self.loaded.borrow().contains(&(table, key.clone())) {
+      return Ok(());
+    }
+    load()?;
+    self.loaded.borrow_mut().insert((table, key));
+    Ok(())
+  }
+
+  /// Marks an entry as loaded without reading the base store, for entries about to be overwritten.
+  fn shadow(&self, table: &'static str, key: Vec<u8>) {
+    self.loaded.borrow_mut().insert((table, key));
+  }
+
+  fn hydrate_balance(&self, script_key: &ScriptKey, tick: &Tick) -> Result<(), R::Error> {
+    self.hydrate("balance", script_tick_key(script_key, tick), || {
+      if let Some(balance) = self.base.get_balance(script_key, tick)? {
+        self
+          .memory
+          .update_token_balance(script_key, balance)
+          .unwrap();
+      }
+      Ok(())
+    })
+  }
+
+  fn hydrate_token_info(&self, tick: &Tick) -> Result<(), R::Error> {
+    self.hydrate("token", tick_key(tick), || {
+      if let Some(info) = self.base.get_token_info(tick)? {
+        self.memory.insert_token_info(tick, &info).unwrap();
+      }
+      Ok(())
+    })
+  }
+
+  fn hydrate_receipts(&self, txid: &Txid) -> Result<(), R::Error> {
+    self.hydrate("receipts", consensus::serialize(txid), || {
+      let receipts = self.base.get_transaction_receipts(txid)?;
+      if !receipts.is_empty() {
+        self
+          .memory
+          .save_transaction_receipts(txid, &receipts)
+          .unwrap();
+      }
+      Ok(())
+    })
+  }
+
+  fn hydrate_transferable(
+    &self,
+    script: &ScriptKey,
+    tick: &Tick,
+    logs: impl FnOnce() -> Result<Vec<TransferableLog>, R::Error>,
+  ) -> Result<(), R::Error> {
+    self.hydrate("transferable", script_tick_key(script, tick), || {
+      for log in logs()? {
+        self.memory.insert_transferable(script, tick, log).unwrap();
+      }
+      Ok(())
+    })
+  }
+
+  fn hydrate_inscribe_transfer(&self, inscription_id: InscriptionId) -> Result<(), R::Error> {
+    self.hydrate(
+      "inscribe_transfer",
+      inscription_id.to_string().into_bytes(),
+      || {
+        if let Some(info) = self
+          .base
+          .get_inscribe_transfer_inscription(inscription_id)?
+        {
+          self
+            .memory
+            .insert_inscribe_transfer_inscription(inscription_id, info)
+            .unwrap();
+        }
+        Ok(())
+      },
+    )
+  }
+}
+
+impl<'a, R: DataStoreReadOnly> DataStoreReadOnly for OverlayStore<'a, R> {
+  type Error = R::Error;
+
+  fn get_balances(&self, script_key: &ScriptKey) -> Result<Vec<Balance>, Self::Error> {
+    for balance in self.base.get_balances(script_key)? {
+      self.hydrate_balance(script_key, &balance.tick)?;
+    }
+    Ok(self.memory.get_balances(script_key).unwrap())
+  }
+
+  fn get_balance(
+    &self,
+    script_key: &ScriptKey,
+    tick: &Tick,
+  ) -> Result<Option<Balance>, Self::Error> {
+    self.hydrate_balance(script_key, tick)?;
+    Ok(self.memory.get_balance(script_key, tick).unwrap())
+  }
+
+  fn get_token_info(&self, tick: &Tick) -> Result<Option<TokenInfo>, Self::Error> {
+    self.hydrate_token_info(tick)?;
+    Ok(self.memory.get_token_info(tick).unwrap())
+  }
+
+  fn get_transaction_receipts(&self, txid: &Txid) -> Result<Vec<Receipt>, Self::Error> {
+    self.hydrate_receipts(txid)?;
+    Ok(self.memory.get_transaction_receipts(txid).unwrap())
+  }
+
+  fn get_transferable(&self, script: &ScriptKey) -> Result<Vec<TransferableLog>, Self::Error> {
+    let mut by_tick = BTreeMap::<Vec<u8>, (Tick, Vec<TransferableLog>)>::new();
+    for log in self.base.get_transferable(script)? {
+      by_tick
+        .entry(tick_key(&log.tick))
+        .or_insert_with(|| (log.tick.clone(), Vec::new()))
+        .1
+        .push(log);
+    }
+    for (tick, logs) in by_tick.into_values() {
+      self.hydrate_transferable(script, &tick, || Ok(logs))?;
+    }
+    Ok(self.memory.get_transferable(script).unwrap())
+  }
+
+  fn get_transferable_by_tick(
+    &self,
+    script: &ScriptKey,
+    tick: &Tick,
+  ) -> Result<Vec<TransferableLog>, Self::Error> {
+    self.hydrate_transferable(script, tick, || {
+      self.base.get_transferable_by_tick(script, tick)
+    })?;
+    Ok(self.memory.get_transferable_by_tick(script, tick).unwrap())
+  }
+
+  fn get_transferable_by_id(
+    &self,
+    script: &ScriptKey,
+    inscription_id: &InscriptionId,
+  ) -> Result<Option<TransferableLog>, Self::Error> {
+    Ok(
+      self
+        .get_transferable(script)?
+        .into_iter()
+        .find(|log| log.inscription_id == *inscription_id),
+    )
+  }
+
+  fn get_inscribe_transfer_inscription(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<TransferInfo>, Self::Error> {
+    self.hydrate_inscribe_transfer(inscription_id)?;
+    Ok(
+      self
+        .memory
+        .get_inscribe_transfer_inscription(inscription_id)
+        .unwrap(),
+    )
+  }
+}
+
+impl<'a, R: DataStoreReadOnly> DataStoreReadWrite for OverlayStore<'a, R> {
+  fn update_token_balance(
+    &self,
+    script_key: &ScriptKey,
+    new_balance: Balance,
+  ) -> Result<(), Self::Error> {
+    self.shadow("balance", script_tick_key(script_key, &new_balance.tick));
+    self
+      .memory
+      .update_token_balance(script_key, new_balance)
+      .unwrap();
+    Ok(())
+  }
+
+  fn insert_token_info(&self, tick: &Tick, new_info: &TokenInfo) -> Result<(), Self::Error> {
+    self.shadow("token", tick_key(tick));
+    self.memory.insert_token_info(tick, new_info).unwrap();
+    Ok(())
+  }
+
+  fn update_mint_token_info(
+    &self,
+    tick: &Tick,
+    minted_amt: u128,
+    minted_block_number: u64,
+  ) -> Result<(), Self::Error> {
+    self.hydrate_token_info(tick)?;
+    self
+      .memory
+      .update_mint_token_info(tick, minted_amt, minted_block_number)
+      .unwrap();
+    Ok(())
+  }
+
+  fn save_transaction_receipts(
+    &self,
+    txid: &Txid,
+    receipts: &[Receipt],
+  ) -> Result<(), Self::Error> {
+    self.shadow("receipts", consensus::serialize(txid));
+    self
+      .memory
+      .save_transaction_receipts(txid, receipts)
+      .unwrap();
+    Ok(())
+  }
+
+  fn add_transaction_receipt(&self, txid: &Txid, receipt: &Receipt) -> Result<(), Self::Error> {
+    self.hydrate_receipts(txid)?;
+    self.memory.add_transaction_receipt(txid, receipt).unwrap();
+    Ok(())
+  }
+
+  fn save_receipt_history(
+    &self,
+    height: u64,
+    position: u32,
+    txid: &Txid,
+    receipts: &[Receipt],
+  ) -> Result<(), Self::Error> {
+    self
+      .memory
+      .save_receipt_history(height, position, txid, receipts)
+      .unwrap();
+    Ok(())
+  }
+
+  fn insert_transferable(
+    &self,
+    script: &ScriptKey,
+    tick: &Tick,
+    inscription: TransferableLog,
+  ) -> Result<(), Self::Error> {
+    self.hydrate_transferable(script, tick, || {
+      self.base.get_transferable_by_tick(script, tick)
+    })?;
+    self
+      .memory
+      .insert_transferable(script, tick, inscription)
+      .unwrap();
+    Ok(())
+  }
+
+  fn remove_transferable(
+    &self,
+    script: &ScriptKey,
+    tick: &Tick,
+    inscription_id: InscriptionId,
+  ) -> Result<(), Self::Error> {
+    self.hydrate_transferable(script, tick, || {
+      self.base.get_transferable_by_tick(script, tick)
+    })?;
+    self
+      .memory
+      .remove_transferable(script, tick, inscription_id)
+      .unwrap();
+    Ok(())
+  }
+
+  fn insert_inscribe_transfer_inscription(
+    &self,
+    inscription_id: InscriptionId,
+    transfer_info: TransferInfo,
+  ) -> Result<(), Self::Error> {
+    self.shadow("inscribe_transfer", inscription_id.to_string().into_bytes());
+    self
+      .memory
+      .insert_inscribe_transfer_inscription(inscription_id, transfer_info)
+      .unwrap();
+    Ok(())
+  }
+
+  fn remove_inscribe_transfer_inscription(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<(), Self::Error> {
+    self.shadow("inscribe_transfer", inscription_id.to_string().into_bytes());
+    self
+      .memory
+      .remove_inscribe_transfer_inscription(inscription_id)
+      .unwrap();
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use bitcoin::Address;
+  use std::str::FromStr;
+
+  fn script() -> ScriptKey {
+    ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    )
+  }
+
+  #[test]
+  fn writes_stay_in_the_overlay() {
+    let base = MemoryStore::new();
+    let script = script();
+    let tick = Tick::from_str("abcd").unwrap();
+
+    base
+      .update_token_balance(
+        &script,
+        Balance {
+          tick: tick.clone(),
+          overall_balance: 10,
+          transferable_balance: 0,
+        },
+      )
+      .unwrap();
+
+    let overlay = OverlayStore::new(&base);
+    assert_eq!(
+      overlay
+        .get_balance(&script, &tick)
+        .unwrap()
+        .unwrap()
+        .overall_balance,
+      10
+    );
+
+    overlay
+      .update_token_balance(
+        &script,
+        Balance {
+          tick: tick.clone(),
+          overall_balance: 30,
+          transferable_balance: 0,
+        },
+      )
+      .unwrap();
+
+    assert_eq!(
+      overlay.get_balances(&script).unwrap()[0].overall_balance,
+      30
+    );
+    assert_eq!(base.get_balances(&script).unwrap()[0].overall_balance, 10);
+  }
+
+  #[test]
+  fn removed_transferable_stays_removed() {
+    let base = MemoryStore::new();
+    let script = script();
+    let tick = Tick::from_str("abcd").unwrap();
+    let inscription_id =
+      InscriptionId::from_str("1111111111111111111111111111111111111111111111111111111111111111i1")
+        .unwrap();
+
+    base
+      .insert_transferable(
+        &script,
+        &tick,
+        TransferableLog {
+          inscription_id,
+          inscription_number: 1,
+          amount: 100,
+          tick: tick.clone(),
+          owner: script.clone(),
+        },
+      )
+      .unwrap();
+
+    let overlay = OverlayStore::new(&base);
+    overlay
+      .remove_transferable(&script, &tick, inscription_id)
+      .unwrap();
+
+    assert!(overlay.get_transferable(&script).unwrap().is_empty());
+    assert!(overlay
+      .get_transferable_by_id(&script, &inscription_id)
+      .unwrap()
+      .is_none());
+    assert_eq!(base.get_transferable(&script).unwrap().len(), 1);
+  }
+}
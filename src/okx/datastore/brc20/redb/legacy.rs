@@ -0,0 +1,195 @@
+use super::*;
+use crate::okx::datastore::{
+  brc20::{Balance, TokenInfo},
+  redb::migrate_legacy_table,
+};
+use anyhow::{anyhow, bail};
+use redb::{ReadTransaction, WriteTransaction};
+use std::str::FromStr;
+
+// Tables written before the binary key layout used string keys under the same names.
+const LEGACY_BRC20_BALANCES: TableDefinition<&str, &[u8]> = TableDefinition::new("BRC20_BALANCES");
+const LEGACY_BRC20_TOKEN: TableDefinition<&str, &[u8]> = TableDefinition::new("BRC20_TOKEN");
+const LEGACY_BRC20_EVENTS: TableDefinition<&str, &[u8]> = TableDefinition::new("BRC20_EVENTS");
+const LEGACY_BRC20_TRANSFERABLELOG: TableDefinition<&str, &[u8]> =
+  TableDefinition::new("BRC20_TRANSFERABLELOG");
+
+/// Rewrites BRC20 tables that still use the legacy string keys into the binary key layout.
+///
+/// The owners of balances are recorded in the owner table, values are copied
+/// unchanged. Returns true if any table was migrated.
+pub fn migrate_legacy_keys<'db, 'a>(
+  wtx: &'a WriteTransaction<'db>,
+  rtx: &'a ReadTransaction<'db>,
+) -> Result<bool> {
+  let mut owners = wtx.open_table(SCRIPT_HASH_TO_SCRIPT_KEY)?;
+  let mut migrated = false;
+  migrated |= migrate_legacy_table(
+    wtx,
+    rtx,
+    LEGACY_BRC20_BALANCES,
+    BRC20_BALANCES,
+    |key, value| {
+      let script = legacy_script(key)?;
+      save_owner(&mut owners, None, &script)?;
+      Ok((
+        script_tick_key(&script, &bincode::deserialize::<Balance>(value)?.tick),
+        value.to_vec(),
+      ))
+    },
+  )?;
+  migrated |= migrate_legacy_table(wtx, rtx, LEGACY_BRC20_TOKEN, BRC20_TOKEN, |_, value| {
+    Ok((
+      tick_key(&bincode::deserialize::<TokenInfo>(value)?.tick),
+      value.to_vec(),
+    ))
+  })?;
+  migrated |= migrate_legacy_table(wtx, rtx, LEGACY_BRC20_EVENTS, BRC20_EVENTS, |key, value| {
+    Ok((txid_key(&Txid::from_str(key)?), value.to_vec()))
+  })?;
+  migrated |= migrate_legacy_table(
+    wtx,
+    rtx,
+    LEGACY_BRC20_TRANSFERABLELOG,
+    BRC20_TRANSFERABLELOG,
+    |key, value| Ok((legacy_script_tick_key(key)?, value.to_vec())),
+  )?;
+  Ok(migrated)
+}
+
+// Legacy keys owned by a script were formatted as `{script}_{...}`.
+fn legacy_script(key: &str) -> Result<ScriptKey> {
+  let (script, _) = key
+    .split_once('_')
+    .ok_or_else(|| anyhow!("missing separator"))?;
+  Ok(ScriptKey::from_str(script)?)
+}
+
+// Legacy keys were formatted as `{script}_{hex(padded lowercase tick)}`.
+fn legacy_script_tick_key(key: &str) -> Result<Vec<u8>> {
+  let (script, tick) = key
+    .split_once('_')
+    .ok_or_else(|| anyhow!("missing separator"))?;
+  let tick = hex::decode(tick)?;
+  if tick.len() != LOWER_TICK_BYTE_COUNT {
+    bail!("unexpected tick length {}", tick.len());
+  }
+  Ok(
+    [
+      ScriptKey::from_str(script)?
+        .script_hash()
+        .as_byte_array()
+        .as_slice(),
+      &tick,
+    ]
+    .concat(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::okx::datastore::brc20::DataStoreReadOnly;
+  use bitcoin::Address;
+  use redb::{Database, ReadableTable};
+  use tempfile::NamedTempFile;
+
+  #[test]
+  fn test_migrate_legacy_balances() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+
+    let script = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    let balance = Balance {
+      tick: Tick::from_str("aBcD").unwrap(),
+      overall_balance: 10,
+      transferable_balance: 5,
+    };
+
+    let wtx = db.begin_write().unwrap();
+    wtx
+      .open_table(LEGACY_BRC20_BALANCES)
+      .unwrap()
+      .insert(
+        format!("{}_{}", script, balance.tick.to_lowercase().hex()).as_str(),
+        bincode::serialize(&balance).unwrap().as_slice(),
+      )
+      .unwrap();
+    wtx.commit().unwrap();
+
+    let wtx = db.begin_write().unwrap();
+    let rtx = db.begin_read().unwrap();
+    assert!(migrate_legacy_keys(&wtx, &rtx).unwrap());
+    try_init_tables(&wtx, &rtx).unwrap();
+    wtx.commit().unwrap();
+
+    let rtx = db.begin_read().unwrap();
+    let reader = DataStoreReader::new(&rtx);
+    assert_eq!(reader.get_balances(&script).unwrap(), vec![balance.clone()]);
+    assert_eq!(
+      reader.get_balance(&script, &balance.tick).unwrap(),
+      Some(balance)
+    );
+
+    let mut owners = Vec::new();
+    reader
+      .for_each_balance(|owner, _| {
+        owners.push(owner);
+        Ok(())
+      })
+      .unwrap();
+    assert_eq!(owners, vec![script]);
+
+    let wtx = db.begin_write().unwrap();
+    let rtx = db.begin_read().unwrap();
+    assert!(!migrate_legacy_keys(&wtx, &rtx).unwrap());
+  }
+
+  #[test]
+  fn test_migrate_legacy_table_in_batches() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+
+    let count = 10_001u32;
+    let txid = |i: u32| {
+      let mut bytes = [0u8; 32];
+      bytes[..4].copy_from_slice(&i.to_be_bytes());
+      Txid::from_byte_array(bytes)
+    };
+
+    let wtx = db.begin_write().unwrap();
+    {
+      let mut table = wtx.open_table(LEGACY_BRC20_EVENTS).unwrap();
+      for i in 0..count {
+        table
+          .insert(txid(i).to_string().as_str(), i.to_le_bytes().as_slice())
+          .unwrap();
+      }
+    }
+    wtx.commit().unwrap();
+
+    let wtx = db.begin_write().unwrap();
+    let rtx = db.begin_read().unwrap();
+    assert!(migrate_legacy_keys(&wtx, &rtx).unwrap());
+    try_init_tables(&wtx, &rtx).unwrap();
+    wtx.commit().unwrap();
+
+    let rtx = db.begin_read().unwrap();
+    let table = rtx.open_table(BRC20_EVENTS).unwrap();
+    assert_eq!(table.len().unwrap(), u64::from(count));
+    for i in [0, count / 2, count - 1] {
+      assert_eq!(
+        table
+          .get(txid_key(&txid(i)).as_slice())
+          .unwrap()
+          .unwrap()
+          .value(),
+        i.to_le_bytes().as_slice()
+      );
+    }
+  }
+}
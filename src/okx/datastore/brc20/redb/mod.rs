@@ -1,30 +1,132 @@
+mod legacy;
 mod read_only;
 mod read_write;
 
-use super::{LowerTick, ScriptKey, Tick};
-use crate::{InscriptionId, Result};
+use super::{
+  DataStoreReadOnly, DataStoreReadWrite, ReceiptHistory, ScriptKey, Tick, LOWER_TICK_BYTE_COUNT,
+};
+use crate::{
+  okx::datastore::{
+    redb::{read_receipt_history, receipt_key, save_owner, OwnerCache, SCRIPT_HASH_TO_SCRIPT_KEY},
+    snapshot::TableVisitor,
+  },
+  InscriptionId, Result,
+};
 
-use bitcoin::Txid;
+use bitcoin::{hashes::Hash, Txid};
 use redb::TableDefinition;
 
-pub use self::{read_only::try_init_tables, read_only::DataStoreReader, read_write::DataStore};
+pub use self::{
+  legacy::migrate_legacy_keys, read_only::try_init_tables, read_only::DataStoreReader,
+  read_write::DataStore,
+};
 
-const BRC20_BALANCES: TableDefinition<&str, &[u8]> = TableDefinition::new("BRC20_BALANCES");
-const BRC20_TOKEN: TableDefinition<&str, &[u8]> = TableDefinition::new("BRC20_TOKEN");
-const BRC20_EVENTS: TableDefinition<&str, &[u8]> = TableDefinition::new("BRC20_EVENTS");
-const BRC20_TRANSFERABLELOG: TableDefinition<&str, &[u8]> =
+const BRC20_BALANCES: TableDefinition<&[u8], &[u8]> = TableDefinition::new("BRC20_BALANCES");
+const BRC20_TOKEN: TableDefinition<&[u8], &[u8]> = TableDefinition::new("BRC20_TOKEN");
+const BRC20_EVENTS: TableDefinition<&[u8], &[u8]> = TableDefinition::new("BRC20_EVENTS");
+const BRC20_TRANSFERABLELOG: TableDefinition<&[u8], &[u8]> =
   TableDefinition::new("BRC20_TRANSFERABLELOG");
 const BRC20_INSCRIBE_TRANSFER: TableDefinition<&[u8; 36], &[u8]> =
   TableDefinition::new("BRC20_INSCRIBE_TRANSFER");
+const BRC20_TICK_RECEIPTS: TableDefinition<&[u8], &[u8]> =
+  TableDefinition::new("BRC20_TICK_RECEIPTS");
+const BRC20_TRANSFER_RECEIPTS: TableDefinition<&[u8], &[u8]> =
+  TableDefinition::new("BRC20_TRANSFER_RECEIPTS");
+const BRC20_DEPLOY_RECEIPTS: TableDefinition<&[u8], &[u8]> =
+  TableDefinition::new("BRC20_DEPLOY_RECEIPTS");
 
-fn script_tick_key(script: &ScriptKey, tick: &Tick) -> String {
-  format!("{}_{}", script, tick.to_lowercase().hex())
+/// Visits every table of this protocol, in a fixed order.
+pub fn visit_tables<T: TableVisitor>(visitor: &mut T) -> Result<()> {
+  visitor.visit(BRC20_BALANCES)?;
+  visitor.visit(BRC20_TOKEN)?;
+  visitor.visit(BRC20_EVENTS)?;
+  visitor.visit(BRC20_TRANSFERABLELOG)?;
+  visitor.visit(BRC20_INSCRIBE_TRANSFER)?;
+  visitor.visit(BRC20_TICK_RECEIPTS)?;
+  visitor.visit(BRC20_TRANSFER_RECEIPTS)?;
+  visitor.visit(BRC20_DEPLOY_RECEIPTS)?;
+  Ok(())
 }
 
-fn min_script_tick_key(script: &ScriptKey) -> String {
-  format!("{}_{}", script, LowerTick::min_hex())
+/// Lists the receipts of the transaction `txid`, at `position` in the block
+/// at `height`, in the receipt histories, for indexes built before the
+/// histories existed.
+pub fn backfill_receipt_history(
+  store: &DataStore,
+  height: u64,
+  position: u32,
+  txid: &Txid,
+) -> Result<()> {
+  let receipts = store.get_transaction_receipts(txid)?;
+  if !receipts.is_empty() {
+    store.save_receipt_history(height, position, txid, &receipts)?;
+  }
+  Ok(())
 }
 
-fn max_script_tick_key(script: &ScriptKey) -> String {
-  format!("{}_{}", script, LowerTick::max_hex())
+// Binary key layouts:
+//   tick:        lowercase tick, zero-padded to 16 bytes
+//   script_tick: script hash (20 bytes) + tick (16 bytes)
+//   txid:        txid (32 bytes)
+//   receipts:    tick (16 bytes), if listed by tick, + receipt key
+//   transferable page cursors: script_tick + inscription id (36 bytes)
+//
+// The script key of each balance owner is kept once in the owner table
+// shared with BRC20S, `SCRIPT_HASH_TO_SCRIPT_KEY`.
+
+pub(super) fn tick_key(tick: &Tick) -> Vec<u8> {
+  tick.to_lowercase().to_padded_bytes().to_vec()
+}
+
+impl ReceiptHistory<'_> {
+  pub(super) fn table(self) -> TableDefinition<'static, &'static [u8], &'static [u8]> {
+    match self {
+      Self::Tick(_) => BRC20_TICK_RECEIPTS,
+      Self::Transfers(_) => BRC20_TRANSFER_RECEIPTS,
+      Self::Deploys => BRC20_DEPLOY_RECEIPTS,
+    }
+  }
+
+  pub(super) fn prefix(self) -> Vec<u8> {
+    match self {
+      Self::Tick(tick) | Self::Transfers(tick) => tick_key(tick),
+      Self::Deploys => Vec::new(),
+    }
+  }
+}
+
+pub(super) fn inscription_id_key(inscription_id: &InscriptionId) -> [u8; 36] {
+  let mut key = [0; 36];
+  let (txid, index) = key.split_at_mut(32);
+  txid.copy_from_slice(inscription_id.txid.as_ref());
+  index.copy_from_slice(&inscription_id.index.to_be_bytes());
+  key
+}
+
+pub(super) fn txid_key(txid: &Txid) -> Vec<u8> {
+  txid.to_byte_array().to_vec()
+}
+
+pub(super) fn script_tick_key(script: &ScriptKey, tick: &Tick) -> Vec<u8> {
+  [
+    script.script_hash().as_byte_array().as_slice(),
+    &tick.to_lowercase().to_padded_bytes(),
+  ]
+  .concat()
+}
+
+pub(super) fn min_script_tick_key(script: &ScriptKey) -> Vec<u8> {
+  [
+    script.script_hash().as_byte_array().as_slice(),
+    &[0u8; LOWER_TICK_BYTE_COUNT],
+  ]
+  .concat()
+}
+
+pub(super) fn max_script_tick_key(script: &ScriptKey) -> Vec<u8> {
+  [
+    script.script_hash().as_byte_array().as_slice(),
+    &[0xffu8; LOWER_TICK_BYTE_COUNT],
+  ]
+  .concat()
 }
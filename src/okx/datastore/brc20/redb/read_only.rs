@@ -1,6 +1,10 @@
 use super::*;
-use crate::okx::datastore::brc20::{
-  Balance, DataStoreReadOnly, Receipt, Tick, TokenInfo, TransferInfo, TransferableLog,
+use crate::okx::datastore::{
+  brc20::{
+    Balance, DataStoreReadOnly, Receipt, ReceiptHistory, Tick, TokenInfo, TransferInfo,
+    TransferableLog,
+  },
+  page::{Page, PageRequest},
 };
 use redb::{
   AccessGuard, Range, ReadOnlyTable, ReadTransaction, ReadableTable, RedbKey, RedbValue,
@@ -19,6 +23,10 @@ pub fn try_init_tables<'db, 'a>(
     wtx.open_table(BRC20_EVENTS)?;
     wtx.open_table(BRC20_TRANSFERABLELOG)?;
     wtx.open_table(BRC20_INSCRIBE_TRANSFER)?;
+    wtx.open_table(BRC20_TICK_RECEIPTS)?;
+    wtx.open_table(BRC20_TRANSFER_RECEIPTS)?;
+    wtx.open_table(BRC20_DEPLOY_RECEIPTS)?;
+    wtx.open_table(SCRIPT_HASH_TO_SCRIPT_KEY)?;
   }
 
   Ok(true)
@@ -42,6 +50,157 @@ impl<'db, 'a> DataStoreReader<'db, 'a> {
   }
 }
 
+impl<'db, 'a> DataStoreReader<'db, 'a> {
+  /// Returns a page of all tokens, ordered by lowercase tick.
+  pub fn get_tokens_info_page(&self, page: &PageRequest) -> Result<Page<TokenInfo>, redb::Error> {
+    let table = self.wrapper.open_table(BRC20_TOKEN)?;
+    let page = page.collect(table.range(page.start()..)?.map(|result| {
+      result.map(|(key, data)| {
+        (
+          key.value().to_vec(),
+          bincode::deserialize::<TokenInfo>(data.value()).unwrap(),
+        )
+      })
+    }))?;
+    Ok(page)
+  }
+
+  /// Returns a page of the balances of `script_key`, ordered by lowercase tick.
+  pub fn get_balances_page(
+    &self,
+    script_key: &ScriptKey,
+    page: &PageRequest,
+  ) -> Result<Page<Balance>, redb::Error> {
+    let Some(bounds) = page.bounds(
+      min_script_tick_key(script_key),
+      max_script_tick_key(script_key),
+    ) else {
+      return Ok(Page::default());
+    };
+    let table = self.wrapper.open_table(BRC20_BALANCES)?;
+    let page = page.collect(
+      table
+        .range(bounds.start().as_slice()..=bounds.end().as_slice())?
+        .map(|result| {
+          result.map(|(key, data)| {
+            (
+              key.value().to_vec(),
+              bincode::deserialize::<Balance>(data.value()).unwrap(),
+            )
+          })
+        }),
+    )?;
+    Ok(page)
+  }
+
+  /// Returns a page of the transferable inscriptions of `script`, ordered by
+  /// lowercase tick and then by inscription id.
+  pub fn get_transferable_page(
+    &self,
+    script: &ScriptKey,
+    page: &PageRequest,
+  ) -> Result<Page<TransferableLog>, redb::Error> {
+    let (min, max) = (min_script_tick_key(script), max_script_tick_key(script));
+    let len = min.len();
+    let Some(bounds) = page.bounds(min, max) else {
+      return Ok(Page::default());
+    };
+    // The cursor points at a log within an entry, so start from its entry.
+    let start = &bounds.start()[..len];
+    let table = self.wrapper.open_table(BRC20_TRANSFERABLELOG)?;
+    let page = page.collect(
+      table
+        .range(start..=bounds.end().as_slice())?
+        .flat_map(|result| match result {
+          Ok((key, data)) => transferable_page_items(
+            key.value(),
+            bincode::deserialize::<Vec<TransferableLog>>(data.value()).unwrap(),
+          )
+          .into_iter()
+          .map(Ok)
+          .collect(),
+          Err(err) => vec![Err(err)],
+        }),
+    )?;
+    Ok(page)
+  }
+
+  /// Returns a page of the transferable inscriptions of `script` for `tick`,
+  /// ordered by inscription id.
+  pub fn get_transferable_by_tick_page(
+    &self,
+    script: &ScriptKey,
+    tick: &Tick,
+    page: &PageRequest,
+  ) -> Result<Page<TransferableLog>, redb::Error> {
+    let logs = self.get_transferable_by_tick(script, tick)?;
+    page.collect(
+      transferable_page_items(&script_tick_key(script, tick), logs)
+        .into_iter()
+        .map(Ok),
+    )
+  }
+
+  /// Returns up to `limit` receipts of `history` matching `filter`, newest
+  /// first, with the height and txid they were made at. At most `scan`
+  /// receipts are read, so a filter that rarely matches stays cheap.
+  pub fn get_receipt_history(
+    &self,
+    history: ReceiptHistory,
+    limit: usize,
+    scan: usize,
+    filter: impl Fn(&Receipt) -> bool,
+  ) -> Result<Vec<(u64, Txid, Receipt)>, redb::Error> {
+    let prefix = history.prefix();
+    let table = self.wrapper.open_table(history.table())?;
+    let receipts = read_receipt_history(
+      table.range(prefix.as_slice()..=[prefix.as_slice(), &[0xff; 16]].concat().as_slice())?,
+      limit,
+      scan,
+      |txid| self.get_transaction_receipts(txid),
+      filter,
+    )?;
+    Ok(receipts)
+  }
+
+  /// Calls `f` with every balance and its owner, ordered by the owner's script hash.
+  pub fn for_each_balance(
+    &self,
+    mut f: impl FnMut(ScriptKey, Balance) -> Result<()>,
+  ) -> Result<()> {
+    let table = self.wrapper.open_table(BRC20_BALANCES)?;
+    let owners = self.wrapper.open_table(SCRIPT_HASH_TO_SCRIPT_KEY)?;
+    let mut cache = OwnerCache::default();
+    for result in table.range([].as_slice()..)? {
+      let (key, data) = result?;
+      let owner = cache.resolve(key.value(), |hash| {
+        Ok(owners.get(hash)?.map(|owner| owner.value().to_vec()))
+      })?;
+      f(owner, bincode::deserialize(data.value())?)?;
+    }
+    Ok(())
+  }
+}
+
+/// Keys each log of a transferable entry by the entry key and its
+/// inscription id, so pages can resume at a log that is still transferable.
+fn transferable_page_items(
+  entry_key: &[u8],
+  logs: Vec<TransferableLog>,
+) -> Vec<(Vec<u8>, TransferableLog)> {
+  let mut items = logs
+    .into_iter()
+    .map(|log| {
+      (
+        [entry_key, &inscription_id_key(&log.inscription_id)].concat(),
+        log,
+      )
+    })
+    .collect::<Vec<_>>();
+  items.sort_by(|(a, _), (b, _)| a.cmp(b));
+  items
+}
+
 enum ReaderWrapper<'db, 'a> {
   Rtx(&'a ReadTransaction<'db>),
   Wtx(&'a WriteTransaction<'db>),
@@ -101,7 +260,9 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(BRC20_BALANCES)?
-        .range(min_script_tick_key(script_key).as_str()..max_script_tick_key(script_key).as_str())?
+        .range(
+          min_script_tick_key(script_key).as_slice()..=max_script_tick_key(script_key).as_slice(),
+        )?
         .flat_map(|result| {
           result.map(|(_, data)| bincode::deserialize::<Balance>(data.value()).unwrap())
         })
@@ -118,7 +279,7 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(BRC20_BALANCES)?
-        .get(script_tick_key(script_key, tick).as_str())?
+        .get(script_tick_key(script_key, tick).as_slice())?
         .map(|v| bincode::deserialize::<Balance>(v.value()).unwrap()),
     )
   }
@@ -128,30 +289,17 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(BRC20_TOKEN)?
-        .get(tick.to_lowercase().hex().as_str())?
+        .get(tick_key(tick).as_slice())?
         .map(|v| bincode::deserialize::<TokenInfo>(v.value()).unwrap()),
     )
   }
 
-  fn get_tokens_info(&self) -> Result<Vec<TokenInfo>, Self::Error> {
-    Ok(
-      self
-        .wrapper
-        .open_table(BRC20_TOKEN)?
-        .range::<&str>(..)?
-        .flat_map(|result| {
-          result.map(|(_, data)| bincode::deserialize::<TokenInfo>(data.value()).unwrap())
-        })
-        .collect(),
-    )
-  }
-
   fn get_transaction_receipts(&self, txid: &Txid) -> Result<Vec<Receipt>, Self::Error> {
     Ok(
       self
         .wrapper
         .open_table(BRC20_EVENTS)?
-        .get(txid.to_string().as_str())?
+        .get(txid_key(txid).as_slice())?
         .map_or(Vec::new(), |v| {
           bincode::deserialize::<Vec<Receipt>>(v.value()).unwrap()
         }),
@@ -163,7 +311,7 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(BRC20_TRANSFERABLELOG)?
-        .range(min_script_tick_key(script).as_str()..max_script_tick_key(script).as_str())?
+        .range(min_script_tick_key(script).as_slice()..=max_script_tick_key(script).as_slice())?
         .flat_map(|result| {
           result.map(|(_, v)| bincode::deserialize::<Vec<TransferableLog>>(v.value()).unwrap())
         })
@@ -181,7 +329,7 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(BRC20_TRANSFERABLELOG)?
-        .get(script_tick_key(script, tick).as_str())?
+        .get(script_tick_key(script, tick).as_slice())?
         .map_or(Vec::new(), |v| {
           bincode::deserialize::<Vec<TransferableLog>>(v.value()).unwrap()
         }),
@@ -206,15 +354,11 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
     &self,
     inscription_id: InscriptionId,
   ) -> Result<Option<TransferInfo>, Self::Error> {
-    let mut value = [0; 36];
-    let (txid, index) = value.split_at_mut(32);
-    txid.copy_from_slice(inscription_id.txid.as_ref());
-    index.copy_from_slice(&inscription_id.index.to_be_bytes());
     Ok(
       self
         .wrapper
         .open_table(BRC20_INSCRIBE_TRANSFER)?
-        .get(&value)?
+        .get(&inscription_id_key(&inscription_id))?
         .map(|v| bincode::deserialize::<TransferInfo>(v.value()).unwrap()),
     )
   }
@@ -1,22 +1,29 @@
 use crate::{
   okx::datastore::brc20::{
-    Balance, DataStoreReadOnly, DataStoreReadWrite, Receipt, Tick, TokenInfo, TransferInfo,
-    TransferableLog,
+    Balance, DataStoreReadOnly, DataStoreReadWrite, Receipt, ReceiptHistory, Tick, TokenInfo,
+    TransferInfo, TransferableLog,
   },
   InscriptionId,
 };
 
 use super::*;
+use crate::index::undo::{self, UndoLog};
 use bitcoin::Txid;
-use redb::WriteTransaction;
+use redb::{ReadableTable, WriteTransaction};
 
 pub struct DataStore<'db, 'a> {
   wtx: &'a WriteTransaction<'db>,
+  undo: Option<&'a UndoLog>,
 }
 
 impl<'db, 'a> DataStore<'db, 'a> {
   pub fn new(wtx: &'a WriteTransaction<'db>) -> Self {
-    Self { wtx }
+    Self { wtx, undo: None }
+  }
+
+  /// Records every mutation in `undo`, if given, so the block can be unwound after a reorg.
+  pub fn with_undo_log(self, undo: Option<&'a UndoLog>) -> Self {
+    Self { undo, ..self }
   }
 }
 
@@ -39,10 +46,6 @@ impl<'db, 'a> DataStoreReadOnly for DataStore<'db, 'a> {
     read_only::new_with_wtx(self.wtx).get_token_info(tick)
   }
 
-  fn get_tokens_info(&self) -> Result<Vec<TokenInfo>, Self::Error> {
-    read_only::new_with_wtx(self.wtx).get_tokens_info()
-  }
-
   fn get_transaction_receipts(&self, txid: &Txid) -> Result<Vec<Receipt>, Self::Error> {
     read_only::new_with_wtx(self.wtx).get_transaction_receipts(txid)
   }
@@ -81,16 +84,32 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     script_key: &ScriptKey,
     new_balance: Balance,
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20_BALANCES)?.insert(
-      script_tick_key(script_key, &new_balance.tick).as_str(),
+    let mut table = self.wtx.open_table(BRC20_BALANCES)?;
+    let key = script_tick_key(script_key, &new_balance.tick);
+    let new = table.get(key.as_slice())?.is_none();
+    undo::insert(
+      self.undo,
+      BRC20_BALANCES,
+      &mut table,
+      key.as_slice(),
       bincode::serialize(&new_balance).unwrap().as_slice(),
     )?;
+    if new {
+      save_owner(
+        &mut self.wtx.open_table(SCRIPT_HASH_TO_SCRIPT_KEY)?,
+        self.undo,
+        script_key,
+      )?;
+    }
     Ok(())
   }
 
   fn insert_token_info(&self, tick: &Tick, new_info: &TokenInfo) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20_TOKEN)?.insert(
-      tick.to_lowercase().hex().as_str(),
+    undo::insert(
+      self.undo,
+      BRC20_TOKEN,
+      &mut self.wtx.open_table(BRC20_TOKEN)?,
+      tick_key(tick).as_slice(),
       bincode::serialize(new_info).unwrap().as_slice(),
     )?;
     Ok(())
@@ -109,8 +128,11 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     info.minted = minted_amt;
     info.latest_mint_number = minted_block_number;
 
-    self.wtx.open_table(BRC20_TOKEN)?.insert(
-      tick.to_lowercase().hex().as_str(),
+    undo::insert(
+      self.undo,
+      BRC20_TOKEN,
+      &mut self.wtx.open_table(BRC20_TOKEN)?,
+      tick_key(tick).as_slice(),
       bincode::serialize(&info).unwrap().as_slice(),
     )?;
     Ok(())
@@ -121,8 +143,11 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     txid: &Txid,
     receipts: &[Receipt],
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20_EVENTS)?.insert(
-      txid.to_string().as_str(),
+    undo::insert(
+      self.undo,
+      BRC20_EVENTS,
+      &mut self.wtx.open_table(BRC20_EVENTS)?,
+      txid_key(txid).as_slice(),
       bincode::serialize(receipts).unwrap().as_slice(),
     )?;
     Ok(())
@@ -134,6 +159,28 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     self.save_transaction_receipts(txid, &receipts)
   }
 
+  fn save_receipt_history(
+    &self,
+    height: u64,
+    position: u32,
+    txid: &Txid,
+    receipts: &[Receipt],
+  ) -> Result<(), Self::Error> {
+    for (index, receipt) in receipts.iter().enumerate() {
+      let key = receipt_key(height, position, u32::try_from(index).unwrap());
+      for history in ReceiptHistory::of(receipt) {
+        undo::insert(
+          self.undo,
+          history.table(),
+          &mut self.wtx.open_table(history.table())?,
+          [history.prefix().as_slice(), &key].concat().as_slice(),
+          txid_key(txid).as_slice(),
+        )?;
+      }
+    }
+    Ok(())
+  }
+
   fn insert_transferable(
     &self,
     script: &ScriptKey,
@@ -150,8 +197,11 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
 
     logs.push(inscription);
 
-    self.wtx.open_table(BRC20_TRANSFERABLELOG)?.insert(
-      script_tick_key(script, tick).as_str(),
+    undo::insert(
+      self.undo,
+      BRC20_TRANSFERABLELOG,
+      &mut self.wtx.open_table(BRC20_TRANSFERABLELOG)?,
+      script_tick_key(script, tick).as_slice(),
       bincode::serialize(&logs).unwrap().as_slice(),
     )?;
     Ok(())
@@ -169,8 +219,11 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     logs.retain(|log| log.inscription_id != inscription_id);
 
     if logs.len() != old_len {
-      self.wtx.open_table(BRC20_TRANSFERABLELOG)?.insert(
-        script_tick_key(script, tick).as_str(),
+      undo::insert(
+        self.undo,
+        BRC20_TRANSFERABLELOG,
+        &mut self.wtx.open_table(BRC20_TRANSFERABLELOG)?,
+        script_tick_key(script, tick).as_slice(),
         bincode::serialize(&logs).unwrap().as_slice(),
       )?;
     }
@@ -187,7 +240,10 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     txid.copy_from_slice(inscription_id.txid.as_ref());
     index.copy_from_slice(&inscription_id.index.to_be_bytes());
 
-    self.wtx.open_table(BRC20_INSCRIBE_TRANSFER)?.insert(
+    undo::insert(
+      self.undo,
+      BRC20_INSCRIBE_TRANSFER,
+      &mut self.wtx.open_table(BRC20_INSCRIBE_TRANSFER)?,
       &value,
       bincode::serialize(&transfer_info).unwrap().as_slice(),
     )?;
@@ -203,10 +259,12 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     txid.copy_from_slice(inscription_id.txid.as_ref());
     index.copy_from_slice(&inscription_id.index.to_be_bytes());
 
-    self
-      .wtx
-      .open_table(BRC20_INSCRIBE_TRANSFER)?
-      .remove(&value)?;
+    undo::remove(
+      self.undo,
+      BRC20_INSCRIBE_TRANSFER,
+      &mut self.wtx.open_table(BRC20_INSCRIBE_TRANSFER)?,
+      &value,
+    )?;
     Ok(())
   }
 }
@@ -219,6 +277,7 @@ mod tests {
   };
 
   use super::*;
+  use crate::okx::datastore::page::PageRequest;
   use crate::SatPoint;
   use bitcoin::Address;
   use redb::Database;
@@ -337,6 +396,60 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_balance_owners_are_kept_once() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20db = DataStore::new(&wtx);
+
+    let script = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    let balances = ["abcd", "efgh"].map(|tick| Balance {
+      tick: Tick::from_str(tick).unwrap(),
+      overall_balance: 30,
+      transferable_balance: 10,
+    });
+    for balance in &balances {
+      brc20db
+        .update_token_balance(&script, balance.clone())
+        .unwrap();
+    }
+
+    let table = wtx.open_table(BRC20_BALANCES).unwrap();
+    for balance in &balances {
+      assert_eq!(
+        table
+          .get(script_tick_key(&script, &balance.tick).as_slice())
+          .unwrap()
+          .unwrap()
+          .value(),
+        bincode::serialize(balance).unwrap()
+      );
+    }
+    drop(table);
+    assert_eq!(
+      wtx
+        .open_table(SCRIPT_HASH_TO_SCRIPT_KEY)
+        .unwrap()
+        .len()
+        .unwrap(),
+      1
+    );
+
+    let mut owned = Vec::new();
+    read_only::new_with_wtx(&wtx)
+      .for_each_balance(|owner, balance| {
+        owned.push((owner, balance));
+        Ok(())
+      })
+      .unwrap();
+    assert_eq!(owned, balances.map(|balance| (script.clone(), balance)));
+  }
+
   #[test]
   fn test_get_set_token_info() {
     let dbfile = NamedTempFile::new().unwrap();
@@ -376,7 +489,7 @@ mod tests {
   }
 
   #[test]
-  fn test_get_tokens_info() {
+  fn test_get_tokens_info_page() {
     let dbfile = NamedTempFile::new().unwrap();
     let db = Database::create(dbfile.path()).unwrap();
     let wtx = db.begin_write().unwrap();
@@ -469,10 +582,12 @@ mod tests {
     brc20db.insert_token_info(&expect3.tick, &expect3).unwrap();
     brc20db.insert_token_info(&expect4.tick, &expect4).unwrap();
 
-    let mut infos = brc20db.get_tokens_info().unwrap();
-    infos.sort_by(|a, b| a.tick.cmp(&b.tick));
+    let infos = read_only::new_with_wtx(&wtx)
+      .get_tokens_info_page(&PageRequest::default())
+      .unwrap()
+      .items;
     let mut expect = vec![expect1, expect2, expect3, expect4];
-    expect.sort_by(|a, b| a.tick.cmp(&b.tick));
+    expect.sort_by_key(|info| info.tick.to_lowercase().to_padded_bytes());
     assert_eq!(infos, expect);
   }
 
@@ -715,6 +830,63 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_transferable_page_resumes_after_removed_logs() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let wtx = db.begin_write().unwrap();
+    let brc20db = DataStore::new(&wtx);
+
+    let script = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    let tick = Tick::from_str("m23e").unwrap();
+    let log = |n: i64| TransferableLog {
+      inscription_id: InscriptionId::from_str(&format!("{}i1", n.to_string().repeat(64))).unwrap(),
+      inscription_number: n,
+      amount: 10,
+      tick: tick.clone(),
+      owner: script.clone(),
+    };
+    for n in [3, 1, 2] {
+      brc20db.insert_transferable(&script, &tick, log(n)).unwrap();
+    }
+
+    let reader = read_only::new_with_wtx(&wtx);
+    let request = PageRequest {
+      limit: Some(2),
+      ..Default::default()
+    };
+    let page = reader.get_transferable_page(&script, &request).unwrap();
+    assert_eq!(page.items, vec![log(1), log(2)]);
+    assert_eq!(
+      reader
+        .get_transferable_by_tick_page(&script, &tick, &request)
+        .unwrap(),
+      page
+    );
+
+    brc20db
+      .remove_transferable(&script, &tick, log(1).inscription_id)
+      .unwrap();
+
+    let request = PageRequest {
+      cursor: page.next,
+      ..request
+    };
+    let page = reader.get_transferable_page(&script, &request).unwrap();
+    assert_eq!(page.items, vec![log(3)]);
+    assert_eq!(page.next, None);
+    assert_eq!(
+      reader
+        .get_transferable_by_tick_page(&script, &tick, &request)
+        .unwrap(),
+      page
+    );
+  }
+
   #[test]
   fn test_get_transferable_by_id() {
     let dbfile = NamedTempFile::new().unwrap();
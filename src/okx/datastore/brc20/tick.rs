@@ -3,6 +3,8 @@ use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::{fmt::Formatter, str::FromStr};
 
 pub const TICK_BYTE_COUNT: usize = 4;
+// a lowercased utf-8 character takes at most 4 bytes
+pub const LOWER_TICK_BYTE_COUNT: usize = TICK_BYTE_COUNT * 4;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Tick([u8; TICK_BYTE_COUNT]);
@@ -70,18 +72,16 @@ impl LowerTick {
     std::str::from_utf8(&self.0).unwrap()
   }
 
+  #[cfg(test)]
   pub fn hex(&self) -> String {
-    let mut data = [0u8; TICK_BYTE_COUNT * 4];
-    data[..self.0.len()].copy_from_slice(&self.0);
-    hex::encode(data)
-  }
-
-  pub fn min_hex() -> String {
-    hex::encode([0u8; TICK_BYTE_COUNT * 4])
+    hex::encode(self.to_padded_bytes())
   }
 
-  pub fn max_hex() -> String {
-    hex::encode([0xffu8; TICK_BYTE_COUNT * 4])
+  /// Returns the lowercase tick zero-padded to a fixed width, used as a binary table key.
+  pub fn to_padded_bytes(&self) -> [u8; LOWER_TICK_BYTE_COUNT] {
+    let mut data = [0u8; LOWER_TICK_BYTE_COUNT];
+    data[..self.0.len()].copy_from_slice(&self.0);
+    data
   }
 }
 
@@ -27,6 +27,23 @@ pub struct Receipt {
   pub result: Result<Vec<Event>, BRC20SError>,
 }
 
+impl Receipt {
+  /// Returns the ids of the ticks the events of the receipt refer to.
+  pub fn tick_ids(&self) -> impl Iterator<Item = TickId> + '_ {
+    self
+      .result
+      .iter()
+      .flatten()
+      .filter_map(|event| match event {
+        Event::DeployTick(event) => Some(event.tick_id),
+        Event::DeployPool(event) => Some(TickId::from(event.pid.clone())),
+        Event::InscribeTransfer(event) => Some(event.tick_id),
+        Event::Transfer(event) => Some(event.tick_id),
+        _ => None,
+      })
+  }
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub enum Event {
   DeployTick(DeployTickEvent),
@@ -0,0 +1,467 @@
+use super::{
+  redb::{
+    max_script_tick_id_key, max_script_transferable_key, max_stake_tickid_key,
+    max_tickid_stake_key, min_script_tick_id_key, min_script_transferable_key,
+    min_stake_tickid_key, min_tickid_stake_key, pid_key, script_pid_key, script_pledged_key,
+    script_tickid_inscriptionid_key, script_tickid_key, stake_tickid_key, tick_id_key,
+    tickid_stake_key,
+  },
+  Balance, DataStoreReadOnly, DataStoreReadWrite, InscriptionOperation, Pid, PledgedTick, PoolInfo,
+  Receipt, ReceiptHistory, StakeInfo, TickId, TickInfo, TransferInfo, TransferableAsset, UserInfo,
+};
+use crate::{
+  okx::datastore::{redb::receipt_key, ScriptKey},
+  InscriptionId,
+};
+use bitcoin::Txid;
+use redb::TableHandle;
+use std::{
+  cell::RefCell,
+  collections::{BTreeMap, HashMap},
+  convert::Infallible,
+};
+
+/// MemoryStore is an in-memory implementation of the BRC20S datastore traits.
+///
+/// It keeps the same key layout and bincode encoding as the redb tables, so range
+/// queries and pagination return results in the same order as the persistent store.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+  #[allow(dead_code)]
+  txid_to_inscription_receipts: RefCell<HashMap<Txid, Vec<u8>>>,
+  tick_info: RefCell<BTreeMap<Vec<u8>, Vec<u8>>>,
+  pid_to_poolinfo: RefCell<BTreeMap<Vec<u8>, Vec<u8>>>,
+  user_stakeinfo: RefCell<BTreeMap<Vec<u8>, Vec<u8>>>,
+  pid_to_userinfo: RefCell<BTreeMap<Vec<u8>, Vec<u8>>>,
+  stake_tickid_to_pid: RefCell<BTreeMap<Vec<u8>, Vec<u8>>>,
+  tickid_stake_to_pid: RefCell<BTreeMap<Vec<u8>, Vec<u8>>>,
+  balances: RefCell<BTreeMap<Vec<u8>, Vec<u8>>>,
+  transferable_assets: RefCell<BTreeMap<Vec<u8>, Vec<u8>>>,
+  txid_to_receipts: RefCell<HashMap<Txid, Vec<u8>>>,
+  inscribe_transfer: RefCell<HashMap<InscriptionId, Vec<u8>>>,
+  receipt_histories: RefCell<BTreeMap<(String, Vec<u8>), Txid>>,
+}
+
+impl MemoryStore {
+  pub fn new() -> Self {
+    Self::default()
+  }
+}
+
+impl DataStoreReadOnly for MemoryStore {
+  type Error = Infallible;
+
+  // TXID_TO_INSCRIPTION_RECEIPTS
+  fn get_txid_to_inscription_receipts(
+    &self,
+    txid: &Txid,
+  ) -> Result<Vec<InscriptionOperation>, Self::Error> {
+    Ok(
+      self
+        .txid_to_inscription_receipts
+        .borrow()
+        .get(txid)
+        .map_or(Vec::new(), |v| {
+          bincode::deserialize::<Vec<InscriptionOperation>>(v).unwrap()
+        }),
+    )
+  }
+
+  // BRC20S_TICKINFO
+  fn get_tick_info(&self, tick_id: &TickId) -> Result<Option<TickInfo>, Self::Error> {
+    Ok(
+      self
+        .tick_info
+        .borrow()
+        .get(&tick_id_key(tick_id))
+        .map(|v| bincode::deserialize::<TickInfo>(v).unwrap()),
+    )
+  }
+
+  // BRC20S_PID_TO_POOLINFO
+  fn get_pid_to_poolinfo(&self, pid: &Pid) -> Result<Option<PoolInfo>, Self::Error> {
+    Ok(
+      self
+        .pid_to_poolinfo
+        .borrow()
+        .get(&pid_key(pid))
+        .map(|v| bincode::deserialize::<PoolInfo>(v).unwrap()),
+    )
+  }
+
+  // BRC20S_USER_STAKEINFO
+  fn get_user_stakeinfo(
+    &self,
+    script_key: &ScriptKey,
+    pledged_tick: &PledgedTick,
+  ) -> Result<Option<StakeInfo>, Self::Error> {
+    Ok(
+      self
+        .user_stakeinfo
+        .borrow()
+        .get(&script_pledged_key(script_key, pledged_tick))
+        .map(|v| bincode::deserialize::<StakeInfo>(v).unwrap()),
+    )
+  }
+
+  // BRC20S_PID_TO_USERINFO
+  fn get_pid_to_use_info(
+    &self,
+    script_key: &ScriptKey,
+    pid: &Pid,
+  ) -> Result<Option<UserInfo>, Self::Error> {
+    Ok(
+      self
+        .pid_to_userinfo
+        .borrow()
+        .get(&script_pid_key(script_key, pid))
+        .map(|v| bincode::deserialize::<UserInfo>(v).unwrap()),
+    )
+  }
+
+  // BRC20S_STAKE_TICKID_TO_PID
+  fn get_tickid_stake_to_pid(
+    &self,
+    tick_id: &TickId,
+    pledged: &PledgedTick,
+  ) -> Result<Option<Pid>, Self::Error> {
+    Ok(
+      self
+        .stake_tickid_to_pid
+        .borrow()
+        .get(&stake_tickid_key(pledged, tick_id))
+        .map(|v| bincode::deserialize::<Pid>(v).unwrap()),
+    )
+  }
+
+  // get_tickid_to_all_pid
+  fn get_tickid_to_all_pid(&self, tick_id: &TickId) -> Result<Vec<Pid>, Self::Error> {
+    Ok(
+      self
+        .tickid_stake_to_pid
+        .borrow()
+        .range(min_tickid_stake_key(tick_id)..=max_tickid_stake_key(tick_id))
+        .map(|(_, data)| bincode::deserialize::<Pid>(data).unwrap())
+        .collect(),
+    )
+  }
+
+  // get_stake_to_all_pid
+  fn get_stake_to_all_pid(&self, pledged: &PledgedTick) -> Result<Vec<Pid>, Self::Error> {
+    Ok(
+      self
+        .stake_tickid_to_pid
+        .borrow()
+        .range(min_stake_tickid_key(pledged)..=max_stake_tickid_key(pledged))
+        .map(|(_, data)| bincode::deserialize::<Pid>(data).unwrap())
+        .collect(),
+    )
+  }
+
+  // BRC20S_BALANCE
+  fn get_balance(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &TickId,
+  ) -> Result<Option<Balance>, Self::Error> {
+    Ok(
+      self
+        .balances
+        .borrow()
+        .get(&script_tickid_key(script_key, tick_id))
+        .map(|v| {
+          let bal = bincode::deserialize::<Balance>(v).unwrap();
+          assert_eq!(&bal.tick_id, tick_id);
+          bal
+        }),
+    )
+  }
+
+  fn get_balances(&self, script_key: &ScriptKey) -> Result<Vec<(TickId, Balance)>, Self::Error> {
+    Ok(
+      self
+        .balances
+        .borrow()
+        .range(min_script_tick_id_key(script_key)..=max_script_tick_id_key(script_key))
+        .map(|(_, data)| {
+          let bal = bincode::deserialize::<Balance>(data).unwrap();
+          (bal.tick_id, bal)
+        })
+        .collect(),
+    )
+  }
+
+  // BRC20S_TRANSFERABLE_ASSETS
+  fn get_transferable_asset(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &TickId,
+    inscription_id: &InscriptionId,
+  ) -> Result<Option<TransferableAsset>, Self::Error> {
+    Ok(
+      self
+        .transferable_assets
+        .borrow()
+        .get(&script_tickid_inscriptionid_key(
+          script_key,
+          tick_id,
+          inscription_id,
+        ))
+        .map(|v| bincode::deserialize::<TransferableAsset>(v).unwrap()),
+    )
+  }
+
+  fn get_transferable(&self, script: &ScriptKey) -> Result<Vec<TransferableAsset>, Self::Error> {
+    Ok(
+      self
+        .transferable_assets
+        .borrow()
+        .range(min_script_transferable_key(script)..=max_script_transferable_key(script))
+        .map(|(_, v)| bincode::deserialize::<TransferableAsset>(v).unwrap())
+        .collect(),
+    )
+  }
+
+  fn get_transferable_by_tickid(
+    &self,
+    script: &ScriptKey,
+    tick_id: &TickId,
+  ) -> Result<Vec<TransferableAsset>, Self::Error> {
+    Ok(
+      self
+        .get_transferable(script)?
+        .into_iter()
+        .filter(|log| log.tick_id == *tick_id)
+        .collect(),
+    )
+  }
+
+  fn get_transferable_by_id(
+    &self,
+    script: &ScriptKey,
+    inscription_id: &InscriptionId,
+  ) -> Result<Option<TransferableAsset>, Self::Error> {
+    Ok(
+      self
+        .get_transferable(script)?
+        .into_iter()
+        .find(|log| log.inscription_id == *inscription_id),
+    )
+  }
+
+  // BRC20S_TXID_TO_RECEIPTS
+  fn get_transaction_receipts(&self, txid: &Txid) -> Result<Vec<Receipt>, Self::Error> {
+    Ok(
+      self
+        .txid_to_receipts
+        .borrow()
+        .get(txid)
+        .map_or(Vec::new(), |v| {
+          bincode::deserialize::<Vec<Receipt>>(v).unwrap()
+        }),
+    )
+  }
+
+  fn get_txid_to_receipts(&self, txid: &Txid) -> Result<Vec<Receipt>, Self::Error> {
+    self.get_transaction_receipts(txid)
+  }
+
+  fn get_inscribe_transfer_inscription(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<TransferInfo>, Self::Error> {
+    Ok(
+      self
+        .inscribe_transfer
+        .borrow()
+        .get(&inscription_id)
+        .map(|v| bincode::deserialize::<TransferInfo>(v).unwrap()),
+    )
+  }
+}
+
+impl DataStoreReadWrite for MemoryStore {
+  // TXID_TO_INSCRIPTION_RECEIPTS
+  fn set_txid_to_inscription_receipts(
+    &self,
+    tx_id: &Txid,
+    inscription_operations: &[InscriptionOperation],
+  ) -> Result<(), Self::Error> {
+    self
+      .txid_to_inscription_receipts
+      .borrow_mut()
+      .insert(*tx_id, bincode::serialize(inscription_operations).unwrap());
+    Ok(())
+  }
+
+  // BRC20S_TICKINFO
+  fn set_tick_info(&self, tick_id: &TickId, tick_info: &TickInfo) -> Result<(), Self::Error> {
+    self
+      .tick_info
+      .borrow_mut()
+      .insert(tick_id_key(tick_id), bincode::serialize(tick_info).unwrap());
+    Ok(())
+  }
+
+  // BRC20S_PID_TO_POOLINFO
+  fn set_pid_to_poolinfo(&self, pid: &Pid, pool_info: &PoolInfo) -> Result<(), Self::Error> {
+    self
+      .pid_to_poolinfo
+      .borrow_mut()
+      .insert(pid_key(pid), bincode::serialize(pool_info).unwrap());
+    Ok(())
+  }
+
+  // BRC20S_USER_STAKEINFO
+  fn set_user_stakeinfo(
+    &self,
+    script_key: &ScriptKey,
+    pledged_tick: &PledgedTick,
+    stake_info: &StakeInfo,
+  ) -> Result<(), Self::Error> {
+    self.user_stakeinfo.borrow_mut().insert(
+      script_pledged_key(script_key, pledged_tick),
+      bincode::serialize(stake_info).unwrap(),
+    );
+    Ok(())
+  }
+
+  // BRC20S_PID_TO_USERINFO
+  fn set_pid_to_use_info(
+    &self,
+    script_key: &ScriptKey,
+    pid: &Pid,
+    user_info: &UserInfo,
+  ) -> Result<(), Self::Error> {
+    self.pid_to_userinfo.borrow_mut().insert(
+      script_pid_key(script_key, pid),
+      bincode::serialize(user_info).unwrap(),
+    );
+    Ok(())
+  }
+
+  // BRC20S_STAKE_TICKID_TO_PID, BRC20S_TICKID_STAKE_TO_PID
+  fn set_tickid_stake_to_pid(
+    &self,
+    tick_id: &TickId,
+    pledged: &PledgedTick,
+    pid: &Pid,
+  ) -> Result<(), Self::Error> {
+    self.stake_tickid_to_pid.borrow_mut().insert(
+      stake_tickid_key(pledged, tick_id),
+      bincode::serialize(pid).unwrap(),
+    );
+    self.tickid_stake_to_pid.borrow_mut().insert(
+      tickid_stake_key(pledged, tick_id),
+      bincode::serialize(pid).unwrap(),
+    );
+    Ok(())
+  }
+
+  // BRC20S_BALANCE
+  fn set_token_balance(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &TickId,
+    balance: Balance,
+  ) -> Result<(), Self::Error> {
+    self.balances.borrow_mut().insert(
+      script_tickid_key(script_key, tick_id),
+      bincode::serialize(&balance).unwrap(),
+    );
+    Ok(())
+  }
+
+  // BRC20S_TRANSFERABLE_ASSETS
+  fn set_transferable_assets(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &TickId,
+    inscription_id: &InscriptionId,
+    transferable_asset: &TransferableAsset,
+  ) -> Result<(), Self::Error> {
+    self.transferable_assets.borrow_mut().insert(
+      script_tickid_inscriptionid_key(script_key, tick_id, inscription_id),
+      bincode::serialize(transferable_asset).unwrap(),
+    );
+    Ok(())
+  }
+
+  // BRC20S_TXID_TO_RECEIPTS
+  fn add_transaction_receipt(&self, tx_id: &Txid, receipt: &Receipt) -> Result<(), Self::Error> {
+    let mut receipts = self.get_transaction_receipts(tx_id)?;
+    receipts.push(receipt.clone());
+    self.save_transaction_receipts(tx_id, &receipts)
+  }
+
+  fn save_transaction_receipts(
+    &self,
+    tx_id: &Txid,
+    receipts: &[Receipt],
+  ) -> Result<(), Self::Error> {
+    self
+      .txid_to_receipts
+      .borrow_mut()
+      .insert(*tx_id, bincode::serialize(receipts).unwrap());
+    Ok(())
+  }
+
+  fn save_receipt_history(
+    &self,
+    height: u64,
+    position: u32,
+    txid: &Txid,
+    receipts: &[Receipt],
+  ) -> Result<(), Self::Error> {
+    for (index, receipt) in receipts.iter().enumerate() {
+      let key = receipt_key(height, position, u32::try_from(index).unwrap());
+      for history in ReceiptHistory::of(receipt) {
+        self.receipt_histories.borrow_mut().insert(
+          (
+            history.table().name().to_string(),
+            [history.prefix().as_slice(), &key].concat(),
+          ),
+          *txid,
+        );
+      }
+    }
+    Ok(())
+  }
+
+  fn remove_transferable(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &TickId,
+    inscription_id: &InscriptionId,
+  ) -> Result<(), Self::Error> {
+    self
+      .transferable_assets
+      .borrow_mut()
+      .remove(&script_tickid_inscriptionid_key(
+        script_key,
+        tick_id,
+        inscription_id,
+      ));
+    Ok(())
+  }
+
+  fn insert_inscribe_transfer_inscription(
+    &self,
+    inscription_id: InscriptionId,
+    transfer_info: TransferInfo,
+  ) -> Result<(), Self::Error> {
+    self
+      .inscribe_transfer
+      .borrow_mut()
+      .insert(inscription_id, bincode::serialize(&transfer_info).unwrap());
+    Ok(())
+  }
+
+  fn remove_inscribe_transfer_inscription(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<(), Self::Error> {
+    self.inscribe_transfer.borrow_mut().remove(&inscription_id);
+    Ok(())
+  }
+}
@@ -1,5 +1,7 @@
 mod balance;
 mod event;
+pub mod memory;
+pub mod overlay;
 pub mod pool_info;
 pub mod redb;
 mod stake_info;
@@ -16,6 +18,53 @@ use crate::InscriptionId;
 use bitcoin::Txid;
 use std::fmt::{Debug, Display};
 
+/// A list of receipts in the order they were made, read newest first.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReceiptHistory {
+  /// Successful receipts of a tick and its pools, except stakes and unstakes.
+  Tick(TickId),
+  /// Successful receipts of a pool.
+  Pool(Pid),
+  /// Successful deploys of every tick and pool.
+  Deploys,
+}
+
+impl ReceiptHistory {
+  /// Returns the histories `receipt` is listed in.
+  pub fn of(receipt: &Receipt) -> Vec<Self> {
+    let Ok(events) = &receipt.result else {
+      return Vec::new();
+    };
+
+    let mut histories = Vec::new();
+    if receipt.op == OperationType::Deploy {
+      histories.push(Self::Deploys);
+    }
+    for event in events {
+      let (tick_id, pid) = match event {
+        Event::DeployTick(deploy) => (Some(deploy.tick_id), None),
+        Event::DeployPool(deploy) => (Some(TickId::from(deploy.pid.clone())), Some(&deploy.pid)),
+        Event::Mint(mint) => (Some(TickId::from(mint.pid.clone())), Some(&mint.pid)),
+        Event::Deposit(deposit) => (None, Some(&deposit.pid)),
+        Event::Withdraw(withdraw) => (None, Some(&withdraw.pid)),
+        Event::PassiveWithdraw(withdraw) => (None, Some(&withdraw.pid)),
+        Event::InscribeTransfer(transfer) => (Some(transfer.tick_id), None),
+        Event::Transfer(transfer) => (Some(transfer.tick_id), None),
+      };
+      for history in tick_id
+        .map(Self::Tick)
+        .into_iter()
+        .chain(pid.cloned().map(Self::Pool))
+      {
+        if !histories.contains(&history) {
+          histories.push(history);
+        }
+      }
+    }
+    histories
+  }
+}
+
 pub trait DataStoreReadOnly {
   type Error: Debug + Display;
 
@@ -28,23 +77,9 @@ pub trait DataStoreReadOnly {
   // BRC20S_TICKINFO
   fn get_tick_info(&self, tick_id: &TickId) -> Result<Option<TickInfo>, Self::Error>;
 
-  fn get_all_tick_info(
-    &self,
-    start: usize,
-    limit: Option<usize>,
-  ) -> Result<(Vec<TickInfo>, usize), Self::Error>;
-
   // BRC20S_PID_TO_POOLINFO
   fn get_pid_to_poolinfo(&self, pid: &Pid) -> Result<Option<PoolInfo>, Self::Error>;
 
-  fn get_all_pools_by_tid(&self, tick_id: &TickId) -> Result<Vec<PoolInfo>, Self::Error>;
-
-  fn get_all_poolinfo(
-    &self,
-    start: usize,
-    limit: Option<usize>,
-  ) -> Result<(Vec<PoolInfo>, usize), Self::Error>;
-
   // BRC20S_USER_STAKEINFO
   fn get_user_stakeinfo(
     &self,
@@ -67,9 +102,11 @@ pub trait DataStoreReadOnly {
   ) -> Result<Option<Pid>, Self::Error>;
 
   // get_tickid_to_all_pid
+  #[allow(unused)]
   fn get_tickid_to_all_pid(&self, tick_id: &TickId) -> Result<Vec<Pid>, Self::Error>;
 
   // get_stake_to_all_pid
+  #[allow(unused)]
   fn get_stake_to_all_pid(&self, pledged: &PledgedTick) -> Result<Vec<Pid>, Self::Error>;
 
   // BRC20S_BALANCE
@@ -173,6 +210,17 @@ pub trait DataStoreReadWrite: DataStoreReadOnly {
   fn save_transaction_receipts(&self, tx_id: &Txid, receipt: &[Receipt])
     -> Result<(), Self::Error>;
 
+  // BRC20S_TICK_RECEIPTS, BRC20S_POOL_RECEIPTS, BRC20S_DEPLOY_RECEIPTS
+  /// Lists the receipts of the transaction at `position` in the block at
+  /// `height` in the receipt histories they belong to.
+  fn save_receipt_history(
+    &self,
+    height: u64,
+    position: u32,
+    txid: &Txid,
+    receipts: &[Receipt],
+  ) -> Result<(), Self::Error>;
+
   fn remove_transferable(
     &self,
     script: &ScriptKey,
@@ -191,3 +239,68 @@ pub trait DataStoreReadWrite: DataStoreReadOnly {
     inscription_id: InscriptionId,
   ) -> Result<(), Self::Error>;
 }
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::okx::protocol::brc20s::BRC20SError, crate::test::*, std::str::FromStr};
+
+  fn receipt(op: OperationType, result: Result<Vec<Event>, BRC20SError>) -> Receipt {
+    Receipt {
+      inscription_id: inscription_id(1),
+      inscription_number: 0,
+      old_satpoint: satpoint(1, 0),
+      new_satpoint: satpoint(1, 0),
+      op,
+      from: ScriptKey::from_address(address()),
+      to: ScriptKey::from_address(address()),
+      result,
+    }
+  }
+
+  #[test]
+  fn receipt_histories() {
+    let pid = Pid::from_str("13395c5283#01").unwrap();
+    let tick_id = TickId::from_str("13395c5283").unwrap();
+
+    assert_eq!(
+      ReceiptHistory::of(&receipt(
+        OperationType::Mint,
+        Ok(vec![Event::Mint(MintEvent {
+          pid: pid.clone(),
+          amt: 1,
+        })]),
+      )),
+      [
+        ReceiptHistory::Tick(tick_id),
+        ReceiptHistory::Pool(pid.clone())
+      ],
+    );
+
+    assert_eq!(
+      ReceiptHistory::of(&receipt(
+        OperationType::Stake,
+        Ok(vec![
+          Event::Deposit(DepositEvent {
+            pid: pid.clone(),
+            amt: 1,
+            period_settlement_reward: 0,
+          }),
+          Event::Deposit(DepositEvent {
+            pid: pid.clone(),
+            amt: 2,
+            period_settlement_reward: 0,
+          }),
+        ]),
+      )),
+      [ReceiptHistory::Pool(pid)],
+    );
+
+    assert_eq!(
+      ReceiptHistory::of(&receipt(
+        OperationType::Deploy,
+        Err(BRC20SError::InvalidTickLen("abcde".to_string())),
+      )),
+      [],
+    );
+  }
+}
@@ -0,0 +1,562 @@
+use super::{
+  memory::MemoryStore,
+  redb::{
+    pid_key, script_pid_key, script_pledged_key, script_tickid_inscriptionid_key,
+    script_tickid_key, stake_tickid_key, tick_id_key, txid_key,
+  },
+  Balance, DataStoreReadOnly, DataStoreReadWrite, InscriptionOperation, Pid, PledgedTick, PoolInfo,
+  Receipt, StakeInfo, TickId, TickInfo, TransferInfo, TransferableAsset, UserInfo,
+};
+use crate::{okx::datastore::ScriptKey, InscriptionId};
+use bitcoin::Txid;
+use std::{cell::RefCell, collections::HashSet};
+
+/// OverlayStore layers a `MemoryStore` over a read-only BRC20S store.
+///
+/// Every entry is copied from the base store into memory the first time it is
+/// read or written, and only the memory copy is touched afterwards, so the base
+/// store never sees a write and entries removed in the overlay stay removed.
+pub struct OverlayStore<'a, R: DataStoreReadOnly> {
+  base: &'a R,
+  memory: MemoryStore,
+  loaded: RefCell<HashSet<(&'static str, Vec<u8>)>>,
+}
+
+impl<'a, R: DataStoreReadOnly> OverlayStore<'a, R> {
+  pub fn new(base: &'a R) -> Self {
+    Self {
+      base,
+      memory: MemoryStore::new(),
+      loaded: RefCell::new(HashSet::new()),
+    }
+  }
+
+  /// Runs `load` to copy an entry from the base store unless it is already in memory.
+  fn hydrate(
+    &self,
+    table: &'static str,
+    key: Vec<u8>,
+    load: impl FnOnce() -> Result<(), R::Error>,
+  ) -> Result<(), R::Error> {
+    if self.loaded.borrow().contains(&(table, key.clone())) {
+      return Ok(());
+    }
+    load()?;
+    self.loaded.borrow_mut().insert((table, key));
+    Ok(())
+  }
+
+  /// Marks an entry as loaded without reading the base store, for entries about to be overwritten.
+  fn shadow(&self, table: &'static str, key: Vec<u8>) {
+    self.loaded.borrow_mut().insert((table, key));
+  }
+
+  #[allow(dead_code)]
+  fn hydrate_inscription_receipts(&self, txid: &Txid) -> Result<(), R::Error> {
+    self.hydrate("inscription_receipts", txid_key(txid), || {
+      let operations = self.base.get_txid_to_inscription_receipts(txid)?;
+      if !operations.is_empty() {
+        self
+          .memory
+          .set_txid_to_inscription_receipts(txid, &operations)
+          .unwrap();
+      }
+      Ok(())
+    })
+  }
+
+  fn hydrate_tick_info(&self, tick_id: &TickId) -> Result<(), R::Error> {
+    self.hydrate("tick_info", tick_id_key(tick_id), || {
+      if let Some(info) = self.base.get_tick_info(tick_id)? {
+        self.memory.set_tick_info(tick_id, &info).unwrap();
+      }
+      Ok(())
+    })
+  }
+
+  fn hydrate_pool_info(&self, pid: &Pid) -> Result<(), R::Error> {
+    self.hydrate("pool_info", pid_key(pid), || {
+      if let Some(info) = self.base.get_pid_to_poolinfo(pid)? {
+        self.memory.set_pid_to_poolinfo(pid, &info).unwrap();
+      }
+      Ok(())
+    })
+  }
+
+  fn hydrate_stake_info(
+    &self,
+    script_key: &ScriptKey,
+    pledged_tick: &PledgedTick,
+  ) -> Result<(), R::Error> {
+    self.hydrate(
+      "stake_info",
+      script_pledged_key(script_key, pledged_tick),
+      || {
+        if let Some(info) = self.base.get_user_stakeinfo(script_key, pledged_tick)? {
+          self
+            .memory
+            .set_user_stakeinfo(script_key, pledged_tick, &info)
+            .unwrap();
+        }
+        Ok(())
+      },
+    )
+  }
+
+  fn hydrate_user_info(&self, script_key: &ScriptKey, pid: &Pid) -> Result<(), R::Error> {
+    self.hydrate("user_info", script_pid_key(script_key, pid), || {
+      if let Some(info) = self.base.get_pid_to_use_info(script_key, pid)? {
+        self
+          .memory
+          .set_pid_to_use_info(script_key, pid, &info)
+          .unwrap();
+      }
+      Ok(())
+    })
+  }
+
+  fn hydrate_stake_pid(&self, tick_id: &TickId, pledged: &PledgedTick) -> Result<(), R::Error> {
+    self.hydrate("stake_pid", stake_tickid_key(pledged, tick_id), || {
+      if let Some(pid) = self.base.get_tickid_stake_to_pid(tick_id, pledged)? {
+        self
+          .memory
+          .set_tickid_stake_to_pid(tick_id, pledged, &pid)
+          .unwrap();
+      }
+      Ok(())
+    })
+  }
+
+  /// Copies the stake-to-pool mappings of `pids` from the base store, using their pool info
+  /// to recover the staked tick the mappings are keyed by.
+  #[allow(unused)]
+  fn hydrate_stake_pids(&self, pids: Vec<Pid>) -> Result<(), R::Error> {
+    for pid in pids {
+      if let Some(pool) = self.base.get_pid_to_poolinfo(&pid)? {
+        self.hydrate_stake_pid(&TickId::from(pid), &pool.stake)?;
+      }
+    }
+    Ok(())
+  }
+
+  fn hydrate_balance(&self, script_key: &ScriptKey, tick_id: &TickId) -> Result<(), R::Error> {
+    self.hydrate("balance", script_tickid_key(script_key, tick_id), || {
+      if let Some(balance) = self.base.get_balance(script_key, tick_id)? {
+        self
+          .memory
+          .set_token_balance(script_key, tick_id, balance)
+          .unwrap();
+      }
+      Ok(())
+    })
+  }
+
+  fn hydrate_transferable_asset(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &TickId,
+    inscription_id: &InscriptionId,
+  ) -> Result<(), R::Error> {
+    self.hydrate(
+      "transferable",
+      script_tickid_inscriptionid_key(script_key, tick_id, inscription_id),
+      || {
+        if let Some(asset) =
+          self
+            .base
+            .get_transferable_asset(script_key, tick_id, inscription_id)?
+        {
+          self
+            .memory
+            .set_transferable_assets(script_key, tick_id, inscription_id, &asset)
+            .unwrap();
+        }
+        Ok(())
+      },
+    )
+  }
+
+  fn hydrate_receipts(&self, txid: &Txid) -> Result<(), R::Error> {
+    self.hydrate("receipts", txid_key(txid), || {
+      let receipts = self.base.get_transaction_receipts(txid)?;
+      if !receipts.is_empty() {
+        self
+          .memory
+          .save_transaction_receipts(txid, &receipts)
+          .unwrap();
+      }
+      Ok(())
+    })
+  }
+
+  fn hydrate_inscribe_transfer(&self, inscription_id: InscriptionId) -> Result<(), R::Error> {
+    self.hydrate(
+      "inscribe_transfer",
+      inscription_id.to_string().into_bytes(),
+      || {
+        if let Some(info) = self
+          .base
+          .get_inscribe_transfer_inscription(inscription_id)?
+        {
+          self
+            .memory
+            .insert_inscribe_transfer_inscription(inscription_id, info)
+            .unwrap();
+        }
+        Ok(())
+      },
+    )
+  }
+}
+
+impl<'a, R: DataStoreReadOnly> DataStoreReadOnly for OverlayStore<'a, R> {
+  type Error = R::Error;
+
+  // TXID_TO_INSCRIPTION_RECEIPTS
+  fn get_txid_to_inscription_receipts(
+    &self,
+    txid: &Txid,
+  ) -> Result<Vec<InscriptionOperation>, Self::Error> {
+    self.hydrate_inscription_receipts(txid)?;
+    Ok(self.memory.get_txid_to_inscription_receipts(txid).unwrap())
+  }
+
+  // BRC20S_TICKINFO
+  fn get_tick_info(&self, tick_id: &TickId) -> Result<Option<TickInfo>, Self::Error> {
+    self.hydrate_tick_info(tick_id)?;
+    Ok(self.memory.get_tick_info(tick_id).unwrap())
+  }
+
+  // BRC20S_PID_TO_POOLINFO
+  fn get_pid_to_poolinfo(&self, pid: &Pid) -> Result<Option<PoolInfo>, Self::Error> {
+    self.hydrate_pool_info(pid)?;
+    Ok(self.memory.get_pid_to_poolinfo(pid).unwrap())
+  }
+
+  // BRC20S_USER_STAKEINFO
+  fn get_user_stakeinfo(
+    &self,
+    script_key: &ScriptKey,
+    pledged_tick: &PledgedTick,
+  ) -> Result<Option<StakeInfo>, Self::Error> {
+    self.hydrate_stake_info(script_key, pledged_tick)?;
+    Ok(
+      self
+        .memory
+        .get_user_stakeinfo(script_key, pledged_tick)
+        .unwrap(),
+    )
+  }
+
+  // BRC20S_PID_TO_USERINFO
+  fn get_pid_to_use_info(
+    &self,
+    script_key: &ScriptKey,
+    pid: &Pid,
+  ) -> Result<Option<UserInfo>, Self::Error> {
+    self.hydrate_user_info(script_key, pid)?;
+    Ok(self.memory.get_pid_to_use_info(script_key, pid).unwrap())
+  }
+
+  // BRC20S_STAKE_TICKID_TO_PID
+  fn get_tickid_stake_to_pid(
+    &self,
+    tick_id: &TickId,
+    pledged: &PledgedTick,
+  ) -> Result<Option<Pid>, Self::Error> {
+    self.hydrate_stake_pid(tick_id, pledged)?;
+    Ok(
+      self
+        .memory
+        .get_tickid_stake_to_pid(tick_id, pledged)
+        .unwrap(),
+    )
+  }
+
+  // get_tickid_to_all_pid
+  fn get_tickid_to_all_pid(&self, tick_id: &TickId) -> Result<Vec<Pid>, Self::Error> {
+    self.hydrate_stake_pids(self.base.get_tickid_to_all_pid(tick_id)?)?;
+    Ok(self.memory.get_tickid_to_all_pid(tick_id).unwrap())
+  }
+
+  // get_stake_to_all_pid
+  fn get_stake_to_all_pid(&self, pledged: &PledgedTick) -> Result<Vec<Pid>, Self::Error> {
+    self.hydrate_stake_pids(self.base.get_stake_to_all_pid(pledged)?)?;
+    Ok(self.memory.get_stake_to_all_pid(pledged).unwrap())
+  }
+
+  // BRC20S_BALANCE
+  fn get_balance(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &TickId,
+  ) -> Result<Option<Balance>, Self::Error> {
+    self.hydrate_balance(script_key, tick_id)?;
+    Ok(self.memory.get_balance(script_key, tick_id).unwrap())
+  }
+
+  fn get_balances(&self, script_key: &ScriptKey) -> Result<Vec<(TickId, Balance)>, Self::Error> {
+    for (tick_id, _) in self.base.get_balances(script_key)? {
+      self.hydrate_balance(script_key, &tick_id)?;
+    }
+    Ok(self.memory.get_balances(script_key).unwrap())
+  }
+
+  // BRC20S_TRANSFERABLE_ASSETS
+  fn get_transferable_asset(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &TickId,
+    inscription_id: &InscriptionId,
+  ) -> Result<Option<TransferableAsset>, Self::Error> {
+    self.hydrate_transferable_asset(script_key, tick_id, inscription_id)?;
+    Ok(
+      self
+        .memory
+        .get_transferable_asset(script_key, tick_id, inscription_id)
+        .unwrap(),
+    )
+  }
+
+  fn get_transferable(&self, script: &ScriptKey) -> Result<Vec<TransferableAsset>, Self::Error> {
+    for asset in self.base.get_transferable(script)? {
+      self.hydrate_transferable_asset(script, &asset.tick_id, &asset.inscription_id)?;
+    }
+    Ok(self.memory.get_transferable(script).unwrap())
+  }
+
+  fn get_transferable_by_tickid(
+    &self,
+    script: &ScriptKey,
+    tick_id: &TickId,
+  ) -> Result<Vec<TransferableAsset>, Self::Error> {
+    Ok(
+      self
+        .get_transferable(script)?
+        .into_iter()
+        .filter(|asset| asset.tick_id == *tick_id)
+        .collect(),
+    )
+  }
+
+  fn get_transferable_by_id(
+    &self,
+    script: &ScriptKey,
+    inscription_id: &InscriptionId,
+  ) -> Result<Option<TransferableAsset>, Self::Error> {
+    Ok(
+      self
+        .get_transferable(script)?
+        .into_iter()
+        .find(|asset| asset.inscription_id == *inscription_id),
+    )
+  }
+
+  // BRC20S_TXID_TO_RECEIPTS
+  fn get_transaction_receipts(&self, txid: &Txid) -> Result<Vec<Receipt>, Self::Error> {
+    self.hydrate_receipts(txid)?;
+    Ok(self.memory.get_transaction_receipts(txid).unwrap())
+  }
+
+  fn get_txid_to_receipts(&self, txid: &Txid) -> Result<Vec<Receipt>, Self::Error> {
+    self.get_transaction_receipts(txid)
+  }
+
+  fn get_inscribe_transfer_inscription(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<TransferInfo>, Self::Error> {
+    self.hydrate_inscribe_transfer(inscription_id)?;
+    Ok(
+      self
+        .memory
+        .get_inscribe_transfer_inscription(inscription_id)
+        .unwrap(),
+    )
+  }
+}
+
+impl<'a, R: DataStoreReadOnly> DataStoreReadWrite for OverlayStore<'a, R> {
+  // TXID_TO_INSCRIPTION_RECEIPTS
+  fn set_txid_to_inscription_receipts(
+    &self,
+    tx_id: &Txid,
+    inscription_operations: &[InscriptionOperation],
+  ) -> Result<(), Self::Error> {
+    self.shadow("inscription_receipts", txid_key(tx_id));
+    self
+      .memory
+      .set_txid_to_inscription_receipts(tx_id, inscription_operations)
+      .unwrap();
+    Ok(())
+  }
+
+  // BRC20S_TICKINFO
+  fn set_tick_info(&self, tick_id: &TickId, tick_info: &TickInfo) -> Result<(), Self::Error> {
+    self.shadow("tick_info", tick_id_key(tick_id));
+    self.memory.set_tick_info(tick_id, tick_info).unwrap();
+    Ok(())
+  }
+
+  // BRC20S_PID_TO_POOLINFO
+  fn set_pid_to_poolinfo(&self, pid: &Pid, pool_info: &PoolInfo) -> Result<(), Self::Error> {
+    self.shadow("pool_info", pid_key(pid));
+    self.memory.set_pid_to_poolinfo(pid, pool_info).unwrap();
+    Ok(())
+  }
+
+  // BRC20S_USER_STAKEINFO
+  fn set_user_stakeinfo(
+    &self,
+    script_key: &ScriptKey,
+    pledged_tick: &PledgedTick,
+    stake_info: &StakeInfo,
+  ) -> Result<(), Self::Error> {
+    self.shadow("stake_info", script_pledged_key(script_key, pledged_tick));
+    self
+      .memory
+      .set_user_stakeinfo(script_key, pledged_tick, stake_info)
+      .unwrap();
+    Ok(())
+  }
+
+  // BRC20S_PID_TO_USERINFO
+  fn set_pid_to_use_info(
+    &self,
+    script_key: &ScriptKey,
+    pid: &Pid,
+    user_info: &UserInfo,
+  ) -> Result<(), Self::Error> {
+    self.shadow("user_info", script_pid_key(script_key, pid));
+    self
+      .memory
+      .set_pid_to_use_info(script_key, pid, user_info)
+      .unwrap();
+    Ok(())
+  }
+
+  // BRC20S_STAKE_TICKID_TO_PID, BRC20S_TICKID_STAKE_TO_PID
+  fn set_tickid_stake_to_pid(
+    &self,
+    tick_id: &TickId,
+    pledged: &PledgedTick,
+    pid: &Pid,
+  ) -> Result<(), Self::Error> {
+    self.shadow("stake_pid", stake_tickid_key(pledged, tick_id));
+    self
+      .memory
+      .set_tickid_stake_to_pid(tick_id, pledged, pid)
+      .unwrap();
+    Ok(())
+  }
+
+  // BRC20S_BALANCE
+  fn set_token_balance(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &TickId,
+    balance: Balance,
+  ) -> Result<(), Self::Error> {
+    self.shadow("balance", script_tickid_key(script_key, tick_id));
+    self
+      .memory
+      .set_token_balance(script_key, tick_id, balance)
+      .unwrap();
+    Ok(())
+  }
+
+  // BRC20S_TRANSFERABLE_ASSETS
+  fn set_transferable_assets(
+    &self,
+    script_key: &ScriptKey,
+    tick_id: &TickId,
+    inscription_id: &InscriptionId,
+    transferable_asset: &TransferableAsset,
+  ) -> Result<(), Self::Error> {
+    self.shadow(
+      "transferable",
+      script_tickid_inscriptionid_key(script_key, tick_id, inscription_id),
+    );
+    self
+      .memory
+      .set_transferable_assets(script_key, tick_id, inscription_id, transferable_asset)
+      .unwrap();
+    Ok(())
+  }
+
+  // BRC20S_TXID_TO_RECEIPTS
+  fn add_transaction_receipt(&self, txid: &Txid, receipt: &Receipt) -> Result<(), Self::Error> {
+    self.hydrate_receipts(txid)?;
+    self.memory.add_transaction_receipt(txid, receipt).unwrap();
+    Ok(())
+  }
+
+  fn save_transaction_receipts(
+    &self,
+    tx_id: &Txid,
+    receipt: &[Receipt],
+  ) -> Result<(), Self::Error> {
+    self.shadow("receipts", txid_key(tx_id));
+    self
+      .memory
+      .save_transaction_receipts(tx_id, receipt)
+      .unwrap();
+    Ok(())
+  }
+
+  fn save_receipt_history(
+    &self,
+    height: u64,
+    position: u32,
+    txid: &Txid,
+    receipts: &[Receipt],
+  ) -> Result<(), Self::Error> {
+    self
+      .memory
+      .save_receipt_history(height, position, txid, receipts)
+      .unwrap();
+    Ok(())
+  }
+
+  fn remove_transferable(
+    &self,
+    script: &ScriptKey,
+    tick_id: &TickId,
+    inscription_id: &InscriptionId,
+  ) -> Result<(), Self::Error> {
+    self.shadow(
+      "transferable",
+      script_tickid_inscriptionid_key(script, tick_id, inscription_id),
+    );
+    self
+      .memory
+      .remove_transferable(script, tick_id, inscription_id)
+      .unwrap();
+    Ok(())
+  }
+
+  fn insert_inscribe_transfer_inscription(
+    &self,
+    inscription_id: InscriptionId,
+    transfer_info: TransferInfo,
+  ) -> Result<(), Self::Error> {
+    self.shadow("inscribe_transfer", inscription_id.to_string().into_bytes());
+    self
+      .memory
+      .insert_inscribe_transfer_inscription(inscription_id, transfer_info)
+      .unwrap();
+    Ok(())
+  }
+
+  fn remove_inscribe_transfer_inscription(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<(), Self::Error> {
+    self.shadow("inscribe_transfer", inscription_id.to_string().into_bytes());
+    self
+      .memory
+      .remove_inscribe_transfer_inscription(inscription_id)
+      .unwrap();
+    Ok(())
+  }
+}
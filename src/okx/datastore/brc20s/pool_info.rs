@@ -29,16 +29,8 @@ impl Pid {
     std::str::from_utf8(self.0.as_slice()).unwrap()
   }
 
-  pub fn hex(&self) -> String {
-    hex::encode(self.0)
-  }
-
-  pub fn min_hex() -> String {
-    Self([0u8; PID_BYTE_COUNT]).hex()
-  }
-
-  pub fn max_hex() -> String {
-    Self([0xffu8; PID_BYTE_COUNT]).hex()
+  pub fn as_bytes(&self) -> &[u8; PID_BYTE_COUNT] {
+    &self.0
   }
 }
 
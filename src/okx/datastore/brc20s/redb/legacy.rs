@@ -0,0 +1,233 @@
+use super::*;
+use crate::okx::datastore::{
+  brc20s::{Balance, PoolInfo, StakeInfo, TransferableAsset, UserInfo},
+  redb::migrate_legacy_table,
+};
+use crate::Result;
+use anyhow::anyhow;
+use redb::{ReadTransaction, ReadableTable, WriteTransaction};
+use std::str::FromStr;
+
+// Tables written before the binary key layout used string keys under the same names.
+const LEGACY_TXID_TO_INSCRIPTION_RECEIPTS: TableDefinition<&str, &[u8]> =
+  TableDefinition::new("TXID_TO_INSCRIPTION_RECEIPTS");
+const LEGACY_BRC20S_TICKINFO: TableDefinition<&str, &[u8]> =
+  TableDefinition::new("BRC20S_TICKINFO");
+const LEGACY_BRC20S_PID_TO_POOLINFO: TableDefinition<&str, &[u8]> =
+  TableDefinition::new("BRC20S_PID_TO_POOLINFO");
+const LEGACY_BRC20S_USER_STAKEINFO: TableDefinition<&str, &[u8]> =
+  TableDefinition::new("BRC20S_USER_STAKEINFO");
+const LEGACY_BRC20S_PID_TO_USERINFO: TableDefinition<&str, &[u8]> =
+  TableDefinition::new("BRC20S_PID_TO_USERINFO");
+const LEGACY_BRC20S_STAKE_TICKID_TO_PID: TableDefinition<&str, &[u8]> =
+  TableDefinition::new("BRC20S_STAKE_TICKID_TO_PID");
+const LEGACY_BRC20S_TICKID_STAKE_TO_PID: TableDefinition<&str, &[u8]> =
+  TableDefinition::new("BRC20S_TICKID_STAKE_TO_PID");
+const LEGACY_BRC20S_BALANCES: TableDefinition<&str, &[u8]> = TableDefinition::new("BRC20S_BALANCE");
+const LEGACY_BRC20S_TRANSFERABLE_ASSETS: TableDefinition<&str, &[u8]> =
+  TableDefinition::new("BRC20S_TRANSFERABLE_ASSETS");
+const LEGACY_BRC20S_TXID_TO_RECEIPTS: TableDefinition<&str, &[u8]> =
+  TableDefinition::new("BRC20S_TXID_TO_RECEIPTS");
+
+/// Rewrites BRC20S tables that still use the legacy string keys into the binary key layout.
+///
+/// The owners of balances and user infos are recorded in the owner table, values
+/// are copied unchanged. Returns true if any table was migrated.
+pub fn migrate_legacy_keys<'db, 'a>(
+  wtx: &'a WriteTransaction<'db>,
+  rtx: &'a ReadTransaction<'db>,
+) -> Result<bool> {
+  // The pid index tables only store the pid, so the pledged tick is taken from the pool,
+  // which was keyed by the hex of the pid.
+  let pools = rtx.open_table(LEGACY_BRC20S_PID_TO_POOLINFO).ok();
+  let pool_stake = |pid: &Pid| -> Result<PledgedTick> {
+    let pool = pools
+      .as_ref()
+      .map(|pools| pools.get(hex::encode(pid.as_bytes()).as_str()))
+      .transpose()?
+      .flatten()
+      .ok_or_else(|| anyhow!("pool {} not found", pid.as_str()))?;
+    Ok(bincode::deserialize::<PoolInfo>(pool.value())?.stake)
+  };
+
+  let mut owners = wtx.open_table(SCRIPT_HASH_TO_SCRIPT_KEY)?;
+  let mut migrated = false;
+  migrated |= migrate_legacy_table(
+    wtx,
+    rtx,
+    LEGACY_TXID_TO_INSCRIPTION_RECEIPTS,
+    TXID_TO_INSCRIPTION_RECEIPTS,
+    |key, value| Ok((txid_key(&Txid::from_str(key)?), value.to_vec())),
+  )?;
+  migrated |= migrate_legacy_table(
+    wtx,
+    rtx,
+    LEGACY_BRC20S_TICKINFO,
+    BRC20S_TICKINFO,
+    |key, value| Ok((tick_id_key(&TickId::from_str(key)?), value.to_vec())),
+  )?;
+  migrated |= migrate_legacy_table(
+    wtx,
+    rtx,
+    LEGACY_BRC20S_PID_TO_POOLINFO,
+    BRC20S_PID_TO_POOLINFO,
+    |_, value| {
+      Ok((
+        pid_key(&bincode::deserialize::<PoolInfo>(value)?.pid),
+        value.to_vec(),
+      ))
+    },
+  )?;
+  migrated |= migrate_legacy_table(
+    wtx,
+    rtx,
+    LEGACY_BRC20S_USER_STAKEINFO,
+    BRC20S_USER_STAKEINFO,
+    |key, value| {
+      Ok((
+        script_pledged_key(
+          &legacy_script(key)?,
+          &bincode::deserialize::<StakeInfo>(value)?.stake,
+        ),
+        value.to_vec(),
+      ))
+    },
+  )?;
+  migrated |= migrate_legacy_table(
+    wtx,
+    rtx,
+    LEGACY_BRC20S_PID_TO_USERINFO,
+    BRC20S_PID_TO_USERINFO,
+    |key, value| {
+      let script = legacy_script(key)?;
+      save_owner(&mut owners, None, &script)?;
+      Ok((
+        script_pid_key(&script, &bincode::deserialize::<UserInfo>(value)?.pid),
+        value.to_vec(),
+      ))
+    },
+  )?;
+  migrated |= migrate_legacy_table(
+    wtx,
+    rtx,
+    LEGACY_BRC20S_STAKE_TICKID_TO_PID,
+    BRC20S_STAKE_TICKID_TO_PID,
+    |_, value| {
+      let pid = bincode::deserialize::<Pid>(value)?;
+      Ok((
+        stake_tickid_key(&pool_stake(&pid)?, &TickId::from(pid)),
+        value.to_vec(),
+      ))
+    },
+  )?;
+  migrated |= migrate_legacy_table(
+    wtx,
+    rtx,
+    LEGACY_BRC20S_TICKID_STAKE_TO_PID,
+    BRC20S_TICKID_STAKE_TO_PID,
+    |_, value| {
+      let pid = bincode::deserialize::<Pid>(value)?;
+      Ok((
+        tickid_stake_key(&pool_stake(&pid)?, &TickId::from(pid)),
+        value.to_vec(),
+      ))
+    },
+  )?;
+  migrated |= migrate_legacy_table(
+    wtx,
+    rtx,
+    LEGACY_BRC20S_BALANCES,
+    BRC20S_BALANCES,
+    |key, value| {
+      let script = legacy_script(key)?;
+      save_owner(&mut owners, None, &script)?;
+      Ok((
+        script_tickid_key(&script, &bincode::deserialize::<Balance>(value)?.tick_id),
+        value.to_vec(),
+      ))
+    },
+  )?;
+  migrated |= migrate_legacy_table(
+    wtx,
+    rtx,
+    LEGACY_BRC20S_TRANSFERABLE_ASSETS,
+    BRC20S_TRANSFERABLE_ASSETS,
+    |_, value| {
+      let asset = bincode::deserialize::<TransferableAsset>(value)?;
+      Ok((
+        script_tickid_inscriptionid_key(&asset.owner, &asset.tick_id, &asset.inscription_id),
+        value.to_vec(),
+      ))
+    },
+  )?;
+  migrated |= migrate_legacy_table(
+    wtx,
+    rtx,
+    LEGACY_BRC20S_TXID_TO_RECEIPTS,
+    BRC20S_TXID_TO_RECEIPTS,
+    |key, value| Ok((txid_key(&Txid::from_str(key)?), value.to_vec())),
+  )?;
+  Ok(migrated)
+}
+
+// Legacy keys owned by a script were formatted as `{script}_{...}`.
+fn legacy_script(key: &str) -> Result<ScriptKey> {
+  let (script, _) = key
+    .split_once('_')
+    .ok_or_else(|| anyhow!("missing separator"))?;
+  Ok(ScriptKey::from_str(script)?)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::okx::datastore::brc20s::DataStoreReadOnly;
+  use bitcoin::Address;
+  use redb::Database;
+  use tempfile::NamedTempFile;
+
+  #[test]
+  fn test_migrate_legacy_balances() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+
+    let script = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    let balance = Balance {
+      tick_id: TickId::from_str("f7c515d6b7").unwrap(),
+      overall_balance: 30,
+      transferable_balance: 10,
+    };
+
+    let wtx = db.begin_write().unwrap();
+    wtx
+      .open_table(LEGACY_BRC20S_BALANCES)
+      .unwrap()
+      .insert(
+        format!("{}_{}", script, balance.tick_id.hex()).as_str(),
+        bincode::serialize(&balance).unwrap().as_slice(),
+      )
+      .unwrap();
+    wtx.commit().unwrap();
+
+    let wtx = db.begin_write().unwrap();
+    let rtx = db.begin_read().unwrap();
+    assert!(migrate_legacy_keys(&wtx, &rtx).unwrap());
+    try_init_tables(&wtx, &rtx).unwrap();
+    wtx.commit().unwrap();
+
+    let rtx = db.begin_read().unwrap();
+    let reader = DataStoreReader::new(&rtx);
+    assert_eq!(
+      reader.get_balances(&script).unwrap(),
+      vec![(balance.tick_id, balance)]
+    );
+    assert_eq!(
+      reader.get_balance(&script, &balance.tick_id).unwrap(),
+      Some(balance)
+    );
+  }
+}
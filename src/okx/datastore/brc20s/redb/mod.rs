@@ -1,126 +1,304 @@
+mod legacy;
 mod read_only;
 mod read_write;
 
-pub use self::{read_only::try_init_tables, read_only::DataStoreReader, read_write::DataStore};
+pub use self::{
+  legacy::migrate_legacy_keys, read_only::try_init_tables, read_only::DataStoreReader,
+  read_write::DataStore,
+};
 
-use crate::okx::datastore::brc20s::{Pid, PledgedTick, TickId};
-use crate::okx::datastore::ScriptKey;
-use crate::InscriptionId;
-use bitcoin::Txid;
+use crate::okx::datastore::brc20::LOWER_TICK_BYTE_COUNT;
+use crate::okx::datastore::brc20s::{
+  DataStoreReadOnly, DataStoreReadWrite, Pid, PledgedTick, ReceiptHistory, TickId,
+};
+use crate::okx::datastore::{
+  redb::{read_receipt_history, receipt_key, save_owner, OwnerCache, SCRIPT_HASH_TO_SCRIPT_KEY},
+  snapshot::TableVisitor,
+  ScriptKey,
+};
+use crate::okx::protocol::brc20s::params::{PID_BYTE_COUNT, TICK_ID_BYTE_COUNT};
+use crate::{InscriptionId, Result};
+use bitcoin::{hashes::Hash, Txid};
 use redb::TableDefinition;
 
-const TXID_TO_INSCRIPTION_RECEIPTS: TableDefinition<&str, &[u8]> =
+const TXID_TO_INSCRIPTION_RECEIPTS: TableDefinition<&[u8], &[u8]> =
   TableDefinition::new("TXID_TO_INSCRIPTION_RECEIPTS");
-const BRC20S_TICKINFO: TableDefinition<&str, &[u8]> = TableDefinition::new("BRC20S_TICKINFO");
-const BRC20S_PID_TO_POOLINFO: TableDefinition<&str, &[u8]> =
+const BRC20S_TICKINFO: TableDefinition<&[u8], &[u8]> = TableDefinition::new("BRC20S_TICKINFO");
+const BRC20S_PID_TO_POOLINFO: TableDefinition<&[u8], &[u8]> =
   TableDefinition::new("BRC20S_PID_TO_POOLINFO");
-const BRC20S_USER_STAKEINFO: TableDefinition<&str, &[u8]> =
+const BRC20S_USER_STAKEINFO: TableDefinition<&[u8], &[u8]> =
   TableDefinition::new("BRC20S_USER_STAKEINFO");
-const BRC20S_PID_TO_USERINFO: TableDefinition<&str, &[u8]> =
+const BRC20S_PID_TO_USERINFO: TableDefinition<&[u8], &[u8]> =
   TableDefinition::new("BRC20S_PID_TO_USERINFO");
-const BRC20S_STAKE_TICKID_TO_PID: TableDefinition<&str, &[u8]> =
+const BRC20S_STAKE_TICKID_TO_PID: TableDefinition<&[u8], &[u8]> =
   TableDefinition::new("BRC20S_STAKE_TICKID_TO_PID");
-const BRC20S_TICKID_STAKE_TO_PID: TableDefinition<&str, &[u8]> =
+const BRC20S_TICKID_STAKE_TO_PID: TableDefinition<&[u8], &[u8]> =
   TableDefinition::new("BRC20S_TICKID_STAKE_TO_PID");
-const BRC20S_BALANCES: TableDefinition<&str, &[u8]> = TableDefinition::new("BRC20S_BALANCE");
-const BRC20S_TRANSFERABLE_ASSETS: TableDefinition<&str, &[u8]> =
+const BRC20S_BALANCES: TableDefinition<&[u8], &[u8]> = TableDefinition::new("BRC20S_BALANCE");
+const BRC20S_TRANSFERABLE_ASSETS: TableDefinition<&[u8], &[u8]> =
   TableDefinition::new("BRC20S_TRANSFERABLE_ASSETS");
-const BRC20S_TXID_TO_RECEIPTS: TableDefinition<&str, &[u8]> =
+const BRC20S_TXID_TO_RECEIPTS: TableDefinition<&[u8], &[u8]> =
   TableDefinition::new("BRC20S_TXID_TO_RECEIPTS");
 const BRC20S_INSCRIBE_TRANSFER: TableDefinition<&[u8; 36], &[u8]> =
   TableDefinition::new("BRC20S_INSCRIBE_TRANSFER");
+const BRC20S_TICK_RECEIPTS: TableDefinition<&[u8], &[u8]> =
+  TableDefinition::new("BRC20S_TICK_RECEIPTS");
+const BRC20S_POOL_RECEIPTS: TableDefinition<&[u8], &[u8]> =
+  TableDefinition::new("BRC20S_POOL_RECEIPTS");
+const BRC20S_DEPLOY_RECEIPTS: TableDefinition<&[u8], &[u8]> =
+  TableDefinition::new("BRC20S_DEPLOY_RECEIPTS");
 
-fn script_tickid_key(script: &ScriptKey, tick_id: &TickId) -> String {
-  format!("{}_{}", script, tick_id.hex())
+/// Visits every table of this protocol, in a fixed order.
+pub fn visit_tables<T: TableVisitor>(visitor: &mut T) -> Result<()> {
+  visitor.visit(TXID_TO_INSCRIPTION_RECEIPTS)?;
+  visitor.visit(BRC20S_TICKINFO)?;
+  visitor.visit(BRC20S_PID_TO_POOLINFO)?;
+  visitor.visit(BRC20S_USER_STAKEINFO)?;
+  visitor.visit(BRC20S_PID_TO_USERINFO)?;
+  visitor.visit(BRC20S_STAKE_TICKID_TO_PID)?;
+  visitor.visit(BRC20S_TICKID_STAKE_TO_PID)?;
+  visitor.visit(BRC20S_BALANCES)?;
+  visitor.visit(BRC20S_TRANSFERABLE_ASSETS)?;
+  visitor.visit(BRC20S_TXID_TO_RECEIPTS)?;
+  visitor.visit(BRC20S_INSCRIBE_TRANSFER)?;
+  visitor.visit(BRC20S_TICK_RECEIPTS)?;
+  visitor.visit(BRC20S_POOL_RECEIPTS)?;
+  visitor.visit(BRC20S_DEPLOY_RECEIPTS)?;
+  Ok(())
 }
 
-fn script_tickid_inscriptionid_key(
+/// Lists the receipts of the transaction `txid`, at `position` in the block
+/// at `height`, in the receipt histories, for indexes built before the
+/// histories existed.
+pub fn backfill_receipt_history(
+  store: &DataStore,
+  height: u64,
+  position: u32,
+  txid: &Txid,
+) -> Result<()> {
+  let receipts = store.get_transaction_receipts(txid)?;
+  if !receipts.is_empty() {
+    store.save_receipt_history(height, position, txid, &receipts)?;
+  }
+  Ok(())
+}
+
+// Binary key layouts:
+//   script:         script hash (20 bytes)
+//   tick_id:        tick id (5 bytes)
+//   pid:            pid (13 bytes)
+//   pledged:        pledged kind tag (1 byte) + tick, zero-padded (16 bytes)
+//   inscription_id: txid (32 bytes) + index in big endian (4 bytes)
+//   txid:           txid (32 bytes)
+//   receipts:       tick id or pid, if listed by one, + receipt key
+//
+// The script key of each balance and user info owner is kept once in the
+// owner table shared with BRC20, `SCRIPT_HASH_TO_SCRIPT_KEY`.
+const PLEDGED_KEY_LEN: usize = 1 + LOWER_TICK_BYTE_COUNT;
+const INSCRIPTION_ID_KEY_LEN: usize = 36;
+
+impl ReceiptHistory {
+  pub(super) fn table(&self) -> TableDefinition<'static, &'static [u8], &'static [u8]> {
+    match self {
+      Self::Tick(_) => BRC20S_TICK_RECEIPTS,
+      Self::Pool(_) => BRC20S_POOL_RECEIPTS,
+      Self::Deploys => BRC20S_DEPLOY_RECEIPTS,
+    }
+  }
+
+  pub(super) fn prefix(&self) -> Vec<u8> {
+    match self {
+      Self::Tick(tick_id) => tick_id_key(tick_id),
+      Self::Pool(pid) => pid_key(pid),
+      Self::Deploys => Vec::new(),
+    }
+  }
+}
+
+pub(super) fn txid_key(txid: &Txid) -> Vec<u8> {
+  txid.to_byte_array().to_vec()
+}
+
+pub(super) fn tick_id_key(tick_id: &TickId) -> Vec<u8> {
+  tick_id.as_bytes().to_vec()
+}
+
+pub(super) fn pid_key(pid: &Pid) -> Vec<u8> {
+  pid.as_bytes().to_vec()
+}
+
+fn pledged_key(pledged_tick: &PledgedTick) -> [u8; PLEDGED_KEY_LEN] {
+  let mut key = [0u8; PLEDGED_KEY_LEN];
+  let (tag, data) = key.split_at_mut(1);
+  match pledged_tick {
+    PledgedTick::Unknown => tag[0] = 0,
+    PledgedTick::Native => tag[0] = 1,
+    PledgedTick::BRC20Tick(tick) => {
+      tag[0] = 2;
+      data.copy_from_slice(&tick.to_lowercase().to_padded_bytes());
+    }
+    PledgedTick::BRC20STick(tick_id) => {
+      tag[0] = 3;
+      data[..TICK_ID_BYTE_COUNT].copy_from_slice(tick_id.as_bytes());
+    }
+  }
+  key
+}
+
+fn inscription_id_key(inscription_id: &InscriptionId) -> [u8; INSCRIPTION_ID_KEY_LEN] {
+  let mut key = [0; INSCRIPTION_ID_KEY_LEN];
+  let (txid, index) = key.split_at_mut(32);
+  txid.copy_from_slice(inscription_id.txid.as_ref());
+  index.copy_from_slice(&inscription_id.index.to_be_bytes());
+  key
+}
+
+pub(super) fn script_tickid_key(script: &ScriptKey, tick_id: &TickId) -> Vec<u8> {
+  [
+    script.script_hash().as_byte_array().as_slice(),
+    tick_id.as_bytes(),
+  ]
+  .concat()
+}
+
+pub(super) fn script_tickid_inscriptionid_key(
   script: &ScriptKey,
   tick_id: &TickId,
   inscriptionid: &InscriptionId,
-) -> String {
-  format!("{}_{}_{}", script, tick_id.hex(), inscriptionid)
+) -> Vec<u8> {
+  [
+    script.script_hash().as_byte_array().as_slice(),
+    tick_id.as_bytes(),
+    &inscription_id_key(inscriptionid),
+  ]
+  .concat()
+}
+
+pub(super) fn script_pid_key(script: &ScriptKey, pid: &Pid) -> Vec<u8> {
+  [
+    script.script_hash().as_byte_array().as_slice(),
+    pid.as_bytes(),
+  ]
+  .concat()
 }
 
-fn script_pid_key(script: &ScriptKey, pid: &Pid) -> String {
-  format!("{}_{}", script, pid.hex(),)
+pub(super) fn script_pledged_key(script: &ScriptKey, pledged_tick: &PledgedTick) -> Vec<u8> {
+  [
+    script.script_hash().as_byte_array().as_slice(),
+    &pledged_key(pledged_tick),
+  ]
+  .concat()
 }
 
-fn script_pledged_key(script: &ScriptKey, pledged_tick: &PledgedTick) -> String {
-  let pledged_key = match pledged_tick {
-    PledgedTick::Native => hex::encode("btc"),
-    PledgedTick::BRC20Tick(tick) => tick.to_lowercase().hex(),
-    PledgedTick::BRC20STick(tick_id) => tick_id.hex(),
-    PledgedTick::Unknown => hex::encode("!@#$%"),
-  };
+pub(super) fn stake_tickid_key(pledged_tick: &PledgedTick, tick_id: &TickId) -> Vec<u8> {
+  [pledged_key(pledged_tick).as_slice(), tick_id.as_bytes()].concat()
+}
 
-  format!("{}_{}", script, pledged_key)
+pub(super) fn tickid_stake_key(pledged_tick: &PledgedTick, tick_id: &TickId) -> Vec<u8> {
+  [tick_id.as_bytes().as_slice(), &pledged_key(pledged_tick)].concat()
 }
 
-fn stake_tickid_key(pledged_tick: &PledgedTick, tick_id: &TickId) -> String {
-  let pledged_key = match pledged_tick {
-    PledgedTick::Native => hex::encode("btc"),
-    PledgedTick::BRC20Tick(tick) => tick.to_lowercase().hex(),
-    PledgedTick::BRC20STick(tick_id) => tick_id.hex(),
-    PledgedTick::Unknown => hex::encode("!@#$%"),
-  };
+#[allow(unused)]
+pub(super) fn min_tickid_stake_key(tick_id: &TickId) -> Vec<u8> {
+  [tick_id.as_bytes().as_slice(), &[0u8; PLEDGED_KEY_LEN]].concat()
+}
 
-  format!("{}_{}", pledged_key, tick_id.hex())
+#[allow(unused)]
+pub(super) fn max_tickid_stake_key(tick_id: &TickId) -> Vec<u8> {
+  [tick_id.as_bytes().as_slice(), &[0xffu8; PLEDGED_KEY_LEN]].concat()
 }
 
-fn tickid_stake_key(pledged_tick: &PledgedTick, tick_id: &TickId) -> String {
-  let pledged_key = match pledged_tick {
-    PledgedTick::Native => hex::encode("btc"),
-    PledgedTick::BRC20Tick(tick) => tick.to_lowercase().hex(),
-    PledgedTick::BRC20STick(tick_id) => tick_id.hex(),
-    PledgedTick::Unknown => hex::encode("!@#$%"),
-  };
+#[allow(unused)]
+pub(super) fn min_stake_tickid_key(pledged: &PledgedTick) -> Vec<u8> {
+  [pledged_key(pledged).as_slice(), &[0u8; TICK_ID_BYTE_COUNT]].concat()
+}
 
-  format!("{}_{}", tick_id.hex(), pledged_key)
+#[allow(unused)]
+pub(super) fn max_stake_tickid_key(pledged: &PledgedTick) -> Vec<u8> {
+  [
+    pledged_key(pledged).as_slice(),
+    &[0xffu8; TICK_ID_BYTE_COUNT],
+  ]
+  .concat()
 }
 
-fn min_script_tick_id_key(script: &ScriptKey) -> String {
-  format!("{}_{}", script, TickId::min_hex())
+pub(super) fn min_script_tick_id_key(script: &ScriptKey) -> Vec<u8> {
+  [
+    script.script_hash().as_byte_array().as_slice(),
+    &[0u8; TICK_ID_BYTE_COUNT],
+  ]
+  .concat()
 }
 
-fn max_script_tick_id_key(script: &ScriptKey) -> String {
-  format!("{}_{}", script, TickId::max_hex())
+pub(super) fn max_script_tick_id_key(script: &ScriptKey) -> Vec<u8> {
+  [
+    script.script_hash().as_byte_array().as_slice(),
+    &[0xffu8; TICK_ID_BYTE_COUNT],
+  ]
+  .concat()
 }
 
-fn min_tickid_stake_key(tick_id: &TickId) -> String {
-  format!("{}_{}", tick_id.hex(), PledgedTick::min_hex())
+pub(super) fn min_script_pid_key(script: &ScriptKey) -> Vec<u8> {
+  [
+    script.script_hash().as_byte_array().as_slice(),
+    &[0u8; PID_BYTE_COUNT],
+  ]
+  .concat()
 }
 
-fn max_tickid_stake_key(tick_id: &TickId) -> String {
-  format!("{}_{}", tick_id.hex(), PledgedTick::max_hex())
+pub(super) fn max_script_pid_key(script: &ScriptKey) -> Vec<u8> {
+  [
+    script.script_hash().as_byte_array().as_slice(),
+    &[0xffu8; PID_BYTE_COUNT],
+  ]
+  .concat()
 }
 
-fn min_stake_tickid_key(pledged: &PledgedTick) -> String {
-  let pledged_key = match pledged {
-    PledgedTick::Native => hex::encode("btc"),
-    PledgedTick::BRC20Tick(tick) => tick.to_lowercase().hex(),
-    PledgedTick::BRC20STick(tick_id) => tick_id.hex(),
-    PledgedTick::Unknown => hex::encode("!@#$%"),
-  };
+pub(super) fn min_script_transferable_key(script: &ScriptKey) -> Vec<u8> {
+  [
+    script.script_hash().as_byte_array().as_slice(),
+    &[0u8; TICK_ID_BYTE_COUNT + INSCRIPTION_ID_KEY_LEN],
+  ]
+  .concat()
+}
 
-  format!("{}_{}", pledged_key, TickId::min_hex())
+pub(super) fn max_script_transferable_key(script: &ScriptKey) -> Vec<u8> {
+  [
+    script.script_hash().as_byte_array().as_slice(),
+    &[0xffu8; TICK_ID_BYTE_COUNT + INSCRIPTION_ID_KEY_LEN],
+  ]
+  .concat()
 }
 
-fn max_stake_tickid_key(pledged: &PledgedTick) -> String {
-  let pledged_key = match pledged {
-    PledgedTick::Native => hex::encode("btc"),
-    PledgedTick::BRC20Tick(tick) => tick.to_lowercase().hex(),
-    PledgedTick::BRC20STick(tick_id) => tick_id.hex(),
-    PledgedTick::Unknown => hex::encode("!@#$%"),
-  };
+pub(super) fn min_script_tickid_transferable_key(script: &ScriptKey, tick_id: &TickId) -> Vec<u8> {
+  [
+    script_tickid_key(script, tick_id).as_slice(),
+    &[0u8; INSCRIPTION_ID_KEY_LEN],
+  ]
+  .concat()
+}
 
-  format!("{}_{}", pledged_key, TickId::max_hex())
+pub(super) fn max_script_tickid_transferable_key(script: &ScriptKey, tick_id: &TickId) -> Vec<u8> {
+  [
+    script_tickid_key(script, tick_id).as_slice(),
+    &[0xffu8; INSCRIPTION_ID_KEY_LEN],
+  ]
+  .concat()
 }
 
-fn min_tid_to_pid_key(tick_id: &TickId) -> String {
-  format!("{}000000", hex::encode(tick_id.hex()).as_str())
+// A pid is the hex string of its tick id followed by `#` and the pool index.
+pub(super) fn min_tid_to_pid_key(tick_id: &TickId) -> Vec<u8> {
+  [
+    tick_id.hex().as_bytes(),
+    &[0u8; PID_BYTE_COUNT - 2 * TICK_ID_BYTE_COUNT],
+  ]
+  .concat()
 }
 
-fn max_tid_to_pid_key(tick_id: &TickId) -> String {
-  format!("{}ffffff", hex::encode(tick_id.hex()).as_str())
+pub(super) fn max_tid_to_pid_key(tick_id: &TickId) -> Vec<u8> {
+  [
+    tick_id.hex().as_bytes(),
+    &[0xffu8; PID_BYTE_COUNT - 2 * TICK_ID_BYTE_COUNT],
+  ]
+  .concat()
 }
@@ -1,13 +1,20 @@
 use super::*;
-use crate::okx::datastore::brc20s::{
-  Balance, DataStoreReadOnly, InscriptionOperation, Pid, PledgedTick, PoolInfo, Receipt, StakeInfo,
-  TickId, TickInfo, TransferInfo, TransferableAsset, UserInfo,
+use crate::okx::datastore::{
+  brc20s::{
+    Balance, DataStoreReadOnly, InscriptionOperation, Pid, PledgedTick, PoolInfo, Receipt,
+    ReceiptHistory, StakeInfo, TickId, TickInfo, TransferInfo, TransferableAsset, UserInfo,
+  },
+  page::{Page, PageRequest},
 };
 use redb::{
   AccessGuard, Range, ReadOnlyTable, ReadTransaction, ReadableTable, RedbKey, RedbValue,
   StorageError, Table, TableDefinition, WriteTransaction,
 };
-use std::{borrow::Borrow, ops::RangeBounds};
+use serde::de::DeserializeOwned;
+use std::{
+  borrow::Borrow,
+  ops::{RangeBounds, RangeInclusive},
+};
 
 pub fn try_init_tables<'db, 'a>(
   wtx: &'a WriteTransaction<'db>,
@@ -24,6 +31,10 @@ pub fn try_init_tables<'db, 'a>(
     wtx.open_table(BRC20S_TRANSFERABLE_ASSETS)?;
     wtx.open_table(BRC20S_TXID_TO_RECEIPTS)?;
     wtx.open_table(BRC20S_INSCRIBE_TRANSFER)?;
+    wtx.open_table(BRC20S_TICK_RECEIPTS)?;
+    wtx.open_table(BRC20S_POOL_RECEIPTS)?;
+    wtx.open_table(BRC20S_DEPLOY_RECEIPTS)?;
+    wtx.open_table(SCRIPT_HASH_TO_SCRIPT_KEY)?;
   }
 
   Ok(true)
@@ -49,6 +60,196 @@ impl<'db, 'a> DataStoreReader<'db, 'a> {
   }
 }
 
+impl<'db, 'a> DataStoreReader<'db, 'a> {
+  /// Returns a page of all ticks, ordered by tick id, and the total number of ticks.
+  pub fn get_tick_info_page(
+    &self,
+    page: &PageRequest,
+  ) -> Result<(Page<TickInfo>, usize), redb::Error> {
+    let table = self.wrapper.open_table(BRC20S_TICKINFO)?;
+    let total = usize::try_from(table.len()?).unwrap();
+    let page = page.collect(table.range(page.start()..)?.map(|result| {
+      result.map(|(key, data)| {
+        (
+          key.value().to_vec(),
+          bincode::deserialize::<TickInfo>(data.value()).unwrap(),
+        )
+      })
+    }))?;
+    Ok((page, total))
+  }
+
+  /// Returns a page of all pools, ordered by pid, and the total number of pools.
+  pub fn get_poolinfo_page(
+    &self,
+    page: &PageRequest,
+  ) -> Result<(Page<PoolInfo>, usize), redb::Error> {
+    let table = self.wrapper.open_table(BRC20S_PID_TO_POOLINFO)?;
+    let total = usize::try_from(table.len()?).unwrap();
+    let page = page.collect(table.range(page.start()..)?.map(|result| {
+      result.map(|(key, data)| {
+        (
+          key.value().to_vec(),
+          bincode::deserialize::<PoolInfo>(data.value()).unwrap(),
+        )
+      })
+    }))?;
+    Ok((page, total))
+  }
+
+  /// Returns a page of the pools earning `tick_id`, ordered by pid, and the
+  /// total number of those pools.
+  pub fn get_pools_by_tid_page(
+    &self,
+    tick_id: &TickId,
+    page: &PageRequest,
+  ) -> Result<(Page<PoolInfo>, usize), redb::Error> {
+    let (min, max) = (min_tid_to_pid_key(tick_id), max_tid_to_pid_key(tick_id));
+    let total = self
+      .wrapper
+      .open_table(BRC20S_PID_TO_POOLINFO)?
+      .range(min.as_slice()..=max.as_slice())?
+      .count();
+    let Some(bounds) = page.bounds(min, max) else {
+      return Ok((Page::default(), total));
+    };
+    Ok((
+      self.collect_range(BRC20S_PID_TO_POOLINFO, bounds, page)?,
+      total,
+    ))
+  }
+
+  /// Returns a page of the balances of `script_key`, ordered by tick id.
+  pub fn get_balances_page(
+    &self,
+    script_key: &ScriptKey,
+    page: &PageRequest,
+  ) -> Result<Page<Balance>, redb::Error> {
+    let Some(bounds) = page.bounds(
+      min_script_tick_id_key(script_key),
+      max_script_tick_id_key(script_key),
+    ) else {
+      return Ok(Page::default());
+    };
+    self.collect_range(BRC20S_BALANCES, bounds, page)
+  }
+
+  /// Returns a page of the transferable assets of `script`, ordered by tick id
+  /// and then by inscription id.
+  pub fn get_transferable_page(
+    &self,
+    script: &ScriptKey,
+    page: &PageRequest,
+  ) -> Result<Page<TransferableAsset>, redb::Error> {
+    let Some(bounds) = page.bounds(
+      min_script_transferable_key(script),
+      max_script_transferable_key(script),
+    ) else {
+      return Ok(Page::default());
+    };
+    self.collect_range(BRC20S_TRANSFERABLE_ASSETS, bounds, page)
+  }
+
+  /// Returns a page of the transferable assets of `script` for `tick_id`, ordered by inscription id.
+  pub fn get_transferable_by_tickid_page(
+    &self,
+    script: &ScriptKey,
+    tick_id: &TickId,
+    page: &PageRequest,
+  ) -> Result<Page<TransferableAsset>, redb::Error> {
+    let Some(bounds) = page.bounds(
+      min_script_tickid_transferable_key(script, tick_id),
+      max_script_tickid_transferable_key(script, tick_id),
+    ) else {
+      return Ok(Page::default());
+    };
+    self.collect_range(BRC20S_TRANSFERABLE_ASSETS, bounds, page)
+  }
+
+  /// Returns the positions of `script` in every pool it staked in, ordered by pid.
+  pub fn get_user_infos(&self, script: &ScriptKey) -> Result<Vec<UserInfo>, redb::Error> {
+    Ok(
+      self
+        .wrapper
+        .open_table(BRC20S_PID_TO_USERINFO)?
+        .range(min_script_pid_key(script).as_slice()..=max_script_pid_key(script).as_slice())?
+        .flat_map(|result| {
+          result.map(|(_, data)| bincode::deserialize::<UserInfo>(data.value()).unwrap())
+        })
+        .collect(),
+    )
+  }
+
+  /// Calls `f` with every balance and its owner, ordered by the owner's script hash.
+  pub fn for_each_balance(&self, f: impl FnMut(ScriptKey, Balance) -> Result<()>) -> Result<()> {
+    self.for_each_owned(BRC20S_BALANCES, f)
+  }
+
+  /// Calls `f` with every pool position and its owner, ordered by the owner's script hash.
+  pub fn for_each_user_info(&self, f: impl FnMut(ScriptKey, UserInfo) -> Result<()>) -> Result<()> {
+    self.for_each_owned(BRC20S_PID_TO_USERINFO, f)
+  }
+
+  /// Returns up to `limit` receipts of `history`, newest first, with the
+  /// height and txid they were made at.
+  pub fn get_receipt_history(
+    &self,
+    history: &ReceiptHistory,
+    limit: usize,
+  ) -> Result<Vec<(u64, Txid, Receipt)>, redb::Error> {
+    let prefix = history.prefix();
+    let table = self.wrapper.open_table(history.table())?;
+    let receipts = read_receipt_history(
+      table.range(prefix.as_slice()..=[prefix.as_slice(), &[0xff; 16]].concat().as_slice())?,
+      limit,
+      limit,
+      |txid| self.get_transaction_receipts(txid),
+      |_| true,
+    )?;
+    Ok(receipts)
+  }
+
+  fn for_each_owned<T: DeserializeOwned>(
+    &self,
+    definition: TableDefinition<'_, &'static [u8], &'static [u8]>,
+    mut f: impl FnMut(ScriptKey, T) -> Result<()>,
+  ) -> Result<()> {
+    let table = self.wrapper.open_table(definition)?;
+    let owners = self.wrapper.open_table(SCRIPT_HASH_TO_SCRIPT_KEY)?;
+    let mut cache = OwnerCache::default();
+    for result in table.range([].as_slice()..)? {
+      let (key, data) = result?;
+      let owner = cache.resolve(key.value(), |hash| {
+        Ok(owners.get(hash)?.map(|owner| owner.value().to_vec()))
+      })?;
+      f(owner, bincode::deserialize(data.value())?)?;
+    }
+    Ok(())
+  }
+
+  fn collect_range<T: DeserializeOwned>(
+    &self,
+    definition: TableDefinition<'_, &'static [u8], &'static [u8]>,
+    bounds: RangeInclusive<Vec<u8>>,
+    page: &PageRequest,
+  ) -> Result<Page<T>, redb::Error> {
+    let table = self.wrapper.open_table(definition)?;
+    let page = page.collect(
+      table
+        .range(bounds.start().as_slice()..=bounds.end().as_slice())?
+        .map(|result| {
+          result.map(|(key, data)| {
+            (
+              key.value().to_vec(),
+              bincode::deserialize::<T>(data.value()).unwrap(),
+            )
+          })
+        }),
+    )?;
+    Ok(page)
+  }
+}
+
 enum ReaderWrapper<'db, 'a> {
   Rtx(&'a ReadTransaction<'db>),
   Wtx(&'a WriteTransaction<'db>),
@@ -119,7 +320,7 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(TXID_TO_INSCRIPTION_RECEIPTS)?
-        .get(txid.to_string().as_str())?
+        .get(txid_key(txid).as_slice())?
         .map_or(Vec::new(), |v| {
           bincode::deserialize::<Vec<InscriptionOperation>>(v.value()).unwrap()
         }),
@@ -132,80 +333,22 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(BRC20S_TICKINFO)?
-        .get(tick_id.hex().as_str())?
+        .get(tick_id_key(tick_id).as_slice())?
         .map(|v| bincode::deserialize::<TickInfo>(v.value()).unwrap()),
     )
   }
 
-  fn get_all_tick_info(
-    &self,
-    start: usize,
-    limit: Option<usize>,
-  ) -> Result<(Vec<TickInfo>, usize), Self::Error> {
-    let table = self.wrapper.open_table(BRC20S_TICKINFO)?;
-    let total = table.len()?;
-    let tickinfos = table
-      .range(TickId::min_hex().as_str()..TickId::max_hex().as_str())?
-      .skip(start)
-      .take(limit.unwrap_or(usize::MAX))
-      .flat_map(|result| {
-        result.map(|(_, data)| {
-          let tick_info = bincode::deserialize::<TickInfo>(data.value()).unwrap();
-          tick_info
-        })
-      })
-      .collect();
-    Ok((tickinfos, usize::try_from(total).unwrap()))
-  }
-
   // BRC20S_PID_TO_POOLINFO
   fn get_pid_to_poolinfo(&self, pid: &Pid) -> Result<Option<PoolInfo>, Self::Error> {
     Ok(
       self
         .wrapper
         .open_table(BRC20S_PID_TO_POOLINFO)?
-        .get(pid.hex().as_str())?
+        .get(pid_key(pid).as_slice())?
         .map(|v| bincode::deserialize::<PoolInfo>(v.value()).unwrap()),
     )
   }
 
-  fn get_all_pools_by_tid(&self, tick_id: &TickId) -> Result<Vec<PoolInfo>, Self::Error> {
-    let table = self.wrapper.open_table(BRC20S_PID_TO_POOLINFO)?;
-    let mut total = 0;
-    let pools = table
-      .range(min_tid_to_pid_key(tick_id).as_str()..max_tid_to_pid_key(tick_id).as_str())?
-      .flat_map(|result| {
-        result.map(|(_, data)| {
-          let pool = bincode::deserialize::<PoolInfo>(data.value()).unwrap();
-          total += 1;
-          pool
-        })
-      })
-      .collect();
-    Ok(pools)
-  }
-
-  fn get_all_poolinfo(
-    &self,
-    start: usize,
-    limit: Option<usize>,
-  ) -> Result<(Vec<PoolInfo>, usize), Self::Error> {
-    let table = self.wrapper.open_table(BRC20S_PID_TO_POOLINFO)?;
-    let total = table.len()?;
-    let pools = table
-      .range(Pid::min_hex().as_str()..Pid::max_hex().as_str())?
-      .skip(start)
-      .take(limit.unwrap_or(usize::MAX))
-      .flat_map(|result| {
-        result.map(|(_, data)| {
-          let pool = bincode::deserialize::<PoolInfo>(data.value()).unwrap();
-          pool
-        })
-      })
-      .collect();
-    Ok((pools, usize::try_from(total).unwrap()))
-  }
-
   // BRC20S_USER_STAKEINFO
   fn get_user_stakeinfo(
     &self,
@@ -216,7 +359,7 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(BRC20S_USER_STAKEINFO)?
-        .get(script_pledged_key(script_key, pledged_tick).as_str())?
+        .get(script_pledged_key(script_key, pledged_tick).as_slice())?
         .map(|v| bincode::deserialize::<StakeInfo>(v.value()).unwrap()),
     )
   }
@@ -231,7 +374,7 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(BRC20S_PID_TO_USERINFO)?
-        .get(script_pid_key(script_key, pid).as_str())?
+        .get(script_pid_key(script_key, pid).as_slice())?
         .map(|v| bincode::deserialize::<UserInfo>(v.value()).unwrap()),
     )
   }
@@ -246,7 +389,7 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(BRC20S_STAKE_TICKID_TO_PID)?
-        .get(stake_tickid_key(pledged, tick_id).as_str())?
+        .get(stake_tickid_key(pledged, tick_id).as_slice())?
         .map(|v| bincode::deserialize::<Pid>(v.value()).unwrap()),
     )
   }
@@ -259,12 +402,9 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(BRC20S_TICKID_STAKE_TO_PID)?
-        .range(min.as_str()..max.as_str())?
+        .range(min.as_slice()..=max.as_slice())?
         .flat_map(|result| {
-          result.map(|(_, data)| {
-            let pid = bincode::deserialize::<Pid>(data.value()).unwrap();
-            pid
-          })
+          result.map(|(_, data)| bincode::deserialize::<Pid>(data.value()).unwrap())
         })
         .collect(),
     )
@@ -272,16 +412,15 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
 
   // get_stake_to_all_pid
   fn get_stake_to_all_pid(&self, pledged: &PledgedTick) -> Result<Vec<Pid>, Self::Error> {
+    let min = min_stake_tickid_key(pledged);
+    let max = max_stake_tickid_key(pledged);
     Ok(
       self
         .wrapper
         .open_table(BRC20S_STAKE_TICKID_TO_PID)?
-        .range(min_stake_tickid_key(pledged).as_str()..max_stake_tickid_key(pledged).as_str())?
+        .range(min.as_slice()..=max.as_slice())?
         .flat_map(|result| {
-          result.map(|(_, data)| {
-            let pid = bincode::deserialize::<Pid>(data.value()).unwrap();
-            pid
-          })
+          result.map(|(_, data)| bincode::deserialize::<Pid>(data.value()).unwrap())
         })
         .collect(),
     )
@@ -297,7 +436,7 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(BRC20S_BALANCES)?
-        .get(script_tickid_key(script_key, tick_id).as_str())?
+        .get(script_tickid_key(script_key, tick_id).as_slice())?
         .map(|v| {
           let bal = bincode::deserialize::<Balance>(v.value()).unwrap();
           assert_eq!(&bal.tick_id, tick_id);
@@ -312,7 +451,8 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
         .wrapper
         .open_table(BRC20S_BALANCES)?
         .range(
-          min_script_tick_id_key(script_key).as_str()..max_script_tick_id_key(script_key).as_str(),
+          min_script_tick_id_key(script_key).as_slice()
+            ..=max_script_tick_id_key(script_key).as_slice(),
         )?
         .flat_map(|result| {
           result.map(|(_, data)| {
@@ -335,7 +475,7 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(BRC20S_TRANSFERABLE_ASSETS)?
-        .get(script_tickid_inscriptionid_key(script_key, tick_id, inscription_id).as_str())?
+        .get(script_tickid_inscriptionid_key(script_key, tick_id, inscription_id).as_slice())?
         .map(|v| bincode::deserialize::<TransferableAsset>(v.value()).unwrap()),
     )
   }
@@ -345,7 +485,10 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(BRC20S_TRANSFERABLE_ASSETS)?
-        .range(min_script_tick_id_key(script).as_str()..max_script_tick_id_key(script).as_str())?
+        .range(
+          min_script_transferable_key(script).as_slice()
+            ..=max_script_transferable_key(script).as_slice(),
+        )?
         .flat_map(|result| {
           result.map(|(_, v)| bincode::deserialize::<TransferableAsset>(v.value()).unwrap())
         })
@@ -388,7 +531,7 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(BRC20S_TXID_TO_RECEIPTS)?
-        .get(txid.to_string().as_str())?
+        .get(txid_key(txid).as_slice())?
         .map_or(Vec::new(), |v| {
           bincode::deserialize::<Vec<Receipt>>(v.value()).unwrap()
         }),
@@ -400,7 +543,7 @@ impl<'db, 'a> DataStoreReadOnly for DataStoreReader<'db, 'a> {
       self
         .wrapper
         .open_table(BRC20S_TXID_TO_RECEIPTS)?
-        .get(tx_id.to_string().as_str())?
+        .get(txid_key(tx_id).as_slice())?
         .map_or(Vec::new(), |v| {
           bincode::deserialize::<Vec<Receipt>>(v.value()).unwrap()
         }),
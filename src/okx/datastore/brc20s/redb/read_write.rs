@@ -1,21 +1,28 @@
 use super::*;
+use crate::index::undo::{self, UndoLog};
 use crate::{
   okx::datastore::brc20s::{
     Balance, DataStoreReadOnly, DataStoreReadWrite, InscriptionOperation, Pid, PoolInfo, Receipt,
-    StakeInfo, TickId, TickInfo, TransferInfo, TransferableAsset, UserInfo,
+    ReceiptHistory, StakeInfo, TickId, TickInfo, TransferInfo, TransferableAsset, UserInfo,
   },
   InscriptionId,
 };
 use bitcoin::Txid;
-use redb::WriteTransaction;
+use redb::{ReadableTable, WriteTransaction};
 
 pub struct DataStore<'db, 'a> {
   wtx: &'a WriteTransaction<'db>,
+  undo: Option<&'a UndoLog>,
 }
 
 impl<'db, 'a> DataStore<'db, 'a> {
   pub fn new(wtx: &'a WriteTransaction<'db>) -> Self {
-    Self { wtx }
+    Self { wtx, undo: None }
+  }
+
+  /// Records every mutation in `undo`, if given, so the block can be unwound after a reorg.
+  pub fn with_undo_log(self, undo: Option<&'a UndoLog>) -> Self {
+    Self { undo, ..self }
   }
 }
 
@@ -35,31 +42,11 @@ impl<'db, 'a> DataStoreReadOnly for DataStore<'db, 'a> {
     read_only::new_with_wtx(self.wtx).get_tick_info(tick_id)
   }
 
-  fn get_all_tick_info(
-    &self,
-    start: usize,
-    limit: Option<usize>,
-  ) -> Result<(Vec<TickInfo>, usize), Self::Error> {
-    read_only::new_with_wtx(self.wtx).get_all_tick_info(start, limit)
-  }
-
   // BRC20S_PID_TO_POOLINFO
   fn get_pid_to_poolinfo(&self, pid: &Pid) -> Result<Option<PoolInfo>, Self::Error> {
     read_only::new_with_wtx(self.wtx).get_pid_to_poolinfo(pid)
   }
 
-  fn get_all_pools_by_tid(&self, tick_id: &TickId) -> Result<Vec<PoolInfo>, Self::Error> {
-    read_only::new_with_wtx(self.wtx).get_all_pools_by_tid(tick_id)
-  }
-
-  fn get_all_poolinfo(
-    &self,
-    start: usize,
-    limit: Option<usize>,
-  ) -> Result<(Vec<PoolInfo>, usize), Self::Error> {
-    read_only::new_with_wtx(self.wtx).get_all_poolinfo(start, limit)
-  }
-
   // BRC20S_USER_STAKEINFO
   fn get_user_stakeinfo(
     &self,
@@ -164,8 +151,11 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     tx_id: &Txid,
     inscription_operations: &[InscriptionOperation],
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(TXID_TO_INSCRIPTION_RECEIPTS)?.insert(
-      tx_id.to_string().as_str(),
+    undo::insert(
+      self.undo,
+      TXID_TO_INSCRIPTION_RECEIPTS,
+      &mut self.wtx.open_table(TXID_TO_INSCRIPTION_RECEIPTS)?,
+      txid_key(tx_id).as_slice(),
       bincode::serialize(inscription_operations)
         .unwrap()
         .as_slice(),
@@ -175,8 +165,11 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
 
   // BRC20S_TICKINFO
   fn set_tick_info(&self, tick_id: &TickId, tick_info: &TickInfo) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_TICKINFO)?.insert(
-      tick_id.hex().as_str(),
+    undo::insert(
+      self.undo,
+      BRC20S_TICKINFO,
+      &mut self.wtx.open_table(BRC20S_TICKINFO)?,
+      tick_id_key(tick_id).as_slice(),
       bincode::serialize(tick_info).unwrap().as_slice(),
     )?;
     Ok(())
@@ -184,8 +177,11 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
 
   // BRC20S_PID_TO_POOLINFO
   fn set_pid_to_poolinfo(&self, pid: &Pid, pool_info: &PoolInfo) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_PID_TO_POOLINFO)?.insert(
-      pid.hex().as_str(),
+    undo::insert(
+      self.undo,
+      BRC20S_PID_TO_POOLINFO,
+      &mut self.wtx.open_table(BRC20S_PID_TO_POOLINFO)?,
+      pid_key(pid).as_slice(),
       bincode::serialize(pool_info).unwrap().as_slice(),
     )?;
     Ok(())
@@ -198,8 +194,11 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     pledged_tick: &PledgedTick,
     stake_info: &StakeInfo,
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_USER_STAKEINFO)?.insert(
-      script_pledged_key(script_key, pledged_tick).as_str(),
+    undo::insert(
+      self.undo,
+      BRC20S_USER_STAKEINFO,
+      &mut self.wtx.open_table(BRC20S_USER_STAKEINFO)?,
+      script_pledged_key(script_key, pledged_tick).as_slice(),
       bincode::serialize(stake_info).unwrap().as_slice(),
     )?;
     Ok(())
@@ -212,10 +211,23 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     pid: &Pid,
     user_info: &UserInfo,
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_PID_TO_USERINFO)?.insert(
-      script_pid_key(script_key, pid).as_str(),
+    let mut table = self.wtx.open_table(BRC20S_PID_TO_USERINFO)?;
+    let key = script_pid_key(script_key, pid);
+    let new = table.get(key.as_slice())?.is_none();
+    undo::insert(
+      self.undo,
+      BRC20S_PID_TO_USERINFO,
+      &mut table,
+      key.as_slice(),
       bincode::serialize(user_info).unwrap().as_slice(),
     )?;
+    if new {
+      save_owner(
+        &mut self.wtx.open_table(SCRIPT_HASH_TO_SCRIPT_KEY)?,
+        self.undo,
+        script_key,
+      )?;
+    }
     Ok(())
   }
 
@@ -226,13 +238,19 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     pledged: &PledgedTick,
     pid: &Pid,
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_STAKE_TICKID_TO_PID)?.insert(
-      stake_tickid_key(pledged, tick_id).as_str(),
+    undo::insert(
+      self.undo,
+      BRC20S_STAKE_TICKID_TO_PID,
+      &mut self.wtx.open_table(BRC20S_STAKE_TICKID_TO_PID)?,
+      stake_tickid_key(pledged, tick_id).as_slice(),
       bincode::serialize(pid).unwrap().as_slice(),
     )?;
 
-    self.wtx.open_table(BRC20S_TICKID_STAKE_TO_PID)?.insert(
-      tickid_stake_key(pledged, tick_id).as_str(),
+    undo::insert(
+      self.undo,
+      BRC20S_TICKID_STAKE_TO_PID,
+      &mut self.wtx.open_table(BRC20S_TICKID_STAKE_TO_PID)?,
+      tickid_stake_key(pledged, tick_id).as_slice(),
       bincode::serialize(pid).unwrap().as_slice(),
     )?;
     Ok(())
@@ -245,10 +263,23 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     tick_id: &TickId,
     balance: Balance,
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_BALANCES)?.insert(
-      script_tickid_key(script_key, tick_id).as_str(),
+    let mut table = self.wtx.open_table(BRC20S_BALANCES)?;
+    let key = script_tickid_key(script_key, tick_id);
+    let new = table.get(key.as_slice())?.is_none();
+    undo::insert(
+      self.undo,
+      BRC20S_BALANCES,
+      &mut table,
+      key.as_slice(),
       bincode::serialize(&balance).unwrap().as_slice(),
     )?;
+    if new {
+      save_owner(
+        &mut self.wtx.open_table(SCRIPT_HASH_TO_SCRIPT_KEY)?,
+        self.undo,
+        script_key,
+      )?;
+    }
     Ok(())
   }
 
@@ -260,8 +291,11 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     inscription_id: &InscriptionId,
     transferable_asset: &TransferableAsset,
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_TRANSFERABLE_ASSETS)?.insert(
-      script_tickid_inscriptionid_key(script_key, tick_id, inscription_id).as_str(),
+    undo::insert(
+      self.undo,
+      BRC20S_TRANSFERABLE_ASSETS,
+      &mut self.wtx.open_table(BRC20S_TRANSFERABLE_ASSETS)?,
+      script_tickid_inscriptionid_key(script_key, tick_id, inscription_id).as_slice(),
       bincode::serialize(transferable_asset).unwrap().as_slice(),
     )?;
     Ok(())
@@ -279,23 +313,51 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     tx_id: &Txid,
     receipts: &[Receipt],
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(BRC20S_TXID_TO_RECEIPTS)?.insert(
-      tx_id.to_string().as_str(),
+    undo::insert(
+      self.undo,
+      BRC20S_TXID_TO_RECEIPTS,
+      &mut self.wtx.open_table(BRC20S_TXID_TO_RECEIPTS)?,
+      txid_key(tx_id).as_slice(),
       bincode::serialize(receipts).unwrap().as_slice(),
     )?;
     Ok(())
   }
 
+  // BRC20S_TICK_RECEIPTS, BRC20S_POOL_RECEIPTS, BRC20S_DEPLOY_RECEIPTS
+  fn save_receipt_history(
+    &self,
+    height: u64,
+    position: u32,
+    txid: &Txid,
+    receipts: &[Receipt],
+  ) -> Result<(), Self::Error> {
+    for (index, receipt) in receipts.iter().enumerate() {
+      let key = receipt_key(height, position, u32::try_from(index).unwrap());
+      for history in ReceiptHistory::of(receipt) {
+        undo::insert(
+          self.undo,
+          history.table(),
+          &mut self.wtx.open_table(history.table())?,
+          [history.prefix().as_slice(), &key].concat().as_slice(),
+          txid_key(txid).as_slice(),
+        )?;
+      }
+    }
+    Ok(())
+  }
+
   fn remove_transferable(
     &self,
     script_key: &ScriptKey,
     tick_id: &TickId,
     inscription_id: &InscriptionId,
   ) -> Result<(), Self::Error> {
-    self
-      .wtx
-      .open_table(BRC20S_TRANSFERABLE_ASSETS)?
-      .remove(script_tickid_inscriptionid_key(script_key, tick_id, inscription_id).as_str())?;
+    undo::remove(
+      self.undo,
+      BRC20S_TRANSFERABLE_ASSETS,
+      &mut self.wtx.open_table(BRC20S_TRANSFERABLE_ASSETS)?,
+      script_tickid_inscriptionid_key(script_key, tick_id, inscription_id).as_slice(),
+    )?;
     Ok(())
   }
 
@@ -309,7 +371,10 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     txid.copy_from_slice(inscription_id.txid.as_ref());
     index.copy_from_slice(&inscription_id.index.to_be_bytes());
 
-    self.wtx.open_table(BRC20S_INSCRIBE_TRANSFER)?.insert(
+    undo::insert(
+      self.undo,
+      BRC20S_INSCRIBE_TRANSFER,
+      &mut self.wtx.open_table(BRC20S_INSCRIBE_TRANSFER)?,
       &value,
       bincode::serialize(&transfer_info).unwrap().as_slice(),
     )?;
@@ -325,10 +390,12 @@ impl<'db, 'a> DataStoreReadWrite for DataStore<'db, 'a> {
     txid.copy_from_slice(inscription_id.txid.as_ref());
     index.copy_from_slice(&inscription_id.index.to_be_bytes());
 
-    self
-      .wtx
-      .open_table(BRC20S_INSCRIBE_TRANSFER)?
-      .remove(&value)?;
+    undo::remove(
+      self.undo,
+      BRC20S_INSCRIBE_TRANSFER,
+      &mut self.wtx.open_table(BRC20S_INSCRIBE_TRANSFER)?,
+      &value,
+    )?;
     Ok(())
   }
 }
@@ -339,6 +406,7 @@ mod tests {
   use crate::okx::datastore::brc20;
   use crate::okx::datastore::brc20s::{DataStoreReadOnly, DataStoreReadWrite, OperationType};
   use crate::okx::datastore::brc20s::{Pid, PledgedTick, PoolType, Tick, TickId};
+  use crate::okx::datastore::page::PageRequest;
   use crate::okx::protocol::brc20s::BRC20SError;
   use crate::SatPoint;
   use bitcoin::Address;
@@ -458,6 +526,24 @@ mod tests {
     let db = Database::create(dbfile.path()).unwrap();
     let wtx = db.begin_write().unwrap();
     let brc20s_db = DataStore::new(&wtx);
+    let reader = read_only::new_with_wtx(&wtx);
+    let all_poolinfo = |skip, limit| {
+      let (page, total) = reader
+        .get_poolinfo_page(&PageRequest {
+          skip,
+          limit,
+          ..Default::default()
+        })
+        .unwrap();
+      (page.items, total)
+    };
+    let pools_by_tid = |tick_id| {
+      reader
+        .get_pools_by_tid_page(tick_id, &PageRequest::default())
+        .unwrap()
+        .0
+        .items
+    };
 
     let inscription_id =
       InscriptionId::from_str("2111111111111111111111111111111111111111111111111111111111111111i1")
@@ -507,7 +593,7 @@ mod tests {
     );
 
     assert_eq!(
-      brc20s_db.get_all_poolinfo(0, None).unwrap(),
+      all_poolinfo(0, None),
       (
         vec![
           pool_info_1.clone(),
@@ -520,7 +606,7 @@ mod tests {
       )
     );
     assert_eq!(
-      brc20s_db.get_all_poolinfo(0, Some(3)).unwrap(),
+      all_poolinfo(0, Some(3)),
       (
         vec![
           pool_info_1.clone(),
@@ -532,7 +618,7 @@ mod tests {
     );
 
     assert_eq!(
-      brc20s_db.get_all_poolinfo(0, Some(5)).unwrap(),
+      all_poolinfo(0, Some(5)),
       (
         vec![
           pool_info_1.clone(),
@@ -546,7 +632,7 @@ mod tests {
     );
 
     assert_eq!(
-      brc20s_db.get_all_poolinfo(0, Some(9)).unwrap(),
+      all_poolinfo(0, Some(9)),
       (
         vec![
           pool_info_1.clone(),
@@ -559,40 +645,31 @@ mod tests {
       )
     );
 
-    assert_eq!(
-      brc20s_db.get_all_poolinfo(3, Some(1)).unwrap(),
-      (vec![pool_info_4.clone()], 5)
-    );
+    assert_eq!(all_poolinfo(3, Some(1)), (vec![pool_info_4.clone()], 5));
 
     assert_eq!(
-      brc20s_db.get_all_poolinfo(3, Some(9)).unwrap(),
+      all_poolinfo(3, Some(9)),
       (vec![pool_info_4.clone(), pool_info_5.clone()], 5)
     );
 
-    assert_eq!(brc20s_db.get_all_poolinfo(5, Some(9)).unwrap(), (vec![], 5));
+    assert_eq!(all_poolinfo(5, Some(9)), (vec![], 5));
 
     // test for all_pools_by_tid
     brc20s_db.set_pid_to_poolinfo(&pid_6, &pool_info_5).unwrap();
     brc20s_db.set_pid_to_poolinfo(&pid_7, &pool_info_5).unwrap();
 
     let tid = TickId::from_str("a234567890").unwrap();
-    assert_eq!(
-      brc20s_db.get_all_pools_by_tid(&tid).unwrap(),
-      vec![pool_info_5.clone()]
-    );
+    assert_eq!(pools_by_tid(&tid), vec![pool_info_5.clone()]);
 
     let tid = TickId::from_str("b234567890").unwrap();
-    assert_eq!(
-      brc20s_db.get_all_pools_by_tid(&tid).unwrap(),
-      vec![pool_info_5.clone()]
-    );
+    assert_eq!(pools_by_tid(&tid), vec![pool_info_5.clone()]);
 
     let tid = TickId::from_str("0234567890").unwrap();
-    assert_eq!(brc20s_db.get_all_pools_by_tid(&tid).unwrap(), vec![]);
+    assert_eq!(pools_by_tid(&tid), vec![]);
 
     let tid = TickId::from_str("1234567890").unwrap();
     assert_eq!(
-      brc20s_db.get_all_pools_by_tid(&tid).unwrap(),
+      pools_by_tid(&tid),
       vec![
         pool_info_1,
         pool_info_2,
@@ -711,6 +788,17 @@ mod tests {
     let db = Database::create(dbfile.path()).unwrap();
     let wtx = db.begin_write().unwrap();
     let brc20s_db = DataStore::new(&wtx);
+    let reader = read_only::new_with_wtx(&wtx);
+    let all_tick_info = |skip, limit| {
+      let (page, total) = reader
+        .get_tick_info_page(&PageRequest {
+          skip,
+          limit,
+          ..Default::default()
+        })
+        .unwrap();
+      (page.items, total)
+    };
 
     let inscription_id =
       InscriptionId::from_str("2111111111111111111111111111111111111111111111111111111111111111i1")
@@ -762,7 +850,7 @@ mod tests {
     );
 
     assert_eq!(
-      brc20s_db.get_all_tick_info(0, None).unwrap(),
+      all_tick_info(0, None),
       (
         vec![
           tick_info1.clone(),
@@ -775,7 +863,7 @@ mod tests {
       )
     );
     assert_eq!(
-      brc20s_db.get_all_tick_info(0, Some(3)).unwrap(),
+      all_tick_info(0, Some(3)),
       (
         vec![tick_info1.clone(), tick_info2.clone(), tick_info3.clone(),],
         5
@@ -783,7 +871,7 @@ mod tests {
     );
 
     assert_eq!(
-      brc20s_db.get_all_tick_info(0, Some(5)).unwrap(),
+      all_tick_info(0, Some(5)),
       (
         vec![
           tick_info1.clone(),
@@ -797,7 +885,7 @@ mod tests {
     );
 
     assert_eq!(
-      brc20s_db.get_all_tick_info(0, Some(9)).unwrap(),
+      all_tick_info(0, Some(9)),
       (
         vec![
           tick_info1,
@@ -810,20 +898,11 @@ mod tests {
       )
     );
 
-    assert_eq!(
-      brc20s_db.get_all_tick_info(3, Some(1)).unwrap(),
-      (vec![tick_info4.clone()], 5)
-    );
+    assert_eq!(all_tick_info(3, Some(1)), (vec![tick_info4.clone()], 5));
 
-    assert_eq!(
-      brc20s_db.get_all_tick_info(3, Some(9)).unwrap(),
-      (vec![tick_info4, tick_info5], 5)
-    );
+    assert_eq!(all_tick_info(3, Some(9)), (vec![tick_info4, tick_info5], 5));
 
-    assert_eq!(
-      brc20s_db.get_all_tick_info(5, Some(9)).unwrap(),
-      (vec![], 5)
-    );
+    assert_eq!(all_tick_info(5, Some(9)), (vec![], 5));
   }
 
   #[test]
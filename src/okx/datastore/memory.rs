@@ -0,0 +1,92 @@
+use super::{
+  brc20::memory::MemoryStore as BRC20MemoryStore, brc20s::memory::MemoryStore as BRC20SMemoryStore,
+  ord::memory::MemoryStore as OrdMemoryStore, StateRWriter, StateReader,
+};
+
+/// StateMemory is an in-memory implementation of the StateReader and StateRWriter traits.
+///
+/// It is used to run the protocol executors without a redb database, e.g. in unit tests
+/// and simulations.
+#[derive(Debug, Default)]
+pub struct StateMemory {
+  ord: OrdMemoryStore,
+  brc20: BRC20MemoryStore,
+  brc20s: BRC20SMemoryStore,
+}
+
+impl StateMemory {
+  pub fn new() -> Self {
+    Self::default()
+  }
+}
+
+impl StateReader for StateMemory {
+  type OrdReader = OrdMemoryStore;
+  type BRC20Reader = BRC20MemoryStore;
+  type BRC20SReader = BRC20SMemoryStore;
+
+  fn ord(&self) -> &Self::OrdReader {
+    &self.ord
+  }
+
+  fn brc20(&self) -> &Self::BRC20Reader {
+    &self.brc20
+  }
+
+  fn brc20s(&self) -> &Self::BRC20SReader {
+    &self.brc20s
+  }
+}
+
+impl StateRWriter for StateMemory {
+  type OrdRWriter = OrdMemoryStore;
+  type BRC20RWriter = BRC20MemoryStore;
+  type BRC20SRWriter = BRC20SMemoryStore;
+
+  fn ord(&self) -> &Self::OrdRWriter {
+    &self.ord
+  }
+
+  fn brc20(&self) -> &Self::BRC20RWriter {
+    &self.brc20
+  }
+
+  fn brc20s(&self) -> &Self::BRC20SRWriter {
+    &self.brc20s
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::okx::datastore::{
+      brc20::{Balance, DataStoreReadOnly as _, DataStoreReadWrite as _, Tick},
+      ScriptKey,
+    },
+    bitcoin::{Network, ScriptBuf},
+    std::str::FromStr,
+  };
+
+  #[test]
+  fn writes_are_visible_through_the_reader() {
+    let state = StateMemory::new();
+    let script = ScriptKey::from_script(&ScriptBuf::new(), Network::Bitcoin);
+    let balance = Balance {
+      tick: Tick::from_str("ordi").unwrap(),
+      overall_balance: 10,
+      transferable_balance: 5,
+    };
+
+    StateRWriter::brc20(&state)
+      .update_token_balance(&script, balance.clone())
+      .unwrap();
+
+    assert_eq!(
+      StateReader::brc20(&state)
+        .get_balance(&script, &balance.tick)
+        .unwrap(),
+      Some(balance)
+    );
+  }
+}
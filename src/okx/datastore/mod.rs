@@ -1,12 +1,19 @@
 pub(crate) mod balance;
 pub mod brc20;
 pub mod brc20s;
+#[cfg(test)]
+mod memory;
 pub mod ord;
+mod overlay;
+pub mod page;
 mod redb;
+pub mod schema;
 mod script_key;
+pub mod snapshot;
 
 pub use self::{
-  redb::{StateReadOnly, StateReadWrite},
+  overlay::StateOverlay,
+  redb::{visit_tables, StateReadOnly, StateReadWrite},
   script_key::ScriptKey,
 };
 
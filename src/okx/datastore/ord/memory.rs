@@ -0,0 +1,137 @@
+use {
+  super::{CollectionKind, DataStoreReadOnly, DataStoreReadWrite, InscriptionOp},
+  crate::{InscriptionId, Result},
+  bitcoin::{OutPoint, TxOut, Txid},
+  std::{cell::RefCell, collections::HashMap, convert::Infallible},
+};
+
+/// MemoryStore is an in-memory implementation of the ord datastore traits.
+///
+/// Inscription numbers live in the core index rather than in the ord tables, so
+/// they are populated with `set_inscription_number` before executing messages.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+  inscription_numbers: RefCell<HashMap<InscriptionId, i64>>,
+  outpoint_to_txout: RefCell<HashMap<OutPoint, TxOut>>,
+  tx_to_operations: RefCell<HashMap<Txid, Vec<InscriptionOp>>>,
+  collection_key_to_inscription_id: RefCell<HashMap<String, InscriptionId>>,
+  inscription_id_to_kinds: RefCell<HashMap<InscriptionId, Vec<CollectionKind>>>,
+}
+
+impl MemoryStore {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  #[allow(dead_code)]
+  pub fn set_inscription_number(&self, inscription_id: InscriptionId, number: i64) {
+    self
+      .inscription_numbers
+      .borrow_mut()
+      .insert(inscription_id, number);
+  }
+}
+
+impl DataStoreReadOnly for MemoryStore {
+  type Error = Infallible;
+
+  fn get_number_by_inscription_id(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<i64>, Self::Error> {
+    Ok(
+      self
+        .inscription_numbers
+        .borrow()
+        .get(&inscription_id)
+        .copied(),
+    )
+  }
+
+  fn get_outpoint_to_txout(&self, outpoint: OutPoint) -> Result<Option<TxOut>, Self::Error> {
+    Ok(self.outpoint_to_txout.borrow().get(&outpoint).cloned())
+  }
+
+  fn get_transaction_operations(&self, txid: &Txid) -> Result<Vec<InscriptionOp>, Self::Error> {
+    Ok(
+      self
+        .tx_to_operations
+        .borrow()
+        .get(txid)
+        .cloned()
+        .unwrap_or_default(),
+    )
+  }
+
+  fn get_collections_of_inscription(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<Vec<CollectionKind>>, Self::Error> {
+    Ok(
+      self
+        .inscription_id_to_kinds
+        .borrow()
+        .get(&inscription_id)
+        .cloned(),
+    )
+  }
+
+  fn get_collection_inscription_id(
+    &self,
+    collection_key: &str,
+  ) -> Result<Option<InscriptionId>, Self::Error> {
+    Ok(
+      self
+        .collection_key_to_inscription_id
+        .borrow()
+        .get(collection_key)
+        .copied(),
+    )
+  }
+}
+
+impl DataStoreReadWrite for MemoryStore {
+  fn set_outpoint_to_txout(&self, outpoint: OutPoint, tx_out: &TxOut) -> Result<(), Self::Error> {
+    self
+      .outpoint_to_txout
+      .borrow_mut()
+      .insert(outpoint, tx_out.clone());
+    Ok(())
+  }
+
+  fn save_transaction_operations(
+    &self,
+    txid: &Txid,
+    operations: &[InscriptionOp],
+  ) -> Result<(), Self::Error> {
+    self
+      .tx_to_operations
+      .borrow_mut()
+      .insert(*txid, operations.to_vec());
+    Ok(())
+  }
+
+  fn set_inscription_by_collection_key(
+    &self,
+    key: &str,
+    inscription_id: InscriptionId,
+  ) -> Result<(), Self::Error> {
+    self
+      .collection_key_to_inscription_id
+      .borrow_mut()
+      .insert(key.to_string(), inscription_id);
+    Ok(())
+  }
+
+  fn set_inscription_attributes(
+    &self,
+    inscription_id: InscriptionId,
+    kind: &[CollectionKind],
+  ) -> Result<(), Self::Error> {
+    self
+      .inscription_id_to_kinds
+      .borrow_mut()
+      .insert(inscription_id, kind.to_vec());
+    Ok(())
+  }
+}
@@ -11,7 +11,9 @@ use {
 };
 pub mod bitmap;
 pub mod collections;
+pub mod memory;
 pub mod operation;
+pub mod overlay;
 pub mod redb;
 
 pub trait DataStoreReadOnly {
@@ -0,0 +1,134 @@
+use {
+  super::{
+    memory::MemoryStore, CollectionKind, DataStoreReadOnly, DataStoreReadWrite, InscriptionOp,
+  },
+  crate::{InscriptionId, Result},
+  bitcoin::{OutPoint, TxOut, Txid},
+};
+
+/// OverlayStore layers a `MemoryStore` over a read-only ord store.
+///
+/// The ord tables are never deleted from, so reads simply prefer the memory
+/// store and fall back to the base store.
+pub struct OverlayStore<'a, R: DataStoreReadOnly> {
+  base: &'a R,
+  memory: MemoryStore,
+}
+
+impl<'a, R: DataStoreReadOnly> OverlayStore<'a, R> {
+  pub fn new(base: &'a R) -> Self {
+    Self {
+      base,
+      memory: MemoryStore::new(),
+    }
+  }
+
+  pub fn set_inscription_number(&self, inscription_id: InscriptionId, number: i64) {
+    self.memory.set_inscription_number(inscription_id, number);
+  }
+}
+
+impl<'a, R: DataStoreReadOnly> DataStoreReadOnly for OverlayStore<'a, R> {
+  type Error = R::Error;
+
+  fn get_number_by_inscription_id(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<i64>, Self::Error> {
+    match self
+      .memory
+      .get_number_by_inscription_id(inscription_id)
+      .unwrap()
+    {
+      Some(number) => Ok(Some(number)),
+      None => self.base.get_number_by_inscription_id(inscription_id),
+    }
+  }
+
+  fn get_outpoint_to_txout(&self, outpoint: OutPoint) -> Result<Option<TxOut>, Self::Error> {
+    match self.memory.get_outpoint_to_txout(outpoint).unwrap() {
+      Some(tx_out) => Ok(Some(tx_out)),
+      None => self.base.get_outpoint_to_txout(outpoint),
+    }
+  }
+
+  fn get_transaction_operations(&self, txid: &Txid) -> Result<Vec<InscriptionOp>, Self::Error> {
+    let operations = self.memory.get_transaction_operations(txid).unwrap();
+    if operations.is_empty() {
+      self.base.get_transaction_operations(txid)
+    } else {
+      Ok(operations)
+    }
+  }
+
+  fn get_collections_of_inscription(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<Vec<CollectionKind>>, Self::Error> {
+    match self
+      .memory
+      .get_collections_of_inscription(inscription_id)
+      .unwrap()
+    {
+      Some(kinds) => Ok(Some(kinds)),
+      None => self.base.get_collections_of_inscription(inscription_id),
+    }
+  }
+
+  fn get_collection_inscription_id(
+    &self,
+    collection_key: &str,
+  ) -> Result<Option<InscriptionId>, Self::Error> {
+    match self
+      .memory
+      .get_collection_inscription_id(collection_key)
+      .unwrap()
+    {
+      Some(inscription_id) => Ok(Some(inscription_id)),
+      None => self.base.get_collection_inscription_id(collection_key),
+    }
+  }
+}
+
+impl<'a, R: DataStoreReadOnly> DataStoreReadWrite for OverlayStore<'a, R> {
+  fn set_outpoint_to_txout(&self, outpoint: OutPoint, tx_out: &TxOut) -> Result<(), Self::Error> {
+    self.memory.set_outpoint_to_txout(outpoint, tx_out).unwrap();
+    Ok(())
+  }
+
+  fn save_transaction_operations(
+    &self,
+    txid: &Txid,
+    operations: &[InscriptionOp],
+  ) -> Result<(), Self::Error> {
+    self
+      .memory
+      .save_transaction_operations(txid, operations)
+      .unwrap();
+    Ok(())
+  }
+
+  fn set_inscription_by_collection_key(
+    &self,
+    key: &str,
+    inscription_id: InscriptionId,
+  ) -> Result<(), Self::Error> {
+    self
+      .memory
+      .set_inscription_by_collection_key(key, inscription_id)
+      .unwrap();
+    Ok(())
+  }
+
+  fn set_inscription_attributes(
+    &self,
+    inscription_id: InscriptionId,
+    kind: &[CollectionKind],
+  ) -> Result<(), Self::Error> {
+    self
+      .memory
+      .set_inscription_attributes(inscription_id, kind)
+      .unwrap();
+    Ok(())
+  }
+}
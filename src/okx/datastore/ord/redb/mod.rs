@@ -5,7 +5,11 @@ pub use self::{
   read_only::OrdDbReader,
   read_write::{try_init_tables, OrdDbReadWriter},
 };
-use {super::CollectionKind, redb::TableDefinition};
+use {
+  super::CollectionKind,
+  crate::{okx::datastore::snapshot::TableVisitor, Result},
+  redb::TableDefinition,
+};
 
 const ORD_TX_TO_OPERATIONS: TableDefinition<&str, &[u8]> =
   TableDefinition::new("ORD_TX_TO_OPERATIONS");
@@ -13,3 +17,11 @@ const COLLECTIONS_KEY_TO_INSCRIPTION_ID: TableDefinition<&str, &[u8; 36]> =
   TableDefinition::new("COLLECTIONS_KEY_TO_INSCRIPTION_ID");
 const COLLECTIONS_INSCRIPTION_ID_TO_KINDS: TableDefinition<&[u8; 36], &[u8]> =
   TableDefinition::new("COLLECTIONS_INSCRIPTION_ID_TO_KINDS");
+
+/// Visits every table of this protocol, in a fixed order.
+pub fn visit_tables<T: TableVisitor>(visitor: &mut T) -> Result<()> {
+  visitor.visit(ORD_TX_TO_OPERATIONS)?;
+  visitor.visit(COLLECTIONS_KEY_TO_INSCRIPTION_ID)?;
+  visitor.visit(COLLECTIONS_INSCRIPTION_ID_TO_KINDS)?;
+  Ok(())
+}
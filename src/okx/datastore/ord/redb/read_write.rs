@@ -1,7 +1,10 @@
 use {
   super::*,
   crate::{
-    index::OUTPOINT_TO_ENTRY,
+    index::{
+      undo::{self, UndoLog},
+      OUTPOINT_TO_ENTRY,
+    },
     okx::datastore::ord::{DataStoreReadOnly, DataStoreReadWrite, InscriptionOp},
     InscriptionId, Result,
   },
@@ -23,6 +26,7 @@ pub fn try_init_tables<'db, 'a>(
 
 pub struct OrdDbReadWriter<'db, 'a> {
   wtx: &'a WriteTransaction<'db>,
+  undo: Option<&'a UndoLog>,
 }
 
 impl<'db, 'a> OrdDbReadWriter<'db, 'a>
@@ -30,7 +34,12 @@ where
   'db: 'a,
 {
   pub fn new(wtx: &'a WriteTransaction<'db>) -> Self {
-    Self { wtx }
+    Self { wtx, undo: None }
+  }
+
+  /// Records every mutation in `undo`, if given, so the block can be unwound after a reorg.
+  pub fn with_undo_log(self, undo: Option<&'a UndoLog>) -> Self {
+    Self { undo, ..self }
   }
 }
 
@@ -76,10 +85,13 @@ impl<'db, 'a> DataStoreReadWrite for OrdDbReadWriter<'db, 'a> {
 
     let mut entry = Vec::new();
     tx_out.consensus_encode(&mut entry)?;
-    self
-      .wtx
-      .open_table(OUTPOINT_TO_ENTRY)?
-      .insert(&value, entry.as_slice())?;
+    undo::insert(
+      self.undo,
+      OUTPOINT_TO_ENTRY,
+      &mut self.wtx.open_table(OUTPOINT_TO_ENTRY)?,
+      &value,
+      entry.as_slice(),
+    )?;
     Ok(())
   }
 
@@ -88,7 +100,10 @@ impl<'db, 'a> DataStoreReadWrite for OrdDbReadWriter<'db, 'a> {
     txid: &Txid,
     operations: &[InscriptionOp],
   ) -> Result<(), Self::Error> {
-    self.wtx.open_table(ORD_TX_TO_OPERATIONS)?.insert(
+    undo::insert(
+      self.undo,
+      ORD_TX_TO_OPERATIONS,
+      &mut self.wtx.open_table(ORD_TX_TO_OPERATIONS)?,
       txid.to_string().as_str(),
       bincode::serialize(operations).unwrap().as_slice(),
     )?;
@@ -103,10 +118,13 @@ impl<'db, 'a> DataStoreReadWrite for OrdDbReadWriter<'db, 'a> {
     let (txid, index) = value.split_at_mut(32);
     txid.copy_from_slice(inscription_id.txid.as_ref());
     index.copy_from_slice(&inscription_id.index.to_be_bytes());
-    self
-      .wtx
-      .open_table(COLLECTIONS_KEY_TO_INSCRIPTION_ID)?
-      .insert(key, &value)?;
+    undo::insert(
+      self.undo,
+      COLLECTIONS_KEY_TO_INSCRIPTION_ID,
+      &mut self.wtx.open_table(COLLECTIONS_KEY_TO_INSCRIPTION_ID)?,
+      key,
+      &value,
+    )?;
     Ok(())
   }
 
@@ -119,10 +137,13 @@ impl<'db, 'a> DataStoreReadWrite for OrdDbReadWriter<'db, 'a> {
     let (txid, index) = key.split_at_mut(32);
     txid.copy_from_slice(inscription_id.txid.as_ref());
     index.copy_from_slice(&inscription_id.index.to_be_bytes());
-    self
-      .wtx
-      .open_table(COLLECTIONS_INSCRIPTION_ID_TO_KINDS)?
-      .insert(&key, bincode::serialize(&kind).unwrap().as_slice())?;
+    undo::insert(
+      self.undo,
+      COLLECTIONS_INSCRIPTION_ID_TO_KINDS,
+      &mut self.wtx.open_table(COLLECTIONS_INSCRIPTION_ID_TO_KINDS)?,
+      &key,
+      bincode::serialize(&kind).unwrap().as_slice(),
+    )?;
     Ok(())
   }
 }
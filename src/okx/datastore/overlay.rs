@@ -0,0 +1,44 @@
+use super::{
+  brc20::overlay::OverlayStore as BRC20OverlayStore,
+  brc20s::overlay::OverlayStore as BRC20SOverlayStore,
+  ord::overlay::OverlayStore as OrdOverlayStore, StateRWriter, StateReader,
+};
+
+/// StateOverlay is an implementation of the StateRWriter trait over a StateReader.
+///
+/// Reads see the state of the underlying reader, writes are kept in memory and
+/// dropped with the overlay. It is used to predict the effects of transactions
+/// without touching the index.
+pub struct StateOverlay<'a, R: StateReader> {
+  ord: OrdOverlayStore<'a, R::OrdReader>,
+  brc20: BRC20OverlayStore<'a, R::BRC20Reader>,
+  brc20s: BRC20SOverlayStore<'a, R::BRC20SReader>,
+}
+
+impl<'a, R: StateReader> StateOverlay<'a, R> {
+  pub fn new(state: &'a R) -> Self {
+    Self {
+      ord: OrdOverlayStore::new(state.ord()),
+      brc20: BRC20OverlayStore::new(state.brc20()),
+      brc20s: BRC20SOverlayStore::new(state.brc20s()),
+    }
+  }
+}
+
+impl<'a, R: StateReader> StateRWriter for StateOverlay<'a, R> {
+  type OrdRWriter = OrdOverlayStore<'a, R::OrdReader>;
+  type BRC20RWriter = BRC20OverlayStore<'a, R::BRC20Reader>;
+  type BRC20SRWriter = BRC20SOverlayStore<'a, R::BRC20SReader>;
+
+  fn ord(&self) -> &Self::OrdRWriter {
+    &self.ord
+  }
+
+  fn brc20(&self) -> &Self::BRC20RWriter {
+    &self.brc20
+  }
+
+  fn brc20s(&self) -> &Self::BRC20SRWriter {
+    &self.brc20s
+  }
+}
@@ -0,0 +1,224 @@
+use {
+  anyhow::{anyhow, Error},
+  std::{fmt, ops::RangeInclusive, str::FromStr},
+};
+
+/// Cursor is an opaque position in a key-ordered listing.
+///
+/// It holds the key of the first item that was not returned yet, so a page
+/// resumes at the same item even if the items before it changed meanwhile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+  key: Vec<u8>,
+}
+
+impl Cursor {
+  pub fn new(key: Vec<u8>) -> Self {
+    Self { key }
+  }
+}
+
+impl fmt::Display for Cursor {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", hex::encode(&self.key))
+  }
+}
+
+impl FromStr for Cursor {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Ok(Self {
+      key: hex::decode(s).map_err(|_| anyhow!("invalid cursor: {s}"))?,
+    })
+  }
+}
+
+/// Page is one slice of a listing, with the cursor of the next one if there are more items.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+  pub items: Vec<T>,
+  pub next: Option<Cursor>,
+}
+
+impl<T> Default for Page<T> {
+  fn default() -> Self {
+    Self {
+      items: Vec::new(),
+      next: None,
+    }
+  }
+}
+
+/// PageRequest selects a page of a listing.
+///
+/// Listings resume at `cursor` when one is given, then drop `skip` items and
+/// return at most `limit` of the rest. Without a limit the whole remainder is returned.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageRequest {
+  pub cursor: Option<Cursor>,
+  pub skip: usize,
+  pub limit: Option<usize>,
+}
+
+impl PageRequest {
+  /// Returns the key to start scanning an unbounded table from.
+  pub fn start(&self) -> &[u8] {
+    self.cursor.as_ref().map_or(&[], |cursor| &cursor.key)
+  }
+
+  /// Narrows the key range `min..=max` down to what is left after the cursor,
+  /// or returns None if the cursor lies beyond it.
+  pub fn bounds(&self, min: Vec<u8>, max: Vec<u8>) -> Option<RangeInclusive<Vec<u8>>> {
+    let start = match &self.cursor {
+      Some(cursor) if cursor.key > min => cursor.key.clone(),
+      _ => min,
+    };
+    (start <= max).then_some(start..=max)
+  }
+
+  /// Collects a page from items in ascending key order, skipping the ones
+  /// before the cursor.
+  pub fn collect<T, E, I>(&self, items: I) -> Result<Page<T>, E>
+  where
+    I: IntoIterator<Item = Result<(Vec<u8>, T), E>>,
+  {
+    let limit = self.limit.unwrap_or(usize::MAX);
+    let mut skip = self.skip;
+    let mut page = Vec::new();
+
+    for item in items {
+      let (key, value) = item?;
+
+      if key.as_slice() < self.start() {
+        continue;
+      }
+
+      if skip > 0 {
+        skip -= 1;
+        continue;
+      }
+
+      if page.len() == limit {
+        return Ok(Page {
+          items: page,
+          next: Some(Cursor::new(key)),
+        });
+      }
+
+      page.push(value);
+    }
+
+    Ok(Page {
+      items: page,
+      next: None,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn items() -> Vec<Result<(Vec<u8>, u32), ()>> {
+    vec![
+      Ok((vec![1], 10)),
+      Ok((vec![2, 0], 20)),
+      Ok((vec![2, 1], 21)),
+      Ok((vec![2, 2], 22)),
+      Ok((vec![3], 30)),
+    ]
+  }
+
+  fn walk(limit: usize) -> Vec<Vec<u32>> {
+    let mut pages = Vec::new();
+    let mut request = PageRequest {
+      limit: Some(limit),
+      ..Default::default()
+    };
+    loop {
+      let page = request.collect(items()).unwrap();
+      pages.push(page.items);
+      match page.next {
+        Some(next) => request.cursor = Some(next.to_string().parse().unwrap()),
+        None => break,
+      }
+    }
+    pages
+  }
+
+  #[test]
+  fn cursor_round_trip() {
+    let cursor = Cursor::new(vec![0xab, 0xcd]);
+    assert_eq!(cursor.to_string(), "abcd");
+    assert_eq!(cursor.to_string().parse::<Cursor>().unwrap(), cursor);
+    assert_eq!(
+      Cursor::new(Vec::new())
+        .to_string()
+        .parse::<Cursor>()
+        .unwrap(),
+      Cursor::new(Vec::new())
+    );
+    assert!("abc".parse::<Cursor>().is_err());
+    assert!("zz".parse::<Cursor>().is_err());
+  }
+
+  #[test]
+  fn pages_resume_at_item_keys() {
+    assert_eq!(walk(2), vec![vec![10, 20], vec![21, 22], vec![30]]);
+    assert_eq!(walk(5), vec![vec![10, 20, 21, 22, 30]]);
+    assert_eq!(walk(1).concat(), vec![10, 20, 21, 22, 30]);
+  }
+
+  #[test]
+  fn pages_resume_at_the_same_item_after_earlier_ones_are_removed() {
+    let request = PageRequest {
+      limit: Some(2),
+      ..Default::default()
+    };
+    let next = request.collect(items()).unwrap().next.unwrap();
+    assert_eq!(next, Cursor::new(vec![2, 1]));
+
+    let mut items = items();
+    items.drain(..2);
+    let page = PageRequest {
+      cursor: Some(next),
+      ..request
+    }
+    .collect(items)
+    .unwrap();
+    assert_eq!(page.items, vec![21, 22]);
+  }
+
+  #[test]
+  fn unlimited_request_returns_everything() {
+    let page = PageRequest::default().collect(items()).unwrap();
+    assert_eq!(page.items, vec![10, 20, 21, 22, 30]);
+    assert_eq!(page.next, None);
+  }
+
+  #[test]
+  fn skip_applies_after_cursor() {
+    let page = PageRequest {
+      cursor: Some(Cursor::new(vec![2, 1])),
+      skip: 1,
+      limit: Some(1),
+    }
+    .collect(items())
+    .unwrap();
+    assert_eq!(page.items, vec![22]);
+    assert_eq!(page.next, Some(Cursor::new(vec![3])));
+  }
+
+  #[test]
+  fn bounds_start_at_cursor() {
+    let request = PageRequest {
+      cursor: Some(Cursor::new(vec![5])),
+      ..Default::default()
+    };
+    assert_eq!(request.bounds(vec![1], vec![9]), Some(vec![5]..=vec![9]));
+    assert_eq!(request.bounds(vec![6], vec![9]), Some(vec![6]..=vec![9]));
+    assert_eq!(request.bounds(vec![1], vec![4]), None);
+    assert_eq!(PageRequest::default().start(), &[] as &[u8]);
+  }
+}
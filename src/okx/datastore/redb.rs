@@ -1,11 +1,31 @@
 use {
   super::{
-    brc20::redb::{DataStore as BRC20StateRW, DataStoreReader as BRC20StateReader},
-    brc20s::redb::{DataStore as BRC20SStateRW, DataStoreReader as BRC20SStateReader},
+    brc20::{
+      self,
+      redb::{DataStore as BRC20StateRW, DataStoreReader as BRC20StateReader},
+    },
+    brc20s::{
+      self,
+      redb::{DataStore as BRC20SStateRW, DataStoreReader as BRC20SStateReader},
+    },
     ord::redb::{OrdDbReadWriter as OrdStateRW, OrdDbReader as OrdStateReader},
-    StateRWriter, StateReader,
+    schema::{Chain, Protocol},
+    snapshot::TableVisitor,
+    ScriptKey, StateRWriter, StateReader,
+  },
+  crate::{
+    index::{
+      undo::{self, UndoLog},
+      HEIGHT_TO_BLOCK_HASH, HEIGHT_TO_LAST_INSCRIPTION_NUMBER,
+    },
+    Result,
+  },
+  anyhow::anyhow,
+  bitcoin::{hashes::Hash, BlockHash, Txid},
+  redb::{
+    Range, ReadTransaction, ReadableTable, StorageError, Table, TableDefinition, TableError,
+    TableHandle, WriteTransaction,
   },
-  redb::{ReadTransaction, WriteTransaction},
 };
 
 /// StateReadOnly, based on `redb`, is an implementation of the StateRWriter trait.
@@ -59,6 +79,15 @@ impl<'db, 'a> StateReadWrite<'db, 'a> {
       brc20s: BRC20SStateRW::new(wtx),
     }
   }
+
+  /// Records every protocol mutation in `undo`, if given.
+  pub fn with_undo_log(self, undo: Option<&'a UndoLog>) -> Self {
+    Self {
+      ord: self.ord.with_undo_log(undo),
+      brc20: self.brc20.with_undo_log(undo),
+      brc20s: self.brc20s.with_undo_log(undo),
+    }
+  }
 }
 
 impl<'db, 'a> StateRWriter for StateReadWrite<'db, 'a> {
@@ -78,3 +107,287 @@ impl<'db, 'a> StateRWriter for StateReadWrite<'db, 'a> {
     &self.brc20s
   }
 }
+
+/// Script key of every owner of BRC20 and BRC20S balances and pool positions,
+/// by its script hash. Those tables are keyed by the hash alone, so the owner
+/// is kept here once rather than in each of their values.
+pub(super) const SCRIPT_HASH_TO_SCRIPT_KEY: TableDefinition<&[u8; SCRIPT_HASH_LEN], &[u8]> =
+  TableDefinition::new("SCRIPT_HASH_TO_SCRIPT_KEY");
+
+const SCRIPT_HASH_LEN: usize = 20;
+
+/// Visits the tables shared by the BRC20 and BRC20S protocols.
+pub fn visit_tables<T: TableVisitor>(visitor: &mut T) -> Result<()> {
+  visitor.visit(SCRIPT_HASH_TO_SCRIPT_KEY)?;
+  Ok(())
+}
+
+/// Records `owner` in `owners`, unless it is already there.
+pub(super) fn save_owner(
+  owners: &mut Table<&[u8; SCRIPT_HASH_LEN], &[u8]>,
+  undo: Option<&UndoLog>,
+  owner: &ScriptKey,
+) -> Result<(), StorageError> {
+  let hash = owner.script_hash().to_byte_array();
+  if owners.get(&hash)?.is_none() {
+    undo::insert(
+      undo,
+      SCRIPT_HASH_TO_SCRIPT_KEY,
+      owners,
+      &hash,
+      bincode::serialize(owner).unwrap().as_slice(),
+    )?;
+  }
+  Ok(())
+}
+
+/// Resolves the owners of entries keyed by script hash. Entries of one owner
+/// are adjacent, so each owner is read from the owner table once per run.
+#[derive(Default)]
+pub(super) struct OwnerCache {
+  last: Option<([u8; SCRIPT_HASH_LEN], ScriptKey)>,
+}
+
+impl OwnerCache {
+  /// Returns the owner of the entry at `key`, reading it with `get` from the
+  /// owner table if it is not the owner of the previous entry.
+  pub(super) fn resolve(
+    &mut self,
+    key: &[u8],
+    get: impl FnOnce(&[u8; SCRIPT_HASH_LEN]) -> Result<Option<Vec<u8>>, StorageError>,
+  ) -> Result<ScriptKey> {
+    let hash = <[u8; SCRIPT_HASH_LEN]>::try_from(&key[..SCRIPT_HASH_LEN]).unwrap();
+    match &self.last {
+      Some((last, owner)) if *last == hash => Ok(owner.clone()),
+      _ => {
+        let owner: ScriptKey = bincode::deserialize(
+          &get(&hash)?.ok_or_else(|| anyhow!("no owner of script hash {}", hex::encode(hash)))?,
+        )?;
+        self.last = Some((hash, owner.clone()));
+        Ok(owner)
+      }
+    }
+  }
+}
+
+// Receipt history keys end with the height of the block (8 bytes), the position
+// of the transaction in it (4 bytes) and the index of the receipt in the
+// transaction (4 bytes), all big endian, so they sort in the order the
+// receipts were made. The value is the txid.
+const RECEIPT_KEY_LEN: usize = 16;
+
+pub(super) fn receipt_key(height: u64, position: u32, index: u32) -> [u8; RECEIPT_KEY_LEN] {
+  let mut key = [0; RECEIPT_KEY_LEN];
+  key[..8].copy_from_slice(&height.to_be_bytes());
+  key[8..12].copy_from_slice(&position.to_be_bytes());
+  key[12..].copy_from_slice(&index.to_be_bytes());
+  key
+}
+
+/// Walks at most `scan` entries of a receipt history from the newest and
+/// returns up to `limit` receipts matching `filter`, with the height and txid
+/// they were made at. `get_receipts` reads the receipts of a transaction.
+pub(super) fn read_receipt_history<T: Clone>(
+  entries: Range<&'static [u8], &'static [u8]>,
+  limit: usize,
+  scan: usize,
+  get_receipts: impl Fn(&Txid) -> Result<Vec<T>, redb::Error>,
+  filter: impl Fn(&T) -> bool,
+) -> Result<Vec<(u64, Txid, T)>, redb::Error> {
+  let mut receipts = Vec::new();
+  let mut transaction: Option<(Txid, Vec<T>)> = None;
+
+  for result in entries.rev().take(scan) {
+    if receipts.len() >= limit {
+      break;
+    }
+
+    let (key, value) = result?;
+    let key = &key.value()[key.value().len() - RECEIPT_KEY_LEN..];
+    let height = u64::from_be_bytes(key[..8].try_into().unwrap());
+    let index = u32::from_be_bytes(key[12..].try_into().unwrap());
+    let txid = Txid::from_slice(value.value()).unwrap();
+
+    if transaction.as_ref().map(|(cached, _)| *cached) != Some(txid) {
+      transaction = Some((txid, get_receipts(&txid)?));
+    }
+
+    let Some(receipt) = transaction
+      .as_ref()
+      .and_then(|(_, receipts)| receipts.get(usize::try_from(index).unwrap()))
+    else {
+      continue;
+    };
+
+    if filter(receipt) {
+      receipts.push((height, txid, receipt.clone()));
+    }
+  }
+
+  Ok(receipts)
+}
+
+/// Calls `f` with the height, position and txid of every transaction in up
+/// to `limit` blocks indexed for inscriptions, starting at height `start`, and
+/// reads the blocks from `chain`. Returns the height to continue from, or None
+/// once the last indexed block was read. Used to backfill tables that need
+/// the position of a transaction in its block.
+pub(super) fn for_each_inscription_block_transaction(
+  rtx: &ReadTransaction,
+  chain: &dyn Chain,
+  start: u64,
+  limit: usize,
+  mut f: impl FnMut(u64, u32, &Txid) -> Result<()>,
+) -> Result<Option<u64>> {
+  let (heights, hashes) = match (
+    rtx.open_table(HEIGHT_TO_LAST_INSCRIPTION_NUMBER),
+    rtx.open_table(HEIGHT_TO_BLOCK_HASH),
+  ) {
+    (Ok(heights), Ok(hashes)) => (heights, hashes),
+    (Err(TableError::TableDoesNotExist(_)), _) | (_, Err(TableError::TableDoesNotExist(_))) => {
+      return Ok(None)
+    }
+    (Err(e), _) | (_, Err(e)) => return Err(e.into()),
+  };
+
+  let mut next = None;
+  for result in heights.range(start..)?.take(limit) {
+    let height = result?.0.value();
+    next = Some(height + 1);
+    let Some(hash) = hashes.get(height)? else {
+      continue;
+    };
+    let hash = BlockHash::from_byte_array(*hash.value());
+    let block = chain
+      .block(hash)?
+      .ok_or_else(|| anyhow!("block {hash} at height {height} not found"))?;
+
+    for (position, tx) in block.txdata.iter().enumerate() {
+      f(height, u32::try_from(position).unwrap(), &tx.txid())?;
+    }
+  }
+
+  let Some(next) = next else {
+    return Ok(None);
+  };
+  let last = heights
+    .iter()?
+    .next_back()
+    .transpose()?
+    .map(|(height, _)| height.value());
+  log::info!(
+    "Read blocks up to height {}, the last one indexed is at height {}",
+    next - 1,
+    last.unwrap_or_default()
+  );
+  Ok(Some(next))
+}
+
+// Receipt histories are backfilled this many blocks at a time.
+const RECEIPT_HISTORY_BLOCKS_PER_CHUNK: usize = 1000;
+
+/// Lists the receipts of every indexed transaction in the receipt histories
+/// of `protocols`, for indexes built before the histories existed. Both
+/// protocols are filled in a single walk over the blocks.
+///
+/// Reads up to `RECEIPT_HISTORY_BLOCKS_PER_CHUNK` blocks from `chain` after
+/// the ones `progress` covers, and returns the progress to resume from, or
+/// None once every block was read.
+pub(super) fn backfill_receipt_histories<'db, 'a>(
+  wtx: &'a WriteTransaction<'db>,
+  rtx: &'a ReadTransaction<'db>,
+  chain: &dyn Chain,
+  protocols: &[Protocol],
+  progress: Option<&[u8]>,
+) -> Result<Option<Vec<u8>>> {
+  let brc20 = protocols
+    .contains(&Protocol::BRC20)
+    .then(|| BRC20StateRW::new(wtx));
+  let brc20s = protocols
+    .contains(&Protocol::BRC20S)
+    .then(|| BRC20SStateRW::new(wtx));
+
+  let start = match progress {
+    Some(progress) => u64::from_be_bytes(progress.try_into()?),
+    None => 0,
+  };
+
+  let next = for_each_inscription_block_transaction(
+    rtx,
+    chain,
+    start,
+    RECEIPT_HISTORY_BLOCKS_PER_CHUNK,
+    |height, position, txid| {
+      if let Some(store) = &brc20 {
+        brc20::redb::backfill_receipt_history(store, height, position, txid)?;
+      }
+      if let Some(store) = &brc20s {
+        brc20s::redb::backfill_receipt_history(store, height, position, txid)?;
+      }
+      Ok(())
+    },
+  )?;
+
+  Ok(next.map(|height| height.to_be_bytes().to_vec()))
+}
+
+// Legacy entries are converted and inserted this many at a time, so memory use
+// does not grow with the size of the table.
+const MIGRATION_BATCH_SIZE: usize = 10_000;
+
+/// Rewrites the legacy string keyed table `legacy` into `current`, converting
+/// each entry with `convert`.
+///
+/// Entries are streamed from `rtx`, which still sees the legacy table after
+/// `wtx` deletes it. Returns false if there is no legacy table to migrate.
+pub(super) fn migrate_legacy_table<'db, 'a>(
+  wtx: &'a WriteTransaction<'db>,
+  rtx: &'a ReadTransaction<'db>,
+  legacy: TableDefinition<&str, &[u8]>,
+  current: TableDefinition<&[u8], &[u8]>,
+  mut convert: impl FnMut(&str, &[u8]) -> Result<(Vec<u8>, Vec<u8>)>,
+) -> Result<bool> {
+  let source = match rtx.open_table(legacy) {
+    Ok(table) => table,
+    Err(TableError::TableDoesNotExist(_)) | Err(TableError::TableTypeMismatch { .. }) => {
+      return Ok(false)
+    }
+    Err(e) => return Err(e.into()),
+  };
+
+  let total = source.len()?;
+  log::info!(
+    "Migrating {total} entries of table {} to binary keys",
+    legacy.name()
+  );
+
+  wtx.delete_table(legacy)?;
+  let mut table = wtx.open_table(current)?;
+
+  let mut entries = source.iter()?;
+  let mut batch = Vec::with_capacity(MIGRATION_BATCH_SIZE);
+  let mut migrated = 0;
+  loop {
+    for result in entries.by_ref().take(MIGRATION_BATCH_SIZE) {
+      let (key, value) = result?;
+      batch.push(
+        convert(key.value(), value.value())
+          .map_err(|e| anyhow!("invalid legacy entry `{}`: {e}", key.value()))?,
+      );
+    }
+    if batch.is_empty() {
+      break;
+    }
+
+    migrated += batch.len();
+    for (key, value) in batch.drain(..) {
+      table.insert(key.as_slice(), value.as_slice())?;
+    }
+    log::info!(
+      "Migrated {migrated}/{total} entries of table {}",
+      legacy.name()
+    );
+  }
+
+  Ok(true)
+}
@@ -0,0 +1,499 @@
+use {
+  super::{brc20, brc20s, ord, redb::backfill_receipt_histories},
+  crate::{index, Result},
+  anyhow::bail,
+  bitcoin::{Block, BlockHash, Transaction, Txid},
+  redb::{Database, ReadTransaction, ReadableTable, TableDefinition, WriteTransaction},
+  std::{fmt, path::Path},
+};
+
+/// Schema version of each protocol, keyed by protocol name.
+pub const OKX_SCHEMA_VERSION: TableDefinition<&str, u64> =
+  TableDefinition::new("OKX_SCHEMA_VERSION");
+
+/// Version assumed for indexes built before protocol versions were recorded.
+const UNVERSIONED: u64 = 1;
+
+const ORD_SCHEMA_VERSION: u64 = 3;
+const BRC20_SCHEMA_VERSION: u64 = 3;
+const BRC20S_SCHEMA_VERSION: u64 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+  Ord,
+  BRC20,
+  BRC20S,
+}
+
+impl Protocol {
+  pub const ALL: [Protocol; 3] = [Protocol::Ord, Protocol::BRC20, Protocol::BRC20S];
+
+  pub fn name(self) -> &'static str {
+    match self {
+      Protocol::Ord => "ord",
+      Protocol::BRC20 => "brc20",
+      Protocol::BRC20S => "brc20s",
+    }
+  }
+
+  /// The schema version this build reads and writes.
+  pub fn schema_version(self) -> u64 {
+    match self {
+      Protocol::Ord => ORD_SCHEMA_VERSION,
+      Protocol::BRC20 => BRC20_SCHEMA_VERSION,
+      Protocol::BRC20S => BRC20S_SCHEMA_VERSION,
+    }
+  }
+
+  fn init_tables<'db, 'a>(
+    self,
+    wtx: &'a WriteTransaction<'db>,
+    rtx: &'a ReadTransaction<'db>,
+  ) -> Result<()> {
+    match self {
+      Protocol::Ord => ord::redb::try_init_tables(wtx, rtx)?,
+      Protocol::BRC20 => brc20::redb::try_init_tables(wtx, rtx)?,
+      Protocol::BRC20S => brc20s::redb::try_init_tables(wtx, rtx)?,
+    };
+    Ok(())
+  }
+}
+
+impl fmt::Display for Protocol {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+/// Reads from the bitcoin node, for migrations that need data the index does
+/// not keep.
+pub trait Chain {
+  fn transaction(&self, txid: Txid) -> Result<Option<Transaction>>;
+
+  fn block(&self, hash: BlockHash) -> Result<Option<Block>>;
+}
+
+/// A chain without any transactions or blocks, for tests of indexes that do
+/// not need them.
+#[cfg(test)]
+pub(crate) struct EmptyChain;
+
+#[cfg(test)]
+impl Chain for EmptyChain {
+  fn transaction(&self, _: Txid) -> Result<Option<Transaction>> {
+    Ok(None)
+  }
+
+  fn block(&self, _: BlockHash) -> Result<Option<Block>> {
+    Ok(None)
+  }
+}
+
+/// A migration upgrades the tables of `protocols` from version `from` to
+/// `from + 1`. Protocols that are already past `from` are left out of it.
+struct Migration {
+  protocols: &'static [Protocol],
+  from: u64,
+  description: &'static str,
+  migrate: Migrate,
+}
+
+enum Migrate {
+  /// Runs in a single write transaction.
+  Once(for<'db, 'a> fn(&'a WriteTransaction<'db>, &'a ReadTransaction<'db>) -> Result<()>),
+  /// Runs a chunk at a time, each committed in a write transaction of its
+  /// own, for migrations that read from the bitcoin node and may take hours.
+  /// Each call continues from the progress returned by the previous one, and
+  /// returns None once there is nothing left. The progress is committed with
+  /// each chunk, so an interrupted upgrade resumes where it stopped.
+  Chunked(
+    for<'db, 'a> fn(
+      &'a WriteTransaction<'db>,
+      &'a ReadTransaction<'db>,
+      &dyn Chain,
+      &[Protocol],
+      Option<&[u8]>,
+    ) -> Result<Option<Vec<u8>>>,
+  ),
+}
+
+/// Progress of the chunked migration in progress, keyed by the protocols and
+/// version it migrates from.
+const OKX_MIGRATION_PROGRESS: TableDefinition<&str, &[u8]> =
+  TableDefinition::new("OKX_MIGRATION_PROGRESS");
+
+/// All known migrations, applied in order. Add an entry here whenever a
+/// protocol schema version is bumped.
+const MIGRATIONS: &[Migration] = &[
+  Migration {
+    protocols: &[Protocol::Ord],
+    from: 1,
+    description: "inscription owners and content types",
+    migrate: Migrate::Chunked(|wtx, rtx, chain, _, progress| {
+      index::owners::backfill(wtx, rtx, chain, progress)
+    }),
+  },
+  Migration {
+    protocols: &[Protocol::Ord],
+    from: 2,
+    description: "children by inscription number",
+    migrate: Migrate::Once(index::children::backfill),
+  },
+  Migration {
+    protocols: &[Protocol::BRC20],
+    from: 1,
+    description: "binary table keys",
+    migrate: Migrate::Once(|wtx, rtx| brc20::redb::migrate_legacy_keys(wtx, rtx).map(|_| ())),
+  },
+  Migration {
+    protocols: &[Protocol::BRC20S],
+    from: 1,
+    description: "binary table keys",
+    migrate: Migrate::Once(|wtx, rtx| brc20s::redb::migrate_legacy_keys(wtx, rtx).map(|_| ())),
+  },
+  Migration {
+    protocols: &[Protocol::BRC20, Protocol::BRC20S],
+    from: 2,
+    description: "receipt histories",
+    migrate: Migrate::Chunked(backfill_receipt_histories),
+  },
+];
+
+impl Migration {
+  fn progress_key(&self) -> String {
+    format!(
+      "{}:{}",
+      self
+        .protocols
+        .iter()
+        .map(|protocol| protocol.name())
+        .collect::<Vec<_>>()
+        .join(","),
+      self.from
+    )
+  }
+
+  fn run(&self, database: &Database, protocols: &[Protocol], chain: &dyn Chain) -> Result<()> {
+    match self.migrate {
+      Migrate::Once(migrate) => {
+        let wtx = database.begin_write()?;
+        migrate(&wtx, &database.begin_read()?)?;
+        self.finish(&wtx, protocols)?;
+        wtx.commit()?;
+      }
+      Migrate::Chunked(migrate) => loop {
+        let wtx = database.begin_write()?;
+        let rtx = database.begin_read()?;
+        let key = self.progress_key();
+        let progress = match rtx.open_table(OKX_MIGRATION_PROGRESS) {
+          Ok(table) => table.get(key.as_str())?.map(|v| v.value().to_vec()),
+          Err(redb::TableError::TableDoesNotExist(_)) => None,
+          Err(e) => return Err(e.into()),
+        };
+
+        let done = match migrate(&wtx, &rtx, chain, protocols, progress.as_deref())? {
+          Some(progress) => {
+            wtx
+              .open_table(OKX_MIGRATION_PROGRESS)?
+              .insert(key.as_str(), progress.as_slice())?;
+            false
+          }
+          None => {
+            wtx
+              .open_table(OKX_MIGRATION_PROGRESS)?
+              .remove(key.as_str())?;
+            self.finish(&wtx, protocols)?;
+            true
+          }
+        };
+        wtx.commit()?;
+
+        if done {
+          break;
+        }
+      },
+    }
+
+    Ok(())
+  }
+
+  fn finish(&self, wtx: &WriteTransaction, protocols: &[Protocol]) -> Result<()> {
+    let mut versions = wtx.open_table(OKX_SCHEMA_VERSION)?;
+    for protocol in protocols {
+      versions.insert(protocol.name(), self.from + 1)?;
+    }
+    Ok(())
+  }
+}
+
+/// Reads the stored schema version of a protocol, if one was recorded.
+pub fn get_schema_version(rtx: &ReadTransaction, protocol: Protocol) -> Result<Option<u64>> {
+  Ok(match rtx.open_table(OKX_SCHEMA_VERSION) {
+    Ok(table) => table.get(protocol.name())?.map(|v| v.value()),
+    Err(redb::TableError::TableDoesNotExist(_)) => None,
+    Err(e) => return Err(e.into()),
+  })
+}
+
+/// Checks the schema version of every protocol, applies pending migrations and
+/// creates any missing tables.
+///
+/// Each migration is committed on its own, and chunked ones commit every
+/// chunk, so an interrupted upgrade continues where it stopped the next time
+/// the index is opened. Fails if an index is newer than this build or too old
+/// to be migrated, before migrating anything.
+pub fn upgrade(database: &Database, path: &Path, chain: &dyn Chain) -> Result<()> {
+  let rtx = database.begin_read()?;
+  for protocol in Protocol::ALL {
+    let current = protocol.schema_version();
+    let version = get_schema_version(&rtx, protocol)?.unwrap_or(UNVERSIONED);
+
+    if version > current {
+      bail!(
+        "index at `{}` appears to have been built with a newer, incompatible version of ord, consider updating ord: index {protocol} schema {version}, ord {protocol} schema {current}",
+        path.display()
+      );
+    }
+
+    if (version..current).any(|from| {
+      !MIGRATIONS
+        .iter()
+        .any(|m| m.protocols.contains(&protocol) && m.from == from)
+    }) {
+      bail!(
+        "index at `{}` appears to have been built with an older, incompatible version of ord, consider deleting and rebuilding the index: index {protocol} schema {version}, ord {protocol} schema {current}",
+        path.display()
+      );
+    }
+  }
+  drop(rtx);
+
+  for migration in MIGRATIONS {
+    let rtx = database.begin_read()?;
+    let mut protocols = Vec::new();
+    for &protocol in migration.protocols {
+      if get_schema_version(&rtx, protocol)?.unwrap_or(UNVERSIONED) == migration.from {
+        protocols.push(protocol);
+      }
+    }
+    drop(rtx);
+
+    if protocols.is_empty() {
+      continue;
+    }
+
+    log::info!(
+      "Migrating {} schema from {} to {}: {}",
+      protocols
+        .iter()
+        .map(|protocol| protocol.name())
+        .collect::<Vec<_>>()
+        .join(" and "),
+      migration.from,
+      migration.from + 1,
+      migration.description
+    );
+    migration.run(database, &protocols, chain)?;
+  }
+
+  let wtx = database.begin_write()?;
+  let rtx = database.begin_read()?;
+  for protocol in Protocol::ALL {
+    protocol.init_tables(&wtx, &rtx)?;
+    wtx
+      .open_table(OKX_SCHEMA_VERSION)?
+      .insert(protocol.name(), protocol.schema_version())?;
+  }
+  wtx.commit()?;
+
+  Ok(())
+}
+
+/// Checks that every protocol is at the schema version of this build, without
+/// migrating anything, for indexes opened read-only.
+pub fn check(rtx: &ReadTransaction, path: &Path) -> Result<()> {
+  for protocol in Protocol::ALL {
+    let current = protocol.schema_version();
+    let version = get_schema_version(rtx, protocol)?.unwrap_or(UNVERSIONED);
+
+    if version != current {
+      bail!(
+        "index at `{}` must be upgraded before it can be opened read-only, run `ord index run` first: index {protocol} schema {version}, ord {protocol} schema {current}",
+        path.display()
+      );
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::NamedTempFile;
+
+  fn set_version(db: &Database, protocol: Protocol, version: u64) {
+    let wtx = db.begin_write().unwrap();
+    wtx
+      .open_table(OKX_SCHEMA_VERSION)
+      .unwrap()
+      .insert(protocol.name(), version)
+      .unwrap();
+    wtx.commit().unwrap();
+  }
+
+  fn run_upgrade(db: &Database) -> Result<()> {
+    upgrade(db, Path::new("index.redb"), &EmptyChain)
+  }
+
+  #[test]
+  fn test_every_version_has_a_migration_path() {
+    for protocol in Protocol::ALL {
+      for from in UNVERSIONED..protocol.schema_version() {
+        assert!(
+          MIGRATIONS
+            .iter()
+            .any(|m| m.protocols.contains(&protocol) && m.from == from),
+          "missing {protocol} migration from {from}"
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn test_upgrade_records_current_versions() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+
+    run_upgrade(&db).unwrap();
+    run_upgrade(&db).unwrap();
+
+    let rtx = db.begin_read().unwrap();
+    for protocol in Protocol::ALL {
+      assert_eq!(
+        get_schema_version(&rtx, protocol).unwrap(),
+        Some(protocol.schema_version())
+      );
+    }
+  }
+
+  #[test]
+  fn test_upgrade_rejects_newer_schema() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    set_version(&db, Protocol::BRC20S, BRC20S_SCHEMA_VERSION + 1);
+
+    assert_eq!(
+      run_upgrade(&db).unwrap_err().to_string(),
+      format!(
+        "index at `index.redb` appears to have been built with a newer, incompatible version of ord, consider updating ord: index brc20s schema {}, ord brc20s schema {BRC20S_SCHEMA_VERSION}",
+        BRC20S_SCHEMA_VERSION + 1
+      )
+    );
+  }
+
+  #[test]
+  fn test_upgrade_rejects_unmigratable_schema() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    set_version(&db, Protocol::BRC20, 0);
+
+    assert_eq!(
+      run_upgrade(&db).unwrap_err().to_string(),
+      format!(
+        "index at `index.redb` appears to have been built with an older, incompatible version of ord, consider deleting and rebuilding the index: index brc20 schema 0, ord brc20 schema {BRC20_SCHEMA_VERSION}"
+      )
+    );
+  }
+
+  #[test]
+  fn test_check_requires_upgraded_schema() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    let path = Path::new("index.redb");
+
+    assert_eq!(
+      check(&db.begin_read().unwrap(), path).unwrap_err().to_string(),
+      format!(
+        "index at `index.redb` must be upgraded before it can be opened read-only, run `ord index run` first: index ord schema {UNVERSIONED}, ord ord schema {ORD_SCHEMA_VERSION}"
+      )
+    );
+
+    run_upgrade(&db).unwrap();
+    check(&db.begin_read().unwrap(), path).unwrap();
+  }
+
+  const TEST_CHUNKS: TableDefinition<u64, ()> = TableDefinition::new("TEST_CHUNKS");
+  const TEST_RESUMED: TableDefinition<(), ()> = TableDefinition::new("TEST_RESUMED");
+
+  // Records three chunks, failing at the second one until `TEST_RESUMED` exists.
+  fn record_chunks<'db, 'a>(
+    wtx: &'a WriteTransaction<'db>,
+    rtx: &'a ReadTransaction<'db>,
+    _: &dyn Chain,
+    _: &[Protocol],
+    progress: Option<&[u8]>,
+  ) -> Result<Option<Vec<u8>>> {
+    let chunk = progress.map_or(0, |progress| {
+      u64::from_be_bytes(progress.try_into().unwrap())
+    });
+    if chunk == 1 && rtx.open_table(TEST_RESUMED).is_err() {
+      bail!("interrupted");
+    }
+    wtx.open_table(TEST_CHUNKS)?.insert(chunk, ())?;
+    Ok((chunk < 2).then(|| (chunk + 1).to_be_bytes().to_vec()))
+  }
+
+  #[test]
+  fn test_chunked_migration_resumes_where_it_stopped() {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    set_version(&db, Protocol::BRC20, 2);
+
+    let migration = Migration {
+      protocols: &[Protocol::BRC20],
+      from: 2,
+      description: "test",
+      migrate: Migrate::Chunked(record_chunks),
+    };
+    let chunks = |db: &Database| {
+      db.begin_read()
+        .unwrap()
+        .open_table(TEST_CHUNKS)
+        .unwrap()
+        .iter()
+        .unwrap()
+        .map(|result| result.unwrap().0.value())
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+      migration
+        .run(&db, &[Protocol::BRC20], &EmptyChain)
+        .unwrap_err()
+        .to_string(),
+      "interrupted"
+    );
+    assert_eq!(chunks(&db), [0]);
+    assert_eq!(
+      get_schema_version(&db.begin_read().unwrap(), Protocol::BRC20).unwrap(),
+      Some(2)
+    );
+
+    let wtx = db.begin_write().unwrap();
+    wtx.open_table(TEST_RESUMED).unwrap();
+    wtx.commit().unwrap();
+
+    migration.run(&db, &[Protocol::BRC20], &EmptyChain).unwrap();
+    assert_eq!(chunks(&db), [0, 1, 2]);
+
+    let rtx = db.begin_read().unwrap();
+    assert_eq!(get_schema_version(&rtx, Protocol::BRC20).unwrap(), Some(3));
+    assert!(rtx
+      .open_table(OKX_MIGRATION_PROGRESS)
+      .unwrap()
+      .get(migration.progress_key().as_str())
+      .unwrap()
+      .is_none());
+  }
+}
@@ -1,6 +1,9 @@
 use bitcoin::{address, Address, Network, Script, ScriptHash};
 use serde::{Deserialize, Serialize};
-use std::fmt::{Display, Formatter};
+use std::{
+  fmt::{Display, Formatter},
+  str::FromStr,
+};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub enum ScriptKey {
@@ -18,6 +21,25 @@ impl ScriptKey {
       Err(_) => ScriptKey::ScriptHash(script.script_hash()),
     }
   }
+
+  /// Returns the hash160 of the script, which identifies the owner in binary table keys.
+  pub fn script_hash(&self) -> ScriptHash {
+    match self {
+      ScriptKey::Address(address) => address.payload.script_pubkey().script_hash(),
+      ScriptKey::ScriptHash(script_hash) => *script_hash,
+    }
+  }
+}
+
+impl FromStr for ScriptKey {
+  type Err = bitcoin::hashes::hex::Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match Address::from_str(s) {
+      Ok(address) => Ok(ScriptKey::Address(address)),
+      Err(_) => Ok(ScriptKey::ScriptHash(ScriptHash::from_str(s)?)),
+    }
+  }
 }
 
 impl Display for ScriptKey {
@@ -71,6 +93,35 @@ mod tests {
       )
     );
   }
+  #[test]
+  fn test_script_key_script_hash() {
+    let address = Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+      .unwrap()
+      .assume_checked();
+    let script_hash = address.script_pubkey().script_hash();
+    assert_eq!(ScriptKey::from_address(address).script_hash(), script_hash);
+    assert_eq!(
+      ScriptKey::ScriptHash(script_hash).script_hash(),
+      script_hash
+    );
+  }
+
+  #[test]
+  fn test_script_key_from_str() {
+    for script_key in [
+      ScriptKey::Address(Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4").unwrap()),
+      ScriptKey::ScriptHash(
+        ScriptHash::from_str("df65c8a338dce7900824e7bd18c336656ca19e57").unwrap(),
+      ),
+    ] {
+      assert_eq!(
+        ScriptKey::from_str(&script_key.to_string()).unwrap(),
+        script_key
+      );
+    }
+    assert!(ScriptKey::from_str("not a script").is_err());
+  }
+
   #[test]
   fn test_script_key_serialize() {
     let script_key =
@@ -0,0 +1,499 @@
+use {
+  super::schema::Protocol,
+  crate::{index, Result},
+  anyhow::{anyhow, bail},
+  bitcoin::hashes::{sha256, Hash, HashEngine},
+  redb::{
+    MultimapTableDefinition, MultimapTableHandle, ReadTransaction, ReadableMultimapTable,
+    ReadableTable, RedbKey, RedbValue, TableDefinition, TableHandle, WriteTransaction,
+  },
+  serde::{de::DeserializeOwned, Deserialize, Serialize},
+  std::io::{self, Read, Write},
+};
+
+const MAGIC: [u8; 8] = *b"OKXSNAP\0";
+
+/// Version of the snapshot file layout, bumped on any incompatible change.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// TableVisitor is called once for every table of the index.
+pub trait TableVisitor {
+  fn visit<K: RedbKey + 'static, V: RedbValue + 'static>(
+    &mut self,
+    table: TableDefinition<K, V>,
+  ) -> Result<()>;
+
+  fn visit_multimap<K: RedbKey + 'static, V: RedbKey + 'static>(
+    &mut self,
+    table: MultimapTableDefinition<K, V>,
+  ) -> Result<()>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+  pub version: u32,
+  pub network: String,
+  pub height: u64,
+  pub block_hash: [u8; 32],
+  /// Per-block state hash recorded by the index at `height`.
+  pub state_hash: [u8; 32],
+  pub index_sats: bool,
+  pub schema_versions: Vec<(String, u64)>,
+}
+
+impl SnapshotHeader {
+  pub fn new(
+    network: String,
+    height: u64,
+    block_hash: [u8; 32],
+    state_hash: [u8; 32],
+    index_sats: bool,
+  ) -> Self {
+    Self {
+      version: SNAPSHOT_VERSION,
+      network,
+      height,
+      block_hash,
+      state_hash,
+      index_sats,
+      schema_versions: [("index".to_string(), index::SCHEMA_VERSION)]
+        .into_iter()
+        .chain(
+          Protocol::ALL
+            .iter()
+            .map(|p| (p.name().to_string(), p.schema_version())),
+        )
+        .collect(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnapshotSummary {
+  pub header: SnapshotHeader,
+  pub entries: u64,
+  /// Hash over the content of every table, identical for any node at the same block.
+  pub content_hash: sha256::Hash,
+  /// Hash over the whole file, used to detect truncation and corruption.
+  pub checksum: sha256::Hash,
+}
+
+/// The transaction a snapshot is read from.
+///
+/// A write transaction lets the caller unwind blocks with their undo logs
+/// before exporting, and must be aborted rather than committed afterwards.
+#[derive(Clone, Copy)]
+pub enum Source<'db, 'a> {
+  Read(&'a ReadTransaction<'db>),
+  Write(&'a WriteTransaction<'db>),
+}
+
+/// Writes the magic, header, table contents, state hash and checksum to `writer`.
+pub fn export<W: Write>(
+  source: Source,
+  header: SnapshotHeader,
+  writer: W,
+) -> Result<SnapshotSummary> {
+  let mut file = HashWriter::new(writer);
+  file.write_all(&MAGIC)?;
+  bincode::serialize_into(&mut file, &header)?;
+
+  let mut exporter = Exporter {
+    source,
+    writer: HashWriter::new(&mut file),
+    entries: 0,
+  };
+  index::visit_tables(&mut exporter)?;
+  let entries = exporter.entries;
+  let content_hash = sha256::Hash::from_engine(exporter.writer.engine);
+
+  file.write_all(content_hash.as_byte_array())?;
+  let checksum = sha256::Hash::from_engine(file.engine.clone());
+  file.inner.write_all(checksum.as_byte_array())?;
+  file.inner.flush()?;
+
+  Ok(SnapshotSummary {
+    header,
+    entries,
+    content_hash,
+    checksum,
+  })
+}
+
+/// Replaces every table of the index with the content of a snapshot.
+///
+/// `check` is called with the header before any table is touched. Nothing is
+/// committed by this function, so dropping `wtx` on error discards the import.
+pub fn import<R: Read>(
+  wtx: &WriteTransaction,
+  reader: R,
+  check: impl FnOnce(&SnapshotHeader) -> Result<()>,
+) -> Result<SnapshotSummary> {
+  let mut file = HashReader::new(reader);
+  let mut magic = [0; MAGIC.len()];
+  file.read_exact(&mut magic)?;
+  if magic != MAGIC {
+    bail!("not a snapshot file");
+  }
+
+  let header: SnapshotHeader = bincode::deserialize_from(&mut file)?;
+  if header.version != SNAPSHOT_VERSION {
+    bail!(
+      "unsupported snapshot version {}, expected {SNAPSHOT_VERSION}",
+      header.version
+    );
+  }
+  check(&header)?;
+
+  let mut importer = Importer {
+    wtx,
+    reader: HashReader::new(&mut file),
+    entries: 0,
+  };
+  index::visit_tables(&mut importer)?;
+  let entries = importer.entries;
+  let content_hash = sha256::Hash::from_engine(importer.reader.engine);
+
+  let mut stored = [0; 32];
+  file.read_exact(&mut stored)?;
+  if stored != content_hash.to_byte_array() {
+    bail!("snapshot content hash mismatch");
+  }
+  let checksum = sha256::Hash::from_engine(file.engine.clone());
+  file.inner.read_exact(&mut stored)?;
+  if stored != checksum.to_byte_array() {
+    bail!("snapshot checksum mismatch, the file may be corrupted");
+  }
+
+  Ok(SnapshotSummary {
+    header,
+    entries,
+    content_hash,
+    checksum,
+  })
+}
+
+struct Exporter<'db, 'a, W: Write> {
+  source: Source<'db, 'a>,
+  writer: HashWriter<W>,
+  entries: u64,
+}
+
+impl<'db, 'a, W: Write> TableVisitor for Exporter<'db, 'a, W> {
+  fn visit<K: RedbKey + 'static, V: RedbValue + 'static>(
+    &mut self,
+    table: TableDefinition<K, V>,
+  ) -> Result<()> {
+    bincode::serialize_into(&mut self.writer, table.name())?;
+    match self.source {
+      Source::Read(rtx) => match rtx.open_table(table) {
+        Ok(table) => self.write_table(&table),
+        Err(redb::TableError::TableDoesNotExist(_)) => self.write_missing(),
+        Err(e) => Err(e.into()),
+      },
+      // Opening a table in a write transaction creates it, so check first.
+      Source::Write(wtx) => {
+        if wtx
+          .list_tables()?
+          .any(|handle| handle.name() == table.name())
+        {
+          self.write_table(&wtx.open_table(table)?)
+        } else {
+          self.write_missing()
+        }
+      }
+    }
+  }
+
+  fn visit_multimap<K: RedbKey + 'static, V: RedbKey + 'static>(
+    &mut self,
+    table: MultimapTableDefinition<K, V>,
+  ) -> Result<()> {
+    bincode::serialize_into(&mut self.writer, table.name())?;
+    match self.source {
+      Source::Read(rtx) => match rtx.open_multimap_table(table) {
+        Ok(table) => self.write_multimap_table(&table),
+        Err(redb::TableError::TableDoesNotExist(_)) => self.write_missing(),
+        Err(e) => Err(e.into()),
+      },
+      Source::Write(wtx) => {
+        if wtx
+          .list_multimap_tables()?
+          .any(|handle| handle.name() == table.name())
+        {
+          self.write_multimap_table(&wtx.open_multimap_table(table)?)
+        } else {
+          self.write_missing()
+        }
+      }
+    }
+  }
+}
+
+// Each table is written as its name and whether it exists, followed by its
+// entries as `Some((key, value))` and a terminating `None`.
+impl<'db, 'a, W: Write> Exporter<'db, 'a, W> {
+  fn write_table<K: RedbKey + 'static, V: RedbValue + 'static>(
+    &mut self,
+    table: &impl ReadableTable<K, V>,
+  ) -> Result<()> {
+    bincode::serialize_into(&mut self.writer, &true)?;
+    for result in table.iter()? {
+      let (key, value) = result?;
+      self.write_entry(K::as_bytes(&key.value()), V::as_bytes(&value.value()))?;
+    }
+    self.write_end()
+  }
+
+  fn write_multimap_table<K: RedbKey + 'static, V: RedbKey + 'static>(
+    &mut self,
+    table: &impl ReadableMultimapTable<K, V>,
+  ) -> Result<()> {
+    bincode::serialize_into(&mut self.writer, &true)?;
+    for result in table.iter()? {
+      let (key, values) = result?;
+      for value in values {
+        self.write_entry(K::as_bytes(&key.value()), V::as_bytes(&value?.value()))?;
+      }
+    }
+    self.write_end()
+  }
+
+  fn write_missing(&mut self) -> Result<()> {
+    bincode::serialize_into(&mut self.writer, &false)?;
+    Ok(())
+  }
+
+  fn write_entry(&mut self, key: impl AsRef<[u8]>, value: impl AsRef<[u8]>) -> Result<()> {
+    bincode::serialize_into(&mut self.writer, &Some((key.as_ref(), value.as_ref())))?;
+    self.entries += 1;
+    Ok(())
+  }
+
+  fn write_end(&mut self) -> Result<()> {
+    bincode::serialize_into(&mut self.writer, &None::<(&[u8], &[u8])>)?;
+    Ok(())
+  }
+}
+
+struct Importer<'db, 'a, R: Read> {
+  wtx: &'a WriteTransaction<'db>,
+  reader: HashReader<R>,
+  entries: u64,
+}
+
+impl<'db, 'a, R: Read> Importer<'db, 'a, R> {
+  fn read<T: DeserializeOwned>(&mut self) -> Result<T> {
+    Ok(bincode::deserialize_from(&mut self.reader)?)
+  }
+
+  /// Reads the name of the next table and whether it exists in the snapshot.
+  fn read_table(&mut self, expected: &str) -> Result<bool> {
+    let name: String = self.read()?;
+    if name != expected {
+      return Err(anyhow!(
+        "unexpected table {name} in snapshot, expected {expected}"
+      ));
+    }
+    self.read()
+  }
+
+  fn read_entry(&mut self) -> Result<Option<(Vec<u8>, Vec<u8>)>> {
+    let entry: Option<(Vec<u8>, Vec<u8>)> = self.read()?;
+    if entry.is_some() {
+      self.entries += 1;
+    }
+    Ok(entry)
+  }
+}
+
+impl<'db, 'a, R: Read> TableVisitor for Importer<'db, 'a, R> {
+  fn visit<K: RedbKey + 'static, V: RedbValue + 'static>(
+    &mut self,
+    table: TableDefinition<K, V>,
+  ) -> Result<()> {
+    let exists = self.read_table(table.name())?;
+    self.wtx.delete_table(table)?;
+    if !exists {
+      return Ok(());
+    }
+
+    let mut writer = self.wtx.open_table(table)?;
+    while let Some((key, value)) = self.read_entry()? {
+      writer.insert(K::from_bytes(&key), V::from_bytes(&value))?;
+    }
+    Ok(())
+  }
+
+  fn visit_multimap<K: RedbKey + 'static, V: RedbKey + 'static>(
+    &mut self,
+    table: MultimapTableDefinition<K, V>,
+  ) -> Result<()> {
+    let exists = self.read_table(table.name())?;
+    self.wtx.delete_multimap_table(table)?;
+    if !exists {
+      return Ok(());
+    }
+
+    let mut writer = self.wtx.open_multimap_table(table)?;
+    while let Some((key, value)) = self.read_entry()? {
+      writer.insert(K::from_bytes(&key), V::from_bytes(&value))?;
+    }
+    Ok(())
+  }
+}
+
+struct HashWriter<W> {
+  inner: W,
+  engine: sha256::HashEngine,
+}
+
+impl<W> HashWriter<W> {
+  fn new(inner: W) -> Self {
+    Self {
+      inner,
+      engine: sha256::Hash::engine(),
+    }
+  }
+}
+
+impl<W: Write> Write for HashWriter<W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let n = self.inner.write(buf)?;
+    self.engine.input(&buf[..n]);
+    Ok(n)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.inner.flush()
+  }
+}
+
+struct HashReader<R> {
+  inner: R,
+  engine: sha256::HashEngine,
+}
+
+impl<R> HashReader<R> {
+  fn new(inner: R) -> Self {
+    Self {
+      inner,
+      engine: sha256::Hash::engine(),
+    }
+  }
+}
+
+impl<R: Read> Read for HashReader<R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let n = self.inner.read(buf)?;
+    self.engine.input(&buf[..n]);
+    Ok(n)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::okx::datastore::{
+    brc20::{self, Balance, DataStoreReadOnly, DataStoreReadWrite, Tick},
+    schema, ScriptKey,
+  };
+  use bitcoin::Address;
+  use redb::Database;
+  use std::{path::Path, str::FromStr};
+  use tempfile::NamedTempFile;
+
+  fn create_db() -> (NamedTempFile, Database) {
+    let dbfile = NamedTempFile::new().unwrap();
+    let db = Database::create(dbfile.path()).unwrap();
+    schema::upgrade(&db, Path::new("index.redb"), &schema::EmptyChain).unwrap();
+    (dbfile, db)
+  }
+
+  fn export_db(db: &Database) -> (Vec<u8>, SnapshotSummary) {
+    let mut buf = Vec::new();
+    let rtx = db.begin_read().unwrap();
+    let summary = export(
+      Source::Read(&rtx),
+      SnapshotHeader::new("regtest".into(), 10, [7; 32], [8; 32], false),
+      &mut buf,
+    )
+    .unwrap();
+    (buf, summary)
+  }
+
+  #[test]
+  fn test_export_import_roundtrip() {
+    let (_source_file, source) = create_db();
+    let script = ScriptKey::from_address(
+      Address::from_str("bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4")
+        .unwrap()
+        .assume_checked(),
+    );
+    let balance = Balance {
+      tick: Tick::from_str("abcd").unwrap(),
+      overall_balance: 10,
+      transferable_balance: 4,
+    };
+    let wtx = source.begin_write().unwrap();
+    brc20::redb::DataStore::new(&wtx)
+      .update_token_balance(&script, balance.clone())
+      .unwrap();
+    wtx.commit().unwrap();
+
+    let (buf, exported) = export_db(&source);
+    // The balance and its owner.
+    assert_eq!(exported.entries, 2);
+
+    let (_target_file, target) = create_db();
+    let wtx = target.begin_write().unwrap();
+    let imported = import(&wtx, buf.as_slice(), |header| {
+      assert_eq!(header.height, 10);
+      Ok(())
+    })
+    .unwrap();
+    wtx.commit().unwrap();
+    assert_eq!(imported, exported);
+
+    let rtx = target.begin_read().unwrap();
+    assert_eq!(
+      brc20::redb::DataStoreReader::new(&rtx)
+        .get_balances(&script)
+        .unwrap(),
+      vec![balance]
+    );
+    assert_eq!(export_db(&target).1.content_hash, exported.content_hash);
+  }
+
+  #[test]
+  fn test_import_rejects_corrupted_file() {
+    let (_source_file, source) = create_db();
+    let (mut buf, _) = export_db(&source);
+    let last = buf.len() - 1;
+    buf[last] ^= 1;
+
+    let (_target_file, target) = create_db();
+    let wtx = target.begin_write().unwrap();
+    assert_eq!(
+      import(&wtx, buf.as_slice(), |_| Ok(()))
+        .unwrap_err()
+        .to_string(),
+      "snapshot checksum mismatch, the file may be corrupted"
+    );
+  }
+
+  #[test]
+  fn test_import_runs_header_check_first() {
+    let (_source_file, source) = create_db();
+    let (buf, _) = export_db(&source);
+
+    let (_target_file, target) = create_db();
+    let wtx = target.begin_write().unwrap();
+    assert_eq!(
+      import(&wtx, buf.as_slice(), |_| Err(anyhow!("wrong chain")))
+        .unwrap_err()
+        .to_string(),
+      "wrong chain"
+    );
+  }
+}
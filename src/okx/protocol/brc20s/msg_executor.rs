@@ -1058,9 +1058,11 @@ mod tests {
   use super::super::*;
   use super::*;
   use crate::index::INSCRIPTION_ID_TO_INSCRIPTION_ENTRY;
+  use crate::okx::datastore::brc20::memory as brc20_memory;
   use crate::okx::datastore::brc20::redb as brc20_db;
   use crate::okx::datastore::brc20::DataStoreReadWrite;
   use crate::okx::datastore::brc20::{Balance as BRC20Balance, TokenInfo};
+  use crate::okx::datastore::brc20s::memory as brc20s_memory;
   use crate::okx::datastore::brc20s::redb as brc20s_db;
   use crate::okx::datastore::brc20s::DataStoreReadOnly;
   use crate::okx::datastore::brc20s::DataStoreReadWrite as BRC20SDataStoreReadWrite;
@@ -1234,12 +1236,8 @@ mod tests {
 
   #[test]
   fn test_process_deploy() {
-    let dbfile = NamedTempFile::new().unwrap();
-    let db = Database::create(dbfile.path()).unwrap();
-    let wtx = db.begin_write().unwrap();
-
-    let brc20_data_store = brc20_db::DataStore::new(&wtx);
-    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+    let brc20_data_store = brc20_memory::MemoryStore::new();
+    let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
     let deploy = Deploy {
       pool_type: "pool".to_string(),
@@ -1403,12 +1401,8 @@ mod tests {
 
   #[test]
   fn test_process_deploy_common() {
-    let dbfile = NamedTempFile::new().unwrap();
-    let db = Database::create(dbfile.path()).unwrap();
-    let wtx = db.begin_write().unwrap();
-
-    let brc20_data_store = brc20_db::DataStore::new(&wtx);
-    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+    let brc20_data_store = brc20_memory::MemoryStore::new();
+    let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
     let deploy = Deploy {
       pool_type: "pool".to_string(),
@@ -2992,11 +2986,8 @@ mod tests {
 
   #[test]
   fn test_process_stake() {
-    let dbfile = NamedTempFile::new().unwrap();
-    let db = Database::create(dbfile.path()).unwrap();
-    let wtx = db.begin_write().unwrap();
-    let brc20_data_store = brc20_db::DataStore::new(&wtx);
-    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+    let brc20_data_store = brc20_memory::MemoryStore::new();
+    let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
     let deploy = Deploy {
       pool_type: "pool".to_string(),
@@ -3632,11 +3623,8 @@ mod tests {
 
   #[test]
   fn test_process_unstake() {
-    let dbfile = NamedTempFile::new().unwrap();
-    let db = Database::create(dbfile.path()).unwrap();
-    let wtx = db.begin_write().unwrap();
-    let brc20_data_store = brc20_db::DataStore::new(&wtx);
-    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+    let brc20_data_store = brc20_memory::MemoryStore::new();
+    let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
     let deploy = Deploy {
       pool_type: "pool".to_string(),
@@ -3857,11 +3845,8 @@ mod tests {
 
   #[test]
   fn test_process_unstake_common() {
-    let dbfile = NamedTempFile::new().unwrap();
-    let db = Database::create(dbfile.path()).unwrap();
-    let wtx = db.begin_write().unwrap();
-    let brc20_data_store = brc20_db::DataStore::new(&wtx);
-    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+    let brc20_data_store = brc20_memory::MemoryStore::new();
+    let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
     let deploy = Deploy {
       pool_type: "pool".to_string(),
@@ -4143,11 +4128,8 @@ mod tests {
 
   #[test]
   fn test_process_passive_unstake() {
-    let dbfile = NamedTempFile::new().unwrap();
-    let db = Database::create(dbfile.path()).unwrap();
-    let wtx = db.begin_write().unwrap();
-    let brc20_data_store = brc20_db::DataStore::new(&wtx);
-    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+    let brc20_data_store = brc20_memory::MemoryStore::new();
+    let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
     let deploy = Deploy {
       pool_type: "pool".to_string(),
@@ -4377,11 +4359,8 @@ mod tests {
 
   #[test]
   fn test_process_passive_error() {
-    let dbfile = NamedTempFile::new().unwrap();
-    let db = Database::create(dbfile.path()).unwrap();
-    let wtx = db.begin_write().unwrap();
-    let brc20_data_store = brc20_db::DataStore::new(&wtx);
-    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+    let brc20_data_store = brc20_memory::MemoryStore::new();
+    let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
     let deploy = Deploy {
       pool_type: "pool".to_string(),
@@ -4667,12 +4646,8 @@ mod tests {
 
   #[test]
   fn test_process_deploy_most() {
-    let dbfile = NamedTempFile::new().unwrap();
-    let db = Database::create(dbfile.path()).unwrap();
-    let wtx = db.begin_write().unwrap();
-
-    let brc20_data_store = brc20_db::DataStore::new(&wtx);
-    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+    let brc20_data_store = brc20_memory::MemoryStore::new();
+    let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
     let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
     let (deploy, msg) = mock_deploy_msg(
@@ -4834,11 +4809,8 @@ mod tests {
 
   #[test]
   fn test_mint() {
-    let dbfile = NamedTempFile::new().unwrap();
-    let db = Database::create(dbfile.path()).unwrap();
-    let wtx = db.begin_write().unwrap();
-    let brc20_data_store = brc20_db::DataStore::new(&wtx);
-    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+    let brc20_data_store = brc20_memory::MemoryStore::new();
+    let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
     // deploy brc20
     let script = ScriptKey::from_address(
@@ -5249,11 +5221,8 @@ mod tests {
 
   #[test]
   fn test_transfer() {
-    let db_file = NamedTempFile::new().unwrap();
-    let db = Database::create(db_file.path()).unwrap();
-    let wtx = db.begin_write().unwrap();
-    let brc20_data_store = brc20_db::DataStore::new(&wtx);
-    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+    let brc20_data_store = brc20_memory::MemoryStore::new();
+    let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
     // deploy brc20
     let script = ScriptKey::from_address(
@@ -5956,12 +5925,8 @@ mod tests {
 
   #[test]
   fn test_process_stake_most() {
-    let dbfile = NamedTempFile::new().unwrap();
-    let db = Database::create(dbfile.path()).unwrap();
-    let wtx = db.begin_write().unwrap();
-
-    let brc20_data_store = brc20_db::DataStore::new(&wtx);
-    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+    let brc20_data_store = brc20_memory::MemoryStore::new();
+    let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
     let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
     let new_addr = "bc1pvk535u5eedhsx75r7mfvdru7t0kcr36mf9wuku7k68stc0ncss8qwzeahv";
@@ -6231,12 +6196,8 @@ mod tests {
 
   #[test]
   fn test_process_unstake_most() {
-    let dbfile = NamedTempFile::new().unwrap();
-    let db = Database::create(dbfile.path()).unwrap();
-    let wtx = db.begin_write().unwrap();
-
-    let brc20_data_store = brc20_db::DataStore::new(&wtx);
-    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+    let brc20_data_store = brc20_memory::MemoryStore::new();
+    let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
     let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
     let new_addr = "bc1pvk535u5eedhsx75r7mfvdru7t0kcr36mf9wuku7k68stc0ncss8qwzeahv";
@@ -6530,12 +6491,8 @@ mod tests {
 
   #[test]
   fn test_process_passive_unstake_normal() {
-    let dbfile = NamedTempFile::new().unwrap();
-    let db = Database::create(dbfile.path()).unwrap();
-    let wtx = db.begin_write().unwrap();
-
-    let brc20_data_store = brc20_db::DataStore::new(&wtx);
-    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+    let brc20_data_store = brc20_memory::MemoryStore::new();
+    let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
     let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
     let new_addr = "bc1pvk535u5eedhsx75r7mfvdru7t0kcr36mf9wuku7k68stc0ncss8qwzeahv";
@@ -7078,12 +7035,8 @@ mod tests {
   fn test_process_passive_unstake_most() {
     // 1-only(50) 2-share(50) 3-only(50) 4-share(50) transfer 50 no passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7121,12 +7074,8 @@ mod tests {
 
     // 1-only(50) 2-share(50) 3-only(50) 4-share(50) transfer 100  passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7176,12 +7125,8 @@ mod tests {
 
     // 1-only(50) 2-share(50) 3-only(50) 4-share(50) transfer 150  passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7235,12 +7180,8 @@ mod tests {
 
     // 1-only(50) 2-share(50) 3-only(50) 4-share(50) transfer 200  passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7298,12 +7239,8 @@ mod tests {
 
     // 1-only(50) 2-share(50) 3-share(50) 4-only(50) transfer 50 no passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7341,12 +7278,8 @@ mod tests {
 
     // 1-only(50) 2-share(50) 3-share(50) 4-only(50)  transfer 100  passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7390,12 +7323,8 @@ mod tests {
 
     // 1-only(50) 2-share(50) 3-share(50) 4-only(50)  transfer 150  passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7449,12 +7378,8 @@ mod tests {
 
     // 1-only(50) 2-share(50) 3-share(50) 4-only(50)  transfer 200  passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7512,12 +7437,8 @@ mod tests {
 
     // 1-share(50) 2-only(50) 3-only(50) 4-share(50) transfer 50 no passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7555,12 +7476,8 @@ mod tests {
 
     // 1-share(50) 2-only(50) 3-only(50) 4-share(50)  transfer 100  passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7610,12 +7527,8 @@ mod tests {
 
     // 1-share(50) 2-only(50) 3-only(50) 4-share(50)  transfer 150  passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7669,12 +7582,8 @@ mod tests {
 
     // 1-share(50) 2-only(50) 3-only(50) 4-share(50)  transfer 200  passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7732,12 +7641,8 @@ mod tests {
 
     // 1-only(50) 2-only(50) 3-only(50) 4-only(50) transfer 50 no passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7781,12 +7686,8 @@ mod tests {
 
     // 1-only(50) 2-only(50) 3-only(50) 4-only(50)  transfer 100  passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7836,12 +7737,8 @@ mod tests {
 
     // 1-only(50) 2-only(50) 3-only(50) 4-only(50)  transfer 150  passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7895,12 +7792,8 @@ mod tests {
 
     // 1-only(50) 2-only(50) 3-only(50) 4-only(50)  transfer 200  passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -7958,12 +7851,8 @@ mod tests {
 
     // 1-share(50) 2-share(50) 3-share(50) 4-share(50) transfer 50 no passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -8001,12 +7890,8 @@ mod tests {
 
     // 1-share(50) 2-share(50) 3-share(50) 4-share(50)  transfer 100  passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -8044,12 +7929,8 @@ mod tests {
 
     // 1-share(50) 2-share(50) 3-share(50) 4-share(50)  transfer 150  passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -8087,12 +7968,8 @@ mod tests {
 
     // 1-share(50) 2-share(50) 3-share(50) 4-share(50)  transfer 200  passwithdraw
     {
-      let dbfile = NamedTempFile::new().unwrap();
-      let db = Database::create(dbfile.path()).unwrap();
-      let wtx = db.begin_write().unwrap();
-
-      let brc20_data_store = brc20_db::DataStore::new(&wtx);
-      let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+      let brc20_data_store = brc20_memory::MemoryStore::new();
+      let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
       let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
 
@@ -8151,12 +8028,8 @@ mod tests {
 
   #[test]
   fn test_process_passive_for_bench() {
-    let dbfile = NamedTempFile::new().unwrap();
-    let db = Database::create(dbfile.path()).unwrap();
-    let wtx = db.begin_write().unwrap();
-
-    let brc20_data_store = brc20_db::DataStore::new(&wtx);
-    let brc20s_data_store = brc20s_db::DataStore::new(&wtx);
+    let brc20_data_store = brc20_memory::MemoryStore::new();
+    let brc20s_data_store = brc20s_memory::MemoryStore::new();
 
     let addr = "bc1pgllnmtxs0g058qz7c6qgaqq4qknwrqj9z7rqn9e2dzhmcfmhlu4sfadf5e";
     let new_addr = "bc1pvk535u5eedhsx75r7mfvdru7t0kcr36mf9wuku7k68stc0ncss8qwzeahv";
@@ -1,14 +1,23 @@
 use {
   super::*,
   crate::{
-    index::BlockData,
+    metrics::{BlockStage, Metrics},
     okx::{
-      datastore::{ord::operation::InscriptionOp, StateRWriter},
+      datastore::{
+        brc20::{DataStoreReadOnly as _, DataStoreReadWrite as _},
+        brc20s::{DataStoreReadOnly as _, DataStoreReadWrite as _},
+        ord::operation::InscriptionOp,
+        StateRWriter,
+      },
       protocol::ord as ord_proto,
     },
-    Instant, Result,
+    Duration, Instant, Result,
+  },
+  anyhow::anyhow,
+  bitcoin::{
+    hashes::{sha256, Hash},
+    Transaction, Txid,
   },
-  bitcoin::Txid,
   bitcoincore_rpc::Client,
   std::collections::HashMap,
 };
@@ -16,32 +25,43 @@ use {
 pub struct ProtocolManager<'a, RW: StateRWriter> {
   state_store: &'a RW,
   config: &'a ProtocolConfig,
+  metrics: &'a Metrics,
   call_man: CallManager<'a, RW>,
   resolve_man: MsgResolveManager<'a, RW>,
 }
 
 impl<'a, RW: StateRWriter> ProtocolManager<'a, RW> {
   // Need three datastore, and they're all in the same write transaction.
-  pub fn new(client: &'a Client, state_store: &'a RW, config: &'a ProtocolConfig) -> Self {
+  pub fn new(
+    client: &'a Client,
+    state_store: &'a RW,
+    config: &'a ProtocolConfig,
+    metrics: &'a Metrics,
+  ) -> Self {
     Self {
       state_store,
       config,
+      metrics,
       call_man: CallManager::new(state_store),
       resolve_man: MsgResolveManager::new(client, state_store, config),
     }
   }
 
+  /// Indexes the protocol messages of a block and returns a digest of the
+  /// BRC20 and BRC20S receipts it produced, in transaction order.
   pub(crate) fn index_block(
     &self,
     context: BlockContext,
-    block: &BlockData,
+    txdata: &[(Transaction, Txid)],
     operations: HashMap<Txid, Vec<InscriptionOp>>,
-  ) -> Result {
+  ) -> Result<sha256::Hash> {
     let start = Instant::now();
+    let mut receipts = sha256::Hash::engine();
     let mut inscriptions_size = 0;
     let mut messages_size = 0;
-    // skip the coinbase transaction.
-    for (tx, txid) in block.txdata.iter() {
+    let (mut resolve_time, mut execute_time) = (Duration::ZERO, Duration::ZERO);
+    let (mut brc20_messages, mut brc20s_messages) = (0, 0);
+    for (position, (tx, txid)) in txdata.iter().enumerate() {
       // skip coinbase transaction.
       if tx
         .input
@@ -63,13 +83,50 @@ impl<'a, RW: StateRWriter> ProtocolManager<'a, RW> {
         }
 
         // Resolve and execute messages.
+        let resolve_start = Instant::now();
         let messages = self
           .resolve_man
           .resolve_message(context, tx, tx_operations)?;
+        resolve_time += resolve_start.elapsed();
+
+        let execute_start = Instant::now();
         for msg in messages.iter() {
           self.call_man.execute_message(context, msg)?;
+          match msg {
+            Message::BRC20(_) => brc20_messages += 1,
+            Message::BRC20S(_) => brc20s_messages += 1,
+          }
         }
+        execute_time += execute_start.elapsed();
         messages_size += messages.len();
+
+        if !messages.is_empty() {
+          let position = u32::try_from(position).unwrap();
+
+          let brc20_receipts = self
+            .state_store
+            .brc20()
+            .get_transaction_receipts(txid)
+            .map_err(|e| anyhow!("failed to get brc20 receipts from state! error: {e}"))?;
+          bincode::serialize_into(&mut receipts, &brc20_receipts)?;
+          self
+            .state_store
+            .brc20()
+            .save_receipt_history(context.blockheight, position, txid, &brc20_receipts)
+            .map_err(|e| anyhow!("failed to save brc20 receipt history to state! error: {e}"))?;
+
+          let brc20s_receipts = self
+            .state_store
+            .brc20s()
+            .get_transaction_receipts(txid)
+            .map_err(|e| anyhow!("failed to get brc20s receipts from state! error: {e}"))?;
+          bincode::serialize_into(&mut receipts, &brc20s_receipts)?;
+          self
+            .state_store
+            .brc20s()
+            .save_receipt_history(context.blockheight, position, txid, &brc20s_receipts)
+            .map_err(|e| anyhow!("failed to save brc20s receipt history to state! error: {e}"))?;
+        }
       }
     }
     let mut bitmap_count = 0;
@@ -77,6 +134,17 @@ impl<'a, RW: StateRWriter> ProtocolManager<'a, RW> {
       bitmap_count = ord_proto::bitmap::index_bitmap(self.state_store.ord(), context, &operations)?;
     }
 
+    self
+      .metrics
+      .observe_block_stage(BlockStage::ProtocolResolve, resolve_time);
+    self
+      .metrics
+      .observe_block_stage(BlockStage::ProtocolExecute, execute_time);
+    self.metrics.add_protocol_messages("brc20", brc20_messages);
+    self
+      .metrics
+      .add_protocol_messages("brc20s", brc20s_messages);
+
     log::info!(
       "Protocol Manager indexed block {} with ord inscriptions {}, messages {}, bitmap {} in {} ms",
       context.blockheight,
@@ -85,6 +153,6 @@ impl<'a, RW: StateRWriter> ProtocolManager<'a, RW> {
       bitmap_count,
       (Instant::now() - start).as_millis(),
     );
-    Ok(())
+    Ok(sha256::Hash::from_engine(receipts))
   }
 }
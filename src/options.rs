@@ -27,6 +27,22 @@ pub(crate) struct Options {
     help = "Use <CHAIN>."
   )]
   pub(crate) chain_argument: Chain,
+  #[arg(
+    long,
+    default_value = "200",
+    help = "Commit the index at least every <COMMIT_INTERVAL> blocks."
+  )]
+  pub(crate) commit_interval: u64,
+  #[arg(
+    long,
+    help = "Also commit the index once <COMMIT_INTERVAL_SECONDS> have passed since the last commit."
+  )]
+  pub(crate) commit_interval_seconds: Option<u64>,
+  #[arg(
+    long,
+    help = "Also commit the index once <COMMIT_CACHE_ENTRIES> sat range entries are held in memory."
+  )]
+  pub(crate) commit_cache_entries: Option<usize>,
   #[arg(long, help = "Load configuration from <CONFIG>.")]
   pub(crate) config: Option<PathBuf>,
   #[arg(long, help = "Load configuration from <CONFIG_DIR>.")]
@@ -40,6 +56,12 @@ pub(crate) struct Options {
     help = "Set index cache to <DB_CACHE_SIZE> bytes. By default takes 1/4 of available RAM."
   )]
   pub(crate) db_cache_size: Option<usize>,
+  #[arg(
+    long,
+    default_value = "1",
+    help = "Fetch up to <FETCH_CONCURRENCY> blocks in parallel."
+  )]
+  pub(crate) fetch_concurrency: usize,
   #[arg(
     long,
     help = "Don't look for inscriptions below <FIRST_INSCRIPTION_HEIGHT>."
@@ -51,6 +73,23 @@ pub(crate) struct Options {
   pub(crate) index: Option<PathBuf>,
   #[arg(long, help = "Track location of all satoshis.")]
   pub(crate) index_sats: bool,
+  #[arg(
+    long,
+    default_value = "100",
+    help = "Keep undo logs to recover from reorgs up to <MAX_REORG_DEPTH> blocks deep. 0 disables them."
+  )]
+  pub(crate) max_reorg_depth: u64,
+  #[arg(
+    long,
+    default_value = "32",
+    help = "Keep up to <PREFETCH_DEPTH> fetched blocks queued for indexing."
+  )]
+  pub(crate) prefetch_depth: usize,
+  #[arg(
+    long,
+    help = "Read blocks from the blk*.dat files in <BITCOIN_DATA_DIR> instead of over RPC, except near the chain tip."
+  )]
+  pub(crate) read_block_files: bool,
   #[arg(long, short, help = "Use regtest. Equivalent to `--chain regtest`.")]
   pub(crate) regtest: bool,
   #[arg(long, help = "Connect to Bitcoin Core RPC at <RPC_URL>.")]
@@ -173,21 +212,37 @@ impl Options {
       return Ok(cookie_file.clone());
     }
 
+    Ok(
+      self
+        .bitcoin_chain_dir()
+        .context("failed to get cookie file path")?
+        .join(".cookie"),
+    )
+  }
+
+  pub(crate) fn bitcoin_blocks_dir(&self) -> Result<PathBuf> {
+    Ok(
+      self
+        .bitcoin_chain_dir()
+        .context("failed to get block files path")?
+        .join("blocks"),
+    )
+  }
+
+  fn bitcoin_chain_dir(&self) -> Result<PathBuf> {
     let path = if let Some(bitcoin_data_dir) = &self.bitcoin_data_dir {
       bitcoin_data_dir.clone()
     } else if cfg!(target_os = "linux") {
       dirs::home_dir()
-        .ok_or_else(|| anyhow!("failed to get cookie file path: could not get home dir"))?
+        .ok_or_else(|| anyhow!("could not get home dir"))?
         .join(".bitcoin")
     } else {
       dirs::data_dir()
-        .ok_or_else(|| anyhow!("failed to get cookie file path: could not get data dir"))?
+        .ok_or_else(|| anyhow!("could not get data dir"))?
         .join("Bitcoin")
     };
 
-    let path = self.chain().join_with_data_dir(&path);
-
-    Ok(path.join(".cookie"))
+    Ok(self.chain().join_with_data_dir(&path))
   }
 
   pub(crate) fn data_dir(&self) -> Result<PathBuf> {
@@ -316,7 +371,6 @@ impl Options {
     Ok(client)
   }
 
-  #[allow(unused)]
   pub(crate) fn bitcoin_rpc_client_for_wallet_command(&self, create: bool) -> Result<Client> {
     let client = self.bitcoin_rpc_client()?;
 
@@ -492,6 +546,31 @@ mod tests {
     }));
   }
 
+  #[test]
+  fn bitcoin_blocks_dir_defaults_to_bitcoin_data_dir() {
+    let arguments = Arguments::try_parse_from([
+      "ord",
+      "--bitcoin-data-dir=foo",
+      "--chain=testnet",
+      "index",
+      "run",
+    ])
+    .unwrap();
+
+    let blocks_dir = arguments
+      .options
+      .bitcoin_blocks_dir()
+      .unwrap()
+      .display()
+      .to_string();
+
+    assert!(blocks_dir.ends_with(if cfg!(windows) {
+      r"foo\testnet3\blocks"
+    } else {
+      "foo/testnet3/blocks"
+    }));
+  }
+
   #[test]
   fn mainnet_data_dir() {
     let data_dir = Arguments::try_parse_from(["ord", "index", "run"])
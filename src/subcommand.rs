@@ -1,28 +1,49 @@
 use super::*;
 
+mod brc20;
+mod brc20s;
 pub mod decode;
 mod index;
+mod preview;
 mod server;
 pub mod wallet;
 
 #[derive(Debug, Parser)]
 pub(crate) enum Subcommand {
+  #[command(subcommand, about = "Query BRC20 state from the index")]
+  Brc20(brc20::BRC20Subcommand),
+  #[command(subcommand, about = "Query BRC20S state from the index")]
+  Brc20s(brc20s::BRC20SSubcommand),
+  #[command(about = "Decode a transaction and the BRC20 and BRC20S operations it inscribes")]
+  Decode(decode::Decode),
   #[command(subcommand, about = "Index commands")]
   Index(index::IndexSubcommand),
+  #[command(
+    about = "Preview the inscription, BRC20 and BRC20S effects of a PSBT or unsigned \
+    transaction"
+  )]
+  Preview(preview::Preview),
   #[command(about = "Run the explorer server")]
   Server(server::Server),
+  #[command(subcommand, about = "Wallet commands")]
+  Wallet(wallet::Wallet),
 }
 
 impl Subcommand {
   pub(crate) fn run(self, options: Options) -> SubcommandResult {
     match self {
+      Self::Brc20(brc20) => brc20.run(options),
+      Self::Brc20s(brc20s) => brc20s.run(options),
+      Self::Decode(decode) => decode.run(),
       Self::Index(index) => index.run(options),
+      Self::Preview(preview) => preview.run(options),
       Self::Server(server) => {
         let index = Arc::new(Index::open(&options)?);
         let handle = axum_server::Handle::new();
         LISTENERS.lock().unwrap().push(handle.clone());
         server.run(options, index, handle)
       }
+      Self::Wallet(wallet) => wallet.run(options),
     }
   }
 }
@@ -0,0 +1,132 @@
+use {
+  super::*,
+  crate::okx::datastore::{brc20, page::PageRequest},
+  bitcoin::address::NetworkUnchecked,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) enum BRC20Subcommand {
+  #[command(about = "Print the BRC20 balances of an address")]
+  Balance(Balance),
+  #[command(about = "Print BRC20 tick info")]
+  Tick(Tick),
+  #[command(about = "Print the transferable BRC20 inscriptions of an address")]
+  Transferable(Transferable),
+  #[command(about = "Print the BRC20 events of a transaction")]
+  Events(Events),
+}
+
+impl BRC20Subcommand {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let index = Index::open_offline(&options)?;
+
+    match self {
+      Self::Balance(balance) => balance.run(&options, &index),
+      Self::Tick(tick) => tick.run(&index),
+      Self::Transferable(transferable) => transferable.run(&options, &index),
+      Self::Events(events) => events.run(&index),
+    }
+  }
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Balance {
+  #[arg(help = "Print balances of <ADDRESS>.")]
+  address: Address<NetworkUnchecked>,
+  #[arg(long, help = "Only print the balance of <TICK>.")]
+  tick: Option<brc20::Tick>,
+}
+
+impl Balance {
+  fn run(self, options: &Options, index: &Index) -> SubcommandResult {
+    let address = self.address.require_network(options.chain().network())?;
+
+    let balances = match self.tick {
+      Some(tick) => index
+        .brc20_get_balance_by_address(&tick, &address)?
+        .into_iter()
+        .collect(),
+      None => {
+        index
+          .brc20_get_all_balance_by_address(&address, &PageRequest::default())?
+          .0
+          .items
+      }
+    };
+
+    Ok(Box::new(balances))
+  }
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Tick {
+  #[arg(help = "Print info of <TICK>, or of all ticks if absent.")]
+  tick: Option<brc20::Tick>,
+}
+
+impl Tick {
+  fn run(self, index: &Index) -> SubcommandResult {
+    match self.tick {
+      Some(tick) => Ok(Box::new(
+        index
+          .brc20_get_tick_info(&tick)?
+          .ok_or_else(|| anyhow!("tick {tick} not found"))?,
+      )),
+      None => Ok(Box::new(
+        index
+          .brc20_get_all_tick_info(&PageRequest::default())?
+          .0
+          .items,
+      )),
+    }
+  }
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Transferable {
+  #[arg(help = "Print transferable inscriptions of <ADDRESS>.")]
+  address: Address<NetworkUnchecked>,
+  #[arg(long, help = "Only print transferable inscriptions of <TICK>.")]
+  tick: Option<brc20::Tick>,
+}
+
+impl Transferable {
+  fn run(self, options: &Options, index: &Index) -> SubcommandResult {
+    let address = self.address.require_network(options.chain().network())?;
+
+    let page = match self.tick {
+      Some(tick) => {
+        index
+          .brc20_get_tick_transferable_by_address(&tick, &address, &PageRequest::default())?
+          .0
+      }
+      None => {
+        index
+          .brc20_get_all_transferable_by_address(&address, &PageRequest::default())?
+          .0
+      }
+    };
+
+    Ok(Box::new(page.items))
+  }
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Events {
+  #[arg(help = "Print events of transaction <TXID>.")]
+  txid: Txid,
+}
+
+impl Events {
+  fn run(self, index: &Index) -> SubcommandResult {
+    let (page, _) = index.brc20_get_txs_events(&[self.txid], &PageRequest::default())?;
+
+    Ok(Box::new(
+      page
+        .items
+        .into_iter()
+        .flat_map(|(_, receipts)| receipts)
+        .collect::<Vec<brc20::Receipt>>(),
+    ))
+  }
+}
@@ -0,0 +1,131 @@
+use {
+  super::*,
+  crate::okx::datastore::{
+    brc20s::{Pid, TickId},
+    page::PageRequest,
+  },
+  bitcoin::address::NetworkUnchecked,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) enum BRC20SSubcommand {
+  #[command(about = "Print BRC20S pool info")]
+  Pool(Pool),
+  #[command(about = "Print BRC20S tick info")]
+  Tick(Tick),
+  #[command(about = "Print the position of an address in a BRC20S pool")]
+  Userinfo(Userinfo),
+  #[command(about = "Print the pending reward of an address in a BRC20S pool")]
+  Reward(Reward),
+}
+
+impl BRC20SSubcommand {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let index = Index::open_offline(&options)?;
+
+    match self {
+      Self::Pool(pool) => pool.run(&index),
+      Self::Tick(tick) => tick.run(&index),
+      Self::Userinfo(userinfo) => userinfo.run(&options, &index),
+      Self::Reward(reward) => reward.run(&options, &index),
+    }
+  }
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Pool {
+  #[arg(help = "Print info of pool <PID>, or of all pools if absent.")]
+  pid: Option<Pid>,
+}
+
+impl Pool {
+  fn run(self, index: &Index) -> SubcommandResult {
+    match self.pid {
+      Some(pid) => Ok(Box::new(
+        index
+          .brc20s_pool_info(&pid)?
+          .0
+          .ok_or_else(|| anyhow!("pool {} not found", pid.as_str()))?,
+      )),
+      None => {
+        let ((page, _), _) = index.brc20s_all_pool_info(&PageRequest::default())?;
+        Ok(Box::new(page.items))
+      }
+    }
+  }
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Tick {
+  #[arg(help = "Print info of tick <TICK_ID>, or of all ticks if absent.")]
+  tick_id: Option<TickId>,
+}
+
+impl Tick {
+  fn run(self, index: &Index) -> SubcommandResult {
+    match self.tick_id {
+      Some(tick_id) => {
+        Ok(Box::new(index.brc20s_tick_info(&tick_id)?.ok_or_else(
+          || anyhow!("tick {} not found", tick_id.hex()),
+        )?))
+      }
+      None => {
+        let ((page, _), _) = index.brc20s_all_tick_info(&PageRequest::default())?;
+        Ok(Box::new(page.items))
+      }
+    }
+  }
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Userinfo {
+  #[arg(help = "Print the position in pool <PID>.")]
+  pid: Pid,
+  #[arg(help = "Print the position of <ADDRESS>.")]
+  address: Address<NetworkUnchecked>,
+}
+
+impl Userinfo {
+  fn run(self, options: &Options, index: &Index) -> SubcommandResult {
+    let address = self.address.require_network(options.chain().network())?;
+
+    Ok(Box::new(
+      index
+        .brc20s_user_info(&self.pid, &address)?
+        .0
+        .ok_or_else(|| {
+          anyhow!(
+            "user info of {address} in pool {} not found",
+            self.pid.as_str()
+          )
+        })?,
+    ))
+  }
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Reward {
+  #[arg(help = "Print the pending reward in pool <PID>.")]
+  pid: Pid,
+  #[arg(help = "Print the pending reward of <ADDRESS>.")]
+  address: Address<NetworkUnchecked>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RewardOutput {
+  pub pending_reward: Option<String>,
+  pub block_num: Option<String>,
+}
+
+impl Reward {
+  fn run(self, options: &Options, index: &Index) -> SubcommandResult {
+    let address = self.address.require_network(options.chain().network())?;
+
+    let (pending_reward, block) = index.brc20s_user_pending_reward(&self.pid, &address)?;
+
+    Ok(Box::new(RewardOutput {
+      pending_reward: Some(pending_reward),
+      block_num: Some(block.map_or(0, |(height, _)| height.n()).to_string()),
+    }))
+  }
+}
@@ -1,11 +1,237 @@
-use super::*;
+use {
+  super::*,
+  crate::okx::{
+    datastore::{brc20 as brc20_store, brc20s as brc20s_store, ord::Action},
+    protocol::{brc20, brc20s},
+  },
+  serde_json::Value,
+};
 
-#[derive(Serialize, Eq, PartialEq, Deserialize, Debug)]
+#[derive(Serialize, PartialEq, Deserialize, Debug)]
 pub struct Output {
   pub inscriptions: Vec<Inscription>,
+  pub operations: Vec<Operations>,
+}
+
+/// How the BRC20 and BRC20S protocols parse the envelope at `input`/`offset`.
+#[derive(Serialize, PartialEq, Deserialize, Debug)]
+pub struct Operations {
+  pub input: u32,
+  pub offset: u32,
+  pub brc20: Parsed<brc20_store::OperationType>,
+  pub brc20s: Parsed<brc20s_store::OperationType>,
+}
+
+/// The operation an envelope parses to, or the reason the protocol ignores it.
+#[derive(Serialize, PartialEq, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Parsed<T> {
+  Operation { op: T, content: Value },
+  Error(String),
+}
+
+impl<T> Parsed<T> {
+  fn new<O>(result: Result<O>, content: impl FnOnce(O) -> (T, serde_json::Result<Value>)) -> Self {
+    match result {
+      Ok(operation) => match content(operation) {
+        (op, Ok(content)) => Self::Operation { op, content },
+        (_, Err(err)) => Self::Error(err.to_string()),
+      },
+      Err(err) => Self::Error(err.to_string()),
+    }
+  }
 }
 
 #[derive(Debug, Parser)]
 pub(crate) struct Decode {
+  #[arg(help = "Read transaction from <TRANSACTION> instead of stdin.")]
   transaction: Option<PathBuf>,
 }
+
+impl Decode {
+  pub(crate) fn run(self) -> SubcommandResult {
+    let transaction: Transaction = match self.transaction {
+      Some(path) => Decodable::consensus_decode(&mut File::open(path)?)?,
+      None => Decodable::consensus_decode(&mut io::stdin())?,
+    };
+
+    Ok(Box::new(Self::decode(&transaction)))
+  }
+
+  fn decode(transaction: &Transaction) -> Output {
+    let inscriptions = Inscription::from_transaction(transaction);
+
+    let operations = inscriptions
+      .iter()
+      .map(|envelope| {
+        // Reinscriptions are also cursed, but telling them apart needs the index.
+        let curse = if envelope.inscription.unrecognized_even_field {
+          Some("it has an unrecognized even field")
+        } else if envelope.tx_in_index != 0 {
+          Some("it is not in the first input")
+        } else if envelope.tx_in_offset != 0 {
+          Some("it is not the first envelope in its input")
+        } else {
+          None
+        };
+
+        if let Some(curse) = curse {
+          let reason = format!("cursed inscription, {curse}");
+          return Operations {
+            input: envelope.tx_in_index,
+            offset: envelope.tx_in_offset,
+            brc20: Parsed::Error(reason.clone()),
+            brc20s: Parsed::Error(reason),
+          };
+        }
+
+        let action = Action::New {
+          cursed: false,
+          unbound: false,
+          inscription: envelope.inscription.clone(),
+        };
+
+        Operations {
+          input: envelope.tx_in_index,
+          offset: envelope.tx_in_offset,
+          brc20: Parsed::new(
+            brc20::deserialize_brc20_operation(&envelope.inscription, &action),
+            |operation| {
+              let content = match &operation {
+                brc20::Operation::Deploy(deploy) => serde_json::to_value(deploy),
+                brc20::Operation::Mint(mint) => serde_json::to_value(mint),
+                brc20::Operation::InscribeTransfer(transfer)
+                | brc20::Operation::Transfer(transfer) => serde_json::to_value(transfer),
+              };
+              (operation.op_type(), content)
+            },
+          ),
+          brc20s: Parsed::new(
+            brc20s::deserialize_brc20s_operation(&envelope.inscription, &action),
+            |operation| {
+              let content = match &operation {
+                brc20s::Operation::Deploy(deploy) => serde_json::to_value(deploy),
+                brc20s::Operation::Mint(mint) => serde_json::to_value(mint),
+                brc20s::Operation::Stake(stake) => serde_json::to_value(stake),
+                brc20s::Operation::UnStake(unstake) => serde_json::to_value(unstake),
+                brc20s::Operation::PassiveUnStake(unstake) => serde_json::to_value(unstake),
+                brc20s::Operation::InscribeTransfer(transfer)
+                | brc20s::Operation::Transfer(transfer) => serde_json::to_value(transfer),
+              };
+              (operation.op_type(), content)
+            },
+          ),
+        }
+      })
+      .collect();
+
+    Output {
+      inscriptions: inscriptions
+        .into_iter()
+        .map(|envelope| envelope.inscription)
+        .collect(),
+      operations,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn decode(inscriptions: &[Inscription]) -> Output {
+    Decode::decode(&Transaction {
+      version: 2,
+      lock_time: bitcoin::locktime::absolute::LockTime::ZERO,
+      input: inscriptions
+        .iter()
+        .enumerate()
+        .map(|(i, inscription)| TxIn {
+          previous_output: outpoint(u64::try_from(i).unwrap() + 1),
+          script_sig: ScriptBuf::new(),
+          sequence: Sequence::MAX,
+          witness: inscription.to_witness(),
+        })
+        .collect(),
+      output: Vec::new(),
+    })
+  }
+
+  #[test]
+  fn reports_parsed_operations() {
+    let output = decode(&[inscription(
+      "text/plain;charset=utf-8",
+      r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"1000"}"#,
+    )]);
+
+    assert_eq!(output.inscriptions.len(), 1);
+    assert_eq!(output.operations.len(), 1);
+    assert_eq!(output.operations[0].input, 0);
+    assert_eq!(
+      output.operations[0].brc20,
+      Parsed::Operation {
+        op: brc20_store::OperationType::Mint,
+        content: serde_json::json!({ "tick": "ordi", "amt": "1000" }),
+      }
+    );
+    assert_eq!(
+      output.operations[0].brc20s,
+      Parsed::Error("not brc20s json".into())
+    );
+  }
+
+  #[test]
+  fn reports_why_envelopes_are_ignored() {
+    for (inscription, reason) in [
+      (
+        inscription(
+          "image/png",
+          r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"1000"}"#,
+        ),
+        "unsupport content type",
+      ),
+      (
+        inscription("text/plain", "{\"p\":\"brc-20\"}"),
+        "not brc20 json",
+      ),
+      (
+        inscription(
+          "text/plain",
+          r#"{"p":"brc-20","op":"mint","tick":"ordi","amount":"1000"}"#,
+        ),
+        "parse operation json error: missing field `amt`",
+      ),
+    ] {
+      assert_eq!(
+        decode(&[inscription]).operations[0].brc20,
+        Parsed::Error(reason.into())
+      );
+    }
+  }
+
+  #[test]
+  fn reports_cursed_envelopes() {
+    let mint = inscription(
+      "text/plain",
+      r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"1000"}"#,
+    );
+
+    let output = decode(&[mint.clone(), mint]);
+
+    assert_eq!(
+      output.operations[0].brc20,
+      Parsed::Operation {
+        op: brc20_store::OperationType::Mint,
+        content: serde_json::json!({ "tick": "ordi", "amt": "1000" }),
+      }
+    );
+    assert_eq!(
+      output.operations[1].brc20,
+      Parsed::Error("cursed inscription, it is not in the first input".into())
+    );
+    assert_eq!(
+      output.operations[1].brc20s,
+      Parsed::Error("cursed inscription, it is not in the first input".into())
+    );
+  }
+}
@@ -1,23 +1,40 @@
-use super::*;
+use {
+  self::holders::{HolderFormat, HolderWriter},
+  super::*,
+  crate::index::HolderRow,
+};
+
+mod holders;
+mod run;
+mod snapshot;
 
 #[derive(Debug, Parser)]
 pub(crate) enum IndexSubcommand {
-  #[command(about = "Write inscription numbers and ids to a tab-separated file")]
+  #[command(about = "Write inscription numbers and ids, or BRC20 and BRC20S holders, to a file")]
   Export(Export),
   #[command(about = "Update the index")]
-  Run,
+  Run(run::Run),
+  #[command(
+    subcommand,
+    about = "Export or import BRC20, BRC20S and ord collection state"
+  )]
+  Snapshot(snapshot::SnapshotSubcommand),
 }
 
 impl IndexSubcommand {
   pub(crate) fn run(self, options: Options) -> SubcommandResult {
     match self {
       Self::Export(export) => export.run(options),
-      Self::Run => index::run(options),
+      Self::Run(run) => run.run(options),
+      Self::Snapshot(snapshot) => snapshot.run(options),
     }
   }
 }
 
 #[derive(Debug, Parser)]
+#[command(group(
+  ArgGroup::new("holders").args(["brc20_balances", "brc20s_balances", "brc20s_positions"])
+))]
 pub(crate) struct Export {
   #[arg(
     long,
@@ -27,6 +44,33 @@ pub(crate) struct Export {
   tsv: String,
   #[arg(long, help = "Whether to include addresses in export")]
   include_addresses: bool,
+  #[arg(
+    long,
+    help = "Write the BRC20 balances of every holder instead of inscriptions."
+  )]
+  brc20_balances: bool,
+  #[arg(
+    long,
+    help = "Write the BRC20S balances of every holder instead of inscriptions."
+  )]
+  brc20s_balances: bool,
+  #[arg(
+    long,
+    help = "Write the BRC20S pool positions of every staker instead of inscriptions."
+  )]
+  brc20s_positions: bool,
+  #[arg(
+    long,
+    value_enum,
+    default_value = "csv",
+    help = "Write holders as <FORMAT>."
+  )]
+  format: HolderFormat,
+  #[arg(
+    long,
+    help = "Write holders to <OUTPUT>. Defaults to the mode and format, e.g. `brc20_balances.csv`."
+  )]
+  output: Option<PathBuf>,
 }
 
 impl Export {
@@ -34,16 +78,42 @@ impl Export {
     let index = Index::open(&options)?;
 
     index.update()?;
+
+    if self.brc20_balances {
+      return self.holders("brc20_balances", |f| index.brc20_holder_balances(f));
+    }
+
+    if self.brc20s_balances {
+      return self.holders("brc20s_balances", |f| index.brc20s_holder_balances(f));
+    }
+
+    if self.brc20s_positions {
+      return self.holders("brc20s_positions", |f| index.brc20s_holder_positions(f));
+    }
+
     index.export(&self.tsv, self.include_addresses)?;
 
     Ok(Box::new(Empty {}))
   }
-}
 
-pub(crate) fn run(options: Options) -> SubcommandResult {
-  let index = Index::open(&options)?;
+  fn holders<R: HolderRow>(
+    &self,
+    name: &str,
+    read: impl FnOnce(&mut dyn FnMut(R) -> Result<()>) -> Result<Option<Height>>,
+  ) -> SubcommandResult {
+    let file = self
+      .output
+      .clone()
+      .unwrap_or_else(|| format!("{name}.{}", self.format.extension()).into());
 
-  index.update()?;
+    let mut writer = HolderWriter::create::<R>(&file, self.format)?;
+    let height = read(&mut |row| writer.write(&row))?;
+    let rows = writer.finish()?;
 
-  Ok(Box::new(Empty {}))
+    Ok(Box::new(holders::Output {
+      file,
+      height: height.map(|height| height.n()),
+      rows,
+    }))
+  }
 }
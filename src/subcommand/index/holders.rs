@@ -0,0 +1,147 @@
+use {
+  super::*,
+  crate::index::HolderRow,
+  clap::ValueEnum,
+  std::io::{BufWriter, Write},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub(crate) enum HolderFormat {
+  Csv,
+  Json,
+}
+
+impl HolderFormat {
+  pub(crate) fn extension(self) -> &'static str {
+    match self {
+      Self::Csv => "csv",
+      Self::Json => "json",
+    }
+  }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub file: PathBuf,
+  pub height: Option<u64>,
+  pub rows: u64,
+}
+
+/// Writes holder rows to a file one at a time, as CSV with a header or as a JSON array.
+pub(crate) struct HolderWriter<W: Write> {
+  out: W,
+  format: HolderFormat,
+  rows: u64,
+}
+
+impl HolderWriter<BufWriter<File>> {
+  pub(crate) fn create<R: HolderRow>(path: &Path, format: HolderFormat) -> Result<Self> {
+    let file =
+      File::create(path).with_context(|| format!("failed to create `{}`", path.display()))?;
+    Self::new::<R>(BufWriter::new(file), format)
+  }
+}
+
+impl<W: Write> HolderWriter<W> {
+  pub(crate) fn new<R: HolderRow>(mut out: W, format: HolderFormat) -> Result<Self> {
+    match format {
+      HolderFormat::Csv => writeln!(out, "{}", R::HEADER.join(","))?,
+      HolderFormat::Json => write!(out, "[")?,
+    }
+
+    Ok(Self {
+      out,
+      format,
+      rows: 0,
+    })
+  }
+
+  pub(crate) fn write<R: HolderRow>(&mut self, row: &R) -> Result<()> {
+    match self.format {
+      HolderFormat::Csv => {
+        let record = row
+          .record()
+          .iter()
+          .map(|field| csv_field(field))
+          .collect::<Vec<String>>();
+        writeln!(self.out, "{}", record.join(","))?;
+      }
+      HolderFormat::Json => {
+        write!(self.out, "{}\n  ", if self.rows == 0 { "" } else { "," })?;
+        serde_json::to_writer(&mut self.out, row)?;
+      }
+    }
+
+    self.rows += 1;
+
+    Ok(())
+  }
+
+  /// Terminates the output and returns the number of rows written.
+  pub(crate) fn finish(mut self) -> Result<u64> {
+    if self.format == HolderFormat::Json {
+      writeln!(self.out, "{}]", if self.rows == 0 { "" } else { "\n" })?;
+    }
+
+    self.out.flush()?;
+
+    Ok(self.rows)
+  }
+}
+
+fn csv_field(field: &str) -> String {
+  if field.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", field.replace('"', "\"\""))
+  } else {
+    field.into()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, crate::index::BRC20BalanceRow};
+
+  fn row(tick: &str) -> BRC20BalanceRow {
+    BRC20BalanceRow {
+      address: "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".into(),
+      tick: tick.into(),
+      overall: "100".into(),
+      transferable: "10".into(),
+      decimals: 18,
+    }
+  }
+
+  fn write(format: HolderFormat, rows: &[BRC20BalanceRow]) -> String {
+    let mut out = Vec::new();
+    let mut writer = HolderWriter::new::<BRC20BalanceRow>(&mut out, format).unwrap();
+    for row in rows {
+      writer.write(row).unwrap();
+    }
+    assert_eq!(writer.finish().unwrap(), u64::try_from(rows.len()).unwrap());
+    String::from_utf8(out).unwrap()
+  }
+
+  #[test]
+  fn csv_quotes_fields_that_need_it() {
+    assert_eq!(
+      write(HolderFormat::Csv, &[row("ordi"), row("a,\"b")]),
+      "address,tick,overall,transferable,decimals\n\
+       bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4,ordi,100,10,18\n\
+       bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4,\"a,\"\"b\",100,10,18\n"
+    );
+    assert_eq!(
+      write(HolderFormat::Csv, &[]),
+      "address,tick,overall,transferable,decimals\n"
+    );
+  }
+
+  #[test]
+  fn json_is_an_array() {
+    let json = write(HolderFormat::Json, &[row("ordi"), row("sats")]);
+    let rows = serde_json::from_str::<Vec<serde_json::Value>>(&json).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1]["tick"], "sats");
+    assert_eq!(rows[1]["decimals"], 18);
+    assert_eq!(write(HolderFormat::Json, &[]), "[]\n");
+  }
+}
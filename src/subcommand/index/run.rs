@@ -0,0 +1,97 @@
+use {
+  super::*,
+  std::{
+    net::{SocketAddr, TcpListener},
+    sync::mpsc,
+  },
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Run {
+  #[arg(long, help = "Keep indexing new blocks until shut down.")]
+  follow: bool,
+  #[arg(
+    long,
+    requires = "follow",
+    default_value = "5000",
+    help = "Check for new blocks every <POLL_INTERVAL> milliseconds when following."
+  )]
+  poll_interval: u64,
+  #[arg(
+    long,
+    requires = "follow",
+    help = "Check for new blocks as soon as a connection is made to <BLOCK_NOTIFY>, e.g. with `-blocknotify='nc -z 127.0.0.1 8335'` in bitcoin.conf, when following."
+  )]
+  block_notify: Option<SocketAddr>,
+}
+
+impl Run {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let index = Index::open(&options)?;
+
+    if !self.follow {
+      index.update()?;
+      return Ok(Box::new(Empty {}));
+    }
+
+    let notifications = self.block_notify.map(Self::listen).transpose()?;
+
+    while !SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+      if let Err(err) = index.update() {
+        if index.is_unrecoverably_reorged() {
+          return Err(err);
+        }
+        log::warn!("Updating index: {err}");
+      }
+
+      self.wait(notifications.as_ref());
+    }
+
+    Ok(Box::new(Empty {}))
+  }
+
+  /// Accepts connections on `address` and turns each one into a notification.
+  fn listen(address: SocketAddr) -> Result<mpsc::Receiver<()>> {
+    let listener = TcpListener::bind(address)
+      .with_context(|| format!("failed to listen for block notifications on {address}"))?;
+
+    log::info!("Listening for block notifications on {address}");
+
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+      for stream in listener.incoming() {
+        if stream.is_ok() && tx.send(()).is_err() {
+          break;
+        }
+      }
+    });
+
+    Ok(rx)
+  }
+
+  /// Waits for the poll interval to elapse, a block notification or shutdown,
+  /// whichever comes first.
+  fn wait(&self, notifications: Option<&mpsc::Receiver<()>>) {
+    let deadline = Instant::now() + Duration::from_millis(self.poll_interval);
+
+    while !SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+      let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+        break;
+      };
+
+      let step = remaining.min(Duration::from_millis(100));
+
+      match notifications {
+        Some(notifications) => {
+          if notifications.recv_timeout(step).is_ok() {
+            // several notifications may have queued up while indexing
+            while notifications.try_recv().is_ok() {}
+            break;
+          }
+        }
+        None => thread::sleep(step),
+      }
+    }
+  }
+}
@@ -0,0 +1,80 @@
+use {super::*, bitcoin::hashes::sha256};
+
+#[derive(Debug, Parser)]
+pub(crate) enum SnapshotSubcommand {
+  #[command(about = "Write the index state at a block height to a snapshot file")]
+  Export(Export),
+  #[command(about = "Fill an empty index with the content of a snapshot file")]
+  Import(Import),
+}
+
+impl SnapshotSubcommand {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    match self {
+      Self::Export(export) => export.run(options),
+      Self::Import(import) => import.run(options),
+    }
+  }
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Export {
+  #[arg(
+    long,
+    help = "Export the state at <HEIGHT> instead of the indexed height. Earlier heights are reached with undo logs, so they must be within `--max-reorg-depth` blocks of the indexed height."
+  )]
+  height: Option<u64>,
+  #[arg(
+    long,
+    default_value = "snapshot.bin",
+    help = "Write snapshot to <OUTPUT>."
+  )]
+  output: PathBuf,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Import {
+  #[arg(help = "Read snapshot from <FILE>.")]
+  file: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub height: u64,
+  pub block_hash: BlockHash,
+  pub state_hash: String,
+  pub content_hash: String,
+  pub checksum: String,
+  pub entries: u64,
+}
+
+impl From<okx::datastore::snapshot::SnapshotSummary> for Output {
+  fn from(summary: okx::datastore::snapshot::SnapshotSummary) -> Self {
+    Self {
+      height: summary.header.height,
+      block_hash: BlockHash::from_byte_array(summary.header.block_hash),
+      state_hash: sha256::Hash::from_byte_array(summary.header.state_hash).to_string(),
+      content_hash: summary.content_hash.to_string(),
+      checksum: summary.checksum.to_string(),
+      entries: summary.entries,
+    }
+  }
+}
+
+impl Export {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let index = Index::open(&options)?;
+
+    Ok(Box::new(Output::from(
+      index.export_snapshot(self.height, &self.output)?,
+    )))
+  }
+}
+
+impl Import {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let index = Index::open(&options)?;
+
+    Ok(Box::new(Output::from(index.import_snapshot(&self.file)?)))
+  }
+}
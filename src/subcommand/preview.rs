@@ -0,0 +1,25 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Preview {
+  #[arg(
+    help = "Preview <TRANSACTION>, a base64 or hex PSBT or a hex raw transaction, instead of \
+    reading it from stdin."
+  )]
+  transaction: Option<String>,
+}
+
+impl Preview {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let transaction = match self.transaction {
+      Some(transaction) => transaction,
+      None => io::read_to_string(io::stdin())?,
+    };
+
+    let index = Index::open_read_only(&options)?;
+
+    Ok(Box::new(index.preview_transaction(
+      &transaction.parse::<UnsignedTransaction>()?,
+    )?))
+  }
+}
@@ -5,21 +5,29 @@ use {
     error::{ApiError, OptionExt, ServerError, ServerResult},
   },
   super::*,
-  crate::page_config::PageConfig,
   crate::templates::{
-    BlockHtml, ClockSvg, HomeHtml, InputHtml, InscriptionHtml, InscriptionJson,
-    InscriptionsBlockHtml, InscriptionsHtml, InscriptionsJson, OutputHtml, OutputJson, PageContent,
-    PageHtml, PreviewAudioHtml, PreviewImageHtml, PreviewModelHtml, PreviewPdfHtml,
-    PreviewTextHtml, PreviewUnknownHtml, PreviewVideoHtml, RangeHtml, RareTxt, SatHtml, SatJson,
-    TransactionHtml,
+    AddressHtml, Amount, BRC20SPoolHtml, BRC20STickHtml, BRC20TickHtml, BlockHtml, ClockSvg,
+    HomeHtml, InputHtml, InscriptionHtml, InscriptionJson, InscriptionsBlockHtml, InscriptionsHtml,
+    InscriptionsJson, OutputHtml, OutputJson, PageContent, PageHtml, PreviewAudioHtml,
+    PreviewImageHtml, PreviewModelHtml, PreviewPdfHtml, PreviewTextHtml, PreviewUnknownHtml,
+    PreviewVideoHtml, RangeHtml, RareTxt, SatHtml, SatJson, TransactionHtml,
+  },
+  crate::{
+    index::OwnedInscriptionFilter,
+    okx::{
+      datastore::{brc20 as brc20_store, brc20s as brc20s_store, ord::Action, page::PageRequest},
+      protocol::brc20 as brc20_protocol,
+    },
+    page_config::PageConfig,
   },
   axum::{
     body,
-    extract::{Extension, Json, Path, Query},
+    extract::{DefaultBodyLimit, Extension, Json, MatchedPath, Path, Query},
     headers::UserAgent,
-    http::{header, HeaderMap, HeaderValue, StatusCode, Uri},
+    http::{header, HeaderMap, HeaderValue, Request, StatusCode, Uri},
+    middleware::{self, Next},
     response::{IntoResponse, Redirect, Response},
-    routing::get,
+    routing::{get, post},
     Router, TypedHeader,
   },
   axum_server::Handle,
@@ -30,7 +38,7 @@ use {
     caches::DirCache,
     AcmeConfig,
   },
-  std::{cmp::Ordering, str, sync::Arc},
+  std::{cmp::Ordering, collections::HashMap, slice, str, sync::Arc},
   tokio_stream::StreamExt,
   tower_http::{
     compression::CompressionLayer,
@@ -45,15 +53,31 @@ mod brc20;
 mod brc20s;
 mod info;
 mod ord;
+mod preview;
 mod types;
 
 mod accept_json;
 mod error;
 mod response;
+mod rpc;
 
 use self::api::*;
 use self::response::ApiResponse;
 
+/// The most events the BRC20 tick page shows.
+const BRC20_TICK_EVENT_LIMIT: usize = 50;
+/// The most deploy receipts the BRC20 deployment feed includes.
+const BRC20_DEPLOY_FEED_LIMIT: usize = 100;
+/// The most deploy receipts the BRC20S deployment feed includes.
+const BRC20S_DEPLOY_FEED_LIMIT: usize = 100;
+/// The most transfers a BRC20 tick's transfer feed includes.
+const BRC20_TRANSFER_FEED_LIMIT: usize = 100;
+/// How many of a tick's latest transfers the transfer feed checks against
+/// its minimum amount.
+const BRC20_TRANSFER_FEED_SCAN: usize = 10_000;
+/// The most receipts the BRC20S tick and pool pages show.
+const BRC20S_RECEIPT_LIMIT: usize = 50;
+
 #[derive(Clone)]
 pub struct ServerConfig {
   pub is_json_api_enabled: bool,
@@ -87,6 +111,11 @@ struct Search {
   query: String,
 }
 
+#[derive(Deserialize)]
+struct TransferFeedQuery {
+  min: Option<String>,
+}
+
 #[derive(RustEmbed)]
 #[folder = "static"]
 struct StaticAssets;
@@ -171,6 +200,7 @@ impl Server {
           brc20::brc20_block_events,
           brc20::brc20_transferable,
           brc20::brc20_all_transferable,
+          brc20::brc20_batch_balance,
 
           brc20s::brc20s_tick_info,
           brc20s::brc20s_all_tick_info,
@@ -185,14 +215,21 @@ impl Server {
           brc20s::brc20s_all_transferable,
           brc20s::brc20s_userinfo,
           brc20s::brc20s_stake_info,
+          brc20s::brc20s_batch_balance,
 
           ord::ord_inscription_id,
           ord::ord_inscription_number,
           ord::ord_outpoint,
           ord::ord_txid_inscriptions,
           ord::ord_block_inscriptions,
+          ord::ord_children,
+          ord::ord_ancestry,
+          ord::ord_address_inscriptions,
 
           info::node_info,
+          info::node_reorgs,
+
+          preview::tx_preview,
         ),
         components(schemas(
 
@@ -211,6 +248,9 @@ impl Server {
           brc20::BlockEvents,
           brc20::TransferableInscription,
           brc20::TransferableInscriptions,
+          brc20::BatchBalanceRequest,
+          brc20::AccountBalance,
+          brc20::BatchBalance,
 
           // BRC20 responses schemas
           response::BRC20Tick,
@@ -220,6 +260,7 @@ impl Server {
           response::BRC20TxEvents,
           response::BRC20BlockEvents,
           response::BRC20Transferable,
+          response::BRC20BatchBalance,
 
           // BRC20S schemas
           brc20s::Tick,
@@ -250,6 +291,9 @@ impl Server {
           brc20s::UserInfo,
           brc20s::StakedInfo,
           brc20s::StakedPid,
+          brc20s::BatchBalanceRequest,
+          brc20s::AccountBalance,
+          brc20s::BatchBalance,
 
           // BRC20S responses schemas
           response::BRC20STick,
@@ -261,6 +305,7 @@ impl Server {
           response::BRC20STxReceipts,
           response::BRC20SBlockReceipts,
           response::BRC20Transferable,
+          response::BRC20SBatchBalance,
 
           // Ord schemas
           ord::OrdInscription,
@@ -271,19 +316,38 @@ impl Server {
           ord::TxInscription,
           ord::TxInscriptions,
           ord::BlockInscriptions,
+          ord::Relative,
+          ord::Children,
+          ord::Ancestry,
+          ord::AddressInscriptions,
 
           // Ord responses schemas
           response::OrdOrdInscription,
           response::OrdTxInscriptions,
           response::OrdBlockInscriptions,
+          response::OrdChildren,
+          response::OrdAncestry,
+          response::OrdAddressInscriptions,
           response::OrdOutPointResult,
 
+          // Preview schemas
+          preview::PreviewRequest,
+          preview::BRC20BalanceChange,
+          preview::BRC20SBalanceChange,
+          preview::TxPreview,
+          response::TxPreviewResponse,
 
           // Node Info schemas
           info::NodeInfo,
           info::ChainInfo,
+          info::OrphanedInscription,
+          info::OrphanedTransaction,
+          info::OrphanedBlock,
+          info::ReorgInfo,
+          info::Reorgs,
           types::ScriptPubkey,
           response::Node,
+          response::NodeReorgs,
           ApiError
         ))
       )]
@@ -305,7 +369,14 @@ impl Server {
           get(|| async { ApiDoc::openapi().to_pretty_json().unwrap() }),
         )
         .route("/node/info", get(info::node_info))
+        .route("/node/reorgs", get(info::node_reorgs))
         .route("/ord/id/:id/inscription", get(ord::ord_inscription_id))
+        .route("/ord/id/:id/children", get(ord::ord_children))
+        .route("/ord/id/:id/ancestry", get(ord::ord_ancestry))
+        .route(
+          "/ord/address/:address/inscriptions",
+          get(ord::ord_address_inscriptions),
+        )
         .route(
           "/ord/number/:number/inscription",
           get(ord::ord_inscription_number),
@@ -341,6 +412,7 @@ impl Server {
           "/brc20/address/:address/transferable",
           get(brc20::brc20_all_transferable),
         )
+        .route("/brc20/batch/balance", post(brc20::brc20_batch_balance))
         .route("/brc20/tx/:txid/events", get(brc20::brc20_tx_events))
         .route("/brc20/tx/:txid", get(brc20::brc20_tx))
         .route(
@@ -399,6 +471,7 @@ impl Server {
           "/brc20s/address/:address/transferable",
           get(brc20s::brc20s_all_transferable),
         )
+        .route("/brc20s/batch/balance", post(brc20s::brc20s_batch_balance))
         .route(
           "/brc20s/tx/:txid/receipts",
           get(brc20s::brc20s_txid_receipts),
@@ -414,12 +487,25 @@ impl Server {
         .route(
           "/brc20s/stake/:address/:tick",
           get(brc20s::brc20s_stake_info),
+        )
+        .route(
+          "/tx/preview",
+          post(preview::tx_preview).layer(DefaultBodyLimit::max(preview::MAX_PREVIEW_REQUEST_SIZE)),
         );
 
+      let api_v1_router = api_v1_router.route_layer(middleware::from_fn(api::require_min_height));
+      let rpc_api = rpc::RpcApi::new(
+        api_v1_router
+          .clone()
+          .layer(Extension(index.clone()))
+          .with_state(server_config.clone()),
+      );
+
       let api_router = Router::new().nest("/v1", api_v1_router);
 
       let router = Router::new()
         .route("/", get(Self::home))
+        .route("/address/:address", get(Self::address))
         .route("/block/:query", get(Self::block))
         .route("/blockcount", get(Self::block_count))
         .route("/blockheight", get(Self::block_height))
@@ -427,6 +513,12 @@ impl Server {
         .route("/blockhash/:height", get(Self::block_hash_from_height))
         .route("/blocktime", get(Self::block_time))
         .route("/bounties", get(Self::bounties))
+        .route("/brc20/feed.xml", get(Self::brc20_feed))
+        .route("/brc20/:tick", get(Self::brc20_tick))
+        .route("/brc20/:tick/feed.xml", get(Self::brc20_transfer_feed))
+        .route("/brc20s/feed.xml", get(Self::brc20s_feed))
+        .route("/brc20s/pool/:pid", get(Self::brc20s_pool))
+        .route("/brc20s/tick/:tick_id", get(Self::brc20s_tick))
         .route("/clock", get(Self::clock))
         .route("/content/:inscription_id", get(Self::content))
         .route("/faq", get(Self::faq))
@@ -446,11 +538,13 @@ impl Server {
         .route("/inscriptions/:from", get(Self::inscriptions_from))
         .route("/inscriptions/:from/:n", get(Self::inscriptions_from_n))
         .route("/install.sh", get(Self::install_script))
+        .route("/metrics", get(Self::metrics))
         .route("/ordinal/:sat", get(Self::ordinal))
         .route("/output/:output", get(Self::output))
         .route("/preview/:inscription_id", get(Self::preview))
         .route("/range/:start/:end", get(Self::range))
         .route("/rare.txt", get(Self::rare_txt))
+        .route("/rpc", post(rpc::rpc))
         .route("/sat/:sat", get(Self::sat))
         .route("/search", get(Self::search_by_query))
         .route("/search/:query", get(Self::search_by_path))
@@ -458,7 +552,9 @@ impl Server {
         .route("/status", get(Self::status))
         .route("/tx/:txid", get(Self::transaction))
         .nest("/api", api_router)
+        .route_layer(middleware::from_fn(Self::observe_request))
         .layer(Extension(index))
+        .layer(Extension(rpc_api))
         .layer(Extension(page_config))
         .layer(Extension(Arc::new(config)))
         .layer(SetResponseHeaderLayer::if_not_present(
@@ -471,7 +567,8 @@ impl Server {
         ))
         .layer(
           CorsLayer::new()
-            .allow_methods([http::Method::GET])
+            .allow_methods([http::Method::GET, http::Method::POST])
+            .allow_headers([header::CONTENT_TYPE])
             .allow_origin(Any),
         )
         .layer(CompressionLayer::new())
@@ -878,6 +975,242 @@ impl Server {
     )
   }
 
+  async fn address(
+    Extension(page_config): Extension<Arc<PageConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(address): Path<String>,
+  ) -> ServerResult<PageHtml<AddressHtml>> {
+    let address = Address::from_str(&address)
+      .and_then(|address| address.require_network(page_config.chain.network()))
+      .map_err(|err| ServerError::BadRequest(format!("invalid address {address}: {err}")))?;
+
+    let (inscriptions, _) = index.ord_address_inscriptions(
+      &address,
+      &OwnedInscriptionFilter::default(),
+      &PageRequest::default(),
+    )?;
+
+    let mut brc20_balances = Vec::new();
+    let mut brc20_transferable = Vec::new();
+
+    if index.has_brc20_index() {
+      let (accounts, _) = index.brc20_batch_accounts(slice::from_ref(&address), None)?;
+      let account = accounts.into_iter().next().unwrap_or_default();
+
+      let mut decimals = HashMap::new();
+      let mut decimal = |tick: &brc20_store::Tick| -> Result<u8> {
+        let key = tick.as_str().to_lowercase();
+        if let Some(decimal) = decimals.get(&key) {
+          return Ok(*decimal);
+        }
+        let decimal = index
+          .brc20_get_tick_info(tick)?
+          .map(|token| token.decimal)
+          .unwrap_or_default();
+        decimals.insert(key, decimal);
+        Ok(decimal)
+      };
+
+      for balance in account.balances {
+        let decimal = decimal(&balance.tick)?;
+        brc20_balances.push((balance, decimal));
+      }
+
+      for transferable in account.transferable {
+        let decimal = decimal(&transferable.tick)?;
+        brc20_transferable.push((transferable, decimal));
+      }
+    }
+
+    let mut brc20s_balances = Vec::new();
+    let mut brc20s_transferable = Vec::new();
+
+    if index.has_brc20s_index() {
+      let (accounts, _) = index.brc20s_batch_accounts(slice::from_ref(&address), None, None)?;
+      let account = accounts.into_iter().next().unwrap_or_default();
+
+      let mut tick_infos = HashMap::<String, brc20s_store::TickInfo>::new();
+      let mut tick_info = |tick_id: &brc20s_store::TickId| -> ServerResult<brc20s_store::TickInfo> {
+        let key = tick_id.hex();
+        if let Some(tick_info) = tick_infos.get(&key) {
+          return Ok(tick_info.clone());
+        }
+        let tick_info = index
+          .brc20s_tick_info(tick_id)?
+          .ok_or_not_found(|| format!("brc20s tick {key}"))?;
+        tick_infos.insert(key, tick_info.clone());
+        Ok(tick_info)
+      };
+
+      for (balance, _) in account.balances {
+        let tick_info = tick_info(&balance.tick_id)?;
+        brc20s_balances.push((balance, tick_info));
+      }
+
+      for (transferable, _, _) in account.transferable {
+        let tick_info = tick_info(&transferable.tick_id)?;
+        brc20s_transferable.push((transferable, tick_info));
+      }
+    }
+
+    Ok(
+      AddressHtml {
+        address,
+        inscriptions: inscriptions
+          .items
+          .into_iter()
+          .map(|(inscription_id, _, _)| inscription_id)
+          .collect(),
+        brc20_balances,
+        brc20_transferable,
+        brc20s_balances,
+        brc20s_transferable,
+      }
+      .page(page_config, index.has_sat_index()?),
+    )
+  }
+
+  async fn brc20_tick(
+    Extension(page_config): Extension<Arc<PageConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(tick): Path<String>,
+  ) -> ServerResult<PageHtml<BRC20TickHtml>> {
+    let tick = brc20_store::Tick::from_str(&tick)
+      .map_err(|err| ServerError::BadRequest(format!("invalid tick {tick}: {err}")))?;
+
+    let token = if index.has_brc20_index() {
+      index.brc20_get_tick_info(&tick)?
+    } else {
+      None
+    }
+    .ok_or_not_found(|| format!("brc20 tick {tick}"))?;
+
+    let events = index.brc20_receipt_history(
+      brc20_store::ReceiptHistory::Tick(&tick),
+      BRC20_TICK_EVENT_LIMIT,
+      BRC20_TICK_EVENT_LIMIT,
+      |_| true,
+    )?;
+
+    Ok(BRC20TickHtml { token, events }.page(page_config, index.has_sat_index()?))
+  }
+
+  async fn brc20s_tick(
+    Extension(page_config): Extension<Arc<PageConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(tick_id): Path<String>,
+  ) -> ServerResult<PageHtml<BRC20STickHtml>> {
+    let tick_id = brc20s_store::TickId::from_str(&tick_id)
+      .map_err(|err| ServerError::BadRequest(format!("invalid tick id {tick_id}: {err}")))?;
+
+    let tick = if index.has_brc20s_index() {
+      index.brc20s_tick_info(&tick_id)?
+    } else {
+      None
+    }
+    .ok_or_not_found(|| format!("brc20s tick {}", tick_id.hex()))?;
+
+    let pools = tick
+      .pids
+      .iter()
+      .map(|pid| {
+        index
+          .brc20s_pool_info(pid)?
+          .0
+          .ok_or_else(|| anyhow!("pool {} of tick {} not found", pid.as_str(), tick_id.hex()))
+      })
+      .collect::<Result<Vec<_>>>()?;
+
+    let receipts = index.brc20s_receipt_history(
+      &brc20s_store::ReceiptHistory::Tick(tick_id),
+      BRC20S_RECEIPT_LIMIT,
+    )?;
+
+    Ok(
+      BRC20STickHtml {
+        tick,
+        pools,
+        receipts,
+      }
+      .page(page_config, index.has_sat_index()?),
+    )
+  }
+
+  async fn brc20s_pool(
+    Extension(page_config): Extension<Arc<PageConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(pid): Path<String>,
+  ) -> ServerResult<PageHtml<BRC20SPoolHtml>> {
+    let pid = brc20s_store::Pid::from_str(&pid)
+      .map_err(|err| ServerError::BadRequest(format!("invalid pool id {pid}: {err}")))?;
+
+    let pool = if index.has_brc20s_index() {
+      index.brc20s_pool_info(&pid)?.0
+    } else {
+      None
+    }
+    .ok_or_not_found(|| format!("brc20s pool {}", pid.as_str()))?;
+
+    let tick_id = brc20s_store::TickId::from(pid.clone());
+
+    let tick = index
+      .brc20s_tick_info(&tick_id)?
+      .ok_or_else(|| anyhow!("tick {} of pool {} not found", tick_id.hex(), pid.as_str()))?;
+
+    let stake_decimal = index.brc20s_stake_decimal(&pool.stake)?;
+
+    let receipts = index.brc20s_receipt_history(
+      &brc20s_store::ReceiptHistory::Pool(pid),
+      BRC20S_RECEIPT_LIMIT,
+    )?;
+
+    Ok(
+      BRC20SPoolHtml {
+        pool,
+        tick,
+        stake_decimal,
+        receipts,
+      }
+      .page(page_config, index.has_sat_index()?),
+    )
+  }
+
+  async fn metrics(Extension(index): Extension<Arc<Index>>) -> ServerResult<Response> {
+    Ok(
+      (
+        [(
+          header::CONTENT_TYPE,
+          "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        index.render_metrics()?,
+      )
+        .into_response(),
+    )
+  }
+
+  async fn observe_request<B>(
+    Extension(index): Extension<Arc<Index>>,
+    matched_path: Option<MatchedPath>,
+    request: Request<B>,
+    next: Next<B>,
+  ) -> Response {
+    let start = Instant::now();
+    let method = request.method().clone();
+    let response = next.run(request).await;
+
+    index.metrics().observe_api_request(
+      matched_path
+        .as_ref()
+        .map(MatchedPath::as_str)
+        .unwrap_or("unmatched"),
+      method.as_str(),
+      response.status().as_u16(),
+      start.elapsed(),
+    );
+
+    response
+  }
+
   async fn status(Extension(index): Extension<Arc<Index>>) -> (StatusCode, &'static str) {
     if index.is_unrecoverably_reorged() {
       (
@@ -962,46 +1295,194 @@ impl Server {
     }
   }
 
-  async fn feed(
-    Extension(page_config): Extension<Arc<PageConfig>>,
-    Extension(index): Extension<Arc<Index>>,
-  ) -> ServerResult<Response> {
+  fn feed_channel(chain: Chain, title: &str) -> rss::ChannelBuilder {
     let mut builder = rss::ChannelBuilder::default();
 
-    let chain = page_config.chain;
     match chain {
-      Chain::Mainnet => builder.title("Inscriptions"),
-      _ => builder.title(format!("Inscriptions – {chain:?}")),
+      Chain::Mainnet => builder.title(title),
+      _ => builder.title(format!("{title} – {chain:?}")),
     };
 
     builder.generator(Some("ord".to_string()));
 
+    builder
+  }
+
+  fn feed_item(title: String, link: String, description: Option<String>) -> rss::Item {
+    rss::ItemBuilder::default()
+      .title(title)
+      .link(link.clone())
+      .description(description)
+      .guid(Some(rss::Guid {
+        value: link,
+        permalink: true,
+      }))
+      .build()
+  }
+
+  fn feed_response(channel: rss::Channel) -> Response {
+    (
+      [
+        (header::CONTENT_TYPE, "application/rss+xml"),
+        (
+          header::CONTENT_SECURITY_POLICY,
+          "default-src 'unsafe-inline'",
+        ),
+      ],
+      channel.to_string(),
+    )
+      .into_response()
+  }
+
+  async fn feed(
+    Extension(page_config): Extension<Arc<PageConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+  ) -> ServerResult<Response> {
+    let mut builder = Self::feed_channel(page_config.chain, "Inscriptions");
+
     for (number, id) in index.get_feed_inscriptions(300)? {
-      builder.item(
-        rss::ItemBuilder::default()
-          .title(format!("Inscription {number}"))
-          .link(format!("/inscription/{id}"))
-          .guid(Some(rss::Guid {
-            value: format!("/inscription/{id}"),
-            permalink: true,
-          }))
-          .build(),
-      );
+      builder.item(Self::feed_item(
+        format!("Inscription {number}"),
+        format!("/inscription/{id}"),
+        None,
+      ));
     }
 
-    Ok(
-      (
-        [
-          (header::CONTENT_TYPE, "application/rss+xml"),
-          (
-            header::CONTENT_SECURITY_POLICY,
-            "default-src 'unsafe-inline'",
-          ),
-        ],
-        builder.build().to_string(),
-      )
-        .into_response(),
-    )
+    Ok(Self::feed_response(builder.build()))
+  }
+
+  async fn brc20_feed(
+    Extension(page_config): Extension<Arc<PageConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+  ) -> ServerResult<Response> {
+    let mut builder = Self::feed_channel(page_config.chain, "BRC20 Deployments");
+
+    if index.has_brc20_index() {
+      for (height, _, receipt) in index.brc20_receipt_history(
+        brc20_store::ReceiptHistory::Deploys,
+        BRC20_DEPLOY_FEED_LIMIT,
+        BRC20_DEPLOY_FEED_LIMIT,
+        |_| true,
+      )? {
+        let Ok(brc20_store::Event::Deploy(deploy)) = receipt.result else {
+          continue;
+        };
+
+        builder.item(Self::feed_item(
+          format!("BRC20 {} deployed", deploy.tick),
+          format!("/brc20/{}", deploy.tick.to_lowercase().as_str()),
+          Some(format!(
+            "Deployed by {} at height {height} with a supply of {} and a limit per mint of {}.",
+            receipt.to,
+            Amount::new(deploy.supply, deploy.decimal),
+            Amount::new(deploy.limit_per_mint, deploy.decimal),
+          )),
+        ));
+      }
+    }
+
+    Ok(Self::feed_response(builder.build()))
+  }
+
+  async fn brc20_transfer_feed(
+    Extension(page_config): Extension<Arc<PageConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(tick): Path<String>,
+    Query(query): Query<TransferFeedQuery>,
+  ) -> ServerResult<Response> {
+    let tick = brc20_store::Tick::from_str(&tick)
+      .map_err(|err| ServerError::BadRequest(format!("invalid tick {tick}: {err}")))?;
+
+    let token = if index.has_brc20_index() {
+      index.brc20_get_tick_info(&tick)?
+    } else {
+      None
+    }
+    .ok_or_not_found(|| format!("brc20 tick {tick}"))?;
+
+    let min = match query.min {
+      Some(min) => brc20_protocol::Num::from_str(&min)
+        .and_then(|min| min.checked_mul(&10u128.pow(u32::from(token.decimal)).into()))
+        .and_then(|min| min.checked_to_u128())
+        .map_err(|err| ServerError::BadRequest(format!("invalid minimum amount {min}: {err}")))?,
+      None => token.limit_per_mint,
+    };
+
+    let mut builder = Self::feed_channel(
+      page_config.chain,
+      &format!("BRC20 {} Transfers", token.tick),
+    );
+
+    for (height, txid, receipt) in index.brc20_receipt_history(
+      brc20_store::ReceiptHistory::Transfers(&tick),
+      BRC20_TRANSFER_FEED_LIMIT,
+      BRC20_TRANSFER_FEED_SCAN,
+      |receipt| {
+        matches!(
+          &receipt.result,
+          Ok(brc20_store::Event::Transfer(transfer)) if transfer.amount >= min
+        )
+      },
+    )? {
+      let Ok(brc20_store::Event::Transfer(transfer)) = receipt.result else {
+        continue;
+      };
+
+      builder.item(Self::feed_item(
+        format!(
+          "{} {} transferred",
+          Amount::new(transfer.amount, token.decimal),
+          token.tick
+        ),
+        format!("/tx/{txid}"),
+        Some(format!(
+          "Transferred from {} to {} at height {height} by inscription {}.",
+          receipt.from, receipt.to, receipt.inscription_id,
+        )),
+      ));
+    }
+
+    Ok(Self::feed_response(builder.build()))
+  }
+
+  async fn brc20s_feed(
+    Extension(page_config): Extension<Arc<PageConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+  ) -> ServerResult<Response> {
+    let mut builder = Self::feed_channel(page_config.chain, "BRC20S Deployments");
+
+    if index.has_brc20s_index() {
+      for (height, _, receipt) in index.brc20s_receipt_history(
+        &brc20s_store::ReceiptHistory::Deploys,
+        BRC20S_DEPLOY_FEED_LIMIT,
+      )? {
+        for event in receipt.result.iter().flatten() {
+          match event {
+            brc20s_store::Event::DeployTick(deploy) => builder.item(Self::feed_item(
+              format!("BRC20S {} deployed", deploy.name.as_str()),
+              format!("/brc20s/tick/{}", deploy.tick_id.hex()),
+              Some(format!(
+                "Deployed by {} at height {height} with a supply of {}.",
+                receipt.to,
+                Amount::new(deploy.supply, deploy.decimal),
+              )),
+            )),
+            brc20s_store::Event::DeployPool(deploy) => builder.item(Self::feed_item(
+              format!("BRC20S pool {} deployed", deploy.pid.as_str()),
+              format!("/brc20s/pool/{}", deploy.pid.as_str().replace('#', "%23")),
+              Some(format!(
+                "Deployed by {} at height {height} staking {}.",
+                receipt.to,
+                deploy.stake.to_string(),
+              )),
+            )),
+            _ => continue,
+          };
+        }
+      }
+    }
+
+    Ok(Self::feed_response(builder.build()))
   }
 
   async fn static_asset(Path(path): Path<String>) -> ServerResult<Response> {
@@ -1255,6 +1736,26 @@ impl Server {
 
     let children = index.get_children_by_inscription_id(inscription_id)?;
 
+    let brc20_tick = if index.has_brc20_index() {
+      brc20_protocol::deserialize_brc20_operation(
+        &inscription,
+        &Action::New {
+          cursed: false,
+          unbound: false,
+          inscription: inscription.clone(),
+        },
+      )
+      .ok()
+      .map(|operation| match operation {
+        brc20_protocol::Operation::Deploy(deploy) => deploy.tick,
+        brc20_protocol::Operation::Mint(mint) => mint.tick,
+        brc20_protocol::Operation::InscribeTransfer(transfer)
+        | brc20_protocol::Operation::Transfer(transfer) => transfer.tick,
+      })
+    } else {
+      None
+    };
+
     Ok(if accept_json.0 {
       Json(InscriptionJson::new(
         page_config.chain,
@@ -1275,6 +1776,7 @@ impl Server {
       .into_response()
     } else {
       InscriptionHtml {
+        brc20_tick,
         chain: page_config.chain,
         genesis_fee: entry.fee,
         genesis_height: entry.height,
@@ -1916,6 +2418,32 @@ mod tests {
     TestServer::new().assert_response("/status", StatusCode::OK, "OK");
   }
 
+  #[test]
+  fn metrics() {
+    let test_server = TestServer::new();
+
+    test_server.mine_blocks(2);
+    test_server.assert_response(
+      "/tx/0000000000000000000000000000000000000000000000000000000000000000",
+      StatusCode::NOT_FOUND,
+      "transaction 0000000000000000000000000000000000000000000000000000000000000000 not found",
+    );
+
+    let response = test_server.get("/metrics");
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CONTENT_TYPE).unwrap(),
+      "text/plain; version=0.0.4; charset=utf-8"
+    );
+
+    let metrics = response.text().unwrap();
+    assert!(metrics.lines().any(|line| line == "ord_index_height 2"));
+    assert!(metrics.lines().any(|line| line == "ord_chain_height 2"));
+    assert!(metrics.contains("ord_block_stage_duration_seconds_count{stage=\"commit\"}"));
+    assert!(metrics.lines().any(|line| line
+      == "ord_api_request_duration_seconds_count{route=\"/tx/:txid\",method=\"GET\",status=\"404\"} 1"));
+  }
+
   #[test]
   fn block_count_endpoint() {
     let test_server = TestServer::new();
@@ -2391,7 +2919,7 @@ mod tests {
 
   #[test]
   fn detect_unrecoverable_reorg() {
-    let test_server = TestServer::new();
+    let test_server = TestServer::new_with_args(&["--max-reorg-depth", "10"], &[]);
 
     test_server.mine_blocks(21);
 
@@ -2407,59 +2935,372 @@ mod tests {
   }
 
   #[test]
-  fn rare_with_index() {
-    TestServer::new_with_sat_index().assert_response(
-      "/rare.txt",
-      StatusCode::OK,
-      "sat\tsatpoint
-0\t4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0
-",
+  fn node_reorgs() {
+    let test_server = TestServer::new();
+
+    test_server.mine_blocks(2);
+    let old_hash = test_server.bitcoin_rpc_server.invalidate_tip();
+    test_server.mine_blocks(2);
+
+    let response = test_server.get("/api/v1/node/reorgs");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = response.json::<serde_json::Value>().unwrap();
+    assert_eq!(json["data"]["total"], 1);
+    assert_eq!(json["data"]["reorgs"][0]["height"], 3);
+    assert_eq!(json["data"]["reorgs"][0]["depth"], 2);
+    assert_eq!(
+      json["data"]["reorgs"][0]["orphanedBlocks"][0]["oldHash"],
+      old_hash.to_string()
     );
   }
 
   #[test]
-  fn rare_without_sat_index() {
-    TestServer::new().assert_response(
-      "/rare.txt",
-      StatusCode::NOT_FOUND,
-      "tracking rare sats requires index created with `--index-sats` flag",
-    );
+  fn list_endpoints_report_height_and_reject_bad_cursors() {
+    let test_server = TestServer::new();
+
+    test_server.mine_blocks(2);
+
+    let response = test_server.get("/api/v1/brc20/tick?limit=10");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = response.json::<serde_json::Value>().unwrap();
+    assert_eq!(json["data"]["tokens"], serde_json::json!([]));
+    assert_eq!(json["data"]["next"], serde_json::Value::Null);
+    assert_eq!(json["height"], 2);
+
+    let response = test_server.get("/api/v1/brc20s/pool?cursor=not-a-cursor");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
   }
 
   #[test]
-  fn show_rare_txt_in_header_with_sat_index() {
-    TestServer::new_with_sat_index().assert_response_regex(
-      "/",
-      StatusCode::OK,
-      ".*
-      <a href=/clock>Clock</a>
-      <a href=/rare.txt>rare.txt</a>
-      <form action=/search method=get>.*",
-    );
+  fn list_endpoints_bound_the_page_limit() {
+    let limit = |limit| {
+      Pagination {
+        cursor: None,
+        start: None,
+        limit,
+      }
+      .request()
+      .ok()
+      .unwrap()
+      .limit
+    };
+
+    assert_eq!(limit(None), Some(DEFAULT_PAGE_LIMIT));
+    assert_eq!(limit(Some(10)), Some(10));
+    assert_eq!(limit(Some(MAX_PAGE_LIMIT + 1)), Some(MAX_PAGE_LIMIT));
   }
 
   #[test]
-  fn rare_sat_location() {
-    TestServer::new_with_sat_index().assert_response_regex(
-      "/sat/0",
-      StatusCode::OK,
-      ".*>4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0<.*",
-    );
+  fn children_reject_cursors_of_other_lists() {
+    let test_server = TestServer::new();
+
+    let response = test_server.get(format!(
+      "/api/v1/ord/id/{}/children?cursor=00",
+      inscription_id(1)
+    ));
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
   }
 
   #[test]
-  fn dont_show_rare_txt_in_header_without_sat_index() {
-    TestServer::new().assert_response_regex(
-      "/",
-      StatusCode::OK,
-      ".*
-      <a href=/clock>Clock</a>
-      <form action=/search method=get>.*",
-    );
+  fn tx_preview_rejects_oversized_requests() {
+    let test_server = TestServer::new();
+
+    let response = reqwest::blocking::Client::new()
+      .post(test_server.join_url("/api/v1/tx/preview"))
+      .json(&serde_json::json!({
+        "transaction": "00".repeat(preview::MAX_PREVIEW_REQUEST_SIZE / 2),
+      }))
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
   }
 
   #[test]
-  fn input() {
+  fn batch_balance() {
+    let test_server = TestServer::new();
+    test_server.mine_blocks(1);
+    test_server.index.update().unwrap();
+
+    let client = reqwest::blocking::Client::new();
+
+    let response = client
+      .post(test_server.join_url("/api/v1/brc20/batch/balance"))
+      .json(&serde_json::json!({
+        "addresses": [
+          "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+          "bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4",
+        ],
+      }))
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = response.json::<serde_json::Value>().unwrap();
+    assert_eq!(json["height"], 1);
+    assert_eq!(
+      json["data"]["accounts"][1]["address"],
+      "bc1qhvd6suvqzjcu9pxjhrwhtrlj85ny3n2mqql5w4"
+    );
+    assert_eq!(
+      json["data"]["accounts"][1]["balance"],
+      serde_json::json!([])
+    );
+
+    let response = client
+      .post(test_server.join_url("/api/v1/brc20s/batch/balance"))
+      .json(&serde_json::json!({
+        "addresses": ["bcrt1qs758ursh4q9z627kt3pp5yysm78ddny6txaqgw"],
+      }))
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+  }
+
+  #[test]
+  fn inscription_children_and_ancestry() {
+    let server = TestServer::new_with_regtest_with_json_api();
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "root").to_witness())],
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+    let root = InscriptionId { txid, index: 0 };
+
+    let child = |height, parent: InscriptionId| {
+      let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[
+          (
+            height,
+            0,
+            0,
+            Inscription {
+              content_type: Some("text/plain".into()),
+              body: Some("child".into()),
+              parent: Some(parent.parent_value()),
+              unrecognized_even_field: false,
+            }
+            .to_witness(),
+          ),
+          (height, 1, 0, Default::default()),
+        ],
+        ..Default::default()
+      });
+      server.mine_blocks(1);
+      InscriptionId { txid, index: 0 }
+    };
+
+    let child_id = child(2, root);
+    let grandchild_id = child(3, child_id);
+
+    let json = server
+      .get(format!("/api/v1/ord/id/{root}/children"))
+      .json::<serde_json::Value>()
+      .unwrap();
+    assert_eq!(
+      json["data"]["children"],
+      serde_json::json!([{ "id": child_id.to_string(), "number": 1 }])
+    );
+    assert_eq!(json["data"]["next"], serde_json::Value::Null);
+
+    let json = server
+      .get(format!("/api/v1/ord/id/{grandchild_id}/children"))
+      .json::<serde_json::Value>()
+      .unwrap();
+    assert_eq!(json["data"]["children"], serde_json::json!([]));
+
+    let json = server
+      .get(format!("/api/v1/ord/id/{grandchild_id}/ancestry"))
+      .json::<serde_json::Value>()
+      .unwrap();
+    assert_eq!(
+      json["data"],
+      serde_json::json!({
+        "ancestors": [
+          { "id": child_id.to_string(), "number": 1 },
+          { "id": root.to_string(), "number": 0 },
+        ],
+        "root": { "id": root.to_string(), "number": 0 },
+      })
+    );
+
+    let json = server
+      .get(format!("/api/v1/ord/id/{root}/ancestry"))
+      .json::<serde_json::Value>()
+      .unwrap();
+    assert_eq!(json["data"]["ancestors"], serde_json::json!([]));
+    assert_eq!(
+      json["data"]["root"],
+      serde_json::json!({ "id": root.to_string(), "number": 0 })
+    );
+
+    assert_eq!(
+      server
+        .get(format!("/api/v1/ord/id/{}/ancestry", inscription_id(9)))
+        .status(),
+      StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+      server.get("/api/v1/ord/id/foo/children").status(),
+      StatusCode::BAD_REQUEST
+    );
+  }
+
+  #[test]
+  fn api_responses_carry_height_and_honor_min_height() {
+    let test_server = TestServer::new();
+    test_server.mine_blocks(2);
+
+    let response = test_server.get("/api/v1/node/info");
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = response.json::<serde_json::Value>().unwrap();
+    assert_eq!(json["height"], 2);
+    assert_eq!(
+      json["blockHash"],
+      test_server
+        .index
+        .block_hash(Some(2))
+        .unwrap()
+        .unwrap()
+        .to_string()
+    );
+
+    let response = test_server.get("/api/v1/brc20/tick?min_height=2");
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = response.json::<serde_json::Value>().unwrap();
+    assert_eq!(json["height"], 2);
+    assert!(json["data"].get("height").is_none());
+
+    let response = test_server.get("/api/v1/brc20/tick/abcd?min_height=2");
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let json = response.json::<serde_json::Value>().unwrap();
+    assert_eq!(json["code"], 3);
+
+    let response = test_server.get("/api/v1/node/info?min_height=3");
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(response.headers()[reqwest::header::RETRY_AFTER], "1");
+    let json = response.json::<serde_json::Value>().unwrap();
+    assert_eq!(json["code"], 4);
+
+    let response = test_server.get("/api/v1/node/info?min_height=tip");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+  }
+
+  #[test]
+  fn rpc() {
+    let test_server = TestServer::new();
+    test_server.mine_blocks(1);
+    test_server.index.update().unwrap();
+
+    let rpc = |body: serde_json::Value| {
+      reqwest::blocking::Client::new()
+        .post(test_server.join_url("/rpc"))
+        .json(&body)
+        .send()
+        .unwrap()
+    };
+
+    let json = rpc(serde_json::json!({
+      "jsonrpc": "2.0",
+      "method": "node_getInfo",
+      "id": 1,
+    }))
+    .json::<serde_json::Value>()
+    .unwrap();
+    assert_eq!(json["jsonrpc"], "2.0");
+    assert_eq!(json["id"], 1);
+    assert_eq!(json["result"]["chainInfo"]["ordHeight"], 1);
+
+    let json = rpc(serde_json::json!([
+      { "jsonrpc": "2.0", "method": "brc20_getTick", "params": { "tick": "abcd" }, "id": 2 },
+      { "jsonrpc": "2.0", "method": "brc20_getAllTicks", "params": { "limit": 1 }, "id": "3" },
+      { "jsonrpc": "2.0", "method": "brc20_getBalance", "params": ["abcd"], "id": 4 },
+      { "jsonrpc": "2.0", "method": "brc20_nope", "id": 5 },
+      { "jsonrpc": "2.0", "method": "node_getInfo" },
+    ]))
+    .json::<serde_json::Value>()
+    .unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 4);
+    assert_eq!(json[0]["id"], 2);
+    assert_eq!(json[0]["error"]["code"], 3);
+    assert_eq!(json[1]["id"], "3");
+    assert_eq!(json[1]["result"]["tokens"], serde_json::json!([]));
+    assert_eq!(json[2]["error"]["code"], -32602);
+    assert_eq!(json[3]["error"]["code"], -32601);
+
+    let response = rpc(serde_json::json!({ "jsonrpc": "2.0", "method": "node_getInfo" }));
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let json = reqwest::blocking::Client::new()
+      .post(test_server.join_url("/rpc"))
+      .body("{")
+      .send()
+      .unwrap()
+      .json::<serde_json::Value>()
+      .unwrap();
+    assert_eq!(json["error"]["code"], -32700);
+    assert_eq!(json["id"], serde_json::Value::Null);
+  }
+
+  #[test]
+  fn rare_with_index() {
+    TestServer::new_with_sat_index().assert_response(
+      "/rare.txt",
+      StatusCode::OK,
+      "sat\tsatpoint
+0\t4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0
+",
+    );
+  }
+
+  #[test]
+  fn rare_without_sat_index() {
+    TestServer::new().assert_response(
+      "/rare.txt",
+      StatusCode::NOT_FOUND,
+      "tracking rare sats requires index created with `--index-sats` flag",
+    );
+  }
+
+  #[test]
+  fn show_rare_txt_in_header_with_sat_index() {
+    TestServer::new_with_sat_index().assert_response_regex(
+      "/",
+      StatusCode::OK,
+      ".*
+      <a href=/clock>Clock</a>
+      <a href=/rare.txt>rare.txt</a>
+      <form action=/search method=get>.*",
+    );
+  }
+
+  #[test]
+  fn rare_sat_location() {
+    TestServer::new_with_sat_index().assert_response_regex(
+      "/sat/0",
+      StatusCode::OK,
+      ".*>4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0<.*",
+    );
+  }
+
+  #[test]
+  fn dont_show_rare_txt_in_header_without_sat_index() {
+    TestServer::new().assert_response_regex(
+      "/",
+      StatusCode::OK,
+      ".*
+      <a href=/clock>Clock</a>
+      <form action=/search method=get>.*",
+    );
+  }
+
+  #[test]
+  fn input() {
     TestServer::new().assert_response_regex(
       "/input/0/0/0",
       StatusCode::OK,
@@ -2956,6 +3797,92 @@ mod tests {
     );
   }
 
+  #[test]
+  fn brc20_feed() {
+    let (server, _, _) = brc20_test_server();
+
+    server.assert_response_regex(
+      "/brc20/feed.xml",
+      StatusCode::OK,
+      ".*<title>BRC20 Deployments – Regtest</title>.*<item><title>BRC20 ordi deployed</title><link>/brc20/ordi</link><description><!\\[CDATA\\[Deployed by bcrt1q.* at height 2 with a supply of 21000000 and a limit per mint of 1000.\\]\\]></description>.*",
+    );
+  }
+
+  #[test]
+  fn brc20_feed_includes_deploys_of_old_blocks() {
+    let (server, _, _) = brc20_test_server();
+    server.mine_blocks(100);
+
+    server.assert_response_regex(
+      "/brc20/feed.xml",
+      StatusCode::OK,
+      ".*<item><title>BRC20 ordi deployed</title>.*at height 2 .*",
+    );
+  }
+
+  #[test]
+  fn brc20_transfer_feed() {
+    let (server, _, _) = brc20_test_server();
+
+    server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        3,
+        0,
+        0,
+        inscription(
+          "text/plain;charset=utf-8",
+          r#"{"p":"brc-20","op":"transfer","tick":"ordi","amt":"10"}"#,
+        )
+        .to_witness(),
+      )],
+      recipient: Some(regtest_address()),
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+
+    let transfer = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(4, 1, 0, Default::default())],
+      recipient: Some(Address::new(Network::Regtest, recipient().payload)),
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+
+    server.assert_response_regex(
+      "/brc20/ordi/feed.xml",
+      StatusCode::OK,
+      "<\\?xml.*<title>BRC20 ordi Transfers – Regtest</title>.*<generator>ord</generator></channel></rss>",
+    );
+
+    let response = server.get("/brc20/ordi/feed.xml");
+    assert!(!response.text().unwrap().contains("<item>"));
+
+    server.assert_response_regex(
+      "/brc20/ordi/feed.xml?min=5",
+      StatusCode::OK,
+      format!(
+        ".*<item><title>10 ordi transferred</title><link>/tx/{transfer}</link><description><!\\[CDATA\\[Transferred from bcrt1q.* to bcrt1q.* at height 5 by inscription [[:xdigit:]]{{64}}i0.\\]\\]></description>.*"
+      ),
+    );
+
+    server.assert_response_regex(
+      "/brc20/ordi/feed.xml?min=1.5.5",
+      StatusCode::BAD_REQUEST,
+      "invalid minimum amount 1.5.5: .*",
+    );
+  }
+
+  #[test]
+  fn brc20s_feed() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    server.assert_response_regex(
+      "/brc20s/feed.xml",
+      StatusCode::OK,
+      ".*<title>BRC20S Deployments – Regtest</title>.*",
+    );
+  }
+
   #[test]
   fn inscription_with_unknown_type_and_no_body_has_unknown_preview() {
     let server = TestServer::new_with_regtest_with_index_sats();
@@ -3158,6 +4085,320 @@ mod tests {
     );
   }
 
+  fn regtest_address() -> Address {
+    Address::new(Network::Regtest, address().payload)
+  }
+
+  fn brc20_test_server() -> (TestServer, InscriptionId, InscriptionId) {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--enable-index-brc20"],
+      &[],
+    );
+    server.mine_blocks(1);
+
+    let deploy = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        1,
+        0,
+        0,
+        inscription(
+          "text/plain;charset=utf-8",
+          r#"{"p":"brc-20","op":"deploy","tick":"ordi","max":"21000000","lim":"1000"}"#,
+        )
+        .to_witness(),
+      )],
+      recipient: Some(regtest_address()),
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+
+    let mint = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        2,
+        0,
+        0,
+        inscription(
+          "text/plain;charset=utf-8",
+          r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"1000"}"#,
+        )
+        .to_witness(),
+      )],
+      recipient: Some(regtest_address()),
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+
+    (
+      server,
+      InscriptionId {
+        txid: deploy,
+        index: 0,
+      },
+      InscriptionId {
+        txid: mint,
+        index: 0,
+      },
+    )
+  }
+
+  #[test]
+  fn brc20_tick_page() {
+    let (server, deploy, mint) = brc20_test_server();
+    let address = regtest_address();
+
+    server.assert_response_regex(
+      "/brc20/ORDI",
+      StatusCode::OK,
+      format!(
+        ".*<title>BRC20 ordi</title>.*
+<h1>BRC20 ordi</h1>
+<dl>
+  <dt>supply</dt>
+  <dd>21000000</dd>
+  <dt>minted</dt>
+  <dd>1000</dd>
+  <dt>limit per mint</dt>
+  <dd>1000</dd>
+  <dt>decimals</dt>
+  <dd>18</dd>
+  <dt>deployer</dt>
+  <dd><a class=monospace href=/address/{address}>{address}</a></dd>
+  <dt>deploy inscription</dt>
+  <dd><a class=monospace href=/inscription/{deploy}>{deploy}</a></dd>
+  <dt>deploy height</dt>
+  <dd><a href=/block/2>2</a></dd>
+.*<h2>Recent Events</h2>
+<ul>
+  <li>
+    <a class=monospace href=/tx/{}>.*</a>
+    <dl>
+      <dt>height</dt><dd><a href=/block/3>3</a></dd>
+      <dt>event</dt><dd>mint</dd>
+      <dt>amount</dt><dd>1000</dd>
+.*<dt>event</dt><dd>deploy</dd>.*
+</ul>.*",
+        mint.txid
+      ),
+    );
+  }
+
+  #[test]
+  fn brc20_tick_page_lists_events_of_old_blocks() {
+    let (server, _, mint) = brc20_test_server();
+    server.mine_blocks(100);
+
+    server.assert_response_regex(
+      "/brc20/ordi",
+      StatusCode::OK,
+      format!(
+        ".*<h2>Recent Events</h2>
+<ul>
+  <li>
+    <a class=monospace href=/tx/{}>.*</a>
+    <dl>
+      <dt>height</dt><dd><a href=/block/3>3</a></dd>
+.*<dt>event</dt><dd>deploy</dd>.*
+</ul>.*",
+        mint.txid
+      ),
+    );
+  }
+
+  #[test]
+  fn brc20_tick_page_not_found() {
+    let (server, _, _) = brc20_test_server();
+
+    server.assert_response(
+      "/brc20/pepe",
+      StatusCode::NOT_FOUND,
+      "brc20 tick pepe not found",
+    );
+  }
+
+  #[test]
+  fn address_page() {
+    let (server, deploy, mint) = brc20_test_server();
+    let address = regtest_address();
+
+    server.assert_response_regex(
+      format!("/address/{address}"),
+      StatusCode::OK,
+      format!(
+        ".*<h1>Address <span class=monospace>{address}</span></h1>
+<h2>2 Inscriptions</h2>
+<div class=thumbnails>
+  <a href=/inscription/({deploy}|{mint})>.*</a>
+  <a href=/inscription/({deploy}|{mint})>.*</a>
+</div>
+<h2>1 BRC20 Balance</h2>
+<ul>
+  <li>
+    <a href=/brc20/ordi>ordi</a>
+    <dl>
+      <dt>available</dt><dd>1000</dd>
+      <dt>transferable</dt><dd>0</dd>
+      <dt>overall</dt><dd>1000</dd>
+    </dl>
+  </li>
+</ul>
+<h2>0 BRC20 Transferable Inscriptions</h2>.*"
+      ),
+    );
+
+    server.assert_response_regex(
+      format!("/inscription/{mint}"),
+      StatusCode::OK,
+      format!(
+        ".*<dt>address</dt>
+  <dd><a class=monospace href=/address/{address}>{address}</a></dd>.*
+  <dt>brc20</dt>
+  <dd><a href=/brc20/ordi>ordi</a></dd>.*"
+      ),
+    );
+  }
+
+  #[test]
+  fn address_inscriptions() {
+    let (server, deploy, mint) = brc20_test_server();
+    let address = regtest_address();
+
+    let transfer = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        3,
+        0,
+        0,
+        inscription(
+          "text/plain;charset=utf-8",
+          r#"{"p":"brc-20","op":"transfer","tick":"ordi","amt":"10"}"#,
+        )
+        .to_witness(),
+      )],
+      recipient: Some(address.clone()),
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+    let transfer = InscriptionId {
+      txid: transfer,
+      index: 0,
+    };
+
+    let ids = |query: &str| {
+      let json = server
+        .get(format!("/api/v1/ord/address/{address}/inscriptions{query}"))
+        .json::<serde_json::Value>()
+        .unwrap();
+      let mut ids = json["data"]["inscriptions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|inscription| inscription["id"].as_str().unwrap().to_string())
+        .collect::<Vec<String>>();
+      ids.sort();
+      ids
+    };
+
+    let sorted = |inscription_ids: &[InscriptionId]| {
+      let mut ids = inscription_ids
+        .iter()
+        .map(InscriptionId::to_string)
+        .collect::<Vec<String>>();
+      ids.sort();
+      ids
+    };
+
+    assert_eq!(ids(""), sorted(&[deploy, mint, transfer]));
+    assert_eq!(
+      ids("?content_type=text/plain"),
+      sorted(&[deploy, mint, transfer])
+    );
+    assert_eq!(ids("?content_type=image/png"), sorted(&[]));
+    assert_eq!(ids("?brc20_transferable=true"), sorted(&[transfer]));
+    assert_eq!(ids("?bitmap=true"), sorted(&[]));
+
+    let json = server
+      .get(format!(
+        "/api/v1/ord/address/{address}/inscriptions?limit=2"
+      ))
+      .json::<serde_json::Value>()
+      .unwrap();
+    assert_eq!(json["data"]["inscriptions"].as_array().unwrap().len(), 2);
+    let next = json["data"]["next"].as_str().unwrap().to_string();
+    assert_eq!(
+      server
+        .get(format!(
+          "/api/v1/ord/address/{address}/inscriptions?limit=2&cursor={next}"
+        ))
+        .json::<serde_json::Value>()
+        .unwrap()["data"]["inscriptions"]
+        .as_array()
+        .unwrap()
+        .len(),
+      1
+    );
+
+    server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(4, 1, 0, Default::default())],
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+
+    assert_eq!(ids(""), sorted(&[deploy, mint]));
+    assert_eq!(ids("?brc20_transferable=true"), sorted(&[]));
+
+    assert_eq!(
+      server
+        .get("/api/v1/ord/address/bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4/inscriptions")
+        .status(),
+      StatusCode::BAD_REQUEST
+    );
+  }
+
+  #[test]
+  fn address_page_rejects_other_networks() {
+    let (server, _, _) = brc20_test_server();
+
+    server.assert_response_regex(
+      format!("/address/{}", address()),
+      StatusCode::BAD_REQUEST,
+      "invalid address .*",
+    );
+  }
+
+  #[test]
+  fn brc20s_pages_not_found() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--enable-index-brc20s"],
+      &[],
+    );
+    server.mine_blocks(1);
+
+    server.assert_response(
+      "/brc20s/tick/13395c5283",
+      StatusCode::NOT_FOUND,
+      "brc20s tick 13395c5283 not found",
+    );
+
+    server.assert_response(
+      "/brc20s/pool/13395c5283%2301",
+      StatusCode::NOT_FOUND,
+      "brc20s pool 13395c5283#01 not found",
+    );
+
+    server.assert_response_regex(
+      "/brc20s/tick/xyz",
+      StatusCode::BAD_REQUEST,
+      "invalid tick id xyz: .*",
+    );
+  }
+
   #[test]
   fn brc20_endpoint() {
     let test_server = TestServer::new();
@@ -1,13 +1,98 @@
-use {super::*, utoipa::IntoParams};
+use {
+  super::*,
+  crate::okx::datastore::page::{Cursor, PageRequest},
+  utoipa::IntoParams,
+};
+
+/// Page size of list endpoints when the request does not give a limit.
+pub(super) const DEFAULT_PAGE_LIMIT: usize = 100;
+/// Largest page size list endpoints return, whatever limit is requested.
+pub(super) const MAX_PAGE_LIMIT: usize = 1000;
 
 #[derive(Deserialize, IntoParams)]
 pub struct Pagination {
+  /// Cursor of the page to resume from, as returned in `next` by the previous page.
+  pub cursor: Option<String>,
   /// Start index of the result.
   pub start: Option<usize>,
-  /// Limit of the result.
+  /// Limit of the result, 100 by default and at most 1000.
   pub limit: Option<usize>,
 }
 
+impl Pagination {
+  pub(super) fn request(&self) -> Result<PageRequest, ApiError> {
+    Ok(PageRequest {
+      cursor: self
+        .cursor
+        .as_deref()
+        .map(Cursor::from_str)
+        .transpose()
+        .map_err(ApiError::bad_request)?,
+      skip: self.start.unwrap_or(0),
+      limit: Some(self.limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT)),
+    })
+  }
+}
+
+#[derive(Deserialize)]
+pub struct HeightGuard {
+  /// Fail with a retryable error until the index has reached this height.
+  pub min_height: Option<u64>,
+}
+
+/// Answers `/api/v1` requests asking for a `min_height` the index has not
+/// reached yet with a retryable error.
+pub(super) async fn require_min_height<B>(
+  Extension(index): Extension<Arc<Index>>,
+  request: Request<B>,
+  next: Next<B>,
+) -> Response {
+  let min_height = match Query::<HeightGuard>::try_from_uri(request.uri()) {
+    Ok(Query(HeightGuard { min_height })) => min_height,
+    Err(err) => return ApiError::bad_request(err.body_text()).into_response(),
+  };
+
+  if let Some(min_height) = min_height {
+    let height = match index.block_height() {
+      Ok(height) => height,
+      Err(err) => return ApiError::from(err).into_response(),
+    };
+
+    if height.map_or(true, |height| height.n() < min_height) {
+      return ApiError::unavailable(format!(
+        "index is at height {}, behind the requested minimum {min_height}",
+        height.map_or_else(|| "none".into(), |height| height.to_string())
+      ))
+      .into_response();
+    }
+  }
+
+  next.run(request).await
+}
+
+/// Largest number of addresses a batch request may query.
+pub(super) const MAX_BATCH_ADDRESSES: usize = 1000;
+
+pub(super) fn parse_batch_addresses(
+  addresses: &[String],
+  network: Network,
+) -> Result<Vec<bitcoin::Address>, ApiError> {
+  if addresses.len() > MAX_BATCH_ADDRESSES {
+    return Err(ApiError::bad_request(format!(
+      "at most {MAX_BATCH_ADDRESSES} addresses can be queried at once"
+    )));
+  }
+
+  addresses
+    .iter()
+    .map(|address| {
+      Address::from_str(address)
+        .and_then(|address| address.require_network(network))
+        .map_err(|err| ApiError::bad_request(format!("invalid address {address}: {err}")))
+    })
+    .collect()
+}
+
 pub(crate) type ApiResult<T> = Result<axum::Json<ApiResponse<T>>, ApiError>;
 
 pub(super) trait ApiOptionExt<T> {
@@ -1,4 +1,9 @@
-use {super::*, crate::okx::datastore::brc20::Tick, axum::Json, utoipa::ToSchema};
+use {
+  super::*,
+  crate::okx::datastore::{brc20::Tick, ScriptKey},
+  axum::Json,
+  utoipa::ToSchema,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -47,20 +52,23 @@ pub(crate) async fn brc20_balance(
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
 
-  let balance = index
-    .brc20_get_balance_by_address(&tick, &address)?
-    .ok_or_api_not_found(BRC20Error::BalanceNotFound)?;
+  let (balance, block) = index
+    .read_at_height(|rtx| rtx.brc20_balance(&ScriptKey::from_address(address.clone()), &tick))?;
+  let balance = balance.ok_or_api_not_found(BRC20Error::BalanceNotFound)?;
 
   let available_balance = balance.overall_balance - balance.transferable_balance;
 
   log::debug!("rpc: get brc20_balance: {} {} {:?}", tick, address, balance);
 
-  Ok(Json(ApiResponse::ok(Balance {
-    tick: balance.tick.to_string(),
-    available_balance: available_balance.to_string(),
-    transferable_balance: balance.transferable_balance.to_string(),
-    overall_balance: balance.overall_balance.to_string(),
-  })))
+  Ok(Json(
+    ApiResponse::ok(Balance {
+      tick: balance.tick.to_string(),
+      available_balance: available_balance.to_string(),
+      transferable_balance: balance.transferable_balance.to_string(),
+      overall_balance: balance.overall_balance.to_string(),
+    })
+    .at(block),
+  ))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -69,6 +77,8 @@ pub(crate) async fn brc20_balance(
 pub struct AllBalance {
   #[schema(value_type = Vec<brc20::Balance>)]
   pub balance: Vec<Balance>,
+  /// Cursor of the next page, absent on the last page.
+  pub next: Option<String>,
 }
 
 /// Get all ticker balances of the address.
@@ -78,7 +88,8 @@ pub struct AllBalance {
     get,
     path = "/api/v1/brc20/address/{address}/balance",
     params(
-        ("address" = String, Path, description = "Address")
+        ("address" = String, Path, description = "Address"),
+        Pagination
   ),
     responses(
       (status = 200, description = "Obtain account balances by query address.", body = BRC20AllBalance),
@@ -90,6 +101,7 @@ pub struct AllBalance {
 pub(crate) async fn brc20_all_balance(
   Extension(index): Extension<Arc<Index>>,
   Path(address): Path<String>,
+  Query(page): Query<Pagination>,
 ) -> ApiResult<AllBalance> {
   log::debug!("rpc: get brc20_all_balance: {}", address);
 
@@ -97,19 +109,24 @@ pub(crate) async fn brc20_all_balance(
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
 
-  let all_balance = index.brc20_get_all_balance_by_address(&address)?;
+  let (all_balance, block) = index.brc20_get_all_balance_by_address(&address, &page.request()?)?;
 
   log::debug!("rpc: get brc20_all_balance: {} {:?}", address, all_balance);
 
-  Ok(Json(ApiResponse::ok(AllBalance {
-    balance: all_balance
-      .iter()
-      .map(|bal| Balance {
-        tick: bal.tick.to_string(),
-        available_balance: (bal.overall_balance - bal.transferable_balance).to_string(),
-        transferable_balance: bal.transferable_balance.to_string(),
-        overall_balance: bal.overall_balance.to_string(),
-      })
-      .collect(),
-  })))
+  Ok(Json(
+    ApiResponse::ok(AllBalance {
+      balance: all_balance
+        .items
+        .iter()
+        .map(|bal| Balance {
+          tick: bal.tick.to_string(),
+          available_balance: (bal.overall_balance - bal.transferable_balance).to_string(),
+          transferable_balance: bal.transferable_balance.to_string(),
+          overall_balance: bal.overall_balance.to_string(),
+        })
+        .collect(),
+      next: all_balance.next.map(|cursor| cursor.to_string()),
+    })
+    .at(block),
+  ))
 }
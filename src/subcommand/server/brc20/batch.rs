@@ -0,0 +1,98 @@
+use {super::*, crate::okx::datastore::brc20::Tick, axum::Json, utoipa::ToSchema};
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20::BatchBalanceRequest)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchBalanceRequest {
+  /// Addresses to query, at most 1000.
+  pub addresses: Vec<String>,
+  /// Tickers to restrict the result to. All tickers are returned if absent.
+  pub ticks: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20::AccountBalance)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBalance {
+  /// The address.
+  pub address: String,
+  #[schema(value_type = Vec<brc20::Balance>)]
+  pub balance: Vec<Balance>,
+  #[schema(value_type = Vec<brc20::TransferableInscription>)]
+  pub transferable: Vec<TransferableInscription>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20::BatchBalance)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchBalance {
+  /// Accounts in the order of the requested addresses.
+  #[schema(value_type = Vec<brc20::AccountBalance>)]
+  pub accounts: Vec<AccountBalance>,
+}
+
+/// Get balances of many addresses.
+///
+/// Retrieve the balances and transferable inscriptions of a list of addresses, all read at the same height.
+#[utoipa::path(
+    post,
+    path = "/api/v1/brc20/batch/balance",
+    request_body = brc20::BatchBalanceRequest,
+    responses(
+      (status = 200, description = "Obtain balances of the addresses.", body = BRC20BatchBalance),
+      (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+      (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+    )
+  )]
+pub(crate) async fn brc20_batch_balance(
+  Extension(index): Extension<Arc<Index>>,
+  Json(request): Json<BatchBalanceRequest>,
+) -> ApiResult<BatchBalance> {
+  log::debug!(
+    "rpc: get brc20_batch_balance: {} addresses",
+    request.addresses.len()
+  );
+
+  let addresses = parse_batch_addresses(&request.addresses, index.get_chain_network())?;
+  let ticks = request
+    .ticks
+    .map(|ticks| {
+      ticks
+        .iter()
+        .map(|tick| Tick::from_str(tick))
+        .collect::<Result<Vec<_>, _>>()
+    })
+    .transpose()
+    .map_err(|_| ApiError::bad_request(BRC20Error::IncorrectTickFormat))?;
+
+  let (accounts, block) = index.brc20_batch_accounts(&addresses, ticks.as_deref())?;
+
+  Ok(Json(
+    ApiResponse::ok(BatchBalance {
+      accounts: request
+        .addresses
+        .into_iter()
+        .zip(accounts)
+        .map(|(address, account)| AccountBalance {
+          address,
+          balance: account
+            .balances
+            .iter()
+            .map(|bal| Balance {
+              tick: bal.tick.to_string(),
+              available_balance: (bal.overall_balance - bal.transferable_balance).to_string(),
+              transferable_balance: bal.transferable_balance.to_string(),
+              overall_balance: bal.overall_balance.to_string(),
+            })
+            .collect(),
+          transferable: account
+            .transferable
+            .iter()
+            .map(|trans| trans.into())
+            .collect(),
+        })
+        .collect(),
+    })
+    .at(block),
+  ))
+}
@@ -1,5 +1,6 @@
 use super::{types::ScriptPubkey, *};
 mod balance;
+mod batch;
 mod receipt;
 mod ticker;
 mod transaction;
@@ -21,4 +22,4 @@ pub(super) enum BRC20Error {
   BlockNotFound,
 }
 
-pub(super) use {balance::*, receipt::*, ticker::*, transaction::*, transferable::*};
+pub(super) use {balance::*, batch::*, receipt::*, ticker::*, transaction::*, transferable::*};
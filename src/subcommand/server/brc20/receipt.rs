@@ -277,16 +277,18 @@ pub(crate) async fn brc20_tx_events(
 ) -> ApiResult<TxEvents> {
   log::debug!("rpc: get brc20_tx_events: {}", txid);
   let txid = bitcoin::Txid::from_str(&txid).map_err(|e| ApiError::bad_request(e.to_string()))?;
-  let tx_events = index
-    .brc20_get_tx_events_by_txid(&txid)?
-    .ok_or_api_not_found(BRC20Error::EventsNotFound)?;
+  let (tx_events, block) = index.brc20_get_tx_events_by_txid(&txid)?;
+  let tx_events = tx_events.ok_or_api_not_found(BRC20Error::EventsNotFound)?;
 
   log::debug!("rpc: get brc20_tx_events: {} {:?}", txid, tx_events);
 
-  Ok(Json(ApiResponse::ok(TxEvents {
-    txid: txid.to_string(),
-    events: tx_events.iter().map(|e| e.into()).collect(),
-  })))
+  Ok(Json(
+    ApiResponse::ok(TxEvents {
+      txid: txid.to_string(),
+      events: tx_events.iter().map(|e| e.into()).collect(),
+    })
+    .at(block),
+  ))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -295,6 +297,8 @@ pub(crate) async fn brc20_tx_events(
 pub struct BlockEvents {
   #[schema(value_type = Vec<brc20::TxEvents>)]
   pub block: Vec<TxEvents>,
+  /// Cursor of the next page, absent on the last page.
+  pub next: Option<String>,
 }
 
 /// Get block events by blockhash.
@@ -304,7 +308,8 @@ pub struct BlockEvents {
     get,
     path = "/api/v1/brc20/block/{blockhash}/events",
     params(
-        ("blockhash" = String, Path, description = "block hash")
+        ("blockhash" = String, Path, description = "block hash"),
+        Pagination
   ),
     responses(
       (status = 200, description = "Obtain block events by block hash", body = BRC20BlockEvents),
@@ -316,6 +321,7 @@ pub struct BlockEvents {
 pub(crate) async fn brc20_block_events(
   Extension(index): Extension<Arc<Index>>,
   Path(blockhash): Path<String>,
+  Query(page): Query<Pagination>,
 ) -> ApiResult<BlockEvents> {
   log::debug!("rpc: get brc20_block_events: {}", blockhash);
 
@@ -337,8 +343,8 @@ pub(crate) async fn brc20_block_events(
     return Err(ApiError::NotFound(BRC20Error::BlockNotFound.to_string()));
   }
 
-  let block_events = index
-    .brc20_get_txs_events(&blockinfo.tx)
+  let (block_events, block) = index
+    .brc20_get_txs_events(&blockinfo.tx, &page.request()?)
     .map_err(ApiError::internal)?;
 
   log::debug!(
@@ -347,13 +353,18 @@ pub(crate) async fn brc20_block_events(
     block_events
   );
 
-  Ok(Json(ApiResponse::ok(BlockEvents {
-    block: block_events
-      .iter()
-      .map(|(txid, events)| TxEvents {
-        txid: txid.to_string(),
-        events: events.iter().map(|e| e.into()).collect(),
-      })
-      .collect(),
-  })))
+  Ok(Json(
+    ApiResponse::ok(BlockEvents {
+      block: block_events
+        .items
+        .iter()
+        .map(|(txid, events)| TxEvents {
+          txid: txid.to_string(),
+          events: events.iter().map(|e| e.into()).collect(),
+        })
+        .collect(),
+      next: block_events.next.map(|cursor| cursor.to_string()),
+    })
+    .at(block),
+  ))
 }
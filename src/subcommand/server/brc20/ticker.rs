@@ -93,13 +93,12 @@ pub(crate) async fn brc20_tick_info(
   log::debug!("rpc: get brc20_tick_info: {}", tick);
   let tick =
     Tick::from_str(&tick).map_err(|_| ApiError::bad_request(BRC20Error::IncorrectTickFormat))?;
-  let tick_info = index
-    .brc20_get_tick_info(&tick)?
-    .ok_or_api_not_found(BRC20Error::TickNotFound)?;
+  let (tick_info, block) = index.read_at_height(|rtx| rtx.brc20_tick_info(&tick))?;
+  let tick_info = tick_info.ok_or_api_not_found(BRC20Error::TickNotFound)?;
 
   log::debug!("rpc: get brc20_tick_info: {:?} {:?}", tick, tick_info);
 
-  Ok(Json(ApiResponse::ok(tick_info.into())))
+  Ok(Json(ApiResponse::ok(tick_info.into()).at(block)))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -108,6 +107,8 @@ pub(crate) async fn brc20_tick_info(
 pub struct AllTickInfo {
   #[schema(value_type = Vec<brc20::TickInfo>)]
   pub tokens: Vec<TickInfo>,
+  /// Cursor of the next page, absent on the last page.
+  pub next: Option<String>,
 }
 
 /// Get all tickers info.
@@ -116,6 +117,9 @@ pub struct AllTickInfo {
 #[utoipa::path(
     get,
     path = "/api/v1/brc20/tick",
+    params(
+        Pagination
+  ),
     responses(
       (status = 200, description = "Obtain matching all BRC20 tickers.", body = BRC20AllTick),
       (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
@@ -125,12 +129,17 @@ pub struct AllTickInfo {
   )]
 pub(crate) async fn brc20_all_tick_info(
   Extension(index): Extension<Arc<Index>>,
+  Query(page): Query<Pagination>,
 ) -> ApiResult<AllTickInfo> {
   log::debug!("rpc: get brc20_all_tick_info");
-  let all_tick_info = index.brc20_get_all_tick_info()?;
+  let (all_tick_info, block) = index.brc20_get_all_tick_info(&page.request()?)?;
   log::debug!("rpc: get brc20_all_tick_info: {:?}", all_tick_info);
 
-  Ok(Json(ApiResponse::ok(AllTickInfo {
-    tokens: all_tick_info.into_iter().map(|t| t.into()).collect(),
-  })))
+  Ok(Json(
+    ApiResponse::ok(AllTickInfo {
+      tokens: all_tick_info.items.into_iter().map(|t| t.into()).collect(),
+      next: all_tick_info.next.map(|cursor| cursor.to_string()),
+    })
+    .at(block),
+  ))
 }
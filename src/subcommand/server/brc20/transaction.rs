@@ -129,21 +129,21 @@ pub(crate) async fn brc20_tx(
   log::debug!("rpc: get brc20_tx: {}", txid);
   let txid = bitcoin::Txid::from_str(&txid).map_err(|e| ApiError::bad_request(e.to_string()))?;
 
-  let tx_info = get_operations_by_txid(&index, &txid, true)?;
+  let (tx_info, block) = get_operations_by_txid(&index, &txid, true)?;
 
   if tx_info.inscriptions.is_empty() {
     return Err(ApiError::not_found(BRC20Error::OperationNotFound));
   }
 
   log::debug!("rpc: get brc20_tx: {} {:?}", txid, tx_info);
-  Ok(Json(ApiResponse::ok(tx_info)))
+  Ok(Json(ApiResponse::ok(tx_info).at(block)))
 }
 
 fn get_operations_by_txid(
   index: &Arc<Index>,
   txid: &bitcoin::Txid,
   with_unconfirmed: bool,
-) -> Result<TxInscriptionInfo> {
+) -> Result<(TxInscriptionInfo, Option<(Height, BlockHash)>)> {
   let mut brc20_operation_infos = Vec::new();
 
   let tx_result = index
@@ -159,7 +159,8 @@ fn get_operations_by_txid(
     .map(|i| i.inscription)
     .collect::<Vec<Inscription>>();
 
-  let rtx = index.begin_read()?.0;
+  let rtx = index.begin_read()?;
+  let block = rtx.latest_block()?;
   let brc20_store = brc20_db::DataStoreReader::new(&rtx);
   for operation in operations {
     match brc20_proto::Message::resolve(&brc20_store, &new_inscriptions, &operation)? {
@@ -196,12 +197,15 @@ fn get_operations_by_txid(
     };
   }
   // if the transaction is not confirmed, try to parsing protocol
-  Ok(TxInscriptionInfo {
-    txid: txid.to_string(),
-    blockhash: tx_result.blockhash.map(|v| v.to_string()),
-    confirmed: tx_result.blockhash.is_some(),
-    inscriptions: brc20_operation_infos,
-  })
+  Ok((
+    TxInscriptionInfo {
+      txid: txid.to_string(),
+      blockhash: tx_result.blockhash.map(|v| v.to_string()),
+      confirmed: tx_result.blockhash.is_some(),
+      inscriptions: brc20_operation_infos,
+    },
+    block,
+  ))
 }
 #[cfg(test)]
 mod tests {
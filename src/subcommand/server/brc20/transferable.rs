@@ -37,7 +37,8 @@ impl From<&brc20_store::TransferableLog> for TransferableInscription {
   path = "/api/v1/brc20/tick/{ticker}/address/{address}/transferable",
   params(
       ("ticker" = String, Path, description = "Token ticker", min_length = 4, max_length = 4),
-      ("address" = String, Path, description = "Address")
+      ("address" = String, Path, description = "Address"),
+      Pagination
 ),
   responses(
     (status = 200, description = "Obtain account transferable inscriptions of ticker.", body = BRC20Transferable),
@@ -49,6 +50,7 @@ impl From<&brc20_store::TransferableLog> for TransferableInscription {
 pub(crate) async fn brc20_transferable(
   Extension(index): Extension<Arc<Index>>,
   Path((tick, address)): Path<(String, String)>,
+  Query(page): Query<Pagination>,
 ) -> ApiResult<TransferableInscriptions> {
   log::debug!("rpc: get brc20_transferable: {tick} {address}");
 
@@ -59,16 +61,25 @@ pub(crate) async fn brc20_transferable(
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
 
-  let transferable = index.brc20_get_tick_transferable_by_address(&tick, &address)?;
+  let (transferable, block) =
+    index.brc20_get_tick_transferable_by_address(&tick, &address, &page.request()?)?;
 
   log::debug!(
     "rpc: get brc20_transferable: {tick} {address} {:?}",
     transferable
   );
 
-  Ok(Json(ApiResponse::ok(TransferableInscriptions {
-    inscriptions: transferable.iter().map(|trans| trans.into()).collect(),
-  })))
+  Ok(Json(
+    ApiResponse::ok(TransferableInscriptions {
+      inscriptions: transferable
+        .items
+        .iter()
+        .map(|trans| trans.into())
+        .collect(),
+      next: transferable.next.map(|cursor| cursor.to_string()),
+    })
+    .at(block),
+  ))
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -77,6 +88,8 @@ pub(crate) async fn brc20_transferable(
 pub struct TransferableInscriptions {
   #[schema(value_type = Vec<brc20::TransferableInscription>)]
   pub inscriptions: Vec<TransferableInscription>,
+  /// Cursor of the next page, absent on the last page.
+  pub next: Option<String>,
 }
 
 /// Get the balance of ticker of the address.
@@ -86,7 +99,8 @@ pub struct TransferableInscriptions {
   get,
   path = "/api/v1/brc20/address/{address}/transferable",
   params(
-      ("address" = String, Path, description = "Address")
+      ("address" = String, Path, description = "Address"),
+      Pagination
 ),
   responses(
     (status = 200, description = "Obtain account all transferable inscriptions.", body = BRC20Transferable),
@@ -98,6 +112,7 @@ pub struct TransferableInscriptions {
 pub(crate) async fn brc20_all_transferable(
   Extension(index): Extension<Arc<Index>>,
   Path(address): Path<String>,
+  Query(page): Query<Pagination>,
 ) -> ApiResult<TransferableInscriptions> {
   log::debug!("rpc: get brc20_all_transferable: {address}");
 
@@ -105,13 +120,22 @@ pub(crate) async fn brc20_all_transferable(
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
 
-  let transferable = index.brc20_get_all_transferable_by_address(&address)?;
+  let (transferable, block) =
+    index.brc20_get_all_transferable_by_address(&address, &page.request()?)?;
   log::debug!(
     "rpc: get brc20_all_transferable: {address} {:?}",
     transferable
   );
 
-  Ok(Json(ApiResponse::ok(TransferableInscriptions {
-    inscriptions: transferable.iter().map(|trans| trans.into()).collect(),
-  })))
+  Ok(Json(
+    ApiResponse::ok(TransferableInscriptions {
+      inscriptions: transferable
+        .items
+        .iter()
+        .map(|trans| trans.into())
+        .collect(),
+      next: transferable.next.map(|cursor| cursor.to_string()),
+    })
+    .at(block),
+  ))
 }
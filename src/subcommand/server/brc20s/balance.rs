@@ -1,4 +1,4 @@
-use {super::*, axum::Json, utoipa::ToSchema};
+use {super::*, crate::okx::datastore::ScriptKey, axum::Json, utoipa::ToSchema};
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[schema(as = brc20s::Balance)]
@@ -70,15 +70,16 @@ pub(crate) async fn brc20s_balance(
   let address: bitcoin::Address = Address::from_str(&address)
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
-  let balance = &index
-    .brc20s_balance(&tick_id, &address)?
-    .ok_or_api_not_found(BRC20SError::BalanceNotFound)?;
+  let ((balance, tick_info), block) = index.read_at_height(|rtx| {
+    Ok((
+      rtx.brc20s_balance(&ScriptKey::from_address(address.clone()), &tick_id)?,
+      rtx.brc20s_tick_info(&tick_id)?,
+    ))
+  })?;
+  let balance = balance.ok_or_api_not_found(BRC20SError::BalanceNotFound)?;
+  let tick_info = tick_info.ok_or_api_not_found(BRC20SError::TickIdNotFound)?;
 
-  let mut balance_result = Balance::from(balance);
-
-  let tick_info = &index
-    .brc20s_tick_info(&tick_id)?
-    .ok_or_api_not_found(BRC20SError::TickIdNotFound)?;
+  let mut balance_result = Balance::from(&balance);
 
   balance_result.set_tick_name(tick_info.name.as_str().to_string());
   log::debug!(
@@ -87,7 +88,7 @@ pub(crate) async fn brc20s_balance(
     balance_result
   );
 
-  Ok(Json(ApiResponse::ok(balance_result)))
+  Ok(Json(ApiResponse::ok(balance_result).at(block)))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -96,6 +97,8 @@ pub(crate) async fn brc20s_balance(
 pub(crate) struct AllBalance {
   #[schema(value_type = Vec<brc20s::Balance>)]
   pub balance: Vec<Balance>,
+  /// Cursor of the next page, absent on the last page.
+  pub next: Option<String>,
 }
 // brc20s/address/:address/balance
 /// Get all ticker balances of the address.
@@ -105,7 +108,8 @@ pub(crate) struct AllBalance {
   get,
   path = "/api/v1/brc20s/address/{address}/balance",
   params(
-      ("address" = String, Path, description = "Address")
+      ("address" = String, Path, description = "Address"),
+      Pagination
 ),
   responses(
     (status = 200, description = "Obtain account balances by query address.", body = BRC20SAllBalance),
@@ -117,6 +121,7 @@ pub(crate) struct AllBalance {
 pub(crate) async fn brc20s_all_balance(
   Extension(index): Extension<Arc<Index>>,
   Path(address): Path<String>,
+  Query(page): Query<Pagination>,
 ) -> ApiResult<AllBalance> {
   log::debug!("rpc: get brc20s_all_balance: {}", address);
 
@@ -124,28 +129,34 @@ pub(crate) async fn brc20s_all_balance(
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
 
-  let all_balance = index.brc20s_all_balance(&address)?;
+  let (all_balance, block) = index.brc20s_all_balance(&address, &page.request()?)?;
 
   log::debug!("rpc: get brc20s_all_balance: {} {:?}", address, all_balance);
 
-  Ok(Json(ApiResponse::ok(AllBalance {
-    balance: all_balance
-      .iter()
-      .map(|(tick_id, balance)| {
-        let mut balance_result = Balance::from(balance);
-
-        let tick_info = &index.brc20s_tick_info(tick_id).unwrap().unwrap();
-
-        balance_result.set_tick_name(tick_info.name.as_str().to_string());
-        log::debug!(
-          "rpc: get brc20s_userinfo: {:?} {:?}",
-          tick_id,
+  Ok(Json(
+    ApiResponse::ok(AllBalance {
+      balance: all_balance
+        .items
+        .iter()
+        .map(|balance| {
+          let tick_id = &balance.tick_id;
+          let mut balance_result = Balance::from(balance);
+
+          let tick_info = &index.brc20s_tick_info(tick_id).unwrap().unwrap();
+
+          balance_result.set_tick_name(tick_info.name.as_str().to_string());
+          log::debug!(
+            "rpc: get brc20s_userinfo: {:?} {:?}",
+            tick_id,
+            balance_result
+          );
           balance_result
-        );
-        balance_result
-      })
-      .collect(),
-  })))
+        })
+        .collect(),
+      next: all_balance.next.map(|cursor| cursor.to_string()),
+    })
+    .at(block),
+  ))
 }
 // brc20s/debug/tick/:tickId/address/:address/balance
 pub(crate) async fn brc20s_debug_balance(
@@ -163,9 +174,8 @@ pub(crate) async fn brc20s_debug_balance(
   let address: bitcoin::Address = Address::from_str(&address)
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
-  let balance = index
-    .brc20s_balance(&tick_id, &address)?
-    .ok_or_api_not_found(BRC20SError::BalanceNotFound)?;
+  let (balance, block) = index.brc20s_balance(&tick_id, &address)?;
+  let balance = balance.ok_or_api_not_found(BRC20SError::BalanceNotFound)?;
 
   log::debug!(
     "rpc: get brc20s_debug_balance: {:?} {:?}",
@@ -173,5 +183,5 @@ pub(crate) async fn brc20s_debug_balance(
     balance
   );
 
-  Ok(Json(ApiResponse::ok(balance)))
+  Ok(Json(ApiResponse::ok(balance).at(block)))
 }
@@ -0,0 +1,124 @@
+use {
+  super::*,
+  axum::Json,
+  brc20s::{Pid, TickId},
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::BatchBalanceRequest)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BatchBalanceRequest {
+  /// Addresses to query, at most 1000.
+  pub addresses: Vec<String>,
+  /// Ticker IDs to restrict balances and transferable inscriptions to. All tickers are returned if absent.
+  pub tick_ids: Option<Vec<String>>,
+  /// Pool IDs to restrict positions to. All pools are returned if absent.
+  pub pids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::AccountBalance)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AccountBalance {
+  /// The address.
+  pub address: String,
+  #[schema(value_type = Vec<brc20s::Balance>)]
+  pub balance: Vec<Balance>,
+  #[schema(value_type = Vec<brc20s::TransferableInscription>)]
+  pub transferable: Vec<TransferableInscription>,
+  /// The positions of the address in the pools it staked in.
+  #[schema(value_type = Vec<brc20s::UserInfo>)]
+  pub positions: Vec<UserInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = brc20s::BatchBalance)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BatchBalance {
+  /// Accounts in the order of the requested addresses.
+  #[schema(value_type = Vec<brc20s::AccountBalance>)]
+  pub accounts: Vec<AccountBalance>,
+}
+
+// brc20s/batch/balance
+/// Get balances and pool positions of many addresses.
+///
+/// Retrieve the balances, transferable inscriptions and pool positions of a list of addresses, all read at the same height.
+#[utoipa::path(
+  post,
+  path = "/api/v1/brc20s/batch/balance",
+  request_body = brc20s::BatchBalanceRequest,
+  responses(
+    (status = 200, description = "Obtain balances and positions of the addresses.", body = BRC20SBatchBalance),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn brc20s_batch_balance(
+  Extension(index): Extension<Arc<Index>>,
+  Json(request): Json<BatchBalanceRequest>,
+) -> ApiResult<BatchBalance> {
+  log::debug!(
+    "rpc: get brc20s_batch_balance: {} addresses",
+    request.addresses.len()
+  );
+
+  let addresses = parse_batch_addresses(&request.addresses, index.get_chain_network())?;
+  let tick_ids = request
+    .tick_ids
+    .map(|tick_ids| {
+      tick_ids
+        .iter()
+        .map(|tick_id| TickId::from_str(tick_id))
+        .collect::<Result<Vec<_>, _>>()
+    })
+    .transpose()
+    .map_err(|_| ApiError::bad_request(BRC20SError::IncorrectTickIdFormat))?;
+  let pids = request
+    .pids
+    .map(|pids| {
+      pids
+        .iter()
+        .map(|pid| Pid::from_str(pid))
+        .collect::<Result<Vec<_>, _>>()
+    })
+    .transpose()
+    .map_err(|_| ApiError::bad_request(BRC20SError::IncorrectPidFormat))?;
+
+  let (accounts, block) =
+    index.brc20s_batch_accounts(&addresses, tick_ids.as_deref(), pids.as_deref())?;
+
+  Ok(Json(
+    ApiResponse::ok(BatchBalance {
+      accounts: request
+        .addresses
+        .into_iter()
+        .zip(accounts)
+        .map(|(address, account)| AccountBalance {
+          address,
+          balance: account
+            .balances
+            .iter()
+            .map(|(balance, name)| {
+              let mut balance = Balance::from(balance);
+              balance.set_tick_name(name.as_str().to_string());
+              balance
+            })
+            .collect(),
+          transferable: account
+            .transferable
+            .iter()
+            .map(|(asset, name, number)| {
+              let mut inscription = TransferableInscription::from(asset);
+              inscription.set_tick_name(name.as_str().to_string());
+              inscription.set_inscription_number(*number);
+              inscription
+            })
+            .collect(),
+          positions: account.positions.iter().map(UserInfo::from).collect(),
+        })
+        .collect(),
+    })
+    .at(block),
+  ))
+}
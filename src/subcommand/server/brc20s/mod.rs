@@ -5,13 +5,14 @@ use {
 };
 
 mod balance;
+mod batch;
 mod pool;
 mod receipt;
 mod reward;
 mod ticker;
 mod transferable;
 
-pub(super) use {balance::*, pool::*, receipt::*, reward::*, ticker::*, transferable::*};
+pub(super) use {balance::*, batch::*, pool::*, receipt::*, reward::*, ticker::*, transferable::*};
 
 #[derive(Debug, thiserror::Error)]
 pub enum BRC20SError {
@@ -121,9 +121,14 @@ pub(crate) async fn brc20s_pool_info(
   let pid =
     Pid::from_str(&pid).map_err(|_| ApiError::bad_request(BRC20SError::IncorrectPidFormat))?;
 
-  let pool_info = &index
-    .brc20s_pool_info(&pid)?
-    .ok_or_api_not_found(BRC20SError::PoolInfoNotFound)?;
+  let ((pool_info, tick_info), block) = index.read_at_height(|rtx| {
+    let Some(pool_info) = rtx.brc20s_pool_info(&pid)? else {
+      return Ok((None, None));
+    };
+    let tick_info = rtx.brc20s_tick_info(&TickId::from(pid.clone()))?;
+    Ok((Some(pool_info), tick_info))
+  })?;
+  let pool_info = &pool_info.ok_or_api_not_found(BRC20SError::PoolInfoNotFound)?;
 
   log::debug!(
     "rpc: get brc20s_pool_info: {:?} {:?}",
@@ -135,11 +140,7 @@ pub(crate) async fn brc20s_pool_info(
     return Err(ApiError::internal("db: not match"));
   }
 
-  let tick_id = TickId::from(pid);
-
-  let tick_info = &index
-    .brc20s_tick_info(&tick_id)?
-    .ok_or_api_not_found(BRC20SError::TickIdNotFound)?;
+  let tick_info = &tick_info.ok_or_api_not_found(BRC20SError::TickIdNotFound)?;
 
   let inscription_number = &index
     .get_inscription_entry(pool_info.inscription_id)
@@ -151,7 +152,7 @@ pub(crate) async fn brc20s_pool_info(
   pool.set_inscription_num(inscription_number.number);
   pool.set_deployer(tick_info.deployer.clone().into());
 
-  Ok(Json(ApiResponse::ok(pool)))
+  Ok(Json(ApiResponse::ok(pool).at(block)))
 }
 
 pub(crate) async fn brc20s_debug_pool_info(
@@ -163,9 +164,8 @@ pub(crate) async fn brc20s_debug_pool_info(
   let pid =
     Pid::from_str(&pid).map_err(|_| ApiError::bad_request(BRC20SError::IncorrectPidFormat))?;
 
-  let pool_info = index
-    .brc20s_pool_info(&pid)?
-    .ok_or_api_not_found(BRC20SError::PoolInfoNotFound)?;
+  let (pool_info, block) = index.brc20s_pool_info(&pid)?;
+  let pool_info = pool_info.ok_or_api_not_found(BRC20SError::PoolInfoNotFound)?;
 
   log::debug!(
     "rpc: get brc20s_debug_pool_info: {:?} {:?}",
@@ -173,7 +173,7 @@ pub(crate) async fn brc20s_debug_pool_info(
     pool_info
   );
 
-  Ok(Json(ApiResponse::ok(pool_info)))
+  Ok(Json(ApiResponse::ok(pool_info).at(block)))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -183,6 +183,8 @@ pub(crate) struct AllPoolInfo {
   #[schema(value_type = Vec<brc20s::Pool>)]
   pub pools: Vec<Pool>,
   pub total: usize,
+  /// Cursor of the next page, absent on the last page.
+  pub next: Option<String>,
 }
 
 // brc20s/pool
@@ -205,29 +207,34 @@ pub(crate) async fn brc20s_all_pool_info(
   Query(page): Query<Pagination>,
 ) -> ApiResult<AllPoolInfo> {
   log::debug!("rpc: get brc20s_all_pool_info");
-  let (all_pool_info, total) = index.brc20s_all_pool_info(page.start.unwrap_or(0), page.limit)?;
+  let ((all_pool_info, total), block) = index.brc20s_all_pool_info(&page.request()?)?;
   log::debug!("rpc: get brc20s_all_pool_info: {:?}", all_pool_info);
-  Ok(Json(ApiResponse::ok(AllPoolInfo {
-    pools: all_pool_info
-      .iter()
-      .map(|pool| {
-        let tick_id = TickId::from(pool.pid.clone());
-        let tick_info = &index.brc20s_tick_info(&tick_id).unwrap().unwrap();
-
-        let inscription_number = &index
-          .get_inscription_entry(pool.inscription_id)
-          .unwrap()
-          .unwrap();
-
-        let mut pool_result = Pool::from(pool);
-        pool_result.set_earn(tick_info.tick_id.hex(), tick_info.name.as_str().to_string());
-        pool_result.set_inscription_num(inscription_number.number);
-        pool_result.set_deployer(tick_info.deployer.clone().into());
-        pool_result
-      })
-      .collect(),
-    total,
-  })))
+  Ok(Json(
+    ApiResponse::ok(AllPoolInfo {
+      pools: all_pool_info
+        .items
+        .iter()
+        .map(|pool| {
+          let tick_id = TickId::from(pool.pid.clone());
+          let tick_info = &index.brc20s_tick_info(&tick_id).unwrap().unwrap();
+
+          let inscription_number = &index
+            .get_inscription_entry(pool.inscription_id)
+            .unwrap()
+            .unwrap();
+
+          let mut pool_result = Pool::from(pool);
+          pool_result.set_earn(tick_info.tick_id.hex(), tick_info.name.as_str().to_string());
+          pool_result.set_inscription_num(inscription_number.number);
+          pool_result.set_deployer(tick_info.deployer.clone().into());
+          pool_result
+        })
+        .collect(),
+      total,
+      next: all_pool_info.next.map(|cursor| cursor.to_string()),
+    })
+    .at(block),
+  ))
 }
 
 // /brc20s/pool/tid/:tick_id
@@ -239,6 +246,7 @@ pub(crate) async fn brc20s_all_pool_info(
   path = "/api/v1/brc20s/pool/tid/{tid}",
   params(
       ("tid" = String, Path, description = "Ticker ID", min_length = 10, max_length = 10, example= "a01234567f"),
+      Pagination
 ),
   responses(
     (status = 200, description = "Obtain pool infomation by ticker ID", body = BRC20SAllPool),
@@ -250,37 +258,44 @@ pub(crate) async fn brc20s_all_pool_info(
 pub(crate) async fn brc20s_all_pools_by_tid(
   Extension(index): Extension<Arc<Index>>,
   Path(tick_id): Path<String>,
+  Query(page): Query<Pagination>,
 ) -> ApiResult<AllPoolInfo> {
   log::debug!("rpc: get brc20s_all_pools_by_tid: {}", tick_id);
 
   let tick_id = TickId::from_str(&tick_id)
     .map_err(|_| ApiError::bad_request(BRC20SError::IncorrectTickIdFormat))?;
-  let all_pool_info = index.brc20s_all_pools_by_tid(&tick_id)?;
+  let ((all_pool_info, total), block) =
+    index.brc20s_all_pools_by_tid(&tick_id, &page.request()?)?;
 
   let _ = index
     .brc20s_tick_info(&tick_id)?
     .ok_or_api_not_found(BRC20SError::TickIdNotFound)?;
 
   log::debug!("rpc: get brc20s_all_pools_by_tid: {:?}", all_pool_info);
-  Ok(Json(ApiResponse::ok(AllPoolInfo {
-    pools: all_pool_info
-      .iter()
-      .map(|pool| {
-        let tick_id = TickId::from(pool.pid.clone());
-        let tick_info = &index.brc20s_tick_info(&tick_id).unwrap().unwrap();
-
-        let inscription_number = &index
-          .get_inscription_entry(pool.inscription_id)
-          .unwrap()
-          .unwrap();
-
-        let mut pool_result = Pool::from(pool);
-        pool_result.set_earn(tick_info.tick_id.hex(), tick_info.name.as_str().to_string());
-        pool_result.set_inscription_num(inscription_number.number);
-        pool_result.set_deployer(tick_info.deployer.clone().into());
-        pool_result
-      })
-      .collect(),
-    total: all_pool_info.len(),
-  })))
+  Ok(Json(
+    ApiResponse::ok(AllPoolInfo {
+      pools: all_pool_info
+        .items
+        .iter()
+        .map(|pool| {
+          let tick_id = TickId::from(pool.pid.clone());
+          let tick_info = &index.brc20s_tick_info(&tick_id).unwrap().unwrap();
+
+          let inscription_number = &index
+            .get_inscription_entry(pool.inscription_id)
+            .unwrap()
+            .unwrap();
+
+          let mut pool_result = Pool::from(pool);
+          pool_result.set_earn(tick_info.tick_id.hex(), tick_info.name.as_str().to_string());
+          pool_result.set_inscription_num(inscription_number.number);
+          pool_result.set_deployer(tick_info.deployer.clone().into());
+          pool_result
+        })
+        .collect(),
+      total,
+      next: all_pool_info.next.map(|cursor| cursor.to_string()),
+    })
+    .at(block),
+  ))
 }
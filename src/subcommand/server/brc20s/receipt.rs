@@ -30,6 +30,14 @@ pub struct Receipt {
 
 impl Receipt {
   pub(super) fn from(receipt: &brc20s::Receipt, index: Arc<Index>) -> Result<Self> {
+    Self::new(receipt, &|tick_id| index.brc20s_tick_info(tick_id))
+  }
+
+  /// Converts `receipt`, looking up the names of the ticks its events refer to with `tick_info`.
+  pub(crate) fn new(
+    receipt: &brc20s::Receipt,
+    tick_info: &impl Fn(&brc20s::TickId) -> Result<Option<brc20s::TickInfo>>,
+  ) -> Result<Self> {
     let mut result = Self {
       op: receipt.op.clone().into(),
       inscription_number: match receipt.op {
@@ -71,7 +79,7 @@ impl Receipt {
           brc20s::Event::DeployPool(deploy_pool) => Event::DeployPool(DeployPoolEvent::new(
             deploy_pool,
             receipt.to.clone().into(),
-            index.clone(),
+            tick_info,
           )?),
           brc20s::Event::Deposit(deposit) => {
             Event::Deposit(DepositEvent::new(deposit, receipt.to.clone().into()))
@@ -83,18 +91,14 @@ impl Receipt {
             PassiveWithdrawEvent::new(passive_withdraw, receipt.from.clone().into()),
           ),
           brc20s::Event::Mint(mint) => Event::Mint(MintEvent::new(mint, receipt.to.clone().into())),
-          brc20s::Event::InscribeTransfer(inscribe_transfer) => {
-            Event::InscribeTransfer(InscribeTransferEvent::new(
-              inscribe_transfer,
-              receipt.to.clone().into(),
-              index.clone(),
-            )?)
-          }
+          brc20s::Event::InscribeTransfer(inscribe_transfer) => Event::InscribeTransfer(
+            InscribeTransferEvent::new(inscribe_transfer, receipt.to.clone().into(), tick_info)?,
+          ),
           brc20s::Event::Transfer(transfer) => Event::Transfer(TransferEvent::new(
             transfer,
             receipt.from.clone().into(),
             receipt.to.clone().into(),
-            index.clone(),
+            tick_info,
           )?),
         });
       }
@@ -220,12 +224,11 @@ impl DeployPoolEvent {
   pub(super) fn new(
     event: brc20s::DeployPoolEvent,
     deployer: ScriptPubkey,
-    index: Arc<Index>,
+    tick_info: &impl Fn(&brc20s::TickId) -> Result<Option<brc20s::TickInfo>>,
   ) -> Result<Self> {
     let tick_id = brc20s::TickId::from(event.pid.clone());
-    let tick_info = index
-      .brc20s_tick_info(&tick_id)?
-      .ok_or(anyhow!("tick not found, pid: {}", event.pid.as_str()))?;
+    let tick_info =
+      tick_info(&tick_id)?.ok_or(anyhow!("tick not found, pid: {}", event.pid.as_str()))?;
 
     Ok(Self {
       pid: event.pid.as_str().to_string(),
@@ -352,11 +355,10 @@ impl InscribeTransferEvent {
   pub(super) fn new(
     event: brc20s::InscribeTransferEvent,
     owner: ScriptPubkey,
-    index: Arc<Index>,
+    tick_info: &impl Fn(&brc20s::TickId) -> Result<Option<brc20s::TickInfo>>,
   ) -> Result<Self> {
-    let tick_info = index
-      .brc20s_tick_info(&event.tick_id)?
-      .ok_or(anyhow!("tick not found, tid: {}", event.tick_id.hex()))?;
+    let tick_info =
+      tick_info(&event.tick_id)?.ok_or(anyhow!("tick not found, tid: {}", event.tick_id.hex()))?;
 
     Ok(Self {
       tick: Tick {
@@ -392,11 +394,10 @@ impl TransferEvent {
     event: brc20s::TransferEvent,
     from: ScriptPubkey,
     to: ScriptPubkey,
-    index: Arc<Index>,
+    tick_info: &impl Fn(&brc20s::TickId) -> Result<Option<brc20s::TickInfo>>,
   ) -> Result<Self> {
-    let tick_info = index
-      .brc20s_tick_info(&event.tick_id)?
-      .ok_or(anyhow!("tick not found, tid: {}", event.tick_id.hex()))?;
+    let tick_info =
+      tick_info(&event.tick_id)?.ok_or(anyhow!("tick not found, tid: {}", event.tick_id.hex()))?;
     Ok(Self {
       tick: Tick {
         id: event.tick_id.hex(),
@@ -442,9 +443,8 @@ pub(crate) async fn brc20s_txid_receipts(
   log::debug!("rpc: get brc20s_txid_receipts: {}", txid);
   let txid = Txid::from_str(&txid).map_err(ApiError::bad_request)?;
 
-  let all_receipt = index
-    .brc20s_txid_receipts(&txid)?
-    .ok_or_api_not_found(BRC20SError::ReceiptsNotFound)?;
+  let (all_receipt, block) = index.brc20s_txid_receipts(&txid)?;
+  let all_receipt = all_receipt.ok_or_api_not_found(BRC20SError::ReceiptsNotFound)?;
 
   log::debug!("rpc: get brc20s_txid_receipts: {:?}", all_receipt);
 
@@ -460,10 +460,13 @@ pub(crate) async fn brc20s_txid_receipts(
     }
   }
 
-  Ok(Json(ApiResponse::ok(TxReceipts {
-    receipts,
-    txid: txid.to_string(),
-  })))
+  Ok(Json(
+    ApiResponse::ok(TxReceipts {
+      receipts,
+      txid: txid.to_string(),
+    })
+    .at(block),
+  ))
 }
 
 // brc20s/debug/tx/:txid/receipts
@@ -474,13 +477,12 @@ pub(crate) async fn brc20s_debug_txid_receipts(
   log::debug!("rpc: get brc20s_debug_txid_receipts: {}", txid);
   let txid = Txid::from_str(&txid).map_err(ApiError::bad_request)?;
 
-  let all_receipt = index
-    .brc20s_txid_receipts(&txid)?
-    .ok_or_api_not_found(BRC20SError::ReceiptsNotFound)?;
+  let (all_receipt, block) = index.brc20s_txid_receipts(&txid)?;
+  let all_receipt = all_receipt.ok_or_api_not_found(BRC20SError::ReceiptsNotFound)?;
 
   log::debug!("rpc: get brc20s_debug_txid_receipts: {:?}", all_receipt);
 
-  Ok(Json(ApiResponse::ok(all_receipt)))
+  Ok(Json(ApiResponse::ok(all_receipt).at(block)))
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
@@ -489,6 +491,8 @@ pub(crate) async fn brc20s_debug_txid_receipts(
 pub struct BlockReceipts {
   #[schema(value_type = Vec<brc20s::TxReceipts>)]
   pub block: Vec<TxReceipts>,
+  /// Cursor of the next page, absent on the last page.
+  pub next: Option<String>,
 }
 
 // brc20s/block/:blockhash/receipts
@@ -499,7 +503,8 @@ pub struct BlockReceipts {
   get,
   path = "/api/v1/brc20s/block/{blockhash}/receipts",
   params(
-      ("blockhash" = String, Path, description = "block hash")
+      ("blockhash" = String, Path, description = "block hash"),
+      Pagination
 ),
   responses(
     (status = 200, description = "Obtain block receipts by block hash", body = BRC20SBlockReceipts),
@@ -511,6 +516,7 @@ pub struct BlockReceipts {
 pub(crate) async fn brc20s_block_receipts(
   Extension(index): Extension<Arc<Index>>,
   Path(blockhash): Path<String>,
+  Query(page): Query<Pagination>,
 ) -> ApiResult<BlockReceipts> {
   log::debug!("rpc: get brc20s_block_receipts: {}", blockhash);
 
@@ -534,13 +540,13 @@ pub(crate) async fn brc20s_block_receipts(
     ));
   }
 
-  let block_receipts = index
-    .brc20s_txs_receipts(&blockinfo.tx)
+  let (block_receipts, block) = index
+    .brc20s_txs_receipts(&blockinfo.tx, &page.request()?)
     .map_err(ApiError::internal)?;
   log::debug!("rpc: get brc20s_block_receipts: {:?}", block_receipts);
 
   let mut api_block_receipts = Vec::new();
-  for (txid, tx_receipts) in block_receipts.iter() {
+  for (txid, tx_receipts) in block_receipts.items.iter() {
     let mut api_tx_receipts = Vec::new();
     for receipt in tx_receipts.iter() {
       match Receipt::from(receipt, index.clone()) {
@@ -560,7 +566,11 @@ pub(crate) async fn brc20s_block_receipts(
     });
   }
 
-  Ok(Json(ApiResponse::ok(BlockReceipts {
-    block: api_block_receipts,
-  })))
+  Ok(Json(
+    ApiResponse::ok(BlockReceipts {
+      block: api_block_receipts,
+      next: block_receipts.next.map(|cursor| cursor.to_string()),
+    })
+    .at(block),
+  ))
 }
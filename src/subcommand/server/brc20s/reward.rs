@@ -124,7 +124,7 @@ pub(crate) async fn brc20s_user_pending_reward(
   let address: bitcoin::Address = Address::from_str(&address)
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
-  let (user_reward, block) = &index.brc20s_user_pending_reward(&pid, &address)?;
+  let (user_reward, block) = index.brc20s_user_pending_reward(&pid, &address)?;
 
   log::debug!(
     "rpc: get brc20s_user_pending_reward: {:?}, {:?}, {:?}",
@@ -133,10 +133,13 @@ pub(crate) async fn brc20s_user_pending_reward(
     block,
   );
 
-  Ok(Json(ApiResponse::ok(UserReward {
-    pending_reward: user_reward.clone().unwrap(),
-    block_num: block.clone().unwrap(),
-  })))
+  Ok(Json(
+    ApiResponse::ok(UserReward {
+      pending_reward: user_reward,
+      block_num: block.map_or(0, |(height, _)| height.n()).to_string(),
+    })
+    .at(block),
+  ))
 }
 
 // brc20s/pool/:pid/address/:address/userinfo
@@ -169,9 +172,8 @@ pub(crate) async fn brc20s_userinfo(
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
 
-  let user_info = &index
-    .brc20s_user_info(&pid, &address)?
-    .ok_or_api_not_found(BRC20SError::UserInfoNotFound)?;
+  let (user_info, block) = index.brc20s_user_info(&pid, &address)?;
+  let user_info = &user_info.ok_or_api_not_found(BRC20SError::UserInfoNotFound)?;
 
   log::debug!(
     "rpc: get brc20s_userinfo: {:?} {:?}",
@@ -183,7 +185,7 @@ pub(crate) async fn brc20s_userinfo(
     return Err(ApiError::internal("db: not match"));
   }
 
-  Ok(Json(ApiResponse::ok(user_info.into())))
+  Ok(Json(ApiResponse::ok(user_info.into()).at(block)))
 }
 
 // brc20s/debug/pool/:pid/address/:address/userinfo
@@ -198,9 +200,8 @@ pub(crate) async fn brc20s_debug_userinfo(
   let address: bitcoin::Address = Address::from_str(&address)
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
-  let user_info = index
-    .brc20s_user_info(&pid, &address)?
-    .ok_or_api_not_found(BRC20SError::UserInfoNotFound)?;
+  let (user_info, block) = index.brc20s_user_info(&pid, &address)?;
+  let user_info = user_info.ok_or_api_not_found(BRC20SError::UserInfoNotFound)?;
 
   log::debug!(
     "rpc: get brc20s_debug_userinfo: {:?} {:?}",
@@ -212,7 +213,7 @@ pub(crate) async fn brc20s_debug_userinfo(
     return Err(ApiError::internal("db: not match"));
   }
 
-  Ok(Json(ApiResponse::ok(user_info)))
+  Ok(Json(ApiResponse::ok(user_info).at(block)))
 }
 
 // brc20s/stake/:address/:tick
@@ -255,16 +256,16 @@ pub(crate) async fn brc20s_stake_info(
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
 
-  let stake_info = index
-    .brc20s_stake_info(&address, &PledgedTick::BRC20Tick(tick.clone()))?
-    .ok_or_api_not_found(BRC20SError::StakeInfoNotFound)?;
+  let (stake_info, block) =
+    index.brc20s_stake_info(&address, &PledgedTick::BRC20Tick(tick.clone()))?;
+  let stake_info = stake_info.ok_or_api_not_found(BRC20SError::StakeInfoNotFound)?;
 
   log::debug!("rpc: get brc20s_stake_info: {:?}", stake_info);
 
   let mut result = StakedInfo::from(&stake_info);
   result.tick = tick.to_string();
 
-  Ok(Json(ApiResponse::ok(result)))
+  Ok(Json(ApiResponse::ok(result).at(block)))
 }
 
 pub(crate) async fn brc20s_debug_stake_info(
@@ -277,11 +278,11 @@ pub(crate) async fn brc20s_debug_stake_info(
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
 
-  let stake_info = index
-    .brc20s_stake_info(&address, &PledgedTick::from_str(tick.as_str()))?
-    .ok_or_api_not_found(BRC20SError::StakeInfoNotFound)?;
+  let (stake_info, block) =
+    index.brc20s_stake_info(&address, &PledgedTick::from_str(tick.as_str()))?;
+  let stake_info = stake_info.ok_or_api_not_found(BRC20SError::StakeInfoNotFound)?;
 
   log::debug!("rpc: get brc20s_debug_stake_info: {:?}", stake_info);
 
-  Ok(Json(ApiResponse::ok(stake_info)))
+  Ok(Json(ApiResponse::ok(stake_info).at(block)))
 }
@@ -82,9 +82,8 @@ pub(crate) async fn brc20s_tick_info(
   let tick_id = brc20s::TickId::from_str(tick_id.as_str())
     .map_err(|_| ApiError::bad_request(BRC20SError::IncorrectTickIdFormat))?;
 
-  let tick_info = &index
-    .brc20s_tick_info(&tick_id)?
-    .ok_or_api_not_found(BRC20SError::TickIdNotFound)?;
+  let (tick_info, block) = index.read_at_height(|rtx| rtx.brc20s_tick_info(&tick_id))?;
+  let tick_info = &tick_info.ok_or_api_not_found(BRC20SError::TickIdNotFound)?;
 
   log::debug!("rpc: get brc20s_tick_info: {:?} {:?}", tick_id, tick_info);
 
@@ -100,7 +99,7 @@ pub(crate) async fn brc20s_tick_info(
   let mut brc20s_tick = TickInfo::from(tick_info);
   brc20s_tick.set_inscription_number(inscription_number.number);
 
-  Ok(Json(ApiResponse::ok(brc20s_tick)))
+  Ok(Json(ApiResponse::ok(brc20s_tick).at(block)))
 }
 
 // /brc20s/tick/:tickId
@@ -113,9 +112,8 @@ pub(crate) async fn brc20s_debug_tick_info(
   let tick_id = brc20s::TickId::from_str(&tick_id)
     .map_err(|_| ApiError::bad_request(BRC20SError::IncorrectTickIdFormat))?;
 
-  let tick_info = index
-    .brc20s_tick_info(&tick_id)?
-    .ok_or_api_not_found(BRC20SError::TickIdNotFound)?;
+  let (tick_info, block) = index.read_at_height(|rtx| rtx.brc20s_tick_info(&tick_id))?;
+  let tick_info = tick_info.ok_or_api_not_found(BRC20SError::TickIdNotFound)?;
 
   log::debug!(
     "rpc: get brc20s_debug_tick_info: {:?} {:?}",
@@ -123,7 +121,7 @@ pub(crate) async fn brc20s_debug_tick_info(
     tick_info
   );
 
-  Ok(Json(ApiResponse::ok(tick_info)))
+  Ok(Json(ApiResponse::ok(tick_info).at(block)))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -133,6 +131,8 @@ pub(crate) struct AllTickInfo {
   #[schema(value_type = Vec<brc20s::TickInfo>)]
   pub tokens: Vec<TickInfo>,
   pub total: usize,
+  /// Cursor of the next page, absent on the last page.
+  pub next: Option<String>,
 }
 
 // brc20s/tick
@@ -156,23 +156,28 @@ pub(crate) async fn brc20s_all_tick_info(
 ) -> ApiResult<AllTickInfo> {
   log::debug!("rpc: get brc20s_all_tick_info");
 
-  let (all_tick_info, total) = index.brc20s_all_tick_info(page.start.unwrap_or(0), page.limit)?;
+  let ((all_tick_info, total), block) = index.brc20s_all_tick_info(&page.request()?)?;
   log::debug!("rpc: get brc20s_all_tick_info: {:?}", all_tick_info);
 
-  Ok(Json(ApiResponse::ok(AllTickInfo {
-    tokens: all_tick_info
-      .iter()
-      .map(|tick_info| {
-        let inscription_number = &index
-          .get_inscription_entry(tick_info.inscription_id)
-          .unwrap()
-          .unwrap();
-
-        let mut brc20s_tick = TickInfo::from(tick_info);
-        brc20s_tick.set_inscription_number(inscription_number.number);
-        brc20s_tick
-      })
-      .collect(),
-    total,
-  })))
+  Ok(Json(
+    ApiResponse::ok(AllTickInfo {
+      tokens: all_tick_info
+        .items
+        .iter()
+        .map(|tick_info| {
+          let inscription_number = &index
+            .get_inscription_entry(tick_info.inscription_id)
+            .unwrap()
+            .unwrap();
+
+          let mut brc20s_tick = TickInfo::from(tick_info);
+          brc20s_tick.set_inscription_number(inscription_number.number);
+          brc20s_tick
+        })
+        .collect(),
+      total,
+      next: all_tick_info.next.map(|cursor| cursor.to_string()),
+    })
+    .at(block),
+  ))
 }
@@ -53,7 +53,8 @@ impl From<&brc20s::TransferableAsset> for TransferableInscription {
   path = "/api/v1/brc20s/tick/{tick_id}/address/{address}/transferable",
   params(
       ("tick_id" = String, Path, description = "Ticker ID", min_length = 10, max_length = 10, example = "a12345678f"),
-      ("address" = String, Path, description = "Address")
+      ("address" = String, Path, description = "Address"),
+      Pagination
 ),
   responses(
     (status = 200, description = "Obtain account transferable inscriptions of ticker ID.", body = BRC20STransferable),
@@ -65,6 +66,7 @@ impl From<&brc20s::TransferableAsset> for TransferableInscription {
 pub(crate) async fn brc20s_transferable(
   Extension(index): Extension<Arc<Index>>,
   Path((tick_id, address)): Path<(String, String)>,
+  Query(page): Query<Pagination>,
 ) -> ApiResult<Transferable> {
   log::debug!("rpc: get brc20s_transferable: {},{}", tick_id, address);
 
@@ -74,7 +76,8 @@ pub(crate) async fn brc20s_transferable(
   let address: bitcoin::Address = Address::from_str(&address)
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
-  let all_transfer = &index.brc20s_tickid_transferable(&tick_id, &address)?;
+  let (all_transfer, block) =
+    index.brc20s_tickid_transferable(&tick_id, &address, &page.request()?)?;
 
   log::debug!(
     "rpc: get brc20s_transferable: {:?} {:?}",
@@ -82,25 +85,30 @@ pub(crate) async fn brc20s_transferable(
     all_transfer
   );
 
-  Ok(Json(ApiResponse::ok(Transferable {
-    inscriptions: all_transfer
-      .iter()
-      .map(|asset| {
-        let mut inscription = TransferableInscription::from(asset);
-
-        let tick_info = &index.brc20s_tick_info(&asset.tick_id).unwrap().unwrap();
-
-        let inscription_number = &index
-          .get_inscription_entry(asset.inscription_id)
-          .unwrap()
-          .unwrap();
-
-        inscription.set_tick_name(tick_info.name.as_str().to_string());
-        inscription.set_inscription_number(inscription_number.number);
-        inscription
-      })
-      .collect(),
-  })))
+  Ok(Json(
+    ApiResponse::ok(Transferable {
+      inscriptions: all_transfer
+        .items
+        .iter()
+        .map(|asset| {
+          let mut inscription = TransferableInscription::from(asset);
+
+          let tick_info = &index.brc20s_tick_info(&asset.tick_id).unwrap().unwrap();
+
+          let inscription_number = &index
+            .get_inscription_entry(asset.inscription_id)
+            .unwrap()
+            .unwrap();
+
+          inscription.set_tick_name(tick_info.name.as_str().to_string());
+          inscription.set_inscription_number(inscription_number.number);
+          inscription
+        })
+        .collect(),
+      next: all_transfer.next.map(|cursor| cursor.to_string()),
+    })
+    .at(block),
+  ))
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
@@ -109,6 +117,8 @@ pub(crate) async fn brc20s_transferable(
 pub(crate) struct Transferable {
   #[schema(value_type = Vec<brc20s::TransferableInscription>)]
   pub inscriptions: Vec<TransferableInscription>,
+  /// Cursor of the next page, absent on the last page.
+  pub next: Option<String>,
 }
 
 // brc20s/address/:address/transferable
@@ -117,7 +127,8 @@ pub(crate) struct Transferable {
   get,
   path = "/api/v1/brc20s/address/{address}/transferable",
   params(
-      ("address" = String, Path, description = "Address")
+      ("address" = String, Path, description = "Address"),
+      Pagination
 ),
   responses(
     (status = 200, description = "Obtain account all transferable inscriptions.", body = BRC20STransferable),
@@ -129,6 +140,7 @@ pub(crate) struct Transferable {
 pub(crate) async fn brc20s_all_transferable(
   Extension(index): Extension<Arc<Index>>,
   Path(address): Path<String>,
+  Query(page): Query<Pagination>,
 ) -> ApiResult<Transferable> {
   log::debug!("rpc: get brc20s_all_transferable: {}", address);
 
@@ -136,27 +148,32 @@ pub(crate) async fn brc20s_all_transferable(
     .and_then(|address| address.require_network(index.get_chain_network()))
     .map_err(ApiError::bad_request)?;
 
-  let all = index.brc20s_all_transferable(&address)?;
+  let (all, block) = index.brc20s_all_transferable(&address, &page.request()?)?;
 
   log::debug!("rpc: get brc20s_all_transferable: {} {:?}", address, all);
 
-  Ok(Json(ApiResponse::ok(Transferable {
-    inscriptions: all
-      .iter()
-      .map(|asset| {
-        let mut inscription = TransferableInscription::from(asset);
-
-        let tick_info = &index.brc20s_tick_info(&asset.tick_id).unwrap().unwrap();
-
-        let inscription_number = &index
-          .get_inscription_entry(asset.inscription_id)
-          .unwrap()
-          .unwrap();
-
-        inscription.set_tick_name(tick_info.name.as_str().to_string());
-        inscription.set_inscription_number(inscription_number.number);
-        inscription
-      })
-      .collect(),
-  })))
+  Ok(Json(
+    ApiResponse::ok(Transferable {
+      inscriptions: all
+        .items
+        .iter()
+        .map(|asset| {
+          let mut inscription = TransferableInscription::from(asset);
+
+          let tick_info = &index.brc20s_tick_info(&asset.tick_id).unwrap().unwrap();
+
+          let inscription_number = &index
+            .get_inscription_entry(asset.inscription_id)
+            .unwrap()
+            .unwrap();
+
+          inscription.set_tick_name(tick_info.name.as_str().to_string());
+          inscription.set_inscription_number(inscription_number.number);
+          inscription
+        })
+        .collect(),
+      next: all.next.map(|cursor| cursor.to_string()),
+    })
+    .at(block),
+  ))
 }
@@ -62,6 +62,10 @@ pub(crate) enum ApiError {
   /// Resource not found.
   #[schema(example = json!(&ApiError::internal("not found")))]
   NotFound(String) = 3,
+
+  /// Temporarily unavailable, the request can be retried.
+  #[schema(example = json!(&ApiError::unavailable("index is behind")))]
+  Unavailable(String) = 4,
 }
 
 impl ApiError {
@@ -70,6 +74,7 @@ impl ApiError {
       Self::Internal(_) => 1,
       Self::BadRequest(_) => 2,
       Self::NotFound(_) => 3,
+      Self::Unavailable(_) => 4,
     }
   }
 
@@ -84,12 +89,19 @@ impl ApiError {
   pub(crate) fn bad_request<S: ToString>(message: S) -> Self {
     Self::BadRequest(message.to_string())
   }
+
+  pub(crate) fn unavailable<S: ToString>(message: S) -> Self {
+    Self::Unavailable(message.to_string())
+  }
 }
 impl Serialize for ApiError {
   fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
     let mut state = serializer.serialize_struct("ApiError", 2)?;
     match self {
-      ApiError::Internal(msg) | ApiError::BadRequest(msg) | ApiError::NotFound(msg) => {
+      ApiError::Internal(msg)
+      | ApiError::BadRequest(msg)
+      | ApiError::NotFound(msg)
+      | ApiError::Unavailable(msg) => {
         state.serialize_field("code", &self.code())?;
         state.serialize_field("msg", &msg)?;
         state.end()
@@ -104,9 +116,16 @@ impl IntoResponse for ApiError {
       Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
       Self::BadRequest(_) => StatusCode::BAD_REQUEST,
       Self::NotFound(_) => StatusCode::NOT_FOUND,
+      Self::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
     };
 
-    (status_code, axum::Json(self)).into_response()
+    let mut response = (status_code, axum::Json(self)).into_response();
+    if status_code == StatusCode::SERVICE_UNAVAILABLE {
+      response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from_static("1"));
+    }
+    response
   }
 }
 
@@ -133,5 +152,9 @@ mod tests {
     let api_error = ApiError::not_found("not found");
     let json = serde_json::to_string(&api_error).unwrap();
     assert_eq!(json, r#"{"code":3,"msg":"not found"}"#);
+
+    let api_error = ApiError::unavailable("index is behind");
+    let json = serde_json::to_string(&api_error).unwrap();
+    assert_eq!(json, r#"{"code":4,"msg":"index is behind"}"#);
   }
 }
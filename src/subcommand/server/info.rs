@@ -1,4 +1,6 @@
 use super::*;
+use crate::index::ReorgEntry;
+use crate::okx::datastore::{brc20s as brc20s_store, ord::InscriptionOp};
 use axum::Json;
 use shadow_rs::shadow;
 use utoipa::{IntoParams, ToSchema};
@@ -58,7 +60,7 @@ pub(crate) async fn node_info(
 ) -> ApiResult<NodeInfo> {
   log::debug!("rpc: get node_info");
 
-  let (ord_height, btc_height) = index.height_btc(query.btc.unwrap_or_default())?;
+  let (ord_block, btc_height) = index.height_btc(query.btc.unwrap_or_default())?;
 
   let node_info = NodeInfo {
     version: Some(build::PKG_VERSION.into()),
@@ -67,10 +69,190 @@ pub(crate) async fn node_info(
     build_time: Some(build::BUILD_TIME.into()),
     chain_info: ChainInfo {
       network: Some(index.get_chain_network().to_string()),
-      ord_height: ord_height.map(|h| h.0),
+      ord_height: ord_block.map(|(height, _)| height.0),
       chain_height: btc_height.map(|h| h.0),
     },
   };
 
-  Ok(Json(ApiResponse::ok(node_info)))
+  Ok(Json(ApiResponse::ok(node_info).at(ord_block)))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedInscription {
+  /// The action of the inscription.
+  #[schema(value_type = ord::InscriptionAction)]
+  pub action: ord::InscriptionAction,
+  /// The inscription number.
+  pub inscription_number: Option<i64>,
+  /// The inscription id.
+  pub inscription_id: String,
+  /// The inscription satpoint of the transaction input.
+  pub old_satpoint: String,
+  /// The inscription satpoint of the transaction output.
+  pub new_satpoint: Option<String>,
+}
+
+impl From<InscriptionOp> for OrphanedInscription {
+  fn from(op: InscriptionOp) -> Self {
+    Self {
+      action: op.action.into(),
+      inscription_number: op.inscription_number,
+      inscription_id: op.inscription_id.to_string(),
+      old_satpoint: op.old_satpoint.to_string(),
+      new_satpoint: op.new_satpoint.map(|satpoint| satpoint.to_string()),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedTransaction {
+  /// The transaction id.
+  pub txid: String,
+  /// Protocols that had receipts for the transaction: `ord`, `brc20` or `brc20s`.
+  pub protocols: Vec<String>,
+  /// The inscriptions the transaction created or moved. Addresses are left out, since the outputs they are read from may have been rolled back.
+  #[schema(value_type = Vec<OrphanedInscription>)]
+  pub inscriptions: Vec<OrphanedInscription>,
+  /// The BRC20 events of the transaction.
+  #[schema(value_type = Vec<brc20::TxEvent>)]
+  pub brc20_events: Vec<brc20::TxEvent>,
+  /// The BRC20S receipts of the transaction.
+  #[schema(value_type = Vec<brc20s::Receipt>)]
+  pub brc20s_receipts: Vec<brc20s::Receipt>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedBlock {
+  /// The height of the orphaned block.
+  #[schema(format = "uint64")]
+  pub height: u64,
+  /// The hash of the orphaned block.
+  pub old_hash: String,
+  /// The hash of the block that replaced it, if the new chain reaches that height yet.
+  pub new_hash: Option<String>,
+  /// Transactions of the orphaned block that had protocol receipts, or null if bitcoind could no longer serve the block.
+  pub transactions: Option<Vec<OrphanedTransaction>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReorgInfo {
+  /// The height at which the reorg was detected.
+  #[schema(format = "uint64")]
+  pub height: u64,
+  /// The depth of the reorg.
+  #[schema(format = "uint64")]
+  pub depth: u64,
+  /// The unix timestamp at which the reorg was handled.
+  #[schema(format = "uint64")]
+  pub timestamp: u64,
+  /// The blocks that were rolled back.
+  pub orphaned_blocks: Vec<OrphanedBlock>,
+}
+
+impl TryFrom<ReorgEntry> for ReorgInfo {
+  type Error = Error;
+
+  fn try_from(entry: ReorgEntry) -> Result<Self> {
+    let mut orphaned_blocks = Vec::new();
+    for block in entry.orphaned_blocks {
+      // The ticks the receipts refer to were recorded along with them, as
+      // ticks deployed in the orphaned block are no longer in the index.
+      let tick_info = |tick_id: &brc20s_store::TickId| {
+        Ok(
+          block
+            .brc20s_ticks
+            .iter()
+            .find(|tick_info| tick_info.tick_id == *tick_id)
+            .cloned(),
+        )
+      };
+
+      let transactions = match block.transactions {
+        Some(transactions) => Some(
+          transactions
+            .into_iter()
+            .map(|tx| {
+              Ok(OrphanedTransaction {
+                txid: tx.txid.to_string(),
+                protocols: tx.protocols(),
+                brc20_events: tx.brc20_receipts.iter().map(brc20::TxEvent::from).collect(),
+                brc20s_receipts: tx
+                  .brc20s_receipts
+                  .iter()
+                  .map(|receipt| brc20s::Receipt::new(receipt, &tick_info))
+                  .collect::<Result<_>>()?,
+                inscriptions: tx
+                  .inscriptions
+                  .into_iter()
+                  .map(OrphanedInscription::from)
+                  .collect(),
+              })
+            })
+            .collect::<Result<_>>()?,
+        ),
+        None => None,
+      };
+
+      orphaned_blocks.push(OrphanedBlock {
+        height: block.height,
+        old_hash: block.old_hash.to_string(),
+        new_hash: block.new_hash.map(|hash| hash.to_string()),
+        transactions,
+      });
+    }
+
+    Ok(Self {
+      height: entry.height,
+      depth: entry.depth,
+      timestamp: entry.timestamp,
+      orphaned_blocks,
+    })
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Reorgs {
+  /// The reorgs handled by the indexer, most recent first.
+  pub reorgs: Vec<ReorgInfo>,
+  /// The total number of reorgs recorded.
+  pub total: usize,
+}
+
+/// Retrieve the reorgs handled by the indexer.
+///
+/// List the reorgs the indexer recovered from, most recent first, with the orphaned blocks and the receipts their transactions had, so that clients can discard data fetched from those blocks.
+#[utoipa::path(
+    get,
+    path = "/api/v1/node/reorgs",
+    params(
+        Pagination
+  ),
+    responses(
+      (status = 200, description = "Obtain the reorg history.", body = NodeReorgs),
+      (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+    )
+  )]
+pub(crate) async fn node_reorgs(
+  Extension(index): Extension<Arc<Index>>,
+  Query(page): Query<Pagination>,
+) -> ApiResult<Reorgs> {
+  log::debug!("rpc: get node_reorgs");
+
+  let ((reorgs, total), block) = index.reorg_history(page.start.unwrap_or(0), page.limit)?;
+
+  Ok(Json(
+    ApiResponse::ok(Reorgs {
+      reorgs: reorgs
+        .into_iter()
+        .map(ReorgInfo::try_from)
+        .collect::<Result<_>>()?,
+      total,
+    })
+    .at(block),
+  ))
 }
@@ -0,0 +1,83 @@
+use {
+  super::{error::ApiError, *},
+  crate::index::OwnedInscriptionFilter,
+  axum::Json,
+  utoipa::{IntoParams, ToSchema},
+};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+pub struct AddressInscriptionsQuery {
+  /// Only inscriptions with this content type, e.g. `text/plain` or `image/png`.
+  content_type: Option<String>,
+  /// Only BRC20 transfer inscriptions that can still be sent.
+  brc20_transferable: Option<bool>,
+  /// Only inscriptions that claimed a bitmap district.
+  bitmap: Option<bool>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = ord::AddressInscriptions)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressInscriptions {
+  #[schema(value_type = Vec<ord::InscriptionDigest>)]
+  /// The inscriptions held by the address.
+  pub inscriptions: Vec<InscriptionDigest>,
+  /// Cursor of the next page, absent on the last page.
+  pub next: Option<String>,
+}
+
+// /ord/address/:address/inscriptions
+/// Retrieve the inscriptions held by the specified address.
+///
+/// List the inscriptions currently held by the address, optionally narrowed down by content type, to BRC20 transferable inscriptions, or to bitmap districts.
+#[utoipa::path(
+  get,
+  path = "/api/v1/ord/address/{address}/inscriptions",
+  params(
+      ("address" = String, Path, description = "Address"),
+      AddressInscriptionsQuery,
+      Pagination
+),
+  responses(
+    (status = 200, description = "Obtain the inscriptions held by the address.", body = OrdAddressInscriptions),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn ord_address_inscriptions(
+  Extension(index): Extension<Arc<Index>>,
+  Path(address): Path<String>,
+  Query(query): Query<AddressInscriptionsQuery>,
+  Query(page): Query<Pagination>,
+) -> ApiResult<AddressInscriptions> {
+  log::debug!("rpc: get ord_address_inscriptions: {address}");
+
+  let address: bitcoin::Address = Address::from_str(&address)
+    .and_then(|address| address.require_network(index.get_chain_network()))
+    .map_err(ApiError::bad_request)?;
+
+  let filter = OwnedInscriptionFilter {
+    content_type: query.content_type,
+    brc20_transferable: query.brc20_transferable.unwrap_or_default(),
+    bitmap: query.bitmap.unwrap_or_default(),
+  };
+
+  let (inscriptions, block) =
+    index.ord_address_inscriptions(&address, &filter, &page.request()?)?;
+
+  Ok(Json(
+    ApiResponse::ok(AddressInscriptions {
+      inscriptions: inscriptions
+        .items
+        .into_iter()
+        .map(|(id, number, satpoint)| InscriptionDigest {
+          id: id.to_string(),
+          number,
+          location: satpoint.to_string(),
+        })
+        .collect(),
+      next: inscriptions.next.map(|cursor| cursor.to_string()),
+    })
+    .at(block),
+  ))
+}
@@ -87,8 +87,9 @@ pub(crate) async fn ord_inscription_number(
 }
 
 fn ord_get_inscription_by_id(index: Arc<Index>, id: InscriptionId) -> ApiResult<OrdInscription> {
-  let inscription_data = get_inscription_all_data_by_id(index.clone(), id)?
-    .ok_or_api_not_found(format!("inscriptionId not found {id}"))?;
+  let (inscription_data, block) = get_inscription_all_data_by_id(index.clone(), id)?;
+  let inscription_data =
+    inscription_data.ok_or_api_not_found(format!("inscriptionId not found {id}"))?;
   let location_outpoint = inscription_data.sat_point.outpoint;
   let mut owner = None;
   if location_outpoint != unbound_outpoint() {
@@ -121,24 +122,27 @@ fn ord_get_inscription_by_id(index: Arc<Index>, id: InscriptionId) -> ApiResult<
     };
   };
 
-  Ok(Json(ApiResponse::ok(OrdInscription {
-    id: id.to_string(),
-    number: inscription_data.entry.number,
-    content_type: inscription_data
-      .inscription
-      .content_type()
-      .map(String::from),
-    content: inscription_data.inscription.body().map(hex::encode),
-    owner,
-    genesis_height: inscription_data.entry.height,
-    location: inscription_data.sat_point.to_string(),
-    collections: inscription_data
-      .collections
-      .iter()
-      .map(|c| c.to_string())
-      .collect(),
-    sat: inscription_data.entry.sat.map(|s| s.0),
-  })))
+  Ok(Json(
+    ApiResponse::ok(OrdInscription {
+      id: id.to_string(),
+      number: inscription_data.entry.number,
+      content_type: inscription_data
+        .inscription
+        .content_type()
+        .map(String::from),
+      content: inscription_data.inscription.body().map(hex::encode),
+      owner,
+      genesis_height: inscription_data.entry.height,
+      location: inscription_data.sat_point.to_string(),
+      collections: inscription_data
+        .collections
+        .iter()
+        .map(|c| c.to_string())
+        .collect(),
+      sat: inscription_data.entry.sat.map(|s| s.0),
+    })
+    .at(block),
+  ))
 }
 
 struct InscriptionAllData {
@@ -152,37 +156,40 @@ struct InscriptionAllData {
 fn get_inscription_all_data_by_id(
   index: Arc<Index>,
   inscription_id: InscriptionId,
-) -> Result<Option<InscriptionAllData>> {
-  let entry = match index.get_inscription_entry(inscription_id)? {
-    Some(entry) => entry,
-    None => return Ok(None),
+) -> Result<(Option<InscriptionAllData>, Option<(Height, BlockHash)>)> {
+  let ((entry, sat_point), block) = index.read_at_height(|rtx| {
+    Ok((
+      rtx.inscription_entry(inscription_id)?,
+      rtx.inscription_satpoint(inscription_id)?,
+    ))
+  })?;
+  let (Some(entry), Some(sat_point)) = (entry, sat_point) else {
+    return Ok((None, block));
   };
   let tx = match index.get_transaction(inscription_id.txid)? {
     Some(tx) => tx,
-    None => return Ok(None),
+    None => return Ok((None, block)),
   };
   let inscription =
     match Inscription::from_transaction(&tx).get(usize::try_from(inscription_id.index).unwrap()) {
       Some(transaction_inscription) => transaction_inscription.inscription.clone(),
-      None => return Ok(None),
+      None => return Ok((None, block)),
     };
 
-  let sat_point = match index.get_inscription_satpoint_by_id(inscription_id)? {
-    Some(sat_point) => sat_point,
-    None => return Ok(None),
-  };
-
   let collections = index
     .ord_get_collections_by_inscription_id(inscription_id)?
     .map_or(Vec::new(), |v| v);
 
-  Ok(Some(InscriptionAllData {
-    entry,
-    tx,
-    inscription,
-    collections,
-    sat_point,
-  }))
+  Ok((
+    Some(InscriptionAllData {
+      entry,
+      tx,
+      inscription,
+      collections,
+      sat_point,
+    }),
+    block,
+  ))
 }
 
 // ord/debug/bitmap/district/:number
@@ -192,9 +199,9 @@ pub(crate) async fn ord_debug_bitmap_district(
 ) -> ApiResult<InscriptionId> {
   log::debug!("rpc: get brc20s_debug_bitmap_district: number:{}", number);
 
-  let inscription_id = index
-    .ord_get_district_inscription_id(number)?
-    .ok_or_api_not_found(format!("district {number} not found"))?;
+  let (inscription_id, block) = index.ord_get_district_inscription_id(number)?;
+  let inscription_id =
+    inscription_id.ok_or_api_not_found(format!("district {number} not found"))?;
 
   log::debug!(
     "rpc: get ord_debug_bitmap_district: {:?} {:?}",
@@ -202,7 +209,7 @@ pub(crate) async fn ord_debug_bitmap_district(
     inscription_id
   );
 
-  Ok(Json(ApiResponse::ok(inscription_id)))
+  Ok(Json(ApiResponse::ok(inscription_id).at(block)))
 }
 
 #[cfg(test)]
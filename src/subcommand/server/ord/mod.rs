@@ -3,11 +3,13 @@ use {
   crate::okx::datastore::ord::{Action, InscriptionOp},
 };
 
+mod address;
 mod inscription;
 mod outpoint;
+mod provenance;
 mod transaction;
 
-pub(super) use {inscription::*, outpoint::*, transaction::*};
+pub(super) use {address::*, inscription::*, outpoint::*, provenance::*, transaction::*};
 
 #[derive(Debug, thiserror::Error)]
 pub enum OrdError {
@@ -67,19 +67,34 @@ pub(crate) async fn ord_outpoint(
 ) -> ApiResult<OutPointResult> {
   log::debug!("rpc: get ord_outpoint: {outpoint}");
 
-  let (latest_height, latest_blockhash) = index
-    .latest_block()
-    .ok()
-    .flatten()
-    .ok_or_api_err(|| ApiError::internal("Failed to get the latest block."))?;
+  let (inscriptions, block) = index.read_at_height(|rtx| {
+    rtx
+      .inscriptions_on_output(outpoint)?
+      .into_iter()
+      .map(|(satpoint, id)| {
+        let number = rtx
+          .inscription_entry(id)?
+          .map(|entry| entry.number)
+          .ok_or(anyhow!(
+            "Failed to get the inscription number by ID, there may be an error in the database."
+          ))?;
+        Ok((satpoint, id, number))
+      })
+      .collect::<Result<Vec<_>>>()
+  })?;
+
+  let (latest_height, latest_blockhash) =
+    block.ok_or_api_err(|| ApiError::internal("Failed to get the latest block."))?;
 
-  let inscriptions = index.get_inscriptions_on_output(outpoint)?;
   if inscriptions.is_empty() {
-    return Ok(Json(ApiResponse::ok(OutPointResult {
-      result: None,
-      latest_height: latest_height.n(),
-      latest_blockhash: latest_blockhash.to_string(),
-    })));
+    return Ok(Json(
+      ApiResponse::ok(OutPointResult {
+        result: None,
+        latest_height: latest_height.n(),
+        latest_blockhash: latest_blockhash.to_string(),
+      })
+      .at(block),
+    ));
   }
 
   // Get the txout from the database store or from an RPC request.
@@ -102,33 +117,26 @@ pub(crate) async fn ord_outpoint(
     .ok_or_api_err(|| ApiError::not_found("Failed to fetch tx output."))?;
 
   let mut inscription_digests = Vec::with_capacity(inscriptions.len());
-  for id in inscriptions {
+  for (satpoint, id, number) in inscriptions {
     inscription_digests.push(InscriptionDigest {
       id: id.to_string(),
-      number: index
-        .get_inscription_entry(id)?
-        .map(|entry| entry.number)
-        .ok_or(anyhow!(
-          "Failed to get the inscription number by ID, there may be an error in the database."
-        ))?,
-      location: index
-        .get_inscription_satpoint_by_id(id)?
-        .ok_or(anyhow!(
-          "Failed to get the inscription location, there may be an error in the database."
-        ))?
-        .to_string(),
+      number,
+      location: satpoint.to_string(),
     });
   }
 
-  Ok(Json(ApiResponse::ok(OutPointResult {
-    result: Some(OutPointData {
-      txid: outpoint.txid.to_string(),
-      script_pub_key: vout.script_pubkey.to_asm_string(),
-      owner: ScriptKey::from_script(&vout.script_pubkey, index.get_chain_network()).into(),
-      value: vout.value,
-      inscription_digest: inscription_digests,
-    }),
-    latest_height: latest_height.n(),
-    latest_blockhash: latest_blockhash.to_string(),
-  })))
+  Ok(Json(
+    ApiResponse::ok(OutPointResult {
+      result: Some(OutPointData {
+        txid: outpoint.txid.to_string(),
+        script_pub_key: vout.script_pubkey.to_asm_string(),
+        owner: ScriptKey::from_script(&vout.script_pubkey, index.get_chain_network()).into(),
+        value: vout.value,
+        inscription_digest: inscription_digests,
+      }),
+      latest_height: latest_height.n(),
+      latest_blockhash: latest_blockhash.to_string(),
+    })
+    .at(block),
+  ))
 }
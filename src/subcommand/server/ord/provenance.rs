@@ -0,0 +1,131 @@
+use {
+  super::{error::ApiError, *},
+  crate::index::children,
+  axum::Json,
+  utoipa::ToSchema,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = ord::Relative)]
+#[serde(rename_all = "camelCase")]
+pub struct Relative {
+  /// The inscription id.
+  pub id: String,
+  /// The inscription number.
+  pub number: i64,
+}
+
+impl From<(InscriptionId, i64)> for Relative {
+  fn from((id, number): (InscriptionId, i64)) -> Self {
+    Self {
+      id: id.to_string(),
+      number,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = ord::Children)]
+#[serde(rename_all = "camelCase")]
+pub struct Children {
+  /// The children of the inscription.
+  pub children: Vec<Relative>,
+  /// Cursor of the next page, absent on the last page.
+  pub next: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[schema(as = ord::Ancestry)]
+#[serde(rename_all = "camelCase")]
+pub struct Ancestry {
+  /// The parent, grandparent and so on of the inscription, nearest first.
+  pub ancestors: Vec<Relative>,
+  /// The oldest ancestor, or the inscription itself if it has no parent.
+  pub root: Relative,
+}
+
+// /ord/id/:id/children
+/// Retrieve the children of the inscription with the specified inscription id.
+#[utoipa::path(
+  get,
+  path = "/api/v1/ord/id/{id}/children",
+  params(
+      ("id" = String, Path, description = "inscription ID"),
+      Pagination
+),
+  responses(
+    (status = 200, description = "Obtain the children of the inscription.", body = OrdChildren),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 404, description = "Not found.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn ord_children(
+  Extension(index): Extension<Arc<Index>>,
+  Path(id): Path<String>,
+  Query(page): Query<Pagination>,
+) -> ApiResult<Children> {
+  log::debug!("rpc: get ord_children: {id}");
+  let id = InscriptionId::from_str(&id).map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+  let page = page.request()?;
+  let start = children::start(&page).ok_or_else(|| ApiError::bad_request("invalid cursor"))?;
+
+  let (children, block) = index.ord_children(id, start, &page)?;
+  let children = children.ok_or_api_not_found(format!("inscriptionId not found {id}"))?;
+
+  Ok(Json(
+    ApiResponse::ok(Children {
+      children: children.items.into_iter().map(Relative::from).collect(),
+      next: children.next.map(|cursor| cursor.to_string()),
+    })
+    .at(block),
+  ))
+}
+
+// /ord/id/:id/ancestry
+/// Retrieve the ancestry of the inscription with the specified inscription id.
+///
+/// Walks the parents of the inscription up to the root, so collections can verify that an inscription belongs to them.
+#[utoipa::path(
+  get,
+  path = "/api/v1/ord/id/{id}/ancestry",
+  params(
+      ("id" = String, Path, description = "inscription ID")
+),
+  responses(
+    (status = 200, description = "Obtain the ancestry of the inscription.", body = OrdAncestry),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 404, description = "Not found.", body = ApiError, example = json!(&ApiError::not_found("not found"))),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn ord_ancestry(
+  Extension(index): Extension<Arc<Index>>,
+  Path(id): Path<String>,
+) -> ApiResult<Ancestry> {
+  log::debug!("rpc: get ord_ancestry: {id}");
+  let id = InscriptionId::from_str(&id).map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+  let (ancestors, block) = index.ord_ancestry(id)?;
+  let ancestors = ancestors.ok_or_api_not_found(format!("inscriptionId not found {id}"))?;
+
+  let root = match ancestors.last() {
+    Some(root) => *root,
+    None => (
+      id,
+      index
+        .get_inscription_entry(id)?
+        .ok_or_api_not_found(format!("inscriptionId not found {id}"))?
+        .number,
+    ),
+  };
+
+  Ok(Json(
+    ApiResponse::ok(Ancestry {
+      ancestors: ancestors.into_iter().map(Relative::from).collect(),
+      root: root.into(),
+    })
+    .at(block),
+  ))
+}
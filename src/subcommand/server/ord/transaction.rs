@@ -106,6 +106,8 @@ pub struct TxInscriptions {
 pub struct BlockInscriptions {
   #[schema(value_type = Vec<ord::TxInscriptions>)]
   pub block: Vec<TxInscriptions>,
+  /// Cursor of the next page, absent on the last page.
+  pub next: Option<String>,
 }
 
 // ord/tx/:txid/inscriptions
@@ -130,9 +132,8 @@ pub(crate) async fn ord_txid_inscriptions(
   log::debug!("rpc: get ord_txid_inscriptions: {}", txid);
   let txid = Txid::from_str(&txid).map_err(ApiError::bad_request)?;
 
-  let ops = index
-    .ord_txid_inscriptions(&txid)?
-    .ok_or_api_not_found(OrdError::OperationNotFound)?;
+  let (ops, block) = index.ord_txid_inscriptions(&txid)?;
+  let ops = ops.ok_or_api_not_found(OrdError::OperationNotFound)?;
 
   log::debug!("rpc: get ord_txid_inscriptions: {:?}", ops);
 
@@ -150,10 +151,13 @@ pub(crate) async fn ord_txid_inscriptions(
     }
   }
 
-  Ok(Json(ApiResponse::ok(TxInscriptions {
-    inscriptions: api_tx_inscriptions,
-    txid: txid.to_string(),
-  })))
+  Ok(Json(
+    ApiResponse::ok(TxInscriptions {
+      inscriptions: api_tx_inscriptions,
+      txid: txid.to_string(),
+    })
+    .at(block),
+  ))
 }
 
 // ord/block/:blockhash/inscriptions
@@ -162,7 +166,8 @@ pub(crate) async fn ord_txid_inscriptions(
   get,
   path = "/api/v1/ord/block/{blockhash}/inscriptions",
   params(
-      ("blockhash" = String, Path, description = "block hash")
+      ("blockhash" = String, Path, description = "block hash"),
+      Pagination
 ),
   responses(
     (status = 200, description = "Obtain inscription actions by blockhash", body = OrdBlockInscriptions),
@@ -174,6 +179,7 @@ pub(crate) async fn ord_txid_inscriptions(
 pub(crate) async fn ord_block_inscriptions(
   Extension(index): Extension<Arc<Index>>,
   Path(blockhash): Path<String>,
+  Query(page): Query<Pagination>,
 ) -> ApiResult<BlockInscriptions> {
   log::debug!("rpc: get ord_block_inscriptions: {}", blockhash);
 
@@ -195,14 +201,14 @@ pub(crate) async fn ord_block_inscriptions(
     return Err(ApiError::NotFound(OrdError::BlockNotFound.to_string()));
   }
 
-  let block_inscriptions = index
-    .ord_get_txs_inscriptions(&blockinfo.tx)
+  let (block_inscriptions, block) = index
+    .ord_get_txs_inscriptions(&blockinfo.tx, &page.request()?)
     .map_err(ApiError::internal)?;
 
   log::debug!("rpc: get ord_block_inscriptions: {:?}", block_inscriptions);
 
   let mut api_block_inscriptions = Vec::new();
-  for (txid, ops) in block_inscriptions {
+  for (txid, ops) in block_inscriptions.items {
     let mut api_tx_inscriptions = Vec::new();
     for op in ops.into_iter() {
       match TxInscription::new(op, index.clone()) {
@@ -222,9 +228,13 @@ pub(crate) async fn ord_block_inscriptions(
     });
   }
 
-  Ok(Json(ApiResponse::ok(BlockInscriptions {
-    block: api_block_inscriptions,
-  })))
+  Ok(Json(
+    ApiResponse::ok(BlockInscriptions {
+      block: api_block_inscriptions,
+      next: block_inscriptions.next.map(|cursor| cursor.to_string()),
+    })
+    .at(block),
+  ))
 }
 
 #[cfg(test)]
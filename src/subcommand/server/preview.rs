@@ -0,0 +1,183 @@
+use {
+  super::{error::ApiError, types::ScriptPubkey, *},
+  crate::okx::datastore::{brc20 as brc20_store, brc20s as brc20s_store},
+  axum::Json,
+  utoipa::ToSchema,
+};
+
+/// The largest preview request body accepted, room for a hex PSBT of a
+/// standard-size transaction together with its previous outputs.
+pub(super) const MAX_PREVIEW_REQUEST_SIZE: usize = 1 << 20;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewRequest {
+  /// A base64 or hex PSBT, or a hex raw transaction.
+  pub transaction: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BRC20BalanceChange {
+  /// The address or script pubkey hash whose balance changes.
+  pub account: ScriptPubkey,
+  /// Balance before the transaction.
+  #[schema(value_type = brc20::Balance)]
+  pub before: brc20::Balance,
+  /// Balance after the transaction.
+  #[schema(value_type = brc20::Balance)]
+  pub after: brc20::Balance,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BRC20SBalanceChange {
+  /// The address or script pubkey hash whose balance changes.
+  pub account: ScriptPubkey,
+  /// Balance before the transaction.
+  #[schema(value_type = brc20s::Balance)]
+  pub before: brc20s::Balance,
+  /// Balance after the transaction.
+  #[schema(value_type = brc20s::Balance)]
+  pub after: brc20s::Balance,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TxPreview {
+  /// The transaction id.
+  pub txid: String,
+  /// The inscriptions the transaction would create or move.
+  #[schema(value_type = Vec<ord::TxInscription>)]
+  pub inscriptions: Vec<ord::TxInscription>,
+  /// The BRC20 events the transaction would cause.
+  #[schema(value_type = Vec<brc20::TxEvent>)]
+  pub brc20_events: Vec<brc20::TxEvent>,
+  /// The BRC20S receipts the transaction would cause.
+  #[schema(value_type = Vec<brc20s::Receipt>)]
+  pub brc20s_receipts: Vec<brc20s::Receipt>,
+  /// The BRC20 balances the transaction would change.
+  pub brc20_balance_changes: Vec<BRC20BalanceChange>,
+  /// The BRC20S balances the transaction would change.
+  pub brc20s_balance_changes: Vec<BRC20SBalanceChange>,
+  /// The inscriptions the transaction would send to the miner as fees.
+  pub fee_inscriptions: Vec<String>,
+}
+
+fn brc20_balance(balance: &brc20_store::Balance) -> brc20::Balance {
+  brc20::Balance {
+    tick: balance.tick.to_string(),
+    available_balance: (balance.overall_balance - balance.transferable_balance).to_string(),
+    transferable_balance: balance.transferable_balance.to_string(),
+    overall_balance: balance.overall_balance.to_string(),
+  }
+}
+
+// tx/preview
+/// Preview the effects of a transaction.
+///
+/// Predict the inscription moves, BRC20 events, BRC20S receipts and balance changes a PSBT or unsigned transaction would cause if it were mined in the next block. Nothing is written to the index.
+#[utoipa::path(
+  post,
+  path = "/api/v1/tx/preview",
+  request_body = PreviewRequest,
+  responses(
+    (status = 200, description = "Obtain the predicted effects of the transaction.", body = TxPreviewResponse),
+    (status = 400, description = "Bad query.", body = ApiError, example = json!(&ApiError::bad_request("bad request"))),
+    (status = 413, description = "Request body too large."),
+    (status = 500, description = "Internal server error.", body = ApiError, example = json!(&ApiError::internal("internal error"))),
+  )
+)]
+pub(crate) async fn tx_preview(
+  Extension(index): Extension<Arc<Index>>,
+  Json(request): Json<PreviewRequest>,
+) -> ApiResult<TxPreview> {
+  log::debug!("rpc: get tx_preview");
+
+  let unsigned = request
+    .transaction
+    .parse::<UnsignedTransaction>()
+    .map_err(ApiError::bad_request)?;
+
+  // Previews run the inscription updater and the protocol executors, so keep
+  // them off the async workers.
+  let preview = task::spawn_blocking(move || index.preview_transaction(&unsigned))
+    .await
+    .map_err(ApiError::internal)??;
+
+  log::debug!("rpc: get tx_preview: {}", preview.txid);
+
+  let tick_info = |tick_id: &brc20s_store::TickId| {
+    Ok(
+      preview
+        .brc20s_ticks
+        .iter()
+        .find(|tick_info| tick_info.tick_id == *tick_id)
+        .cloned(),
+    )
+  };
+
+  let brc20s_balance = |balance: &brc20s_store::Balance| {
+    let mut result = brc20s::Balance::from(balance);
+    if let Ok(Some(tick_info)) = tick_info(&balance.tick_id) {
+      result.set_tick_name(tick_info.name.as_str().to_string());
+    }
+    result
+  };
+
+  Ok(Json(
+    ApiResponse::ok(TxPreview {
+      txid: preview.txid.to_string(),
+      inscriptions: preview
+        .inscriptions
+        .iter()
+        .map(|inscription| ord::TxInscription {
+          action: inscription.operation.action.clone().into(),
+          inscription_number: inscription.operation.inscription_number,
+          inscription_id: inscription.operation.inscription_id.to_string(),
+          old_satpoint: inscription.operation.old_satpoint.to_string(),
+          new_satpoint: inscription
+            .operation
+            .new_satpoint
+            .map(|satpoint| satpoint.to_string()),
+          from: inscription.from.clone().into(),
+          to: inscription.to.clone().map(Into::into),
+        })
+        .collect(),
+      brc20_events: preview
+        .brc20_receipts
+        .iter()
+        .map(|receipt| receipt.into())
+        .collect(),
+      brc20s_receipts: preview
+        .brc20s_receipts
+        .iter()
+        .map(|receipt| brc20s::Receipt::new(receipt, &tick_info))
+        .collect::<Result<_>>()?,
+      brc20_balance_changes: preview
+        .brc20_balance_changes
+        .iter()
+        .map(|change| BRC20BalanceChange {
+          account: change.script_key.clone().into(),
+          before: brc20_balance(&change.before),
+          after: brc20_balance(&change.after),
+        })
+        .collect(),
+      brc20s_balance_changes: preview
+        .brc20s_balance_changes
+        .iter()
+        .map(|change| BRC20SBalanceChange {
+          account: change.script_key.clone().into(),
+          before: brc20s_balance(&change.before),
+          after: brc20s_balance(&change.after),
+        })
+        .collect(),
+      fee_inscriptions: preview
+        .fee_inscriptions
+        .iter()
+        .map(|inscription_id| inscription_id.to_string())
+        .collect(),
+    })
+    .at(preview.block),
+  ))
+}
@@ -1,5 +1,9 @@
 use {
-  super::{info::NodeInfo, *},
+  super::{
+    info::{NodeInfo, Reorgs},
+    preview::TxPreview,
+    *,
+  },
   utoipa::ToSchema,
 };
 #[derive(Default, Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -11,6 +15,7 @@ use {
   BRC20TxEvents = ApiResponse<brc20::TxEvents>,
   BRC20BlockEvents = ApiResponse<brc20::BlockEvents>,
   BRC20Transferable = ApiResponse<brc20::TransferableInscriptions>,
+  BRC20BatchBalance = ApiResponse<brc20::BatchBalance>,
 
   BRC20STick = ApiResponse<brc20s::TickInfo>,
   BRC20SAllTick = ApiResponse<brc20s::AllTickInfo>,
@@ -23,14 +28,21 @@ use {
   BRC20STransferable = ApiResponse<brc20s::Transferable>,
   BRC20SUserInfo = ApiResponse<brc20s::UserInfo>,
   BRC20SStakedInfo = ApiResponse<brc20s::StakedInfo>,
+  BRC20SBatchBalance = ApiResponse<brc20s::BatchBalance>,
 
   OrdOrdInscription = ApiResponse<ord::OrdInscription>,
   OrdOutPointData = ApiResponse<ord::OutPointData>,
   OrdOutPointResult = ApiResponse<ord::OutPointResult>,
   OrdTxInscriptions = ApiResponse<ord::TxInscriptions>,
   OrdBlockInscriptions = ApiResponse<ord::BlockInscriptions>,
+  OrdChildren = ApiResponse<ord::Children>,
+  OrdAncestry = ApiResponse<ord::Ancestry>,
+  OrdAddressInscriptions = ApiResponse<ord::AddressInscriptions>,
 
-  Node = ApiResponse<NodeInfo>
+  TxPreviewResponse = ApiResponse<TxPreview>,
+
+  Node = ApiResponse<NodeInfo>,
+  NodeReorgs = ApiResponse<Reorgs>
 )]
 pub(crate) struct ApiResponse<T: Serialize> {
   pub code: i32,
@@ -38,6 +50,14 @@ pub(crate) struct ApiResponse<T: Serialize> {
   #[schema(example = "ok")]
   pub msg: String,
   pub data: T,
+  /// Indexed height the data was read at, absent before the first block is
+  /// indexed.
+  #[serde(default)]
+  #[schema(format = "uint64")]
+  pub height: Option<u64>,
+  /// Hash of the block at `height`.
+  #[serde(default, rename = "blockHash")]
+  pub block_hash: Option<String>,
 }
 
 impl<T> ApiResponse<T>
@@ -45,10 +65,26 @@ where
   T: Serialize,
 {
   fn new(code: i32, msg: String, data: T) -> Self {
-    Self { code, msg, data }
+    Self {
+      code,
+      msg,
+      data,
+      height: None,
+      block_hash: None,
+    }
   }
 
   pub fn ok(data: T) -> Self {
     Self::new(0, "ok".to_string(), data)
   }
+
+  /// Records the block the data was read at, which must come from the same
+  /// read transaction as the data.
+  pub fn at(self, block: Option<(Height, BlockHash)>) -> Self {
+    Self {
+      height: block.map(|(height, _)| height.n()),
+      block_hash: block.map(|(_, hash)| hash.to_string()),
+      ..self
+    }
+  }
 }
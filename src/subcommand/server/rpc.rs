@@ -0,0 +1,378 @@
+use {
+  super::*,
+  axum::{
+    body::{Body, Bytes},
+    http::Method,
+  },
+  serde_json::{json, Map, Value},
+  tower::ServiceExt,
+};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// The `/api/v1` routes served over JSON-RPC, as method name, HTTP method and
+/// path. Path parameters are taken from the call's params, by name or in order,
+/// and the remaining params become the query string, or the body of POST routes.
+const METHODS: &[(&str, Method, &str)] = &[
+  ("node_getInfo", Method::GET, "/node/info"),
+  ("node_getReorgs", Method::GET, "/node/reorgs"),
+  ("ord_getInscription", Method::GET, "/ord/id/:id/inscription"),
+  ("ord_getChildren", Method::GET, "/ord/id/:id/children"),
+  ("ord_getAncestry", Method::GET, "/ord/id/:id/ancestry"),
+  (
+    "ord_getAddressInscriptions",
+    Method::GET,
+    "/ord/address/:address/inscriptions",
+  ),
+  (
+    "ord_getInscriptionByNumber",
+    Method::GET,
+    "/ord/number/:number/inscription",
+  ),
+  (
+    "ord_getOutpoint",
+    Method::GET,
+    "/ord/outpoint/:outpoint/info",
+  ),
+  (
+    "ord_getTxInscriptions",
+    Method::GET,
+    "/ord/tx/:txid/inscriptions",
+  ),
+  (
+    "ord_getBlockInscriptions",
+    Method::GET,
+    "/ord/block/:block_hash/inscriptions",
+  ),
+  ("brc20_getTick", Method::GET, "/brc20/tick/:tick"),
+  ("brc20_getAllTicks", Method::GET, "/brc20/tick"),
+  (
+    "brc20_getBalance",
+    Method::GET,
+    "/brc20/tick/:tick/address/:address/balance",
+  ),
+  (
+    "brc20_getAllBalance",
+    Method::GET,
+    "/brc20/address/:address/balance",
+  ),
+  (
+    "brc20_getTransferable",
+    Method::GET,
+    "/brc20/tick/:tick/address/:address/transferable",
+  ),
+  (
+    "brc20_getAllTransferable",
+    Method::GET,
+    "/brc20/address/:address/transferable",
+  ),
+  (
+    "brc20_getBatchBalance",
+    Method::POST,
+    "/brc20/batch/balance",
+  ),
+  ("brc20_getTxEvents", Method::GET, "/brc20/tx/:txid/events"),
+  ("brc20_getTx", Method::GET, "/brc20/tx/:txid"),
+  (
+    "brc20_getBlockEvents",
+    Method::GET,
+    "/brc20/block/:block_hash/events",
+  ),
+  ("brc20s_getAllTicks", Method::GET, "/brc20s/tick"),
+  ("brc20s_getTick", Method::GET, "/brc20s/tick/:tick_id"),
+  ("brc20s_getAllPools", Method::GET, "/brc20s/pool"),
+  ("brc20s_getPool", Method::GET, "/brc20s/pool/:pid"),
+  (
+    "brc20s_getPoolsByTick",
+    Method::GET,
+    "/brc20s/pool/tid/:tick_id",
+  ),
+  (
+    "brc20s_getUserInfo",
+    Method::GET,
+    "/brc20s/pool/:pid/address/:address/userinfo",
+  ),
+  (
+    "brc20s_getBalance",
+    Method::GET,
+    "/brc20s/tick/:tick_id/address/:address/balance",
+  ),
+  (
+    "brc20s_getAllBalance",
+    Method::GET,
+    "/brc20s/address/:address/balance",
+  ),
+  (
+    "brc20s_getTransferable",
+    Method::GET,
+    "/brc20s/tick/:tick_id/address/:address/transferable",
+  ),
+  (
+    "brc20s_getAllTransferable",
+    Method::GET,
+    "/brc20s/address/:address/transferable",
+  ),
+  (
+    "brc20s_getBatchBalance",
+    Method::POST,
+    "/brc20s/batch/balance",
+  ),
+  (
+    "brc20s_getTxReceipts",
+    Method::GET,
+    "/brc20s/tx/:txid/receipts",
+  ),
+  (
+    "brc20s_getBlockReceipts",
+    Method::GET,
+    "/brc20s/block/:block_hash/receipts",
+  ),
+  (
+    "brc20s_getStakeInfo",
+    Method::GET,
+    "/brc20s/stake/:address/:tick",
+  ),
+  ("tx_preview", Method::POST, "/tx/preview"),
+];
+
+/// The `/api/v1` router that JSON-RPC calls are dispatched to.
+#[derive(Clone)]
+pub(super) struct RpcApi(Arc<Mutex<Router>>);
+
+impl RpcApi {
+  pub(super) fn new(router: Router) -> Self {
+    Self(Arc::new(Mutex::new(router)))
+  }
+
+  fn router(&self) -> Router {
+    self.0.lock().unwrap().clone()
+  }
+}
+
+#[derive(Debug, Serialize)]
+struct RpcError {
+  code: i64,
+  message: String,
+}
+
+impl RpcError {
+  fn new<S: ToString>(code: i64, message: S) -> Self {
+    Self {
+      code,
+      message: message.to_string(),
+    }
+  }
+}
+
+impl From<ApiError> for RpcError {
+  fn from(error: ApiError) -> Self {
+    let code = error.code().into();
+    match error {
+      ApiError::Internal(message)
+      | ApiError::BadRequest(message)
+      | ApiError::NotFound(message)
+      | ApiError::Unavailable(message) => Self { code, message },
+    }
+  }
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+  json!({ "jsonrpc": "2.0", "error": error, "id": id })
+}
+
+/// Serves a JSON-RPC 2.0 request or batch of requests.
+pub(super) async fn rpc(Extension(api): Extension<RpcApi>, body: Bytes) -> Response {
+  let responses = match serde_json::from_slice::<Value>(&body) {
+    Err(err) => Some(error_response(Value::Null, RpcError::new(PARSE_ERROR, err))),
+    Ok(Value::Array(calls)) if calls.is_empty() => Some(error_response(
+      Value::Null,
+      RpcError::new(INVALID_REQUEST, "empty batch"),
+    )),
+    Ok(Value::Array(calls)) => {
+      let mut responses = Vec::new();
+      for call in calls {
+        responses.extend(self::call(api.router(), call).await);
+      }
+      (!responses.is_empty()).then_some(Value::Array(responses))
+    }
+    Ok(call) => self::call(api.router(), call).await,
+  };
+
+  match responses {
+    Some(responses) => Json(responses).into_response(),
+    None => StatusCode::NO_CONTENT.into_response(),
+  }
+}
+
+/// Runs one call, returning its response unless it is a notification.
+async fn call(api: Router, call: Value) -> Option<Value> {
+  let Value::Object(mut call) = call else {
+    return Some(error_response(
+      Value::Null,
+      RpcError::new(INVALID_REQUEST, "request must be an object"),
+    ));
+  };
+
+  let id = call.remove("id");
+
+  let params = call.remove("params").unwrap_or(Value::Null);
+
+  let outcome = match (
+    call.get("jsonrpc").and_then(Value::as_str),
+    call.get("method").and_then(Value::as_str),
+    params,
+  ) {
+    (Some("2.0"), Some(method), params @ (Value::Null | Value::Object(_) | Value::Array(_))) => {
+      dispatch(api, method, params).await
+    }
+    _ => Err(RpcError::new(INVALID_REQUEST, "invalid request")),
+  };
+
+  let id = id?;
+
+  Some(match outcome {
+    Ok(result) => json!({ "jsonrpc": "2.0", "result": result, "id": id }),
+    Err(error) => error_response(id, error),
+  })
+}
+
+/// Serves a call with the `/api/v1` handler of its method.
+async fn dispatch(api: Router, method: &str, params: Value) -> Result<Value, RpcError> {
+  let (_, http_method, path) = METHODS
+    .iter()
+    .find(|(name, _, _)| *name == method)
+    .ok_or_else(|| RpcError::new(METHOD_NOT_FOUND, format!("method not found: {method}")))?;
+
+  let request = api_request(http_method, path, params)?;
+
+  let response = match api.oneshot(request).await {
+    Ok(response) => response,
+    Err(err) => match err {},
+  };
+
+  let status = response.status();
+  let body = hyper::body::to_bytes(response.into_body())
+    .await
+    .map_err(|err| RpcError::from(ApiError::internal(err)))?;
+
+  match serde_json::from_slice::<Value>(&body) {
+    Ok(Value::Object(mut envelope)) if envelope.contains_key("code") => {
+      match envelope.get("code").and_then(Value::as_i64) {
+        Some(0) => Ok(envelope.remove("data").unwrap_or(Value::Null)),
+        code => Err(RpcError::new(
+          code.unwrap_or_else(|| ApiError::internal("").code().into()),
+          envelope
+            .get("msg")
+            .and_then(Value::as_str)
+            .unwrap_or_default(),
+        )),
+      }
+    }
+    _ => {
+      let message = String::from_utf8_lossy(&body);
+      Err(
+        match status {
+          StatusCode::NOT_FOUND if message.is_empty() => ApiError::not_found("not found"),
+          StatusCode::NOT_FOUND => ApiError::not_found(message),
+          StatusCode::SERVICE_UNAVAILABLE => ApiError::unavailable(message),
+          status if status.is_client_error() => ApiError::bad_request(message),
+          _ => ApiError::internal(message),
+        }
+        .into(),
+      )
+    }
+  }
+}
+
+/// Builds the `/api/v1` request for a call to the route at `path`.
+fn api_request(method: &Method, path: &str, params: Value) -> Result<Request<Body>, RpcError> {
+  let names = path
+    .split('/')
+    .filter_map(|segment| segment.strip_prefix(':'))
+    .collect::<Vec<&str>>();
+
+  let mut params = match params {
+    Value::Array(values) => {
+      if values.len() > names.len() {
+        return Err(RpcError::new(
+          INVALID_PARAMS,
+          format!("expected at most {} params", names.len()),
+        ));
+      }
+      names
+        .iter()
+        .map(|name| name.to_string())
+        .zip(values)
+        .collect::<Map<String, Value>>()
+    }
+    Value::Object(params) => params,
+    _ => Map::new(),
+  };
+
+  let mut uri = String::new();
+  for segment in path.split('/').skip(1) {
+    uri.push('/');
+    match segment.strip_prefix(':') {
+      Some(name) => {
+        let value = params
+          .remove(name)
+          .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("missing param `{name}`")))?;
+        uri.push_str(&percent_encode(&scalar(name, value)?));
+      }
+      None => uri.push_str(segment),
+    }
+  }
+
+  let request = if method == Method::POST {
+    Request::post(uri)
+      .header(header::CONTENT_TYPE, "application/json")
+      .body(Body::from(serde_json::to_vec(&params).unwrap()))
+  } else {
+    let query = params
+      .into_iter()
+      .map(|(name, value)| {
+        Ok(format!(
+          "{}={}",
+          percent_encode(&name),
+          percent_encode(&scalar(&name, value)?)
+        ))
+      })
+      .collect::<Result<Vec<String>, RpcError>>()?;
+
+    if !query.is_empty() {
+      uri.push('?');
+      uri.push_str(&query.join("&"));
+    }
+
+    Request::get(uri).body(Body::empty())
+  };
+
+  request.map_err(|err| RpcError::new(INVALID_PARAMS, err))
+}
+
+fn scalar(name: &str, value: Value) -> Result<String, RpcError> {
+  match value {
+    Value::String(value) => Ok(value),
+    Value::Number(value) => Ok(value.to_string()),
+    Value::Bool(value) => Ok(value.to_string()),
+    _ => Err(RpcError::new(
+      INVALID_PARAMS,
+      format!("param `{name}` must be a string, number or boolean"),
+    )),
+  }
+}
+
+fn percent_encode(s: &str) -> String {
+  s.bytes()
+    .map(|byte| {
+      if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+        char::from(byte).to_string()
+      } else {
+        format!("%{byte:02X}")
+      }
+    })
+    .collect()
+}
@@ -1,5 +1,242 @@
-use super::*;
+use {
+  super::*,
+  bitcoin::{
+    bip32::{ChildNumber, DerivationPath, ExtendedPrivKey, Fingerprint},
+    secp256k1::{constants::SCHNORR_SIGNATURE_SIZE, All, Secp256k1},
+    Witness,
+  },
+  bitcoincore_rpc::bitcoincore_rpc_json::{ImportDescriptors, Timestamp},
+  miniscript::descriptor::{Descriptor, DescriptorSecretKey, DescriptorXKey, Wildcard},
+};
 
+pub mod brc20;
 pub mod create;
 pub mod inscribe;
 pub mod outputs;
+pub mod restore;
+
+/// Value of the outputs that inscriptions are created on and sent in.
+const TARGET_POSTAGE: u64 = 10_000;
+
+#[derive(Debug, Parser)]
+pub(crate) enum Wallet {
+  #[command(subcommand, about = "BRC20 wallet commands")]
+  Brc20(brc20::Brc20),
+  #[command(about = "Create new wallet")]
+  Create(create::Create),
+  #[command(about = "Create inscription")]
+  Inscribe(inscribe::Inscribe),
+  #[command(about = "List wallet outputs")]
+  Outputs,
+  #[command(about = "Restore wallet")]
+  Restore(restore::Restore),
+}
+
+impl Wallet {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    match self {
+      Self::Brc20(brc20) => brc20.run(options),
+      Self::Create(create) => create.run(options),
+      Self::Inscribe(inscribe) => inscribe.run(options),
+      Self::Outputs => outputs::run(options),
+      Self::Restore(restore) => restore.run(options),
+    }
+  }
+}
+
+/// Returns a client for the `ord` wallet and an index brought up to date with it.
+fn open(options: &Options) -> Result<(Client, Index)> {
+  let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
+
+  let index = Index::open(options)?;
+  index.update()?;
+
+  Ok((client, index))
+}
+
+pub(crate) fn get_change_address(client: &Client, chain: Chain) -> Result<Address> {
+  Ok(
+    client
+      .call::<Address<NetworkUnchecked>>("getrawchangeaddress", &["bech32m".into()])
+      .context("could not get change addresses from wallet")?
+      .require_network(chain.network())?,
+  )
+}
+
+/// Returns the unlocked wallet outputs that carry no inscriptions, the only
+/// ones wallet commands spend to pay for transactions.
+fn cardinal_outputs(client: &Client, index: &Index) -> Result<BTreeMap<OutPoint, TxOut>> {
+  let inscribed = index
+    .get_inscriptions(index.get_unspent_outputs()?)?
+    .into_keys()
+    .map(|satpoint| satpoint.outpoint)
+    .collect::<HashSet<OutPoint>>();
+
+  let mut cardinals = BTreeMap::new();
+
+  for utxo in client.list_unspent(None, None, None, None, None)? {
+    let outpoint = OutPoint::new(utxo.txid, utxo.vout);
+
+    if inscribed.contains(&outpoint) {
+      continue;
+    }
+
+    let tx_out = index
+      .get_outpoint_entry(outpoint)?
+      .ok_or_else(|| anyhow!("output in Bitcoin Core wallet but not in ord index: {outpoint}"))?;
+
+    cardinals.insert(outpoint, tx_out);
+  }
+
+  Ok(cardinals)
+}
+
+/// Adds inputs from `cardinals` to `tx`, whose current inputs are worth
+/// `input_value`, until they pay for its outputs and its fee at `fee_rate`,
+/// sending the excess to `change` unless it would be dust. Spent cardinals are
+/// removed from `cardinals`. Returns the fee paid.
+fn fund_transaction(
+  tx: &mut Transaction,
+  mut input_value: u64,
+  cardinals: &mut BTreeMap<OutPoint, TxOut>,
+  change: ScriptBuf,
+  fee_rate: FeeRate,
+) -> Result<u64> {
+  let output_value = tx.output.iter().map(|tx_out| tx_out.value).sum::<u64>();
+
+  let mut change = TxOut {
+    value: 0,
+    script_pubkey: change,
+  };
+  let dust = change.script_pubkey.dust_value().to_sat();
+
+  loop {
+    let mut with_change = tx.clone();
+    with_change.output.push(change.clone());
+    let fee = fee_rate.fee(estimated_vsize(&with_change)).to_sat();
+
+    if input_value >= output_value + fee + dust {
+      change.value = input_value - output_value - fee;
+      tx.output.push(change);
+      return Ok(fee);
+    }
+
+    if input_value >= output_value + fee_rate.fee(estimated_vsize(tx)).to_sat() {
+      return Ok(input_value - output_value);
+    }
+
+    let (outpoint, tx_out) = cardinals
+      .iter()
+      .max_by_key(|(_, tx_out)| tx_out.value)
+      .map(|(outpoint, tx_out)| (*outpoint, tx_out.clone()))
+      .ok_or_else(|| {
+        anyhow!("wallet does not contain enough cardinal outputs to pay for transaction")
+      })?;
+
+    cardinals.remove(&outpoint);
+
+    tx.input.push(TxIn {
+      previous_output: outpoint,
+      script_sig: ScriptBuf::new(),
+      sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+      witness: Witness::new(),
+    });
+
+    input_value += tx_out.value;
+  }
+}
+
+/// Returns the vsize of `tx` once the wallet signs its unsigned inputs, which
+/// are all taproot key spends.
+fn estimated_vsize(tx: &Transaction) -> usize {
+  let mut tx = tx.clone();
+
+  for input in &mut tx.input {
+    if input.witness.is_empty() {
+      input.witness = Witness::from_slice(&[&[0; SCHNORR_SIGNATURE_SIZE]]);
+    }
+  }
+
+  tx.vsize()
+}
+
+/// Signs the wallet inputs of `tx` and broadcasts it.
+fn sign_and_send(client: &Client, tx: &Transaction) -> Result<Txid> {
+  let signed = client.sign_raw_transaction_with_wallet(tx, None, None)?;
+
+  if !signed.complete {
+    bail!("failed to sign transaction {}", tx.txid());
+  }
+
+  Ok(client.send_raw_transaction(&signed.hex)?)
+}
+
+fn initialize_wallet(options: &Options, seed: [u8; 64]) -> Result {
+  let client = options.bitcoin_rpc_client_for_wallet_command(true)?;
+  let network = options.chain().network();
+
+  client.create_wallet(&options.wallet, None, Some(true), None, None)?;
+
+  let secp = Secp256k1::new();
+
+  let master_private_key = ExtendedPrivKey::new_master(network, &seed)?;
+
+  let fingerprint = master_private_key.fingerprint(&secp);
+
+  let derivation_path = DerivationPath::master()
+    .child(ChildNumber::Hardened { index: 86 })
+    .child(ChildNumber::Hardened {
+      index: u32::from(network != Network::Bitcoin),
+    })
+    .child(ChildNumber::Hardened { index: 0 });
+
+  let derived_private_key = master_private_key.derive_priv(&secp, &derivation_path)?;
+
+  for change in [false, true] {
+    derive_and_import_descriptor(
+      &client,
+      &secp,
+      (fingerprint, derivation_path.clone()),
+      derived_private_key,
+      change,
+    )?;
+  }
+
+  Ok(())
+}
+
+fn derive_and_import_descriptor(
+  client: &Client,
+  secp: &Secp256k1<All>,
+  origin: (Fingerprint, DerivationPath),
+  derived_private_key: ExtendedPrivKey,
+  change: bool,
+) -> Result {
+  let secret_key = DescriptorSecretKey::XPrv(DescriptorXKey {
+    origin: Some(origin),
+    xkey: derived_private_key,
+    derivation_path: DerivationPath::master().child(ChildNumber::Normal {
+      index: change.into(),
+    }),
+    wildcard: Wildcard::Unhardened,
+  });
+
+  let public_key = secret_key.to_public(secp)?;
+
+  let mut key_map = std::collections::HashMap::new();
+  key_map.insert(public_key.clone(), secret_key);
+
+  let desc = Descriptor::new_tr(public_key, None)?;
+
+  client.import_descriptors(ImportDescriptors {
+    descriptor: desc.to_string_with_secret(&key_map),
+    timestamp: Timestamp::Now,
+    active: Some(true),
+    range: None,
+    next_index: None,
+    internal: Some(change),
+    label: None,
+  })?;
+
+  Ok(())
+}
@@ -0,0 +1,169 @@
+use {
+  super::{inscribe::CommitReveal, *},
+  crate::okx::{datastore::brc20 as brc20_store, protocol::brc20::Num},
+  bitcoin::blockdata::locktime::absolute::LockTime,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) enum Brc20 {
+  #[command(about = "Inscribe a BRC20 transfer and send it to an address")]
+  Transfer(Transfer),
+}
+
+impl Brc20 {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    match self {
+      Self::Transfer(transfer) => transfer.run(options),
+    }
+  }
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Transfer {
+  #[arg(long, help = "Transfer <TICK>.")]
+  tick: brc20_store::Tick,
+  #[arg(long, help = "Transfer <AMOUNT> tokens.")]
+  amount: Num,
+  #[arg(long, help = "Send the transfer to <TO>.")]
+  to: Address<NetworkUnchecked>,
+  #[arg(long, help = "Use fee rate of <FEE_RATE> sats/vB.")]
+  fee_rate: FeeRate,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Output {
+  pub from: Address<NetworkUnchecked>,
+  pub commit: Txid,
+  pub inscription: InscriptionId,
+  pub reveal: Txid,
+  pub send: Txid,
+  pub total_fees: u64,
+}
+
+impl Transfer {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let chain = options.chain();
+
+    let to = self.to.clone().require_network(chain.network())?;
+
+    let (client, index) = open(&options)?;
+
+    let from = self.sender(&index, chain)?;
+
+    let inscription = Inscription::new(
+      Some("text/plain;charset=utf-8".into()),
+      Some(
+        serde_json::json!({
+          "p": "brc-20",
+          "op": "transfer",
+          "tick": self.tick.to_string(),
+          "amt": self.amount.to_string(),
+        })
+        .to_string()
+        .into_bytes(),
+      ),
+    );
+
+    let mut cardinals = cardinal_outputs(&client, &index)?;
+
+    let inscribe = CommitReveal::new(
+      &inscription,
+      from.script_pubkey(),
+      &mut cardinals,
+      get_change_address(&client, chain)?.script_pubkey(),
+      self.fee_rate,
+    )?;
+
+    cardinals.extend(inscribe.change_output());
+
+    let (inscription_output, postage) = inscribe.inscription_output();
+
+    let mut send = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: inscription_output,
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::new(),
+      }],
+      output: vec![TxOut {
+        value: postage.value,
+        script_pubkey: to.script_pubkey(),
+      }],
+    };
+
+    let send_fee = fund_transaction(
+      &mut send,
+      postage.value,
+      &mut cardinals,
+      get_change_address(&client, chain)?.script_pubkey(),
+      self.fee_rate,
+    )?;
+
+    let (commit, reveal) = inscribe.send(&client)?;
+
+    let send = sign_and_send(&client, &send).context("failed to send transfer transaction")?;
+
+    Ok(Box::new(Output {
+      from: Address::new(chain.network(), from.payload),
+      commit,
+      inscription: inscribe.inscription_id(),
+      reveal,
+      send,
+      total_fees: inscribe.fees + send_fee,
+    }))
+  }
+
+  /// Returns the wallet address with enough available balance to inscribe the
+  /// transfer, which must be inscribed to the address holding the balance.
+  fn sender(&self, index: &Index, chain: Chain) -> Result<Address> {
+    let token = index
+      .brc20_get_tick_info(&self.tick)?
+      .ok_or_else(|| anyhow!("tick {} not found", self.tick))?;
+
+    if self.amount.scale() > i64::from(token.decimal) {
+      bail!(
+        "amount {} has more than {} decimals",
+        self.amount,
+        token.decimal
+      );
+    }
+
+    let base: Num = 10u128.pow(u32::from(token.decimal)).into();
+    let amount = self.amount.checked_mul(&base)?.checked_to_u128()?;
+
+    if amount == 0 {
+      bail!("amount must be greater than zero");
+    }
+
+    let mut addresses = Vec::new();
+    for outpoint in index.get_unspent_outputs()?.into_keys() {
+      let Some(tx_out) = index.get_outpoint_entry(outpoint)? else {
+        continue;
+      };
+
+      let Ok(address) = Address::from_script(&tx_out.script_pubkey, chain.network()) else {
+        continue;
+      };
+
+      if !addresses.contains(&address) {
+        addresses.push(address);
+      }
+    }
+
+    for address in addresses {
+      if let Some(balance) = index.brc20_get_balance_by_address(&self.tick, &address)? {
+        if balance.overall_balance - balance.transferable_balance >= amount {
+          return Ok(address);
+        }
+      }
+    }
+
+    bail!(
+      "insufficient {} balance: no wallet address has {} available",
+      self.tick,
+      self.amount
+    )
+  }
+}
@@ -1,4 +1,13 @@
-use super::*;
+use {
+  super::*,
+  bitcoin::{
+    blockdata::{locktime::absolute::LockTime, opcodes, script},
+    key::UntweakedKeyPair,
+    secp256k1::{self, rand, XOnlyPublicKey},
+    sighash::{Prevouts, SighashCache, TapSighashType},
+    taproot::{LeafVersion, Signature, TapLeafHash, TaprootBuilder},
+  },
+};
 
 #[derive(Serialize, Deserialize)]
 pub struct Output {
@@ -8,3 +17,211 @@ pub struct Output {
   pub reveal: Txid,
   pub total_fees: u64,
 }
+
+#[derive(Debug, Parser)]
+pub(crate) struct Inscribe {
+  #[arg(help = "Inscribe sat with contents of <FILE>.")]
+  file: PathBuf,
+  #[arg(long, help = "Use fee rate of <FEE_RATE> sats/vB.")]
+  fee_rate: FeeRate,
+  #[arg(long, help = "Send inscription to <DESTINATION>.")]
+  destination: Option<Address<NetworkUnchecked>>,
+}
+
+impl Inscribe {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    let chain = options.chain();
+
+    let inscription = Inscription::from_file(chain, &self.file, None)?;
+
+    let (client, index) = open(&options)?;
+
+    let destination = match self.destination {
+      Some(destination) => destination.require_network(chain.network())?,
+      None => get_change_address(&client, chain)?,
+    };
+
+    let mut cardinals = cardinal_outputs(&client, &index)?;
+
+    let transactions = CommitReveal::new(
+      &inscription,
+      destination.script_pubkey(),
+      &mut cardinals,
+      get_change_address(&client, chain)?.script_pubkey(),
+      self.fee_rate,
+    )?;
+
+    let (commit, reveal) = transactions.send(&client)?;
+
+    Ok(Box::new(Output {
+      commit,
+      inscription: transactions.inscription_id(),
+      parent: None,
+      reveal,
+      total_fees: transactions.fees,
+    }))
+  }
+}
+
+/// A commit transaction paid for by the wallet, and the reveal transaction
+/// spending it to inscribe the first sat of its only output.
+pub(super) struct CommitReveal {
+  pub(super) commit: Transaction,
+  pub(super) reveal: Transaction,
+  pub(super) fees: u64,
+}
+
+impl CommitReveal {
+  /// Builds the transactions inscribing `inscription` on an output to
+  /// `destination`, funding the commit with `cardinals` and sending its change
+  /// to `change`. The reveal is signed with a throwaway key, the commit is left
+  /// for the wallet to sign.
+  pub(super) fn new(
+    inscription: &Inscription,
+    destination: ScriptBuf,
+    cardinals: &mut BTreeMap<OutPoint, TxOut>,
+    change: ScriptBuf,
+    fee_rate: FeeRate,
+  ) -> Result<Self> {
+    let secp256k1 = Secp256k1::new();
+    let key_pair = UntweakedKeyPair::new(&secp256k1, &mut rand::thread_rng());
+    let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
+
+    let reveal_script = inscription.append_reveal_script(
+      script::Builder::new()
+        .push_slice(public_key.serialize())
+        .push_opcode(opcodes::all::OP_CHECKSIG),
+    );
+
+    let taproot_spend_info = TaprootBuilder::new()
+      .add_leaf(0, reveal_script.clone())
+      .expect("adding leaf should work")
+      .finalize(&secp256k1, public_key)
+      .expect("finalizing taproot builder should work");
+
+    let control_block = taproot_spend_info
+      .control_block(&(reveal_script.clone(), LeafVersion::TapScript))
+      .expect("should compute control block");
+
+    let mut reveal = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: OutPoint::null(),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::from_slice(&[
+          [0; SCHNORR_SIGNATURE_SIZE].as_slice(),
+          reveal_script.as_bytes(),
+          &control_block.serialize(),
+        ]),
+      }],
+      output: vec![TxOut {
+        value: TARGET_POSTAGE,
+        script_pubkey: destination,
+      }],
+    };
+
+    let reveal_fee = fee_rate.fee(reveal.vsize()).to_sat();
+
+    let commit_output = TxOut {
+      value: TARGET_POSTAGE + reveal_fee,
+      script_pubkey: ScriptBuf::new_v1_p2tr_tweaked(taproot_spend_info.output_key()),
+    };
+
+    let mut commit = Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: Vec::new(),
+      output: vec![commit_output.clone()],
+    };
+
+    let commit_fee = fund_transaction(&mut commit, 0, cardinals, change, fee_rate)?;
+
+    reveal.input[0].previous_output = OutPoint {
+      txid: commit.txid(),
+      vout: 0,
+    };
+
+    let mut sighash_cache = SighashCache::new(&mut reveal);
+
+    let sighash = sighash_cache
+      .taproot_script_spend_signature_hash(
+        0,
+        &Prevouts::All(&[commit_output]),
+        TapLeafHash::from_script(&reveal_script, LeafVersion::TapScript),
+        TapSighashType::Default,
+      )
+      .expect("signature hash should compute");
+
+    let sig = secp256k1.sign_schnorr(
+      &secp256k1::Message::from_slice(sighash.as_ref())
+        .expect("should be cryptographically secure hash"),
+      &key_pair,
+    );
+
+    let witness = sighash_cache
+      .witness_mut(0)
+      .expect("getting mutable witness reference should work");
+
+    witness.clear();
+    witness.push(
+      Signature {
+        sig,
+        hash_ty: TapSighashType::Default,
+      }
+      .to_vec(),
+    );
+    witness.push(reveal_script);
+    witness.push(control_block.serialize());
+
+    Ok(Self {
+      commit,
+      reveal,
+      fees: commit_fee + reveal_fee,
+    })
+  }
+
+  pub(super) fn inscription_id(&self) -> InscriptionId {
+    InscriptionId {
+      txid: self.reveal.txid(),
+      index: 0,
+    }
+  }
+
+  /// The output of the reveal transaction that carries the inscription.
+  pub(super) fn inscription_output(&self) -> (OutPoint, TxOut) {
+    (
+      OutPoint {
+        txid: self.reveal.txid(),
+        vout: 0,
+      },
+      self.reveal.output[0].clone(),
+    )
+  }
+
+  /// The change output of the commit transaction, if it has one.
+  pub(super) fn change_output(&self) -> Option<(OutPoint, TxOut)> {
+    self.commit.output.get(1).map(|tx_out| {
+      (
+        OutPoint {
+          txid: self.commit.txid(),
+          vout: 1,
+        },
+        tx_out.clone(),
+      )
+    })
+  }
+
+  /// Signs the commit with the wallet and broadcasts both transactions.
+  pub(super) fn send(&self, client: &Client) -> Result<(Txid, Txid)> {
+    let commit =
+      sign_and_send(client, &self.commit).context("failed to send commit transaction")?;
+
+    let reveal = client
+      .send_raw_transaction(&self.reveal)
+      .context("failed to send reveal transaction")?;
+
+    Ok((commit, reveal))
+  }
+}
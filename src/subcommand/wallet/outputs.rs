@@ -5,3 +5,17 @@ pub struct Output {
   pub output: OutPoint,
   pub amount: u64,
 }
+
+pub(crate) fn run(options: Options) -> SubcommandResult {
+  let (_, index) = open(&options)?;
+
+  let mut outputs = Vec::new();
+  for (output, amount) in index.get_unspent_outputs()? {
+    outputs.push(Output {
+      output,
+      amount: amount.to_sat(),
+    });
+  }
+
+  Ok(Box::new(outputs))
+}
@@ -0,0 +1,21 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Restore {
+  #[arg(help = "Use <MNEMONIC> to derive wallet seed.")]
+  mnemonic: Mnemonic,
+  #[arg(
+    long,
+    default_value = "",
+    help = "Use <PASSPHRASE> when deriving wallet seed."
+  )]
+  pub(crate) passphrase: String,
+}
+
+impl Restore {
+  pub(crate) fn run(self, options: Options) -> SubcommandResult {
+    initialize_wallet(&options, self.mnemonic.to_seed(self.passphrase))?;
+
+    Ok(Box::new(Empty {}))
+  }
+}
@@ -1,7 +1,13 @@
 use {super::*, boilerplate::Boilerplate};
 
 pub(crate) use {
+  account::Account,
+  address::AddressHtml,
+  amount::Amount,
   block::BlockHtml,
+  brc20_tick::BRC20TickHtml,
+  brc20s_pool::BRC20SPoolHtml,
+  brc20s_tick::BRC20STickHtml,
   clock::ClockSvg,
   home::HomeHtml,
   iframe::Iframe,
@@ -11,6 +17,7 @@ pub(crate) use {
   inscriptions_block::InscriptionsBlockHtml,
   output::{OutputHtml, OutputJson},
   page_config::PageConfig,
+  pool::Pool,
   preview::{
     PreviewAudioHtml, PreviewImageHtml, PreviewModelHtml, PreviewPdfHtml, PreviewTextHtml,
     PreviewUnknownHtml, PreviewVideoHtml,
@@ -21,7 +28,13 @@ pub(crate) use {
   transaction::TransactionHtml,
 };
 
+mod account;
+mod address;
+mod amount;
 mod block;
+mod brc20_tick;
+mod brc20s_pool;
+mod brc20s_tick;
 mod clock;
 mod home;
 mod iframe;
@@ -30,6 +43,7 @@ pub mod inscription;
 pub mod inscriptions;
 mod inscriptions_block;
 pub mod output;
+mod pool;
 mod preview;
 mod range;
 mod rare;
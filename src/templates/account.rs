@@ -0,0 +1,53 @@
+use {super::*, crate::okx::datastore::ScriptKey};
+
+/// The owner of a BRC20 or BRC20S balance, linked to its address page when
+/// the script is an address.
+pub(crate) struct Account {
+  script_key: ScriptKey,
+}
+
+impl Account {
+  pub(crate) fn link(script_key: &ScriptKey) -> Trusted<Self> {
+    Trusted(Self {
+      script_key: script_key.clone(),
+    })
+  }
+}
+
+impl Display for Account {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match &self.script_key {
+      ScriptKey::Address(_) => write!(
+        f,
+        "<a class=monospace href=/address/{0}>{0}</a>",
+        self.script_key
+      ),
+      ScriptKey::ScriptHash(_) => write!(f, "<span class=monospace>{}</span>", self.script_key),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn address_is_linked() {
+    assert_eq!(
+      Account::link(&ScriptKey::from_address(address()))
+        .0
+        .to_string(),
+      "<a class=monospace href=/address/bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4>bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</a>",
+    );
+  }
+
+  #[test]
+  fn script_hash_is_not_linked() {
+    assert_regex_match!(
+      Account::link(&ScriptKey::from_script(&ScriptBuf::new(), Network::Bitcoin))
+        .0
+        .to_string(),
+      "<span class=monospace>[[:xdigit:]]{40}</span>",
+    );
+  }
+}
@@ -0,0 +1,122 @@
+use {
+  super::*,
+  crate::okx::datastore::{brc20, brc20s},
+};
+
+#[derive(Boilerplate)]
+pub(crate) struct AddressHtml {
+  pub(crate) address: Address,
+  pub(crate) inscriptions: Vec<InscriptionId>,
+  pub(crate) brc20_balances: Vec<(brc20::Balance, u8)>,
+  pub(crate) brc20_transferable: Vec<(brc20::TransferableLog, u8)>,
+  pub(crate) brc20s_balances: Vec<(brc20s::Balance, brc20s::TickInfo)>,
+  pub(crate) brc20s_transferable: Vec<(brc20s::TransferableAsset, brc20s::TickInfo)>,
+}
+
+impl PageContent for AddressHtml {
+  fn title(&self) -> String {
+    format!("Address {}", self.address)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::okx::datastore::{brc20::Tick, ScriptKey},
+  };
+
+  #[test]
+  fn empty() {
+    assert_regex_match!(
+      AddressHtml {
+        address: address(),
+        inscriptions: Vec::new(),
+        brc20_balances: Vec::new(),
+        brc20_transferable: Vec::new(),
+        brc20s_balances: Vec::new(),
+        brc20s_transferable: Vec::new(),
+      },
+      "
+        <h1>Address <span class=monospace>bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</span></h1>
+        <h2>0 Inscriptions</h2>
+        <div class=thumbnails>
+        </div>
+        <h2>0 BRC20 Balances</h2>
+        <ul>
+        </ul>
+        <h2>0 BRC20 Transferable Inscriptions</h2>
+        <ul>
+        </ul>
+        <h2>0 BRC20S Balances</h2>
+        <ul>
+        </ul>
+        <h2>0 BRC20S Transferable Inscriptions</h2>
+        <ul>
+        </ul>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_brc20() {
+    let tick = Tick::from_str("ordi").unwrap();
+    assert_regex_match!(
+      AddressHtml {
+        address: address(),
+        inscriptions: vec![inscription_id(1)],
+        brc20_balances: vec![(
+          brc20::Balance {
+            tick: tick.clone(),
+            overall_balance: 1500,
+            transferable_balance: 500,
+          },
+          2
+        )],
+        brc20_transferable: vec![(
+          brc20::TransferableLog {
+            inscription_id: inscription_id(1),
+            inscription_number: 3,
+            amount: 500,
+            tick,
+            owner: ScriptKey::from_address(address()),
+          },
+          2
+        )],
+        brc20s_balances: Vec::new(),
+        brc20s_transferable: Vec::new(),
+      },
+      "
+        <h1>Address .*</h1>
+        <h2>1 Inscription</h2>
+        <div class=thumbnails>
+          <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+        </div>
+        <h2>1 BRC20 Balance</h2>
+        <ul>
+          <li>
+            <a href=/brc20/ordi>ordi</a>
+            <dl>
+              <dt>available</dt><dd>10</dd>
+              <dt>transferable</dt><dd>5</dd>
+              <dt>overall</dt><dd>15</dd>
+            </dl>
+          </li>
+        </ul>
+        <h2>1 BRC20 Transferable Inscription</h2>
+        <ul>
+          <li>
+            <a class=monospace href=/inscription/1{64}i1>1{64}i1</a>
+            <dl>
+              <dt>tick</dt><dd><a href=/brc20/ordi>ordi</a></dd>
+              <dt>amount</dt><dd>5</dd>
+            </dl>
+          </li>
+        </ul>
+        .*
+      "
+      .unindent()
+    );
+  }
+}
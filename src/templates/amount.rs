@@ -0,0 +1,49 @@
+use super::*;
+
+/// A token amount stored as an integer number of `10^-decimal` units,
+/// displayed as a decimal number without trailing zeros.
+pub(crate) struct Amount {
+  value: u128,
+  decimal: u8,
+}
+
+impl Amount {
+  pub(crate) fn new(value: u128, decimal: u8) -> Self {
+    Self { value, decimal }
+  }
+}
+
+impl Display for Amount {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    let digits = format!(
+      "{:0>width$}",
+      self.value,
+      width = usize::from(self.decimal) + 1
+    );
+    let (integer, fraction) = digits.split_at(digits.len() - usize::from(self.decimal));
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+      write!(f, "{integer}")
+    } else {
+      write!(f, "{integer}.{fraction}")
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn display() {
+    assert_eq!(Amount::new(0, 18).to_string(), "0");
+    assert_eq!(Amount::new(1000, 0).to_string(), "1000");
+    assert_eq!(Amount::new(1005 * 10u128.pow(17), 18).to_string(), "100.5");
+    assert_eq!(Amount::new(1, 18).to_string(), "0.000000000000000001");
+    assert_eq!(
+      Amount::new(u128::MAX, 18).to_string(),
+      "340282366920938463463.374607431768211455"
+    );
+  }
+}
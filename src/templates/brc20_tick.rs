@@ -0,0 +1,126 @@
+use {
+  super::*,
+  crate::okx::datastore::brc20::{Event, Receipt, TokenInfo},
+};
+
+#[derive(Boilerplate)]
+#[boilerplate(filename = "brc20-tick.html")]
+pub(crate) struct BRC20TickHtml {
+  pub(crate) token: TokenInfo,
+  pub(crate) events: Vec<(u64, Txid, Receipt)>,
+}
+
+impl PageContent for BRC20TickHtml {
+  fn title(&self) -> String {
+    format!("BRC20 {}", self.token.tick)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::okx::datastore::{
+      brc20::{MintEvent, OperationType, Tick},
+      ScriptKey,
+    },
+  };
+
+  fn token() -> TokenInfo {
+    TokenInfo {
+      tick: Tick::from_str("ordi").unwrap(),
+      inscription_id: inscription_id(1),
+      inscription_number: 0,
+      supply: 21_000_000 * 10u128.pow(18),
+      minted: 1000 * 10u128.pow(18),
+      limit_per_mint: 1000 * 10u128.pow(18),
+      decimal: 18,
+      deploy_by: ScriptKey::from_address(address()),
+      deployed_number: 2,
+      deployed_timestamp: 0,
+      latest_mint_number: 3,
+    }
+  }
+
+  #[test]
+  fn without_events() {
+    assert_regex_match!(
+      BRC20TickHtml {
+        token: token(),
+        events: Vec::new(),
+      },
+      "
+        <h1>BRC20 ordi</h1>
+        <dl>
+          <dt>supply</dt>
+          <dd>21000000</dd>
+          <dt>minted</dt>
+          <dd>1000</dd>
+          <dt>limit per mint</dt>
+          <dd>1000</dd>
+          <dt>decimals</dt>
+          <dd>18</dd>
+          <dt>deployer</dt>
+          <dd><a class=monospace href=/address/bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4>bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</a></dd>
+          <dt>deploy inscription</dt>
+          <dd><a class=monospace href=/inscription/1{64}i1>1{64}i1</a></dd>
+          <dt>deploy height</dt>
+          <dd><a href=/block/2>2</a></dd>
+          <dt>deploy timestamp</dt>
+          <dd><time>1970-01-01 00:00:00 UTC</time></dd>
+          <dt>latest mint height</dt>
+          <dd><a href=/block/3>3</a></dd>
+        </dl>
+        <h2>Recent Events</h2>
+        <p>No events yet.</p>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_events() {
+    assert_regex_match!(
+      BRC20TickHtml {
+        token: token(),
+        events: vec![(
+          3,
+          txid(2),
+          Receipt {
+            inscription_id: inscription_id(2),
+            inscription_number: 1,
+            old_satpoint: satpoint(2, 0),
+            new_satpoint: satpoint(2, 0),
+            op: OperationType::Mint,
+            from: ScriptKey::from_address(address()),
+            to: ScriptKey::from_address(address()),
+            result: Ok(Event::Mint(MintEvent {
+              tick: Tick::from_str("ordi").unwrap(),
+              amount: 1000 * 10u128.pow(18),
+              msg: None,
+            })),
+          },
+        )],
+      },
+      "
+        <h1>BRC20 ordi</h1>
+        .*
+        <h2>Recent Events</h2>
+        <ul>
+          <li>
+            <a class=monospace href=/tx/2{64}>2{64}</a>
+            <dl>
+              <dt>height</dt><dd><a href=/block/3>3</a></dd>
+              <dt>event</dt><dd>mint</dd>
+              <dt>amount</dt><dd>1000</dd>
+              <dt>from</dt><dd><a class=monospace href=/address/bc1q.*>bc1q.*</a></dd>
+              <dt>to</dt><dd><a class=monospace href=/address/bc1q.*>bc1q.*</a></dd>
+              <dt>inscription</dt><dd><a class=monospace href=/inscription/2{64}i2>2{64}i2</a></dd>
+            </dl>
+          </li>
+        </ul>
+      "
+      .unindent()
+    );
+  }
+}
@@ -0,0 +1,175 @@
+use {
+  super::*,
+  crate::okx::datastore::brc20s::{Event, Pid, PledgedTick, PoolInfo, Receipt, TickInfo},
+};
+
+#[derive(Boilerplate)]
+#[boilerplate(filename = "brc20s-pool.html")]
+pub(crate) struct BRC20SPoolHtml {
+  pub(crate) pool: PoolInfo,
+  pub(crate) tick: TickInfo,
+  pub(crate) stake_decimal: u8,
+  pub(crate) receipts: Vec<(u64, Txid, Receipt)>,
+}
+
+impl BRC20SPoolHtml {
+  /// Whether `event` concerns the pool `pid` rather than another pool or
+  /// tick touched by the same receipt.
+  pub(crate) fn is_relevant(pid: &Pid, event: &Event) -> bool {
+    match event {
+      Event::DeployPool(deploy) => deploy.pid == *pid,
+      Event::Deposit(deposit) => deposit.pid == *pid,
+      Event::Withdraw(withdraw) => withdraw.pid == *pid,
+      Event::PassiveWithdraw(withdraw) => withdraw.pid == *pid,
+      Event::Mint(mint) => mint.pid == *pid,
+      Event::DeployTick(_) | Event::InscribeTransfer(_) | Event::Transfer(_) => false,
+    }
+  }
+}
+
+impl PageContent for BRC20SPoolHtml {
+  fn title(&self) -> String {
+    format!("BRC20S Pool {}", self.pool.pid.as_str())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::okx::datastore::{
+      brc20,
+      brc20s::{DepositEvent, OperationType, PoolType, Tick, TickId},
+      ScriptKey,
+    },
+  };
+
+  fn pool() -> PoolInfo {
+    PoolInfo {
+      pid: Pid::from_str("13395c5283#01").unwrap(),
+      ptype: PoolType::Fixed,
+      inscription_id: inscription_id(1),
+      stake: PledgedTick::BRC20Tick(brc20::Tick::from_str("ordi").unwrap()),
+      erate: 10u128.pow(8),
+      minted: 25 * 10u128.pow(7),
+      staked: 3 * 10u128.pow(18),
+      dmax: 500 * 10u128.pow(8),
+      acc_reward_per_share: "12345".into(),
+      last_update_block: 4,
+      only: true,
+      deploy_block: 2,
+      deploy_block_time: 0,
+    }
+  }
+
+  fn tick() -> TickInfo {
+    TickInfo {
+      tick_id: TickId::from_str("13395c5283").unwrap(),
+      name: Tick::from_str("ordi1").unwrap(),
+      inscription_id: inscription_id(1),
+      allocated: 500 * 10u128.pow(8),
+      decimal: 8,
+      circulation: 0,
+      supply: 1000 * 10u128.pow(8),
+      deployer: ScriptKey::from_address(address()),
+      deploy_block: 2,
+      deploy_block_time: 0,
+      latest_mint_block: 3,
+      pids: vec![Pid::from_str("13395c5283#01").unwrap()],
+    }
+  }
+
+  #[test]
+  fn without_receipts() {
+    assert_regex_match!(
+      BRC20SPoolHtml {
+        pool: pool(),
+        tick: tick(),
+        stake_decimal: 18,
+        receipts: Vec::new(),
+      },
+      "
+        <h1>BRC20S Pool <span class=monospace>13395c5283#01</span></h1>
+        <dl>
+          <dt>type</dt>
+          <dd>fixed</dd>
+          <dt>tick</dt>
+          <dd><a href=/brc20s/tick/13395c5283>ordi1</a></dd>
+          <dt>stake</dt>
+          <dd><a href=/brc20/ordi>ordi</a></dd>
+          <dt>only</dt>
+          <dd>true</dd>
+          <dt>earn rate</dt>
+          <dd>1</dd>
+          <dt>dmax</dt>
+          <dd>500</dd>
+          <dt>minted</dt>
+          <dd>2.5</dd>
+          <dt>staked</dt>
+          <dd>3</dd>
+          <dt>acc reward per share</dt>
+          <dd>12345</dd>
+          <dt>last update height</dt>
+          <dd><a href=/block/4>4</a></dd>
+          <dt>deploy inscription</dt>
+          <dd><a class=monospace href=/inscription/1{64}i1>1{64}i1</a></dd>
+          <dt>deploy height</dt>
+          <dd><a href=/block/2>2</a></dd>
+          <dt>deploy timestamp</dt>
+          <dd><time>1970-01-01 00:00:00 UTC</time></dd>
+        </dl>
+        <h2>History</h2>
+        <p>No receipts yet.</p>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_receipts() {
+    assert_regex_match!(
+      BRC20SPoolHtml {
+        pool: pool(),
+        tick: tick(),
+        stake_decimal: 18,
+        receipts: vec![(
+          3,
+          txid(2),
+          Receipt {
+            inscription_id: inscription_id(2),
+            inscription_number: 1,
+            old_satpoint: satpoint(2, 0),
+            new_satpoint: satpoint(2, 0),
+            op: OperationType::Stake,
+            from: ScriptKey::from_address(address()),
+            to: ScriptKey::from_address(address()),
+            result: Ok(vec![Event::Deposit(DepositEvent {
+              pid: Pid::from_str("13395c5283#01").unwrap(),
+              amt: 3 * 10u128.pow(18),
+              period_settlement_reward: 5 * 10u128.pow(7),
+            })]),
+          },
+        )],
+      },
+      "
+        <h1>BRC20S Pool .*</h1>
+        .*
+        <h2>History</h2>
+        <ul>
+          <li>
+            <a class=monospace href=/tx/2{64}>2{64}</a>
+            <dl>
+              <dt>height</dt><dd><a href=/block/3>3</a></dd>
+              <dt>from</dt><dd><a class=monospace href=/address/bc1q.*>bc1q.*</a></dd>
+              <dt>to</dt><dd><a class=monospace href=/address/bc1q.*>bc1q.*</a></dd>
+              <dt>inscription</dt><dd><a class=monospace href=/inscription/2{64}i2>2{64}i2</a></dd>
+              <dt>deposit</dt><dd>3</dd>
+              <dt>settled reward</dt><dd>0.5</dd>
+            </dl>
+          </li>
+        </ul>
+      "
+      .unindent()
+    );
+  }
+}
@@ -0,0 +1,179 @@
+use {
+  super::*,
+  crate::okx::datastore::brc20s::{Event, PoolInfo, Receipt, TickInfo},
+};
+
+#[derive(Boilerplate)]
+#[boilerplate(filename = "brc20s-tick.html")]
+pub(crate) struct BRC20STickHtml {
+  pub(crate) tick: TickInfo,
+  pub(crate) pools: Vec<PoolInfo>,
+  pub(crate) receipts: Vec<(u64, Txid, Receipt)>,
+}
+
+impl BRC20STickHtml {
+  /// Whether `event` concerns `tick` rather than another tick touched by the
+  /// same receipt.
+  pub(crate) fn is_relevant(tick: &TickInfo, event: &Event) -> bool {
+    match event {
+      Event::DeployTick(deploy) => deploy.tick_id == tick.tick_id,
+      Event::DeployPool(deploy) => tick.pids.contains(&deploy.pid),
+      Event::Mint(mint) => tick.pids.contains(&mint.pid),
+      Event::InscribeTransfer(transfer) => transfer.tick_id == tick.tick_id,
+      Event::Transfer(transfer) => transfer.tick_id == tick.tick_id,
+      Event::Deposit(_) | Event::Withdraw(_) | Event::PassiveWithdraw(_) => false,
+    }
+  }
+}
+
+impl PageContent for BRC20STickHtml {
+  fn title(&self) -> String {
+    format!("BRC20S {}", self.tick.name.as_str())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::okx::datastore::{
+      brc20s::{MintEvent, OperationType, Pid, PledgedTick, PoolType, Tick, TickId, TransferEvent},
+      ScriptKey,
+    },
+  };
+
+  fn tick() -> TickInfo {
+    TickInfo {
+      tick_id: TickId::from_str("13395c5283").unwrap(),
+      name: Tick::from_str("ordi1").unwrap(),
+      inscription_id: inscription_id(1),
+      allocated: 500 * 10u128.pow(8),
+      decimal: 8,
+      circulation: 200 * 10u128.pow(8),
+      supply: 1000 * 10u128.pow(8),
+      deployer: ScriptKey::from_address(address()),
+      deploy_block: 2,
+      deploy_block_time: 0,
+      latest_mint_block: 3,
+      pids: vec![Pid::from_str("13395c5283#01").unwrap()],
+    }
+  }
+
+  #[test]
+  fn without_receipts() {
+    assert_regex_match!(
+      BRC20STickHtml {
+        tick: tick(),
+        pools: vec![PoolInfo {
+          pid: Pid::from_str("13395c5283#01").unwrap(),
+          ptype: PoolType::Pool,
+          inscription_id: inscription_id(1),
+          stake: PledgedTick::Native,
+          erate: 10u128.pow(8),
+          minted: 0,
+          staked: 0,
+          dmax: 500 * 10u128.pow(8),
+          acc_reward_per_share: "0".into(),
+          last_update_block: 2,
+          only: false,
+          deploy_block: 2,
+          deploy_block_time: 0,
+        }],
+        receipts: Vec::new(),
+      },
+      "
+        <h1>BRC20S ordi1</h1>
+        <dl>
+          <dt>tick id</dt>
+          <dd class=monospace>13395c5283</dd>
+          <dt>supply</dt>
+          <dd>1000</dd>
+          <dt>allocated</dt>
+          <dd>500</dd>
+          <dt>circulation</dt>
+          <dd>200</dd>
+          <dt>decimals</dt>
+          <dd>8</dd>
+          <dt>deployer</dt>
+          <dd><a class=monospace href=/address/bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4>bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</a></dd>
+          <dt>deploy inscription</dt>
+          <dd><a class=monospace href=/inscription/1{64}i1>1{64}i1</a></dd>
+          <dt>deploy height</dt>
+          <dd><a href=/block/2>2</a></dd>
+          <dt>deploy timestamp</dt>
+          <dd><time>1970-01-01 00:00:00 UTC</time></dd>
+          <dt>latest mint height</dt>
+          <dd><a href=/block/3>3</a></dd>
+        </dl>
+        <h2>1 Pool</h2>
+        <ul>
+          <li>
+            <a class=monospace href=/brc20s/pool/13395c5283%2301>13395c5283#01</a>
+            <dl>
+              <dt>type</dt><dd>pool</dd>
+              <dt>stake</dt><dd>btc</dd>
+              <dt>earn rate</dt><dd>1</dd>
+              <dt>minted</dt><dd>0</dd>
+              <dt>dmax</dt><dd>500</dd>
+            </dl>
+          </li>
+        </ul>
+        <h2>Recent Receipts</h2>
+        <p>No receipts yet.</p>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_receipts() {
+    assert_regex_match!(
+      BRC20STickHtml {
+        tick: tick(),
+        pools: Vec::new(),
+        receipts: vec![(
+          3,
+          txid(2),
+          Receipt {
+            inscription_id: inscription_id(2),
+            inscription_number: 1,
+            old_satpoint: satpoint(2, 0),
+            new_satpoint: satpoint(2, 0),
+            op: OperationType::Mint,
+            from: ScriptKey::from_address(address()),
+            to: ScriptKey::from_address(address()),
+            result: Ok(vec![
+              Event::Mint(MintEvent {
+                pid: Pid::from_str("13395c5283#01").unwrap(),
+                amt: 15 * 10u128.pow(7),
+              }),
+              Event::Transfer(TransferEvent {
+                tick_id: TickId::from_str("aaaaaaaaaa").unwrap(),
+                amt: 1,
+                msg: None,
+              }),
+            ]),
+          },
+        )],
+      },
+      "
+        <h1>BRC20S ordi1</h1>
+        .*
+        <h2>Recent Receipts</h2>
+        <ul>
+          <li>
+            <a class=monospace href=/tx/2{64}>2{64}</a>
+            <dl>
+              <dt>height</dt><dd><a href=/block/3>3</a></dd>
+              <dt>from</dt><dd><a class=monospace href=/address/bc1q.*>bc1q.*</a></dd>
+              <dt>to</dt><dd><a class=monospace href=/address/bc1q.*>bc1q.*</a></dd>
+              <dt>inscription</dt><dd><a class=monospace href=/inscription/2{64}i2>2{64}i2</a></dd>
+              <dt>mint</dt><dd>1.5 from <a class=monospace href=/brc20s/pool/13395c5283%2301>13395c5283#01</a></dd>
+            </dl>
+          </li>
+        </ul>
+      "
+      .unindent()
+    );
+  }
+}
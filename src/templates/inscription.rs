@@ -2,6 +2,7 @@ use super::*;
 
 #[derive(Boilerplate)]
 pub(crate) struct InscriptionHtml {
+  pub(crate) brc20_tick: Option<String>,
   pub(crate) chain: Chain,
   pub(crate) children: Vec<InscriptionId>,
   pub(crate) genesis_fee: u64,
@@ -95,6 +96,7 @@ mod tests {
   fn without_sat_nav_links_or_output() {
     assert_regex_match!(
       InscriptionHtml {
+        brc20_tick: None,
         children: Vec::new(),
         parent: None,
         chain: Chain::Mainnet,
@@ -152,6 +154,7 @@ mod tests {
   fn with_output() {
     assert_regex_match!(
       InscriptionHtml {
+        brc20_tick: None,
         children: Vec::new(),
         parent: None,
         chain: Chain::Mainnet,
@@ -177,7 +180,7 @@ mod tests {
         <dl>
           .*
           <dt>address</dt>
-          <dd class=monospace>bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</dd>
+          <dd><a class=monospace href=/address/bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4>bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</a></dd>
           <dt>output value</dt>
           <dd>1</dd>
           .*
@@ -191,6 +194,7 @@ mod tests {
   fn with_sat() {
     assert_regex_match!(
       InscriptionHtml {
+        brc20_tick: None,
         children: Vec::new(),
         parent: None,
         chain: Chain::Mainnet,
@@ -225,6 +229,7 @@ mod tests {
   fn with_prev_and_next() {
     assert_regex_match!(
       InscriptionHtml {
+        brc20_tick: None,
         children: Vec::new(),
         parent: None,
         chain: Chain::Mainnet,
@@ -257,6 +262,7 @@ mod tests {
   fn with_cursed_and_unbound() {
     assert_regex_match!(
       InscriptionHtml {
+        brc20_tick: None,
         children: Vec::new(),
         parent: None,
         chain: Chain::Mainnet,
@@ -295,6 +301,7 @@ mod tests {
   fn with_parent() {
     assert_regex_match!(
       InscriptionHtml {
+        brc20_tick: None,
         children: Vec::new(),
         parent: Some(inscription_id(2)),
         chain: Chain::Mainnet,
@@ -354,6 +361,7 @@ mod tests {
   fn with_children() {
     assert_regex_match!(
       InscriptionHtml {
+        brc20_tick: None,
         children: vec![inscription_id(2), inscription_id(3)],
         parent: None,
         chain: Chain::Mainnet,
@@ -0,0 +1,39 @@
+use {super::*, crate::okx::datastore::brc20s::Pid};
+
+/// A BRC20S pool id linked to its pool page. Pool ids contain a `#`, which
+/// has to be escaped to survive as part of the path.
+pub(crate) struct Pool {
+  pid: Pid,
+}
+
+impl Pool {
+  pub(crate) fn link(pid: &Pid) -> Trusted<Self> {
+    Trusted(Self { pid: pid.clone() })
+  }
+}
+
+impl Display for Pool {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(
+      f,
+      "<a class=monospace href=/brc20s/pool/{}>{}</a>",
+      self.pid.as_str().replace('#', "%23"),
+      self.pid.as_str()
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn pid_is_escaped() {
+    assert_eq!(
+      Pool::link(&Pid::from_str("13395c5283#01").unwrap())
+        .0
+        .to_string(),
+      "<a class=monospace href=/brc20s/pool/13395c5283%2301>13395c5283#01</a>",
+    );
+  }
+}
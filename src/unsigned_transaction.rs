@@ -0,0 +1,143 @@
+use {super::*, base64::Engine, bitcoin::psbt::PartiallySignedTransaction};
+
+const PSBT_MAGIC: &[u8] = b"psbt\xff";
+
+/// A transaction that has not been broadcast, given as a base64 or hex PSBT or
+/// as a hex raw transaction, together with the previous outputs the PSBT
+/// carries for its inputs.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct UnsignedTransaction {
+  pub(crate) transaction: Transaction,
+  pub(crate) prevouts: BTreeMap<OutPoint, TxOut>,
+}
+
+impl UnsignedTransaction {
+  fn from_psbt(psbt: PartiallySignedTransaction) -> Result<Self> {
+    let mut prevouts = BTreeMap::new();
+
+    for (tx_in, input) in psbt.unsigned_tx.input.iter().zip(&psbt.inputs) {
+      let outpoint = tx_in.previous_output;
+
+      let tx_out = match (&input.witness_utxo, &input.non_witness_utxo) {
+        (Some(tx_out), _) => tx_out.clone(),
+        (None, Some(tx)) => tx
+          .output
+          .get(usize::try_from(outpoint.vout).unwrap())
+          .cloned()
+          .ok_or_else(|| anyhow!("PSBT previous transaction has no output {outpoint}"))?,
+        (None, None) => continue,
+      };
+
+      prevouts.insert(outpoint, tx_out);
+    }
+
+    Ok(Self {
+      transaction: psbt.unsigned_tx,
+      prevouts,
+    })
+  }
+}
+
+impl FromStr for UnsignedTransaction {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let s = s.trim();
+
+    let bytes = match hex::decode(s) {
+      Ok(bytes) => bytes,
+      Err(_) => base64::engine::general_purpose::STANDARD
+        .decode(s)
+        .map_err(|_| {
+          anyhow!("transaction must be a base64 or hex PSBT or a hex raw transaction")
+        })?,
+    };
+
+    if bytes.starts_with(PSBT_MAGIC) {
+      Self::from_psbt(PartiallySignedTransaction::deserialize(&bytes)?)
+    } else {
+      Ok(Self {
+        transaction: consensus::deserialize(&bytes)?,
+        prevouts: BTreeMap::new(),
+      })
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, bitcoin::locktime::absolute::LockTime};
+
+  fn transaction() -> Transaction {
+    Transaction {
+      version: 2,
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: OutPoint {
+          txid: txid(1),
+          vout: 1,
+        },
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Default::default(),
+      }],
+      output: vec![TxOut {
+        value: 5000,
+        script_pubkey: ScriptBuf::new(),
+      }],
+    }
+  }
+
+  #[test]
+  fn parse_raw_transaction() {
+    assert_eq!(
+      consensus::encode::serialize_hex(&transaction())
+        .parse::<UnsignedTransaction>()
+        .unwrap(),
+      UnsignedTransaction {
+        transaction: transaction(),
+        prevouts: BTreeMap::new(),
+      }
+    );
+  }
+
+  #[test]
+  fn parse_psbt() {
+    let mut psbt = PartiallySignedTransaction::from_unsigned_tx(transaction()).unwrap();
+    let prevout = TxOut {
+      value: 10_000,
+      script_pubkey: ScriptBuf::new(),
+    };
+    psbt.inputs[0].witness_utxo = Some(prevout.clone());
+
+    let expected = UnsignedTransaction {
+      transaction: transaction(),
+      prevouts: [(transaction().input[0].previous_output, prevout)].into(),
+    };
+
+    assert_eq!(
+      base64::engine::general_purpose::STANDARD
+        .encode(psbt.serialize())
+        .parse::<UnsignedTransaction>()
+        .unwrap(),
+      expected
+    );
+    assert_eq!(
+      hex::encode(psbt.serialize())
+        .parse::<UnsignedTransaction>()
+        .unwrap(),
+      expected
+    );
+  }
+
+  #[test]
+  fn parse_garbage() {
+    assert_eq!(
+      "not a transaction!"
+        .parse::<UnsignedTransaction>()
+        .unwrap_err()
+        .to_string(),
+      "transaction must be a base64 or hex PSBT or a hex raw transaction"
+    );
+  }
+}
@@ -123,6 +123,7 @@ pub struct TransactionTemplate<'a> {
   pub inputs: &'a [(usize, usize, usize, Witness)],
   pub output_values: &'a [u64],
   pub outputs: usize,
+  pub recipient: Option<Address>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -154,6 +155,7 @@ impl<'a> Default for TransactionTemplate<'a> {
       inputs: &[],
       output_values: &[],
       outputs: 1,
+      recipient: None,
     }
   }
 }
@@ -52,7 +52,7 @@ impl State {
       input: vec![TxIn {
         previous_output: OutPoint::null(),
         script_sig: script::Builder::new()
-          .push_int(self.blocks.len().try_into().unwrap())
+          .push_int(self.hashes.len().try_into().unwrap())
           .into_script(),
         sequence: Sequence::MAX,
         witness: Witness::new(),
@@ -89,7 +89,7 @@ impl State {
         version: Version::ONE,
         prev_blockhash: *self.hashes.last().unwrap(),
         merkle_root: TxMerkleNode::all_zeros(),
-        time: self.blocks.len().try_into().unwrap(),
+        time: self.hashes.len().try_into().unwrap(),
         bits: CompactTarget::from_consensus(0),
         nonce: self.nonce,
       },
@@ -121,11 +121,9 @@ impl State {
     block
   }
 
+  /// Drops the tip from the active chain, keeping the block around as bitcoind does.
   pub(crate) fn pop_block(&mut self) -> BlockHash {
-    let blockhash = self.hashes.pop().unwrap();
-    self.blocks.remove(&blockhash);
-
-    blockhash
+    self.hashes.pop().unwrap()
   }
 
   pub(crate) fn broadcast_tx(&mut self, template: TransactionTemplate) -> Txid {
@@ -159,7 +157,11 @@ impl State {
             .get(i)
             .cloned()
             .unwrap_or(value_per_output),
-          script_pubkey: script::Builder::new().into_script(),
+          script_pubkey: template
+            .recipient
+            .as_ref()
+            .map(Address::script_pubkey)
+            .unwrap_or_default(),
         })
         .collect(),
     };
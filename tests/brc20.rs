@@ -0,0 +1,43 @@
+use {super::*, ord::subcommand::Empty};
+
+#[test]
+fn query_commands_read_the_index_without_bitcoin_core() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  let tempdir = TempDir::new().unwrap();
+
+  let index_path = tempdir.path().join("index.redb");
+
+  CommandBuilder::new(format!("--index {} index run", index_path.display()))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Empty>();
+
+  drop(rpc_server);
+
+  assert_eq!(
+    CommandBuilder::new(format!("--index {} brc20 tick", index_path.display()))
+      .run_and_deserialize_output::<Vec<serde_json::Value>>(),
+    Vec::<serde_json::Value>::new()
+  );
+
+  assert_eq!(
+    CommandBuilder::new(format!(
+      "--index {} brc20 balance bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+      index_path.display()
+    ))
+    .run_and_deserialize_output::<Vec<serde_json::Value>>(),
+    Vec::<serde_json::Value>::new()
+  );
+
+  assert_eq!(
+    CommandBuilder::new(format!("--index {} brc20s pool", index_path.display()))
+      .run_and_deserialize_output::<Vec<serde_json::Value>>(),
+    Vec::<serde_json::Value>::new()
+  );
+
+  CommandBuilder::new(format!("--index {} brc20 tick abcd", index_path.display()))
+    .expected_stderr("error: tick abcd not found\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
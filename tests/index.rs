@@ -1,4 +1,13 @@
-use {super::*, crate::command_builder::ToArgs, ord::subcommand::Empty};
+use {
+  super::*,
+  crate::command_builder::ToArgs,
+  ord::subcommand::Empty,
+  std::{
+    io::{BufRead, BufReader},
+    sync::mpsc,
+    time::Instant,
+  },
+};
 
 #[test]
 fn custom_index_path() {
@@ -64,3 +73,102 @@ fn index_runs_with_rpc_user_and_pass_as_env_vars() {
 
   assert_eq!(ord.wait_with_output().unwrap().status.code(), Some(0));
 }
+
+#[test]
+#[cfg(unix)]
+fn follow_indexes_new_blocks_until_terminated() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  let tempdir = TempDir::new().unwrap();
+  fs::write(tempdir.path().join(".cookie"), "foo:bar").unwrap();
+  let index_path = tempdir.path().join("foo.redb");
+
+  let notify_port = TcpListener::bind("127.0.0.1:0")
+    .unwrap()
+    .local_addr()
+    .unwrap()
+    .port();
+
+  let mut child = Command::new(executable_path("ord"))
+    .args(
+      format!(
+        "--rpc-url {} --bitcoin-data-dir {} --index {} --log-level info --log-dir {} index run --follow --poll-interval 600000 --block-notify 127.0.0.1:{notify_port}",
+        rpc_server.url(),
+        tempdir.path().display(),
+        index_path.display(),
+        tempdir.path().join("logs").display(),
+      )
+      .to_args(),
+    )
+    .stdout(Stdio::piped())
+    .spawn()
+    .unwrap();
+
+  let (tx, rx) = mpsc::channel();
+  let stdout = child.stdout.take().unwrap();
+  let reader = thread::spawn(move || {
+    for line in BufReader::new(stdout).lines() {
+      if tx.send(line.unwrap()).is_err() {
+        break;
+      }
+    }
+  });
+
+  let deadline = Instant::now() + Duration::from_secs(10);
+  let mut lines = Vec::new();
+
+  let mut wait_for = |needle: &str| loop {
+    let remaining = deadline
+      .checked_duration_since(Instant::now())
+      .unwrap_or_else(|| {
+        panic!(
+          "timed out waiting for `{needle}`, got:\n{}",
+          lines.join("\n")
+        )
+      });
+
+    let line = rx.recv_timeout(remaining).unwrap();
+    let found = line.contains(needle);
+    lines.push(line);
+    if found {
+      break;
+    }
+  };
+
+  wait_for("Listening for block notifications");
+  wait_for("Committing at block height 2");
+
+  rpc_server.mine_blocks(2);
+  std::net::TcpStream::connect(("127.0.0.1", notify_port)).unwrap();
+
+  wait_for("Committing at block height 4");
+
+  assert!(Command::new("kill")
+    .args(["-TERM", &child.id().to_string()])
+    .status()
+    .unwrap()
+    .success());
+
+  assert!(child.wait().unwrap().success());
+  reader.join().unwrap();
+  lines.extend(rx.try_iter());
+
+  assert_eq!(
+    lines[lines.len() - 2..],
+    [
+      "Shutting down gracefully. Press <CTRL-C> again to shutdown immediately.",
+      "{}",
+    ]
+  );
+
+  let snapshot = CommandBuilder::new(format!(
+    "--index {} index snapshot export --output {}",
+    index_path.display(),
+    tempdir.path().join("snapshot.bin").display()
+  ))
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<serde_json::Value>();
+
+  assert_eq!(snapshot["height"], 3);
+}
@@ -75,7 +75,10 @@ mod command_builder;
 mod expected;
 mod test_server;
 
+mod brc20;
 mod index;
 mod json_api;
+mod preview;
 mod server;
 mod version;
+mod wallet;
@@ -0,0 +1,200 @@
+use {
+  super::*,
+  bitcoin::{
+    address::NetworkUnchecked, consensus, locktime::absolute::LockTime, Address, OutPoint,
+    ScriptBuf, Sequence, Transaction, TxIn, TxOut, Witness,
+  },
+  ord::subcommand::{wallet::create, Empty},
+  serde::Deserialize,
+};
+
+#[derive(Deserialize)]
+struct Preview {
+  brc20_receipts: Vec<Receipt>,
+  brc20_balance_changes: Vec<BalanceChange>,
+  fee_inscriptions: Vec<InscriptionId>,
+}
+
+#[derive(Deserialize)]
+struct Receipt {
+  inscription_id: InscriptionId,
+  from: serde_json::Value,
+  to: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct BalanceChange {
+  script_key: serde_json::Value,
+  before: Balance,
+  after: Balance,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct Balance {
+  overall_balance: u128,
+  transferable_balance: u128,
+}
+
+fn address(s: &str) -> Address {
+  s.parse::<Address<NetworkUnchecked>>()
+    .unwrap()
+    .assume_checked()
+}
+
+fn spend(outpoint: OutPoint, outputs: &[&Address]) -> String {
+  consensus::encode::serialize_hex(&Transaction {
+    version: 2,
+    lock_time: LockTime::ZERO,
+    input: vec![TxIn {
+      previous_output: outpoint,
+      script_sig: ScriptBuf::new(),
+      sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+      witness: Witness::new(),
+    }],
+    output: outputs
+      .iter()
+      .map(|address| TxOut {
+        value: 10_000,
+        script_pubkey: address.script_pubkey(),
+      })
+      .collect(),
+  })
+}
+
+#[test]
+fn preview_predicts_brc20_transfer_without_indexing_it() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  let tempdir = TempDir::new().unwrap();
+  let index = format!(
+    "--index {} --enable-index-brc20",
+    tempdir.path().join("index.redb").display()
+  );
+
+  CommandBuilder::new(format!("{index} wallet create"))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<create::Output>();
+
+  rpc_server.mine_blocks(2);
+
+  let owner = address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+  let recipient = address("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq");
+
+  let mut txid = None;
+  for (height, body) in [
+    (
+      1,
+      r#"{"p":"brc-20","op":"deploy","tick":"ordi","max":"21000000","lim":"1000"}"#,
+    ),
+    (
+      2,
+      r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"1000"}"#,
+    ),
+    (
+      3,
+      r#"{"p":"brc-20","op":"transfer","tick":"ordi","amt":"100"}"#,
+    ),
+  ] {
+    txid = Some(rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        height,
+        0,
+        0,
+        envelope(&[
+          b"ord",
+          &[1],
+          b"text/plain;charset=utf-8",
+          &[],
+          body.as_bytes(),
+        ]),
+      )],
+      recipient: Some(owner.clone()),
+      ..Default::default()
+    }));
+    rpc_server.mine_blocks(1);
+  }
+
+  CommandBuilder::new(format!("{index} index run"))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Empty>();
+
+  let transfer = OutPoint {
+    txid: txid.unwrap(),
+    vout: 0,
+  };
+  let inscription_id = InscriptionId {
+    txid: transfer.txid,
+    index: 0,
+  };
+
+  let base = 10u128.pow(18);
+
+  let preview = CommandBuilder::new(format!(
+    "{index} preview {}",
+    spend(transfer, &[&recipient])
+  ))
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<Preview>();
+
+  assert_eq!(preview.brc20_receipts.len(), 1);
+  assert_eq!(preview.brc20_receipts[0].inscription_id, inscription_id);
+  assert!(preview.brc20_receipts[0]
+    .from
+    .to_string()
+    .contains(&owner.to_string()));
+  assert!(preview.brc20_receipts[0]
+    .to
+    .to_string()
+    .contains(&recipient.to_string()));
+  assert!(preview.fee_inscriptions.is_empty());
+
+  assert_eq!(preview.brc20_balance_changes.len(), 2);
+  assert!(preview.brc20_balance_changes[0]
+    .script_key
+    .to_string()
+    .contains(&owner.to_string()));
+  assert_eq!(
+    preview.brc20_balance_changes[0].before,
+    Balance {
+      overall_balance: 1000 * base,
+      transferable_balance: 100 * base,
+    }
+  );
+  assert_eq!(
+    preview.brc20_balance_changes[0].after,
+    Balance {
+      overall_balance: 900 * base,
+      transferable_balance: 0,
+    }
+  );
+  assert!(preview.brc20_balance_changes[1]
+    .script_key
+    .to_string()
+    .contains(&recipient.to_string()));
+  assert_eq!(
+    preview.brc20_balance_changes[1].before,
+    Balance {
+      overall_balance: 0,
+      transferable_balance: 0,
+    }
+  );
+  assert_eq!(
+    preview.brc20_balance_changes[1].after,
+    Balance {
+      overall_balance: 100 * base,
+      transferable_balance: 0,
+    }
+  );
+
+  let preview = CommandBuilder::new(format!("{index} preview {}", spend(transfer, &[])))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Preview>();
+
+  assert_eq!(preview.fee_inscriptions, [inscription_id]);
+
+  assert!(
+    CommandBuilder::new(format!("{index} brc20 balance {recipient}"))
+      .run_and_deserialize_output::<Vec<Balance>>()
+      .is_empty()
+  );
+}
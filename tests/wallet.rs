@@ -0,0 +1,137 @@
+use {
+  super::*,
+  bitcoin::{address::NetworkUnchecked, Address, OutPoint},
+  ord::subcommand::{
+    wallet::{brc20, create},
+    Empty,
+  },
+  serde::Deserialize,
+};
+
+#[derive(Deserialize)]
+struct Balance {
+  overall_balance: u128,
+  transferable_balance: u128,
+}
+
+fn address(s: &str) -> Address {
+  s.parse::<Address<NetworkUnchecked>>()
+    .unwrap()
+    .assume_checked()
+}
+
+fn brc20_balance(index: &str, address: &Address) -> Balance {
+  CommandBuilder::new(format!("{index} brc20 balance {address}"))
+    .run_and_deserialize_output::<Vec<Balance>>()
+    .pop()
+    .unwrap()
+}
+
+#[test]
+fn brc20_transfer_inscribes_and_sends_a_transfer() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  let tempdir = TempDir::new().unwrap();
+  let index = format!(
+    "--index {} --enable-index-brc20",
+    tempdir.path().join("index.redb").display()
+  );
+
+  CommandBuilder::new(format!("{index} wallet create"))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<create::Output>();
+
+  rpc_server.mine_blocks(2);
+
+  let owner = address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4");
+  let recipient = address("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq");
+
+  let mut inscriptions = Vec::new();
+  for (height, body) in [
+    (
+      1,
+      r#"{"p":"brc-20","op":"deploy","tick":"ordi","max":"21000000","lim":"1000"}"#,
+    ),
+    (
+      2,
+      r#"{"p":"brc-20","op":"mint","tick":"ordi","amt":"1000"}"#,
+    ),
+  ] {
+    let txid = rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        height,
+        0,
+        0,
+        envelope(&[
+          b"ord",
+          &[1],
+          b"text/plain;charset=utf-8",
+          &[],
+          body.as_bytes(),
+        ]),
+      )],
+      recipient: Some(owner.clone()),
+      ..Default::default()
+    });
+    inscriptions.push(OutPoint { txid, vout: 0 });
+    rpc_server.mine_blocks(1);
+  }
+
+  CommandBuilder::new(format!(
+    "{index} wallet brc20 transfer --fee-rate 1 --tick ordi --amount 1001 --to {recipient}"
+  ))
+  .rpc_server(&rpc_server)
+  .expected_stderr("error: insufficient ordi balance: no wallet address has 1001 available\n")
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+
+  let output = CommandBuilder::new(format!(
+    "{index} wallet brc20 transfer --fee-rate 1 --tick ordi --amount 100.5 --to {recipient}"
+  ))
+  .rpc_server(&rpc_server)
+  .run_and_deserialize_output::<brc20::Output>();
+
+  assert_eq!(output.from.assume_checked(), owner);
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(
+    mempool.iter().map(|tx| tx.txid()).collect::<Vec<_>>(),
+    [output.commit, output.reveal, output.send]
+  );
+
+  for tx in &mempool {
+    for input in &tx.input {
+      assert!(!inscriptions.contains(&input.previous_output));
+    }
+  }
+
+  assert_eq!(mempool[1].output[0].script_pubkey, owner.script_pubkey());
+  assert_eq!(output.inscription.txid, output.reveal);
+  assert_eq!(
+    mempool[2].input[0].previous_output,
+    OutPoint {
+      txid: output.reveal,
+      vout: 0
+    }
+  );
+  assert_eq!(
+    mempool[2].output[0].script_pubkey,
+    recipient.script_pubkey()
+  );
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!("{index} index run"))
+    .rpc_server(&rpc_server)
+    .run_and_deserialize_output::<Empty>();
+
+  let base = 10u128.pow(18);
+
+  let balance = brc20_balance(&index, &owner);
+  assert_eq!(balance.overall_balance, 8995 * base / 10);
+  assert_eq!(balance.transferable_balance, 0);
+
+  let balance = brc20_balance(&index, &recipient);
+  assert_eq!(balance.overall_balance, 1005 * base / 10);
+  assert_eq!(balance.transferable_balance, 0);
+}